@@ -4,6 +4,8 @@ use criterion::{black_box, criterion_group, criterion_main, Criterion, Benchmark
 use PunkVM::bytecode::files::{BytecodeFile, BytecodeVersion, SegmentMetadata, SegmentType};
 use PunkVM::bytecode::instructions::Instruction;
 use PunkVM::bytecode::opcodes::Opcode;
+use PunkVM::pvm::branch_predictor::PredictorType;
+use PunkVM::pvm::cache_configs::WritePolicy;
 use PunkVM::pvm::vm::{PunkVM, VMConfig};
 use std::time::Duration;
 
@@ -61,11 +63,30 @@ fn run_simple_cache_benchmark(l1_size: usize, l2_size: usize, data_size: usize)
         stack_size: 4 * 1024,
         stack_base: 0xC000,
         fetch_buffer_size: 8,
+        issue_width: 1,
+        register_write_ports: 2,
+        mmio_base: 0xFFFF_F000,
+        l1_write_policy: WritePolicy::WriteThrough,
+        l2_write_policy: WritePolicy::WriteBack,
+        copy_on_write_memory: false,
+        max_cycles: None,
+        misprediction_penalty: 0,
+        l1_latency: 1,
+        l2_latency: 12,
+        memory_latency: 100,
+        simd_op_cache_size: 1,
+        max_speculation_depth: None,
         btb_size: 16,
         ras_size: 4,
+        branch_predictor_kind: PredictorType::Hybrid,
         enable_forwarding: true,
+        forward_ex_to_ex: true,
+        forward_mem_to_ex: true,
         enable_hazard_detection: true,
         enable_tracing: false,
+        enable_opcode_histogram: false,
+        enable_icache_prefetch: false,
+        enable_simd256: true,
     };
 
     let mut vm = PunkVM::with_config(vm_config);
@@ -4,6 +4,12 @@
 
 use std::collections::HashMap;
 
+/// Latence (cycles) d'une adresse résolue tôt par le prédicteur de stride ou le cache de base
+pub const AGU_EARLY_LATENCY: u64 = 1;
+
+/// Latence (cycles) d'un calcul complet à partir d'une base fraîchement produite
+pub const AGU_FULL_LATENCY: u64 = 3;
+
 /// Configuration de l'AGU
 #[derive(Debug, Clone)]
 pub struct AGUConfig {
@@ -87,6 +93,9 @@ pub struct AGUStats {
     pub base_cache_hits: u64,
     pub base_cache_misses: u64,
     pub parallel_executions: u64,
+    /// Somme des latences de chaque calcul d'adresse
+    pub total_latency: u64,
+    /// Moyenne réelle: total_latency / total_calculations
     pub average_latency: f64,
 }
 
@@ -143,33 +152,38 @@ impl StridePredictor {
     }
     
     pub fn update(&mut self, pc: u64, actual_address: u64, current_cycle: u64) {
-        let entry = self.entries.entry(pc).or_insert(StrideEntry {
-            last_address: actual_address,
-            stride: 0,
-            confidence: 0,
-            hits: 0,
-            last_used: current_cycle,
-        });
-        
-        let new_stride = (actual_address as i64) - (entry.last_address as i64);
-        
-        if new_stride == entry.stride {
-            // Correct prediction
-            entry.confidence = entry.confidence.saturating_add(1);
-            entry.hits += 1;
-            self.hits += 1;
-        } else {
-            // Wrong prediction
-            entry.confidence = entry.confidence.saturating_sub(1);
-            if entry.confidence == 0 {
-                entry.stride = new_stride;
+        if let Some(entry) = self.entries.get_mut(&pc) {
+            let new_stride = (actual_address as i64) - (entry.last_address as i64);
+
+            if new_stride == entry.stride {
+                // Correct prediction
+                entry.confidence = entry.confidence.saturating_add(1);
+                entry.hits += 1;
+                self.hits += 1;
+            } else {
+                // Wrong prediction
+                entry.confidence = entry.confidence.saturating_sub(1);
+                if entry.confidence == 0 {
+                    // Nouveau stride adopté: il vient d'être observé une fois
+                    entry.stride = new_stride;
+                    entry.confidence = 1;
+                }
+                self.misses += 1;
             }
-            self.misses += 1;
+
+            entry.last_address = actual_address;
+            entry.last_used = current_cycle;
+        } else {
+            // Première observation: on mémorise seulement l'adresse, aucun stride connu
+            self.entries.insert(pc, StrideEntry {
+                last_address: actual_address,
+                stride: 0,
+                confidence: 0,
+                hits: 0,
+                last_used: current_cycle,
+            });
         }
         
-        entry.last_address = actual_address;
-        entry.last_used = current_cycle;
-        
         // LRU eviction if over capacity
         if self.entries.len() > self.capacity {
             let oldest_pc = self.entries.iter()
@@ -259,6 +273,7 @@ impl AGU {
             None
         };
         
+        let mut base_cache_hit = false;
         let address = match mode {
             AddressingMode::BaseOffset { base, offset } => {
                 let base_val = registers.get(base as usize)
@@ -267,6 +282,7 @@ impl AGU {
                 // Check base cache
                 if self.config.enable_base_cache {
                     let cache_hit = self.base_cache.lookup(base, *base_val);
+                    base_cache_hit = cache_hit;
                     if cache_hit {
                         self.stats.base_cache_hits += 1;
                         println!("AGU: Base cache HIT for R{} = 0x{:X}", base, base_val);
@@ -293,6 +309,7 @@ impl AGU {
                 // Check base cache for BaseIndexScale too
                 if self.config.enable_base_cache {
                     let cache_hit = self.base_cache.lookup(base, *base_val);
+                    base_cache_hit = cache_hit;
                     if cache_hit {
                         self.stats.base_cache_hits += 1;
                         println!("AGU: Base cache HIT (IndexScale) for R{} = 0x{:X}", base, base_val);
@@ -327,10 +344,12 @@ impl AGU {
         };
         
         // Valider prédiction stride et mettre à jour statistiques
+        let mut stride_hit = false;
         if self.config.enable_stride_prediction {
             if let Some(predicted) = predicted_address {
                 self.stats.stride_predictions_total += 1;
                 if predicted == address {
+                    stride_hit = true;
                     self.stats.stride_predictions_correct += 1;
                     println!("AGU: Stride prediction HIT! Predicted=0x{:X}, Actual=0x{:X}", predicted, address);
                 } else {
@@ -342,6 +361,17 @@ impl AGU {
             self.stride_predictor.update(pc, address, self.current_cycle);
         }
         
+        // Latence effective: l'adresse est connue dès le premier cycle si le stride
+        // l'a prédite ou si la base n'a pas changé, sinon il faut attendre la base
+        let latency = if stride_hit || base_cache_hit {
+            self.stats.early_resolutions += 1;
+            AGU_EARLY_LATENCY
+        } else {
+            AGU_FULL_LATENCY
+        };
+        self.stats.total_latency += latency;
+        self.stats.average_latency = self.stats.total_latency as f64 / self.stats.total_calculations as f64;
+
         self.last_address = Some(address);
         Ok(address)
     }
@@ -366,6 +396,15 @@ impl AGU {
         None
     }
     
+    /// Prochaine adresse attendue pour l'accès mémoire à `pc`, seulement si son stride
+    /// est établi avec confiance (à appeler après `calculate_address`)
+    pub fn predict_next_address(&mut self, pc: u64) -> Option<u64> {
+        if !self.config.enable_stride_prediction {
+            return None;
+        }
+        self.stride_predictor.predict(pc, 0)
+    }
+
     /// Addition avec gestion de débordement
     fn add_with_overflow(&self, base: u64, offset: i64) -> u64 {
         if offset >= 0 {
@@ -467,6 +506,31 @@ mod tests {
         let prediction = predictor.predict(pc, 0x1010);
         assert_eq!(prediction, Some(0x1018));
     }
+
+    #[test]
+    fn test_stride_first_observation_is_neither_hit_nor_miss() {
+        let mut predictor = StridePredictor::new(16);
+        predictor.update(0x100, 0x1000, 1);
+
+        assert_eq!((predictor.hits, predictor.misses), (0, 0));
+        assert_eq!(predictor.predict(0x100, 0x1000), None);
+    }
+
+    #[test]
+    fn test_stride_change_is_relearned_after_two_accesses() {
+        let mut predictor = StridePredictor::new(16);
+        let pc = 0x100;
+        for (cycle, addr) in [0x1000u64, 0x1008, 0x1010, 0x1018].into_iter().enumerate() {
+            predictor.update(pc, addr, cycle as u64);
+        }
+        assert_eq!(predictor.predict(pc, 0), Some(0x1020));
+
+        // Nouveau stride (0x40): la confiance retombe puis le nouveau pas est adopté
+        for (cycle, addr) in [0x1058u64, 0x1098, 0x10D8, 0x1118, 0x1158].into_iter().enumerate() {
+            predictor.update(pc, addr, 10 + cycle as u64);
+        }
+        assert_eq!(predictor.predict(pc, 0), Some(0x1198));
+    }
     
     #[test]
     fn test_pc_relative_addressing() {
@@ -544,6 +608,33 @@ mod tests {
         assert_eq!(cache.misses, 2);
     }
     
+    #[test]
+    fn test_average_latency_drops_with_stride_predictions() {
+        let mut agu = AGU::new(AGUConfig::default());
+        let mut registers = vec![0; 16];
+
+        // Base fraîchement incrémentée à chaque accès: le cache de base rate toujours,
+        // seul le prédicteur de stride peut résoudre l'adresse tôt
+        for i in 0..16u64 {
+            registers[5] = 0x1000 + i * 8;
+            agu.calculate_address(
+                AddressingMode::BaseOffset { base: 5, offset: 0 },
+                &registers, 0x40, 0
+            ).unwrap();
+            if i < 2 {
+                assert_eq!(agu.stats.average_latency, AGU_FULL_LATENCY as f64);
+            }
+        }
+
+        assert_eq!(agu.stats.base_cache_hits, 0);
+        assert!(agu.stats.early_resolutions > 0);
+        assert_eq!(agu.stats.early_resolutions, agu.stats.stride_predictions_correct);
+        assert!(agu.stats.average_latency < AGU_FULL_LATENCY as f64);
+        assert!(agu.stats.average_latency >= AGU_EARLY_LATENCY as f64);
+        let expected = agu.stats.total_latency as f64 / agu.stats.total_calculations as f64;
+        assert_eq!(agu.stats.average_latency, expected);
+    }
+
     #[test]
     fn test_stride_predictor_accuracy() {
         let mut predictor = StridePredictor::new(16);
@@ -26,12 +26,22 @@ pub enum ALUOperation {
     Sar, // Shift Arithmetic Right
     Rol, // Rotate Left
     Ror, // Rotate Right
+    Shld(u64), // Shift Left Double: décale a de `count` bits, complété par les bits hauts de b
+    Shrd(u64), // Shift Right Double: décale a de `count` bits, complété par les bits bas de b
     Inc,
     Dec,
     Neg,
     Cmp,  // Compare (comme Sub mais ne stocke pas le résultat)
     Test, // Test (comme And mais ne stocke pas le résultat)
     Mov,  // Copie la valeur
+    Crc32, // Accumule un octet dans un CRC-32C (comme l'instruction x86 crc32)
+    AddSat(u32, bool), // Addition saturée sur `width` bits au lieu de boucler (width, signé)
+    SubSat(u32, bool), // Soustraction saturée sur `width` bits au lieu de boucler (width, signé)
+    Popcnt, // Nombre de bits à 1
+    Clz,    // Zéros de tête (64 pour une valeur nulle)
+    Ctz,    // Zéros de queue (64 pour une valeur nulle)
+    Bfe(u32, u32), // Extrait `width` bits de a à partir de `lsb` (lsb, width)
+    Bfi(u32, u32), // Insère les `width` bits bas de b dans a à partir de `lsb` (lsb, width)
           // Instructions de contrôle de flux
           // Jumps, branches, etc.
           // Jmp,
@@ -39,6 +49,44 @@ pub enum ALUOperation {
           // Ret,
 }
 
+/// Polynôme CRC-32C (Castagnoli) en représentation réfléchie, celui de l'instruction x86 crc32
+const CRC32C_POLY: u32 = 0x82F6_3B78;
+
+/// Replie un octet dans le CRC courant. Comme sur x86, ni l'init ni le XOR final ne sont appliqués:
+/// le programme initialise l'accumulateur à 0xFFFFFFFF et inverse le résultat lui-même.
+fn crc32c_update(crc: u32, byte: u8) -> u32 {
+    let mut crc = crc ^ byte as u32;
+    for _ in 0..8 {
+        let mask = (crc & 1).wrapping_neg();
+        crc = (crc >> 1) ^ (CRC32C_POLY & mask);
+    }
+    crc
+}
+
+/// Masque des `width` bits de poids faible (0 pour une largeur nulle)
+fn bitfield_mask(width: u32) -> u64 {
+    if width >= 64 { u64::MAX } else { (1u64 << width) - 1 }
+}
+
+/// Addition (ou soustraction si `sub`) des `width` bits bas de a et b, bornée à l'intervalle
+/// de la largeur: [0, 2^width - 1] en non signé, [-2^(width-1), 2^(width-1) - 1] en signé.
+/// Un résultat signé est étendu sur 64 bits (comme LoadSD). Retourne (résultat, saturé).
+fn saturating_op(a: u64, b: u64, width: u32, signed: bool, sub: bool) -> (u64, bool) {
+    let mask = bitfield_mask(width);
+    let (a, b, min, max) = if signed {
+        let shift = 128 - width;
+        let extend = |v: u64| ((v as i128) << shift) >> shift;
+        let max = (mask >> 1) as i128;
+        (extend(a), extend(b), -max - 1, max)
+    } else {
+        ((a & mask) as i128, (b & mask) as i128, 0, mask as i128)
+    };
+    let exact = if sub { a - b } else { a + b };
+    let clamped = exact.clamp(min, max);
+    let result = if signed { clamped as i64 as u64 } else { clamped as u64 };
+    (result, clamped != exact)
+}
+
 /// Unité ALU (Arithmetic Logic Unit)
 pub struct ALU {
     /// Flags de l'ALU
@@ -227,27 +275,52 @@ impl ALU {
             }
 
             ALUOperation::Rol => {
-                let shift_amount = (b % 64) as u32;
-                if shift_amount == 0 {
-                    a
-                } else {
-                    let result = a.rotate_left(shift_amount);
-                    self.flags.carry = (result & 1) != 0;
-                    self.flags.overflow = false;
-                    result
-                }
+                // Une rotation de 64 bits est l'identité: on masque le compteur
+                let shift_amount = (b & 63) as u32;
+                let result = a.rotate_left(shift_amount);
+                // Le carry reçoit le dernier bit ayant fait le tour (aucun si rotation nulle)
+                self.flags.carry = shift_amount != 0 && (result & 1) != 0;
+                self.flags.overflow = false;
+                result
             }
 
             ALUOperation::Ror => {
-                let shift_amount = (b % 64) as u32;
-                if shift_amount == 0 {
-                    a
-                } else {
-                    let result = a.rotate_right(shift_amount);
-                    self.flags.carry = ((result >> 63) & 1) != 0;
-                    self.flags.overflow = false;
-                    result
-                }
+                // Une rotation de 64 bits est l'identité: on masque le compteur
+                let shift_amount = (b & 63) as u32;
+                let result = a.rotate_right(shift_amount);
+                // Le carry reçoit le dernier bit ayant fait le tour (aucun si rotation nulle)
+                self.flags.carry = shift_amount != 0 && ((result >> 63) & 1) != 0;
+                self.flags.overflow = false;
+                result
+            }
+
+            ALUOperation::Shld(count) => {
+                // Décalage 128 bits du couple a:b dont on garde le mot haut: au-delà de 64,
+                // a est entièrement remplacé par b; à partir de 128 il ne reste que des zéros
+                let wide = ((a as u128) << 64) | b as u128;
+                let (result, carry) = match count {
+                    0 => (a, false),
+                    1..=127 => (((wide << count) >> 64) as u64, (wide >> (128 - count)) & 1 != 0),
+                    128 => (0, b & 1 != 0),
+                    _ => (0, false),
+                };
+                self.flags.carry = carry;
+                self.flags.overflow = false;
+                result
+            }
+
+            ALUOperation::Shrd(count) => {
+                // Décalage 128 bits du couple b:a dont on garde le mot bas
+                let wide = ((b as u128) << 64) | a as u128;
+                let (result, carry) = match count {
+                    0 => (a, false),
+                    1..=127 => ((wide >> count) as u64, (wide >> (count - 1)) & 1 != 0),
+                    128 => (0, b >> 63 != 0),
+                    _ => (0, false),
+                };
+                self.flags.carry = carry;
+                self.flags.overflow = false;
+                result
             }
 
             ALUOperation::Inc => {
@@ -342,6 +415,58 @@ impl ALU {
                 result // Retourne le résultat, mais il n'est normalement pas utilisé
             }
 
+            ALUOperation::Crc32 => {
+                self.flags.carry = false;
+                self.flags.overflow = false;
+                crc32c_update(a as u32, b as u8) as u64
+            }
+
+            ALUOperation::AddSat(width, signed) => {
+                // Carry et overflow signalent que la saturation a eu lieu
+                let (result, saturated) = saturating_op(a, b, width, signed, false);
+                self.flags.carry = saturated;
+                self.flags.overflow = saturated;
+                result
+            }
+
+            ALUOperation::SubSat(width, signed) => {
+                let (result, saturated) = saturating_op(a, b, width, signed, true);
+                self.flags.carry = saturated;
+                self.flags.overflow = saturated;
+                result
+            }
+
+            ALUOperation::Popcnt => {
+                self.flags.carry = false;
+                self.flags.overflow = false;
+                a.count_ones() as u64
+            }
+
+            ALUOperation::Clz => {
+                self.flags.carry = false;
+                self.flags.overflow = false;
+                a.leading_zeros() as u64
+            }
+
+            ALUOperation::Ctz => {
+                self.flags.carry = false;
+                self.flags.overflow = false;
+                a.trailing_zeros() as u64
+            }
+
+            ALUOperation::Bfe(lsb, width) => {
+                self.flags.carry = false;
+                self.flags.overflow = false;
+                a.checked_shr(lsb).unwrap_or(0) & bitfield_mask(width)
+            }
+
+            ALUOperation::Bfi(lsb, width) => {
+                self.flags.carry = false;
+                self.flags.overflow = false;
+                let mask = bitfield_mask(width).checked_shl(lsb).unwrap_or(0);
+                (a & !mask) | (b.checked_shl(lsb).unwrap_or(0) & mask)
+            }
+
             ALUOperation::Mov => {
                 // Simplement retourne (pas d'impact sur les flags)
                 self.flags.carry = false;
@@ -390,39 +515,36 @@ impl ALU {
                 );
                 result
             }
-            // BranchCondition::Greater => !self.flags.zero && !self.flags.negative,
+            // Comparaisons signées: SF seul est faux en cas de dépassement, on utilise SF XOR OF
             BranchCondition::Greater => {
-                let result = !self.flags.zero && !self.flags.negative;
+                let result = !self.flags.zero && self.flags.negative == self.flags.overflow;
                 println!(
-                    "BranchCondition::Greater: zero={}, negative={}, result={}",
-                    self.flags.zero, self.flags.negative, result
+                    "BranchCondition::Greater: zero={}, negative={}, overflow={}, result={}",
+                    self.flags.zero, self.flags.negative, self.flags.overflow, result
                 );
                 result
             }
-            // BranchCondition::GreaterEqual => !self.flags.negative,
             BranchCondition::GreaterEqual => {
-                let result = !self.flags.negative;
+                let result = self.flags.negative == self.flags.overflow;
                 println!(
-                    "BranchCondition::GreaterEqual: negative={}, result={}",
-                    self.flags.negative, result
+                    "BranchCondition::GreaterEqual: negative={}, overflow={}, result={}",
+                    self.flags.negative, self.flags.overflow, result
                 );
                 result
             }
-            // BranchCondition::Less => self.flags.negative,
             BranchCondition::Less => {
-                let result = self.flags.negative;
+                let result = self.flags.negative != self.flags.overflow;
                 println!(
-                    "BranchCondition::Less: negative={}, result={}",
-                    self.flags.negative, result
+                    "BranchCondition::Less: negative={}, overflow={}, result={}",
+                    self.flags.negative, self.flags.overflow, result
                 );
                 result
             }
-            // BranchCondition::LessEqual => self.flags.zero || self.flags.negative,
             BranchCondition::LessEqual => {
-                let result = self.flags.zero || self.flags.negative;
+                let result = self.flags.zero || self.flags.negative != self.flags.overflow;
                 println!(
-                    "BranchCondition::LessEqual: zero={}, negative={}, result={}",
-                    self.flags.zero, self.flags.negative, result
+                    "BranchCondition::LessEqual: zero={}, negative={}, overflow={}, result={}",
+                    self.flags.zero, self.flags.negative, self.flags.overflow, result
                 );
                 result
             }
@@ -531,10 +653,10 @@ pub enum BranchCondition {
     Always,       // Toujours pris
     Equal,        // ZF = 1
     NotEqual,     // ZF = 0
-    Greater,      // ZF = 0 et SF = 0
-    GreaterEqual, // SF = 0
-    Less,         // SF = 1
-    LessEqual,    // ZF = 1 ou SF = 1
+    Greater,      // ZF = 0 et SF = OF (signé)
+    GreaterEqual, // SF = OF (signé)
+    Less,         // SF != OF (signé)
+    LessEqual,    // ZF = 1 ou SF != OF (signé)
     Above,        // CF = 0 et ZF = 0 (non signé)
     AboveEqual,   // CF = 0 (non signé)
     Below,        // CF = 1 (non signé)
@@ -771,6 +893,171 @@ mod tests {
         assert_eq!(result, 0x8000000000000000u64);
     }
 
+    #[test]
+    fn test_rotate_wraparound() {
+        let mut alu = ALU::new();
+        let value = 0x8000_0000_0000_0001u64;
+
+        // Rotation nulle: valeur inchangée, carry effacé
+        alu.flags.carry = true;
+        assert_eq!(alu.execute(ALUOperation::Rol, value, 0).unwrap(), value);
+        assert!(!alu.flags.carry);
+        assert_eq!(alu.execute(ALUOperation::Ror, value, 0).unwrap(), value);
+        assert!(!alu.flags.carry);
+
+        // Rotation de 64 (et plus) masquée sur 6 bits
+        assert_eq!(alu.execute(ALUOperation::Rol, value, 64).unwrap(), value);
+        assert_eq!(alu.execute(ALUOperation::Ror, value, 64).unwrap(), value);
+        assert_eq!(alu.execute(ALUOperation::Rol, value, 68).unwrap(), value.rotate_left(4));
+
+        // Rotation normale
+        assert_eq!(alu.execute(ALUOperation::Rol, value, 4).unwrap(), 0x18);
+        assert!(!alu.flags.carry);
+        assert!(!alu.flags.zero);
+        assert_eq!(alu.execute(ALUOperation::Ror, value, 4).unwrap(), 0x1800_0000_0000_0000);
+        assert!(!alu.flags.carry);
+        assert_eq!(alu.execute(ALUOperation::Ror, value, 1).unwrap(), 0xC000_0000_0000_0000);
+        assert!(alu.flags.carry);
+        assert!(alu.flags.negative);
+    }
+
+    #[test]
+    fn test_saturating_add_sub() {
+        let mut alu = ALU::new();
+
+        // Borne haute: Add boucle avec carry, AddSat reste à u64::MAX
+        assert_eq!(alu.execute(ALUOperation::Add, u64::MAX, 1).unwrap(), 0);
+        assert!(alu.flags.carry);
+        assert_eq!(alu.execute(ALUOperation::AddSat(64, false), u64::MAX, 1).unwrap(), u64::MAX);
+        assert!(alu.flags.carry);
+        assert!(alu.flags.overflow);
+        assert!(!alu.flags.zero);
+
+        // Borne basse: SubSat reste à 0
+        assert_eq!(alu.execute(ALUOperation::SubSat(64, false), 3, 5).unwrap(), 0);
+        assert!(alu.flags.carry);
+        assert!(alu.flags.overflow);
+        assert!(alu.flags.zero);
+
+        // Sans saturation, même résultat que les opérations ordinaires et flags baissés
+        let plain = alu.execute(ALUOperation::Add, u32::MAX as u64, 1).unwrap();
+        assert_eq!(alu.execute(ALUOperation::AddSat(64, false), u32::MAX as u64, 1).unwrap(), plain);
+        assert!(!alu.flags.carry);
+        assert!(!alu.flags.overflow);
+        let plain = alu.execute(ALUOperation::Sub, 10, 4).unwrap();
+        assert_eq!(alu.execute(ALUOperation::SubSat(64, false), 10, 4).unwrap(), plain);
+        assert!(!alu.flags.carry);
+        assert!(!alu.flags.overflow);
+    }
+
+    #[test]
+    fn test_saturating_widths_and_signed() {
+        let mut alu = ALU::new();
+
+        // 32 bits non signé: u32::MAX + 1 reste à u32::MAX au lieu de passer à 2^32
+        assert_eq!(alu.execute(ALUOperation::AddSat(32, false), u32::MAX as u64, 1).unwrap(), u32::MAX as u64);
+        assert!(alu.flags.carry);
+        assert!(alu.flags.overflow);
+        assert_eq!(alu.execute(ALUOperation::SubSat(32, false), 3, 5).unwrap(), 0);
+        assert!(alu.flags.carry);
+        assert_eq!(alu.execute(ALUOperation::AddSat(32, false), 40, 2).unwrap(), 42);
+        assert!(!alu.flags.carry);
+        assert!(!alu.flags.overflow);
+
+        // 64 bits signé: bornes i64::MAX et i64::MIN
+        let max = i64::MAX as u64;
+        let min = i64::MIN as u64;
+        assert_eq!(alu.execute(ALUOperation::AddSat(64, true), max, 1).unwrap(), max);
+        assert!(alu.flags.overflow);
+        assert_eq!(alu.execute(ALUOperation::SubSat(64, true), min, 1).unwrap(), min);
+        assert!(alu.flags.overflow);
+        assert!(alu.flags.negative);
+        // -1 + 1 = 0 sans saturation (en non signé, u64::MAX + 1 saturerait)
+        assert_eq!(alu.execute(ALUOperation::AddSat(64, true), u64::MAX, 1).unwrap(), 0);
+        assert!(!alu.flags.overflow);
+        assert!(alu.flags.zero);
+
+        // 32 bits signé: résultat étendu sur 64 bits
+        let i32_min = i32::MIN as i64 as u64;
+        assert_eq!(alu.execute(ALUOperation::AddSat(32, true), i32::MAX as u64, 1).unwrap(), i32::MAX as u64);
+        assert!(alu.flags.overflow);
+        assert_eq!(alu.execute(ALUOperation::SubSat(32, true), i32_min, 1).unwrap(), i32_min);
+        assert!(alu.flags.overflow);
+        assert_eq!(alu.execute(ALUOperation::SubSat(32, true), 5, 7).unwrap(), -2i64 as u64);
+        assert!(!alu.flags.overflow);
+    }
+
+    #[test]
+    fn test_crc32c_check_value() {
+        let mut alu = ALU::new();
+        let mut crc = 0xFFFF_FFFFu64;
+        for &byte in b"123456789" {
+            crc = alu.execute(ALUOperation::Crc32, crc, byte as u64).unwrap();
+        }
+        assert_eq!(crc ^ 0xFFFF_FFFF, 0xE306_9283);
+        // Seul l'octet de poids faible de la donnée est replié
+        assert_eq!(
+            alu.execute(ALUOperation::Crc32, 0, 0xAB00).unwrap(),
+            alu.execute(ALUOperation::Crc32, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_bit_counts() {
+        let mut alu = ALU::new();
+        let cases = [
+            (ALUOperation::Popcnt, [0, 1, 8]),
+            (ALUOperation::Clz, [64, 63, 48]),
+            (ALUOperation::Ctz, [64, 0, 8]),
+        ];
+        for (operation, expected) in cases {
+            for (input, want) in [0u64, 1, 0xFF00].into_iter().zip(expected) {
+                assert_eq!(alu.execute(operation, input, 0).unwrap(), want, "{:?}({:#X})", operation, input);
+                assert_eq!(alu.flags.zero, want == 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bitfield_extract_insert() {
+        let mut alu = ALU::new();
+        assert_eq!(alu.execute(ALUOperation::Bfe(8, 8), 0xABCD, 0).unwrap(), 0xAB);
+        assert_eq!(alu.execute(ALUOperation::Bfe(0, 64), u64::MAX, 0).unwrap(), u64::MAX);
+        // Largeur nulle: résultat nul
+        assert_eq!(alu.execute(ALUOperation::Bfe(4, 0), 0xABCD, 0).unwrap(), 0);
+        assert!(alu.flags.zero);
+
+        assert_eq!(alu.execute(ALUOperation::Bfi(8, 8), 0x00CD, 0xAB).unwrap(), 0xABCD);
+        // Seuls les `width` bits bas de la source sont insérés
+        assert_eq!(alu.execute(ALUOperation::Bfi(4, 4), 0xFFFF, 0x1230).unwrap(), 0xFF0F);
+        assert_eq!(alu.execute(ALUOperation::Bfi(8, 0), 0x1234, 0xFF).unwrap(), 0x1234);
+    }
+
+    #[test]
+    fn test_signed_conditions_use_sign_xor_overflow() {
+        let mut alu = ALU::new();
+
+        // i64::MIN - 1 déborde: le résultat est positif (SF = 0) mais OF = 1
+        alu.execute(ALUOperation::Cmp, 1u64 << 63, 1).unwrap();
+        assert!(!alu.flags.negative);
+        assert!(alu.flags.overflow);
+        assert!(alu.check_condition(BranchCondition::Less));
+        assert!(alu.check_condition(BranchCondition::LessEqual));
+        assert!(!alu.check_condition(BranchCondition::Greater));
+        assert!(!alu.check_condition(BranchCondition::GreaterEqual));
+        // En non signé, 0x8000... est au-dessus de 1
+        assert!(alu.check_condition(BranchCondition::Above));
+
+        // 1 comparé à -1: supérieur en signé, inférieur en non signé
+        alu.execute(ALUOperation::Cmp, 1, u64::MAX).unwrap();
+        assert!(alu.check_condition(BranchCondition::Greater));
+        assert!(!alu.check_condition(BranchCondition::Less));
+        assert!(alu.check_condition(BranchCondition::Below));
+        assert!(alu.check_condition(BranchCondition::BelowEqual));
+        assert!(!alu.check_condition(BranchCondition::Above));
+        assert!(!alu.check_condition(BranchCondition::AboveEqual));
+    }
+
     #[test]
     fn test_division_by_zero() {
         let mut alu = ALU::new();
@@ -1,5 +1,7 @@
 //src/alu/v_alu.rs
 
+use std::collections::VecDeque;
+
 use crate::bytecode::simds::{Vector128, Vector256, VectorDataType, Vector256DataType};
 use crate::pvm::vm_errors::{VMResult, VMError};
 
@@ -18,17 +20,20 @@ pub struct VectorALU {
     pub simd_stats: SimdPerformanceStats,
 }
 
+/// Entrée du cache d'opérations: opération, type, octets des deux opérandes et résultat
+pub type CachedOperation128 = (VectorOperation, VectorDataType, [u8; 16], [u8; 16], Vector128);
+/// Entrée du cache d'opérations 256-bit, même clé que la version 128-bit
+pub type CachedOperation256 = (VectorOperation, Vector256DataType, [u8; 32], [u8; 32], Vector256);
+
 /// Cache pour optimiser les opérations vectorielles répétitives
 #[derive(Debug, Clone)]
 pub struct VectorOperationCache {
-    /// Dernière opération 128-bit effectuée
-    pub last_128_op: Option<(VectorOperation, u8, u8, VectorDataType)>,
-    /// Résultat de la dernière opération 128-bit
-    pub last_128_result: Option<Vector128>,
-    /// Dernière opération 256-bit effectuée
-    pub last_256_op: Option<(VectorOperation, u8, u8, Vector256DataType)>,
-    /// Résultat de la dernière opération 256-bit
-    pub last_256_result: Option<Vector256>,
+    /// Opérations 128-bit récentes, de la moins récemment utilisée à la plus récente
+    pub entries_128: VecDeque<CachedOperation128>,
+    /// Opérations 256-bit récentes, de la moins récemment utilisée à la plus récente
+    pub entries_256: VecDeque<CachedOperation256>,
+    /// Nombre maximal d'opérations mémorisées par largeur (0 = cache désactivé)
+    pub capacity: usize,
     /// Compteur de hits du cache
     pub cache_hits: u64,
     /// Compteur de misses du cache
@@ -50,32 +55,79 @@ pub struct SimdPerformanceStats {
     pub parallel_ops: u64,
 }
 
+/// Taille par défaut du cache d'opérations SIMD: seule la dernière opération est retenue
+pub const DEFAULT_SIMD_OP_CACHE_SIZE: usize = 1;
+
 impl VectorOperationCache {
     pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_SIMD_OP_CACHE_SIZE)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
         Self {
-            last_128_op: None,
-            last_128_result: None,
-            last_256_op: None,
-            last_256_result: None,
+            entries_128: VecDeque::with_capacity(capacity),
+            entries_256: VecDeque::with_capacity(capacity),
+            capacity,
             cache_hits: 0,
             cache_misses: 0,
         }
     }
     
-    /// Vérifie si l'opération 128-bit est en cache
-    pub fn check_128_cache(&self, op: VectorOperation, src1: u8, src2: u8, data_type: VectorDataType) -> Option<Vector128> {
-        if let Some((cached_op, cached_src1, cached_src2, cached_type)) = &self.last_128_op {
-            if *cached_op == op && *cached_src1 == src1 && *cached_src2 == src2 && *cached_type == data_type {
-                return self.last_128_result;
-            }
-        }
-        None
+    /// Vérifie si l'opération 128-bit est en cache. La clé porte sur les valeurs des
+    /// opérandes, pas sur les registres: une entrée ne devient jamais périmée.
+    pub fn check_128_cache(&mut self, op: VectorOperation, a: &[u8; 16], b: &[u8; 16], data_type: VectorDataType) -> Option<Vector128> {
+        let position = self.entries_128.iter().position(|(cached_op, cached_type, cached_a, cached_b, _)| {
+            *cached_op == op && *cached_type == data_type && cached_a == a && cached_b == b
+        })?;
+        // L'entrée devient la plus récemment utilisée
+        let entry = self.entries_128.remove(position)?;
+        let result = entry.4;
+        self.entries_128.push_back(entry);
+        Some(result)
     }
     
-    /// Met en cache le résultat d'une opération 128-bit
-    pub fn cache_128_result(&mut self, op: VectorOperation, src1: u8, src2: u8, data_type: VectorDataType, result: Vector128) {
-        self.last_128_op = Some((op, src1, src2, data_type));
-        self.last_128_result = Some(result);
+    /// Met en cache le résultat d'une opération 128-bit, en évinçant la moins récente si plein
+    pub fn cache_128_result(&mut self, op: VectorOperation, a: [u8; 16], b: [u8; 16], data_type: VectorDataType, result: Vector128) {
+        if self.capacity == 0 {
+            return;
+        }
+        while self.entries_128.len() >= self.capacity {
+            self.entries_128.pop_front();
+        }
+        self.entries_128.push_back((op, data_type, a, b, result));
+    }
+
+    /// Vérifie si l'opération 256-bit est en cache, par valeur des opérandes
+    pub fn check_256_cache(&mut self, op: VectorOperation, a: &[u8; 32], b: &[u8; 32], data_type: Vector256DataType) -> Option<Vector256> {
+        let position = self.entries_256.iter().position(|(cached_op, cached_type, cached_a, cached_b, _)| {
+            *cached_op == op && *cached_type == data_type && cached_a == a && cached_b == b
+        })?;
+        let entry = self.entries_256.remove(position)?;
+        let result = entry.4;
+        self.entries_256.push_back(entry);
+        Some(result)
+    }
+
+    /// Met en cache le résultat d'une opération 256-bit, en évinçant la moins récente si plein
+    pub fn cache_256_result(&mut self, op: VectorOperation, a: [u8; 32], b: [u8; 32], data_type: Vector256DataType, result: Vector256) {
+        if self.capacity == 0 {
+            return;
+        }
+        while self.entries_256.len() >= self.capacity {
+            self.entries_256.pop_front();
+        }
+        self.entries_256.push_back((op, data_type, a, b, result));
+    }
+
+    /// Change la capacité; les entrées les plus anciennes en excès sont évincées
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.entries_128.len() > capacity {
+            self.entries_128.pop_front();
+        }
+        while self.entries_256.len() > capacity {
+            self.entries_256.pop_front();
+        }
     }
     
     /// Statistiques du cache
@@ -106,6 +158,13 @@ pub struct VectorFlags {
     pub invalid: bool,
 }
 
+/// Borne d'erreur relative des approximations Rcp/Rsqrt (1.5 * 2^-12),
+/// identique à celle garantie par les instructions matérielles RCPPS/RSQRTPS.
+pub const SIMD_APPROX_REL_ERROR: f32 = 1.5 / 4096.0;
+
+/// Nombre de bits de mantisse conservés par les approximations Rcp/Rsqrt
+const SIMD_APPROX_MANTISSA_BITS: u32 = 12;
+
 /// Types d'operations vectorielles
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum VectorOperation {
@@ -120,11 +179,23 @@ pub enum VectorOperation {
     Min,
     Max,
     Sqrt,
+    /// Réciproque approchée 1/x (erreur relative <= SIMD_APPROX_REL_ERROR)
+    Rcp,
+    /// Réciproque approchée 1/sqrt(x) (erreur relative <= SIMD_APPROX_REL_ERROR)
+    Rsqrt,
     Cmp,
     Shuffle,
 
 }
 
+/// Réductions horizontales (toutes les lanes vers un scalaire)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VectorReduction {
+    Sum,
+    Min,
+    Max,
+}
+
 /// Resultats d'operations vectorielles
 #[derive(Debug, Clone, PartialEq)]
 pub enum VectorResult {
@@ -204,9 +275,13 @@ impl VectorALU {
         src2: Option<u8>,
         data_type: VectorDataType,
     ) -> VMResult<()> {
-        // Optimisation SIMD : Vérifier le cache d'abord
-        let src2_reg = src2.unwrap_or(0);
-        if let Some(cached_result) = self.operation_cache.check_128_cache(op, src1, src2_reg, data_type) {
+        // Optimisation SIMD : Vérifier le cache d'abord (opérande absent = zéro)
+        let key_a = *self.read_v128(src1)?.as_bytes();
+        let key_b = match src2 {
+            Some(reg) => *self.read_v128(reg)?.as_bytes(),
+            None => [0; 16],
+        };
+        if let Some(cached_result) = self.operation_cache.check_128_cache(op, &key_a, &key_b, data_type) {
             self.operation_cache.cache_hits += 1;
             self.write_v128(dst, cached_result)?;
             println!("SIMD Cache Hit: V{} = cached result", dst);
@@ -258,6 +333,8 @@ impl VectorALU {
                 self.max_v128(&vec1, &vec2, data_type)?
             }
             VectorOperation::Sqrt => self.sqrt_v128(&vec1, data_type)?,
+            VectorOperation::Rcp => self.rcp_v128(&vec1, data_type)?,
+            VectorOperation::Rsqrt => self.rsqrt_v128(&vec1, data_type)?,
             VectorOperation::Cmp => {
                 let vec2 = self.read_v128(src2.ok_or(VMError::instruction_error("Invalid SIMD instruction"))?)?;
                 self.cmp_v128(&vec1, &vec2, data_type)?
@@ -270,11 +347,113 @@ impl VectorALU {
         };
 
         // Mettre en cache le résultat pour les futurs accès
-        self.operation_cache.cache_128_result(op, src1, src2_reg, data_type, result);
+        self.operation_cache.cache_128_result(op, key_a, key_b, data_type, result);
         
         self.write_v128(dst, result)
     }
 
+    /// Multiplication-addition fusionnée 128-bit : dst = a * b + c, avec un seul arrondi par lane.
+    /// Compte pour une seule opération SIMD; ne passe pas par le cache d'opérations, dont la
+    /// clé ne couvre que deux sources, et n'en modifie donc pas les compteurs.
+    pub fn fma_v128(
+        &mut self,
+        dst: u8,
+        src_a: u8,
+        src_b: u8,
+        src_c: u8,
+        data_type: VectorDataType,
+    ) -> VMResult<()> {
+        let a = self.read_v128(src_a)?;
+        let b = self.read_v128(src_b)?;
+        let c = self.read_v128(src_c)?;
+
+        let result = match data_type {
+            VectorDataType::F32x4 => {
+                let (a, b, c) = unsafe { (a.f32x4, b.f32x4, c.f32x4) };
+                Vector128::from_f32x4(std::array::from_fn(|i| a[i].mul_add(b[i], c[i])))
+            }
+            VectorDataType::F64x2 => {
+                let (a, b, c) = unsafe { (a.f64x2, b.f64x2, c.f64x2) };
+                Vector128::from_f64x2(std::array::from_fn(|i| a[i].mul_add(b[i], c[i])))
+            }
+            _ => return Err(VMError::instruction_error("Invalid SIMD instruction")), // FMA flottante uniquement
+        };
+
+        self.simd_stats.simd128_ops += 1;
+        self.write_v128(dst, result)
+    }
+
+    /// Sélection par lane 128-bit : dst[i] = a[i] si le bit de signe de mask[i] (lane 32 bits) est levé,
+    /// b[i] sinon. Un masque tout-à-un/tout-à-zéro issu de `Cmp` convient quelle que soit la largeur des lanes.
+    pub fn blend_v128(&mut self, dst: u8, src_a: u8, src_b: u8, mask: u8) -> VMResult<()> {
+        let a = unsafe { self.read_v128(src_a)?.i32x4 };
+        let b = unsafe { self.read_v128(src_b)?.i32x4 };
+        let mask = unsafe { self.read_v128(mask)?.i32x4 };
+
+        let result = std::array::from_fn(|i| if mask[i] < 0 { a[i] } else { b[i] });
+        self.simd_stats.simd128_ops += 1;
+        self.write_v128(dst, Vector128::from_i32x4(result))
+    }
+
+    /// Réduit les lanes d'un registre V128 en un scalaire 64 bits
+    ///
+    /// - I32x4 : somme en arithmétique modulaire 32 bits, résultat étendu en signe
+    /// - F32x4 : somme dans l'ordre des lanes, min/max ignorant les NaN
+    ///   (NaN seulement si toutes les lanes le sont), bits f32 dans les 32 bits de poids faible
+    pub fn reduce_v128(
+        &mut self,
+        op: VectorReduction,
+        src: u8,
+        data_type: VectorDataType,
+    ) -> VMResult<u64> {
+        let vec = self.read_v128(src)?;
+        self.simd_stats.simd128_ops += 1;
+
+        match data_type {
+            VectorDataType::I32x4 => {
+                let lanes = unsafe { vec.i32x4 };
+                let value = match op {
+                    VectorReduction::Sum => lanes.iter().fold(0i32, |acc, &x| acc.wrapping_add(x)),
+                    VectorReduction::Min => lanes.iter().copied().min().unwrap_or(0),
+                    VectorReduction::Max => lanes.iter().copied().max().unwrap_or(0),
+                };
+                Ok(value as i64 as u64)
+            }
+            VectorDataType::F32x4 => {
+                let lanes = unsafe { vec.f32x4 };
+                let value = match op {
+                    VectorReduction::Sum => lanes.iter().fold(0.0f32, |acc, &x| acc + x),
+                    VectorReduction::Min => lanes.iter().copied().fold(f32::NAN, f32::min),
+                    VectorReduction::Max => lanes.iter().copied().fold(f32::NAN, f32::max),
+                };
+                Ok(value.to_bits() as u64)
+            }
+            _ => Err(VMError::instruction_error("Invalid SIMD instruction")), // Réductions i32x4/f32x4 uniquement
+        }
+    }
+
+    /// Lit la lane i32 `lane` (0..4) de `src`, étendue en signe sur 64 bits
+    pub fn extract_lane_v128(&mut self, src: u8, lane: u8) -> VMResult<u64> {
+        if lane >= 4 {
+            return Err(VMError::instruction_error(&format!("Invalid V128 lane: {}", lane)));
+        }
+        let vec = self.read_v128(src)?;
+        self.simd_stats.simd128_ops += 1;
+        Ok(unsafe { vec.i32x4 }[lane as usize] as i64 as u64)
+    }
+
+    /// Remplace la lane i32 `lane` (0..4) de `dst` par les 32 bits de poids faible de `value`,
+    /// les autres lanes sont conservées
+    pub fn insert_lane_v128(&mut self, dst: u8, lane: u8, value: u64) -> VMResult<()> {
+        if lane >= 4 {
+            return Err(VMError::instruction_error(&format!("Invalid V128 lane: {}", lane)));
+        }
+        let mut lanes = unsafe { self.read_v128(dst)?.i32x4 };
+        lanes[lane as usize] = value as u32 as i32;
+        self.simd_stats.simd128_ops += 1;
+        self.write_v128(dst, Vector128 { i32x4: lanes })
+    }
+
     /// Execute une operation vectorielle 256-bit
     pub fn execute_v256(
         &mut self,
@@ -284,6 +463,22 @@ impl VectorALU {
         src2: Option<u8>,
         data_type: Vector256DataType,
     ) -> VMResult<()> {
+        // Même cache que la version 128-bit, indexé par la valeur des opérandes
+        let key_a = *self.read_v256(src1)?.as_bytes();
+        let key_b = match src2 {
+            Some(reg) => *self.read_v256(reg)?.as_bytes(),
+            None => [0; 32],
+        };
+        if let Some(cached_result) = self.operation_cache.check_256_cache(op, &key_a, &key_b, data_type) {
+            self.operation_cache.cache_hits += 1;
+            self.write_v256(dst, cached_result)?;
+            println!("SIMD Cache Hit: Y{} = cached result", dst);
+            return Ok(());
+        }
+
+        self.operation_cache.cache_misses += 1;
+        self.simd_stats.simd256_ops += 1;
+
         let vec1 = self.read_v256(src1)?;
         
         let result = match op {
@@ -328,6 +523,8 @@ impl VectorALU {
                 self.max_v256(&vec1, &vec2, data_type)?
             }
             VectorOperation::Sqrt => self.sqrt_v256(&vec1, data_type)?,
+            VectorOperation::Rcp => self.rcp_v256(&vec1, data_type)?,
+            VectorOperation::Rsqrt => self.rsqrt_v256(&vec1, data_type)?,
             VectorOperation::Cmp => {
                 let vec2 = self.read_v256(src2.ok_or(VMError::instruction_error("Invalid SIMD instruction"))?)?;
                 self.cmp_v256(&vec1, &vec2, data_type)?
@@ -338,6 +535,8 @@ impl VectorALU {
             }
         };
 
+        self.operation_cache.cache_256_result(op, key_a, key_b, data_type, result);
+
         self.write_v256(dst, result)
     }
 
@@ -694,6 +893,28 @@ impl VectorALU {
         }
     }
 
+    /// Réciproque approchée vectorielle 128-bit (f32x4 uniquement)
+    fn rcp_v128(&self, a: &Vector128, data_type: VectorDataType) -> VMResult<Vector128> {
+        match data_type {
+            VectorDataType::F32x4 => {
+                let a_vals = unsafe { a.f32x4 };
+                Ok(Vector128::from_f32x4(a_vals.map(|x| approx_f32(1.0 / x))))
+            }
+            _ => Err(VMError::instruction_error("Invalid SIMD instruction")), // Rcp uniquement pour f32
+        }
+    }
+
+    /// Réciproque approchée de la racine carrée vectorielle 128-bit (f32x4 uniquement)
+    fn rsqrt_v128(&self, a: &Vector128, data_type: VectorDataType) -> VMResult<Vector128> {
+        match data_type {
+            VectorDataType::F32x4 => {
+                let a_vals = unsafe { a.f32x4 };
+                Ok(Vector128::from_f32x4(a_vals.map(|x| approx_f32(1.0 / x.sqrt()))))
+            }
+            _ => Err(VMError::instruction_error("Invalid SIMD instruction")), // Rsqrt uniquement pour f32
+        }
+    }
+
     /// Comparaison vectorielle 128-bit (retourne masque de bits)
     fn cmp_v128(&self, a: &Vector128, b: &Vector128, data_type: VectorDataType) -> VMResult<Vector128> {
         match data_type {
@@ -1163,6 +1384,28 @@ impl VectorALU {
         }
     }
 
+    /// Réciproque approchée vectorielle 256-bit (f32x8 uniquement)
+    fn rcp_v256(&self, a: &Vector256, data_type: Vector256DataType) -> VMResult<Vector256> {
+        match data_type {
+            Vector256DataType::F32x8 => {
+                let a_vals = unsafe { a.f32x8 };
+                Ok(Vector256::from_f32x8(a_vals.map(|x| approx_f32(1.0 / x))))
+            }
+            _ => Err(VMError::instruction_error("Invalid SIMD instruction")),
+        }
+    }
+
+    /// Réciproque approchée de la racine carrée vectorielle 256-bit (f32x8 uniquement)
+    fn rsqrt_v256(&self, a: &Vector256, data_type: Vector256DataType) -> VMResult<Vector256> {
+        match data_type {
+            Vector256DataType::F32x8 => {
+                let a_vals = unsafe { a.f32x8 };
+                Ok(Vector256::from_f32x8(a_vals.map(|x| approx_f32(1.0 / x.sqrt()))))
+            }
+            _ => Err(VMError::instruction_error("Invalid SIMD instruction")),
+        }
+    }
+
     /// Comparaison vectorielle 256-bit
     fn cmp_v256(&self, a: &Vector256, b: &Vector256, data_type: Vector256DataType) -> VMResult<Vector256> {
         match data_type {
@@ -1279,6 +1522,11 @@ impl VectorALU {
         &self.simd_stats
     }
     
+    /// Fixe le nombre d'opérations retenues par largeur dans le cache d'opérations
+    pub fn set_operation_cache_size(&mut self, size: usize) {
+        self.operation_cache.set_capacity(size);
+    }
+
     /// Retourne les statistiques du cache d'opérations
     pub fn get_cache_stats(&self) -> (u64, u64, f64) {
         (
@@ -1318,6 +1566,19 @@ impl VectorALU {
     }
 }
 
+/// Réduit la mantisse d'un f32 à SIMD_APPROX_MANTISSA_BITS bits (arrondi au plus proche),
+/// ce qui reproduit la précision des approximations matérielles.
+/// Les valeurs spéciales (0, infini, NaN) sont conservées telles quelles.
+fn approx_f32(exact: f32) -> f32 {
+    if !exact.is_finite() || exact == 0.0 {
+        return exact;
+    }
+    let drop = 23 - SIMD_APPROX_MANTISSA_BITS;
+    let bits = exact.to_bits();
+    let rounded = (bits + (1 << (drop - 1))) & !((1 << drop) - 1);
+    f32::from_bits(rounded)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1446,4 +1707,55 @@ mod tests {
         let result = alu.execute_v128(VectorOperation::Div, 2, 0, Some(1), VectorDataType::I32x4);
         assert!(result.is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_v128_rsqrt_approximation() {
+        let mut alu = VectorALU::new();
+        alu.write_v128(0, Vector128::from_f32x4([4.0, 16.0, 64.0, 256.0])).unwrap();
+
+        alu.execute_v128(VectorOperation::Rsqrt, 1, 0, None, VectorDataType::F32x4).unwrap();
+
+        let result = unsafe { alu.read_v128(1).unwrap().f32x4 };
+        let expected = [0.5, 0.25, 0.125, 0.0625];
+        for (r, e) in result.iter().zip(expected.iter()) {
+            assert!(((r - e) / e).abs() <= SIMD_APPROX_REL_ERROR, "{} vs {}", r, e);
+        }
+        assert_eq!(alu.get_simd_stats().simd128_ops, 1);
+    }
+
+    #[test]
+    fn test_v128_rcp_approximation() {
+        let mut alu = VectorALU::new();
+        alu.write_v128(0, Vector128::from_f32x4([3.0, -7.0, 0.1, 1000.0])).unwrap();
+
+        alu.execute_v128(VectorOperation::Rcp, 1, 0, None, VectorDataType::F32x4).unwrap();
+
+        let result = unsafe { alu.read_v128(1).unwrap().f32x4 };
+        for (r, x) in result.iter().zip([3.0f32, -7.0, 0.1, 1000.0].iter()) {
+            let e = 1.0 / x;
+            assert!(((r - e) / e).abs() <= SIMD_APPROX_REL_ERROR, "{} vs {}", r, e);
+        }
+
+        // Rcp n'est défini que pour les flottants
+        assert!(alu.execute_v128(VectorOperation::Rcp, 2, 0, None, VectorDataType::I32x4).is_err());
+    }
+
+    #[test]
+    fn test_v128_horizontal_reductions() {
+        let mut alu = VectorALU::new();
+        alu.write_v128(0, Vector128::from_i32x4([7, -3, 12, 5])).unwrap();
+        alu.write_v128(1, Vector128::from_f32x4([1.5, f32::NAN, -2.0, 4.0])).unwrap();
+
+        assert_eq!(alu.reduce_v128(VectorReduction::Sum, 0, VectorDataType::I32x4).unwrap() as i64, 21);
+        assert_eq!(alu.reduce_v128(VectorReduction::Min, 0, VectorDataType::I32x4).unwrap() as i64, -3);
+        assert_eq!(alu.reduce_v128(VectorReduction::Max, 0, VectorDataType::I32x4).unwrap() as i64, 12);
+
+        let as_f32 = |bits: u64| f32::from_bits(bits as u32);
+        let min = alu.reduce_v128(VectorReduction::Min, 1, VectorDataType::F32x4).unwrap();
+        let max = alu.reduce_v128(VectorReduction::Max, 1, VectorDataType::F32x4).unwrap();
+        let sum = alu.reduce_v128(VectorReduction::Sum, 1, VectorDataType::F32x4).unwrap();
+        assert_eq!(as_f32(min), -2.0);
+        assert_eq!(as_f32(max), 4.0);
+        assert!(as_f32(sum).is_nan());
+    }
+}
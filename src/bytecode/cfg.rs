@@ -0,0 +1,202 @@
+//src/bytecode/cfg.rs
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+
+use crate::bytecode::instructions::{ArgValue, Instruction};
+use crate::bytecode::opcodes::Opcode;
+
+/// Bloc de base: instructions contiguës, seule la dernière peut changer de flot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BasicBlock {
+    /// Adresse de la première instruction
+    pub start: u32,
+    /// Adresse de la dernière instruction
+    pub last: u32,
+    /// Adresses de début des blocs successeurs connus statiquement
+    pub successors: Vec<u32>,
+}
+
+/// Cible d'un branchement ou d'un appel situé à `pc` (None pour RET et les non-branchements)
+pub fn branch_target(instruction: &Instruction, pc: u32) -> Option<u32> {
+    if !instruction.opcode.is_branch() {
+        return None;
+    }
+    match instruction.get_arg2_value() {
+        Ok(ArgValue::RelativeAddr(offset)) => {
+            let next_pc = pc + instruction.total_size() as u32;
+            Some((next_pc as i32 + offset) as u32)
+        }
+        Ok(ArgValue::AbsoluteAddr(addr)) => Some(addr as u32),
+        _ => None,
+    }
+}
+
+/// Découpe `code`, chargé à l'adresse `code_base`, en blocs de base. Un bloc commence au
+/// début du code, à chaque cible de branchement et après chaque branchement ou HALT. Les
+/// successeurs d'un CALL sont l'appelé et l'instruction suivante (point de retour); RET et
+/// HALT n'en ont pas.
+pub fn build_cfg(code: &[Instruction], code_base: u32) -> Vec<BasicBlock> {
+    let mut addresses = Vec::with_capacity(code.len());
+    let mut addr = code_base;
+    for instruction in code {
+        addresses.push(addr);
+        addr += instruction.total_size() as u32;
+    }
+    let code_end = addr;
+
+    let mut leaders = BTreeSet::new();
+    if !code.is_empty() {
+        leaders.insert(code_base);
+    }
+    for (instruction, &pc) in code.iter().zip(&addresses) {
+        let next_pc = pc + instruction.total_size() as u32;
+        if instruction.opcode.is_branch() || instruction.opcode == Opcode::Halt {
+            leaders.insert(next_pc);
+        }
+        if let Some(target) = branch_target(instruction, pc) {
+            leaders.insert(target);
+        }
+    }
+    // Seules les frontières d'instruction réelles délimitent des blocs
+    leaders.retain(|leader| addresses.binary_search(leader).is_ok());
+
+    let mut blocks: Vec<BasicBlock> = Vec::new();
+    for (index, (instruction, &pc)) in code.iter().zip(&addresses).enumerate() {
+        if leaders.contains(&pc) {
+            blocks.push(BasicBlock { start: pc, last: pc, successors: Vec::new() });
+        }
+        let block = blocks.last_mut().expect("le début du code est toujours un début de bloc");
+        block.last = pc;
+
+        let next_pc = pc + instruction.total_size() as u32;
+        let ends_block = index + 1 == code.len() || leaders.contains(&next_pc);
+        if !ends_block {
+            continue;
+        }
+        let target = branch_target(instruction, pc);
+        let falls_through = match instruction.opcode {
+            Opcode::Halt | Opcode::Ret | Opcode::Jmp => false,
+            _ => next_pc < code_end,
+        };
+        block.successors.extend(target.filter(|t| (code_base..code_end).contains(t)));
+        if falls_through && !block.successors.contains(&next_pc) {
+            block.successors.push(next_pc);
+        }
+    }
+    blocks
+}
+
+/// Profil d'exécution: nombre de retraits de chaque instruction et transitions
+/// entre instructions retirées consécutivement (dans l'ordre du programme)
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionProfile {
+    retired: HashMap<u32, u64>,
+    transitions: HashMap<(u32, u32), u64>,
+    last_pc: Option<u32>,
+}
+
+impl ExecutionProfile {
+    /// Enregistre le retrait de l'instruction située à `pc`
+    pub fn record(&mut self, pc: u32) {
+        *self.retired.entry(pc).or_insert(0) += 1;
+        if let Some(previous) = self.last_pc.replace(pc) {
+            *self.transitions.entry((previous, pc)).or_insert(0) += 1;
+        }
+    }
+
+    /// Nombre de retraits de l'instruction à `pc` (0 si jamais exécutée)
+    pub fn execution_count(&self, pc: u32) -> u64 {
+        self.retired.get(&pc).copied().unwrap_or(0)
+    }
+
+    /// Nombre de fois où l'instruction à `to` a été retirée juste après celle à `from`
+    pub fn transition_count(&self, from: u32, to: u32) -> u64 {
+        self.transitions.get(&(from, to)).copied().unwrap_or(0)
+    }
+
+    /// Vrai si l'instruction à `pc` a été exécutée au moins une fois
+    pub fn is_covered(&self, pc: u32) -> bool {
+        self.retired.contains_key(&pc)
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Graphe DOT (Graphviz) du CFG de `code` chargé à `code_base`: chaque bloc porte son
+/// nombre d'exécutions, chaque arc son nombre de traversées mesurées. Les arcs dynamiques
+/// absents du CFG statique (retours de RET) sont ajoutés en pointillés.
+pub fn cfg_to_dot(code: &[Instruction], code_base: u32, profile: &ExecutionProfile) -> String {
+    let blocks = build_cfg(code, code_base);
+    let block_of = |pc: u32| blocks.iter().find(|b| b.start <= pc && pc <= b.last);
+
+    let mut dot = String::from("digraph cfg {\n    node [shape=box, fontname=\"monospace\"];\n");
+    for block in &blocks {
+        let count = profile.execution_count(block.start);
+        let _ = writeln!(
+            dot,
+            "    \"0x{:08X}\" [label=\"0x{:08X}..0x{:08X}\\nexec: {}\"{}];",
+            block.start,
+            block.start,
+            block.last,
+            count,
+            if count == 0 { ", style=dashed" } else { "" }
+        );
+    }
+    for block in &blocks {
+        for &successor in &block.successors {
+            let _ = writeln!(
+                dot,
+                "    \"0x{:08X}\" -> \"0x{:08X}\" [label=\"{}\"];",
+                block.start,
+                successor,
+                profile.transition_count(block.last, successor)
+            );
+        }
+    }
+
+    let mut dynamic_edges: Vec<_> = profile
+        .transitions
+        .iter()
+        .filter_map(|(&(from, to), &count)| {
+            let source = block_of(from).filter(|b| b.last == from)?;
+            let target = blocks.iter().find(|b| b.start == to)?;
+            (!source.successors.contains(&to)).then_some((source.start, target.start, count))
+        })
+        .collect();
+    dynamic_edges.sort_unstable();
+    for (from, to, count) in dynamic_edges {
+        let _ = writeln!(dot, "    \"0x{:08X}\" -> \"0x{:08X}\" [label=\"{}\", style=dotted];", from, to, count);
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_cfg_splits_on_branches_and_targets() {
+        // 0: Mov; 1: Dec (cible de la boucle); 2: JmpIfNotZero -> 1; 3: Halt
+        let mut code = vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 0, 3),
+            Instruction::create_single_reg(Opcode::Dec, 0),
+        ];
+        let loop_addr = Instruction::calculate_current_address(&code[..1]);
+        let branch_addr = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_jump_if_not_zero(branch_addr, loop_addr));
+        code.push(Instruction::create_no_args(Opcode::Halt));
+        let halt_addr = Instruction::calculate_current_address(&code[..3]);
+
+        let blocks = build_cfg(&code, 0);
+
+        assert_eq!(blocks.iter().map(|b| b.start).collect::<Vec<_>>(), vec![0, loop_addr, halt_addr]);
+        assert_eq!(blocks[0].successors, vec![loop_addr]);
+        assert_eq!(blocks[1].last, branch_addr);
+        assert_eq!(blocks[1].successors, vec![loop_addr, halt_addr]);
+        assert!(blocks[2].successors.is_empty());
+    }
+}
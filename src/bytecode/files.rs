@@ -1,10 +1,15 @@
 //src/bytecode/files.rs
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs::File;
 use std::io::{self, Read, Write};
 use std::path::Path;
+use super::format::{ArgType, InstructionFormat};
 use super::instructions::Instruction;
+use super::lint::{lint_instructions, Lint};
+use super::lowering::lower_simd256_instructions;
+use super::opcodes::Opcode;
+use crate::pvm::vm_errors::{VMError, VMResult};
 
 ///Signature d'un fichier de bytecode PunkVM (PunkVM en ASCII)
 pub const PUNK_SIGNATURE: [u8; 4] = [0x50, 0x55, 0x4E, 0x4B];
@@ -46,14 +51,20 @@ impl BytecodeVersion {
     }
 }
 
+impl BytecodeVersion {
+    /// Première version du format écrit par `write_to_file` (table des segments,
+    /// sections préfixées par leur taille): les fichiers antérieurs ne sont pas relus
+    pub const SECTIONED: BytecodeVersion = BytecodeVersion { major: 0, minor: 2, patch: 0, build: 0 };
+
+    /// Le fichier de cette version suit-il la disposition courante ?
+    pub fn is_readable(&self) -> bool {
+        (self.major, self.minor) >= (Self::SECTIONED.major, Self::SECTIONED.minor)
+    }
+}
+
 impl Default for BytecodeVersion {
     fn default() -> Self {
-        Self {
-            major: 0,
-            minor: 1,
-            patch: 0,
-            build: 0,
-        }
+        Self::SECTIONED
     }
 }
 
@@ -137,12 +148,16 @@ impl SegmentMetadata {
 #[derive(Debug, Clone)]
 pub struct BytecodeFile {
     pub version: BytecodeVersion,
-    pub metadata: HashMap<String, String>,
+    /// Triées par clé pour une sérialisation identique d'une exécution à l'autre
+    pub metadata: BTreeMap<String, String>,
     pub segments: Vec<SegmentMetadata>,
     pub code: Vec<Instruction>,
+    /// Octets du segment de code situés après la dernière instruction décodable
+    /// (opcode inconnu): chargés tels quels, ils déclenchent un trap à l'exécution
+    pub undecoded_code: Vec<u8>,
     pub data: Vec<u8>,
     pub readonly_data: Vec<u8>,
-    pub symbols: HashMap<String, u32>,
+    pub symbols: BTreeMap<String, u32>,
     pub debug_info: Vec<u8>,
 }
 
@@ -157,12 +172,13 @@ impl BytecodeFile {
     pub fn new() -> Self {
         Self {
             version: BytecodeVersion::default(),
-            metadata: HashMap::new(),
+            metadata: BTreeMap::new(),
             segments: Vec::new(),
             code: Vec::new(),
+            undecoded_code: Vec::new(),
             data: Vec::new(),
             readonly_data: Vec::new(),
-            symbols: HashMap::new(),
+            symbols: BTreeMap::new(),
             debug_info: Vec::new(),
         }
     }
@@ -179,6 +195,158 @@ impl BytecodeFile {
     //     self.code.push(simd);
     // }
 
+    /// Adresse de chargement du segment de code (0 s'il n'est pas décrit): PC de la première instruction
+    pub fn code_load_addr(&self) -> u32 {
+        self.segments
+            .iter()
+            .find(|s| s.segment_type == SegmentType::Code)
+            .map_or(0, |s| s.load_addr)
+    }
+
+    /// Taille en octets du segment de code (adresse de la prochaine instruction)
+    pub fn code_size(&self) -> u32 {
+        self.code.iter().map(|instr| instr.total_size() as u32).sum()
+    }
+
+    /// Ajoute `instrs` après le code existant et retourne l'adresse (relative au début du
+    /// code) de la première instruction ajoutée. Les adresses de `instrs` sont celles d'un
+    /// bloc commençant à 0: les branchements relatifs restent valides tels quels, les
+    /// cibles absolues des branchements sont décalées de l'adresse mémoire du bloc
+    /// (adresse de chargement du code + adresse de début du bloc).
+    pub fn append_program(&mut self, instrs: &[Instruction]) -> Result<u32, String> {
+        let base = self.code_size();
+        let appended_size: u32 = instrs.iter().map(|instr| instr.total_size() as u32).sum();
+
+        if let Some(code_segment) = self.segments.iter().find(|s| s.segment_type == SegmentType::Code) {
+            let code_start = code_segment.load_addr;
+            let grown_end = code_start + base + appended_size;
+            let overlap = self.segments.iter().any(|s| {
+                s.segment_type != SegmentType::Code && s.load_addr >= code_start + base && s.load_addr < grown_end
+            });
+            if overlap {
+                return Err(format!("Le code ajouté (fin 0x{:X}) chevauche un segment de données", grown_end));
+            }
+        }
+
+        let block_addr = self.code_load_addr() + base;
+        for instruction in instrs {
+            let mut relocated = instruction.clone();
+            if relocated.opcode.is_branch() {
+                let mut offset = 0usize;
+                for arg_type in [relocated.format.arg1_type, relocated.format.arg2_type, relocated.format.arg3_type] {
+                    if arg_type == ArgType::AbsoluteAddr && offset + 4 <= relocated.args.len() {
+                        let bytes = &mut relocated.args[offset..offset + 4];
+                        let target = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                        bytes.copy_from_slice(&(target + block_addr).to_le_bytes());
+                    }
+                    offset += arg_type.size();
+                }
+            }
+            self.code.push(relocated);
+        }
+
+        let new_size = self.code_size();
+        if let Some(code_segment) = self.segments.iter_mut().find(|s| s.segment_type == SegmentType::Code) {
+            code_segment.size = new_size;
+        }
+        Ok(base)
+    }
+
+    /// Complète le code avec des NOP pour que la prochaine instruction commence
+    /// sur une frontière de `boundary` octets. Retourne l'adresse alignée.
+    ///
+    /// Un NOP fait au minimum 4 octets: le dernier NOP de remplissage porte des
+    /// octets d'arguments (ignorés) pour atteindre exactement la frontière.
+    pub fn align_to(&mut self, boundary: u32) -> u32 {
+        let current = self.code_size();
+        if boundary <= 1 {
+            return current;
+        }
+
+        let min_nop = Instruction::create_no_args(Opcode::Nop).total_size() as u32;
+        let mut padding = (boundary - current % boundary) % boundary;
+        while padding != 0 && padding < min_nop {
+            padding += boundary;
+        }
+
+        while padding > 0 {
+            // Tant qu'il reste de quoi placer un NOP complet après celui-ci, on émet un NOP minimal
+            let nop_size = if padding >= 2 * min_nop { min_nop } else { padding };
+            let filler = vec![0; (nop_size - min_nop) as usize];
+            self.code.push(Instruction::new(Opcode::Nop, InstructionFormat::no_args(), filler));
+            padding -= nop_size;
+        }
+
+        self.code_size()
+    }
+
+    /// Applique une directive d'assembleur (ex: `.align 16`)
+    pub fn apply_directive(&mut self, directive: &str) -> Result<(), String> {
+        let mut parts = directive.split_whitespace();
+        match parts.next() {
+            Some(".align") => {
+                let boundary = parts
+                    .next()
+                    .ok_or_else(|| "Directive .align: frontière manquante".to_string())?
+                    .parse::<u32>()
+                    .map_err(|e| format!("Directive .align: frontière invalide ({})", e))?;
+                if boundary == 0 {
+                    return Err("Directive .align: la frontière doit être non nulle".to_string());
+                }
+                self.align_to(boundary);
+                Ok(())
+            }
+            Some(other) => Err(format!("Directive inconnue: {}", other)),
+            None => Err("Directive vide".to_string()),
+        }
+    }
+
+    /// Signale les encodages suspects du segment de code (nombre d'opérandes,
+    /// auto-MOV, écritures sans effet). Les avertissements ne bloquent pas le chargement.
+    pub fn lint(&self) -> Vec<Lint> {
+        lint_instructions(&self.code)
+    }
+
+    /// Copie du programme où chaque instruction SIMD 256-bit est remplacée par deux
+    /// instructions 128-bit (Yn devient la paire V(2n), V(2n+1)), pour les configurations
+    /// sans support 256-bit. Les branchements relatifs, la taille du segment de code et
+    /// les symboles pointant dans le code suivent le déplacement des instructions.
+    pub fn lower_simd256_to_simd128(&self) -> Result<BytecodeFile, String> {
+        let (code, address_map) = lower_simd256_instructions(&self.code, self.code_load_addr())?;
+        let mut lowered = self.clone();
+        let new_size = lowered.replace_code(code, &address_map);
+
+        if let Some(code_segment) = self.segments.iter().find(|s| s.segment_type == SegmentType::Code) {
+            let (code_start, old_size) = (code_segment.load_addr, code_segment.size);
+            let grown_end = code_start + new_size;
+            let overlap = self.segments.iter().any(|s| {
+                s.segment_type != SegmentType::Code && s.load_addr >= code_start + old_size && s.load_addr < grown_end
+            });
+            if overlap {
+                return Err(format!("Le code abaissé (fin 0x{:X}) chevauche un segment de données", grown_end));
+            }
+        }
+        Ok(lowered)
+    }
+
+    /// Remplace le code par `code`, issu d'une réécriture décrite par `address_map`
+    /// (ancienne adresse -> nouvelle adresse, relatives au début du code): la taille du
+    /// segment de code et les symboles pointant dans le code suivent. Retourne la nouvelle taille.
+    pub(crate) fn replace_code(&mut self, code: Vec<Instruction>, address_map: &HashMap<u32, u32>) -> u32 {
+        self.code = code;
+        let new_size = self.code_size() + self.undecoded_code.len() as u32;
+        if let Some(code_segment) = self.segments.iter_mut().find(|s| s.segment_type == SegmentType::Code) {
+            let code_start = code_segment.load_addr;
+            code_segment.size = new_size;
+            for address in self.symbols.values_mut() {
+                if let Some(&new_addr) = address.checked_sub(code_start).and_then(|offset| address_map.get(&offset)) {
+                    *address = code_start + new_addr;
+                }
+            }
+        }
+        new_size
+    }
+
     /// Ajoute une donnée au segment de données
     pub fn add_data(&mut self, data: &[u8]) -> u32 {
         let offset = self.data.len() as u32;
@@ -201,7 +369,11 @@ impl BytecodeFile {
         self.metadata.insert(key.to_string(), value.to_string());
     }
     /// Écrit le fichier bytecode sur disque
-    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+    ///
+    /// Format: signature | version | métadonnées | table des segments |
+    /// puis les sections code, data, rodata, symboles et debug, chacune
+    /// précédée de sa taille (u32 little-endian).
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> VMResult<()> {
         let mut file = File::create(path)?;
 
         // Écriture de l'en-tête
@@ -210,218 +382,126 @@ impl BytecodeFile {
 
         // Écriture des métadonnées
         let metadata_bytes = self.encode_metadata();
-        let metadata_size = metadata_bytes.len() as u32;
-        file.write_all(&metadata_size.to_le_bytes())?;
+        file.write_all(&(metadata_bytes.len() as u32).to_le_bytes())?;
         file.write_all(&metadata_bytes)?;
 
-        // Calcul des offsets pour les segments
-        let header_size = 8; // Signature (4) + Version (4)
-        let metadata_header_size = 4; // Taille des métadonnées
-
-        let mut current_offset = header_size + metadata_header_size + metadata_size as usize;
-
-        // Nombre de segments
-        let num_segments = 5; // Code, Data, ReadOnlyData, Symbols, Debug
-        file.write_all(&(num_segments as u32).to_le_bytes())?;
-        current_offset += 4;
-
-        // Calcul de la taille de la table des segments
-        let segments_table_size = num_segments * 13; // 13 bytes par segment
-        current_offset += segments_table_size as usize;
-
-        // Encodage des segments
-        let mut segments = Vec::new();
-
-        // Segment de code
-        let code_bytes = self.encode_code();
-        segments.push(SegmentMetadata::new(
-            SegmentType::Code,
-            current_offset as u32,
-            code_bytes.len() as u32,
-            0, // Adresse de chargement, typiquement 0 pour le code
-        ));
-        current_offset += code_bytes.len();
-
-        // Segment de données
-        segments.push(SegmentMetadata::new(
-            SegmentType::Data,
-            current_offset as u32,
-            self.data.len() as u32,
-            0, // Adresse de chargement à déterminer au chargement
-        ));
-        current_offset += self.data.len();
-
-        // Segment de données en lecture seule
-        segments.push(SegmentMetadata::new(
-            SegmentType::ReadOnlyData,
-            current_offset as u32,
-            self.readonly_data.len() as u32,
-            0, // Adresse de chargement à déterminer au chargement
-        ));
-        current_offset += self.readonly_data.len();
-
-        // Segment de symboles
-        let symbols_bytes = self.encode_symbols();
-        segments.push(SegmentMetadata::new(
-            SegmentType::Symbols,
-            current_offset as u32,
-            symbols_bytes.len() as u32,
-            0, // Non applicable pour les symboles
-        ));
-        current_offset += symbols_bytes.len();
-
-        // Segment de debug
-        segments.push(SegmentMetadata::new(
-            SegmentType::Debug,
-            current_offset as u32,
-            self.debug_info.len() as u32,
-            0, // Non applicable pour les infos de debug
-        ));
-
-        // Écriture de la table des segments
-        for segment in &segments {
+        // Écriture de la table des segments (telle que définie par le programme)
+        file.write_all(&(self.segments.len() as u32).to_le_bytes())?;
+        for segment in &self.segments {
             file.write_all(&segment.encode())?;
         }
 
-        // Écriture des données des segments
-        file.write_all(&code_bytes)?;
-        file.write_all(&self.data)?;
-        file.write_all(&self.readonly_data)?;
-        file.write_all(&symbols_bytes)?;
-        file.write_all(&self.debug_info)?;
+        // Écriture des sections
+        let sections: [&[u8]; 5] = [
+            &self.encode_code(),
+            &self.data,
+            &self.readonly_data,
+            &self.encode_symbols(),
+            &self.debug_info,
+        ];
+        for section in sections {
+            file.write_all(&(section.len() as u32).to_le_bytes())?;
+            file.write_all(section)?;
+        }
 
         Ok(())
     }
 
     /// Lit un fichier bytecode depuis le disque
-    pub fn read_from_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+    pub fn read_from_file<P: AsRef<Path>>(path: P) -> VMResult<Self> {
         let mut file = File::open(path)?;
         let mut buffer = Vec::new();
         file.read_to_end(&mut buffer)?;
 
         if buffer.len() < 8 {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "Fichier bytecode trop petit",
-            ));
+            return Err(VMError::decode_error("Fichier bytecode trop petit"));
         }
 
         // Vérification de la signature
         if buffer[0..4] != PUNK_SIGNATURE {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "Signature de fichier bytecode invalide",
-            ));
+            return Err(VMError::decode_error("Signature de fichier bytecode invalide"));
         }
 
         // Lecture de la version
         let version = BytecodeVersion::decode([buffer[4], buffer[5], buffer[6], buffer[7]]);
-
-        // Lecture de la taille des métadonnées
-        if buffer.len() < 12 {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "Données invalides dans l'en-tête du fichier",
-            ));
+        if !version.is_readable() {
+            return Err(VMError::decode_error(&format!(
+                "Format de bytecode {} non supporté (minimum {})",
+                version.to_string(),
+                BytecodeVersion::SECTIONED.to_string()
+            )));
         }
-
-        let metadata_size =
-            u32::from_le_bytes([buffer[8], buffer[9], buffer[10], buffer[11]]) as usize;
-        let mut offset = 12;
+        let mut offset = 8;
 
         // Lecture des métadonnées
-        if buffer.len() < offset + metadata_size {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "Données de métadonnées incomplètes",
-            ));
-        }
+        let metadata_bytes = Self::read_section(&buffer, &mut offset, "métadonnées")?;
+        let metadata = Self::decode_metadata(metadata_bytes).map_err(Self::invalid_data)?;
 
-        let metadata = Self::decode_metadata(&buffer[offset..offset + metadata_size])?;
-        offset += metadata_size;
-
-        // Lecture du nombre de segments
-        if buffer.len() < offset + 4 {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "Données de segments incomplètes",
-            ));
-        }
-
-        let num_segments = u32::from_le_bytes([
-            buffer[offset],
-            buffer[offset + 1],
-            buffer[offset + 2],
-            buffer[offset + 3],
-        ]) as usize;
-        offset += 4;
-
-        // Lecture des métadonnées de segments
+        // Lecture de la table des segments
+        let num_segments = Self::read_u32(&buffer, &mut offset, "table des segments")? as usize;
         let mut segments = Vec::with_capacity(num_segments);
         for _ in 0..num_segments {
             if buffer.len() < offset + 13 {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidData,
-                    "Données de segment incomplètes",
-                ));
+                return Err(VMError::decode_error("Données de segment incomplètes"));
             }
 
-            let segment =
-                SegmentMetadata::decode(&buffer[offset..offset + 13]).ok_or_else(|| {
-                    io::Error::new(
-                        io::ErrorKind::InvalidData,
-                        "Métadonnées de segment invalides",
-                    )
-                })?;
+            let segment = SegmentMetadata::decode(&buffer[offset..offset + 13])
+                .ok_or_else(|| VMError::decode_error("Métadonnées de segment invalides"))?;
 
             segments.push(segment);
             offset += 13;
         }
 
-        // Création de l'objet de fichier
-        let mut bytecode_file = BytecodeFile {
+        // Lecture des sections
+        let code = Self::read_section(&buffer, &mut offset, "code")?;
+        let data = Self::read_section(&buffer, &mut offset, "data")?;
+        let readonly_data = Self::read_section(&buffer, &mut offset, "rodata")?;
+        let symbols = Self::read_section(&buffer, &mut offset, "symboles")?;
+        let debug_info = Self::read_section(&buffer, &mut offset, "debug")?;
+
+        let (code, undecoded_code) = Self::decode_code_prefix(code).map_err(Self::invalid_data)?;
+
+        Ok(BytecodeFile {
             version,
             metadata,
-            segments: segments.clone(),
-            code: Vec::new(),
-            data: Vec::new(),
-            readonly_data: Vec::new(),
-            symbols: HashMap::new(),
-            debug_info: Vec::new(),
-        };
-
-        // Lecture des données des segments
-        for segment in segments {
-            let start = segment.offset as usize;
-            let end = start + segment.size as usize;
+            segments,
+            code,
+            undecoded_code,
+            data: data.to_vec(),
+            readonly_data: readonly_data.to_vec(),
+            symbols: Self::decode_symbols(symbols).map_err(Self::invalid_data)?,
+            debug_info: debug_info.to_vec(),
+        })
+    }
 
-            if buffer.len() < end {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidData,
-                    format!("Segment incomplet: {:?}", segment.segment_type),
-                ));
-            }
+    /// Lit un u32 little-endian à la position courante
+    fn read_u32(buffer: &[u8], offset: &mut usize, what: &str) -> VMResult<u32> {
+        if buffer.len() < *offset + 4 {
+            return Err(VMError::decode_error(&format!("Taille manquante: {}", what)));
+        }
+        let value = u32::from_le_bytes([
+            buffer[*offset],
+            buffer[*offset + 1],
+            buffer[*offset + 2],
+            buffer[*offset + 3],
+        ]);
+        *offset += 4;
+        Ok(value)
+    }
 
-            match segment.segment_type {
-                SegmentType::Code => {
-                    bytecode_file.code = Self::decode_code(&buffer[start..end])?;
-                }
-                SegmentType::Data => {
-                    bytecode_file.data = buffer[start..end].to_vec();
-                }
-                SegmentType::ReadOnlyData => {
-                    bytecode_file.readonly_data = buffer[start..end].to_vec();
-                }
-                SegmentType::Symbols => {
-                    bytecode_file.symbols = Self::decode_symbols(&buffer[start..end])?;
-                }
-                SegmentType::Debug => {
-                    bytecode_file.debug_info = buffer[start..end].to_vec();
-                }
-            }
+    /// Lit une section précédée de sa taille et avance l'offset
+    fn read_section<'a>(buffer: &'a [u8], offset: &mut usize, what: &str) -> VMResult<&'a [u8]> {
+        let size = Self::read_u32(buffer, offset, what)? as usize;
+        if buffer.len() < *offset + size {
+            return Err(VMError::decode_error(&format!("Section incomplète: {}", what)));
         }
+        let section = &buffer[*offset..*offset + size];
+        *offset += size;
+        Ok(section)
+    }
 
-        Ok(bytecode_file)
+    fn invalid_data(err: io::Error) -> VMError {
+        VMError::decode_error(&err.to_string())
     }
 
     /// Encode les métadonnées en bytes
@@ -448,7 +528,7 @@ impl BytecodeFile {
     }
 
     /// Décode les métadonnées depuis des bytes
-    pub fn decode_metadata(bytes: &[u8]) -> io::Result<HashMap<String, String>> {
+    pub fn decode_metadata(bytes: &[u8]) -> io::Result<BTreeMap<String, String>> {
         if bytes.len() < 4 {
             return Err(io::Error::new(
                 io::ErrorKind::InvalidData,
@@ -456,7 +536,7 @@ impl BytecodeFile {
             ));
         }
 
-        let mut metadata = HashMap::new();
+        let mut metadata = BTreeMap::new();
         let num_entries = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize;
         let mut offset = 4;
 
@@ -545,11 +625,37 @@ impl BytecodeFile {
             let encoded = instruction.encode();
             bytes.extend_from_slice(&encoded);
         }
+        bytes.extend_from_slice(&self.undecoded_code);
+        bytes
+    }
+
+    /// Octets du segment de code tels qu'ils sont chargés en mémoire
+    pub fn code_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = self.code.iter().flat_map(|instr| instr.encode()).collect();
+        bytes.extend_from_slice(&self.undecoded_code);
         bytes
     }
 
     /// Décode le segment de code depuis des bytes
     pub fn decode_code(bytes: &[u8]) -> io::Result<Vec<Instruction>> {
+        let (instructions, undecoded) = Self::decode_code_prefix(bytes)?;
+        if instructions.len() < Self::declared_instruction_count(bytes) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Erreur de décodage d'instruction: opcode invalide 0x{:02X}", undecoded[0]),
+            ));
+        }
+        Ok(instructions)
+    }
+
+    fn declared_instruction_count(bytes: &[u8]) -> usize {
+        u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize
+    }
+
+    /// Décode le segment de code jusqu'au premier opcode inconnu. Retourne les
+    /// instructions décodées et les octets restants (vides pour un code valide),
+    /// que la VM charge tels quels pour lever `VMError::IllegalInstruction` à l'exécution.
+    pub fn decode_code_prefix(bytes: &[u8]) -> io::Result<(Vec<Instruction>, Vec<u8>)> {
         if bytes.len() < 4 {
             return Err(io::Error::new(
                 io::ErrorKind::InvalidData,
@@ -557,8 +663,7 @@ impl BytecodeFile {
             ));
         }
 
-        let num_instructions =
-            u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize;
+        let num_instructions = Self::declared_instruction_count(bytes);
         let mut instructions = Vec::with_capacity(num_instructions);
         let mut offset = 4;
 
@@ -570,6 +675,11 @@ impl BytecodeFile {
                 ));
             }
 
+            // Opcode inconnu: le reste du segment est conservé brut
+            if Opcode::from_u8(bytes[offset]).is_none() {
+                break;
+            }
+
             let (instruction, size) = Instruction::decode(&bytes[offset..]).map_err(|err| {
                 io::Error::new(
                     io::ErrorKind::InvalidData,
@@ -581,7 +691,7 @@ impl BytecodeFile {
             offset += size;
         }
 
-        Ok(instructions)
+        Ok((instructions, bytes[offset..].to_vec()))
     }
 
     /// Encode les symboles en bytes
@@ -606,7 +716,7 @@ impl BytecodeFile {
     }
 
     /// Décode les symboles depuis des bytes
-    pub fn decode_symbols(bytes: &[u8]) -> io::Result<HashMap<String, u32>> {
+    pub fn decode_symbols(bytes: &[u8]) -> io::Result<BTreeMap<String, u32>> {
         if bytes.len() < 4 {
             return Err(io::Error::new(
                 io::ErrorKind::InvalidData,
@@ -614,7 +724,7 @@ impl BytecodeFile {
             ));
         }
 
-        let mut symbols = HashMap::new();
+        let mut symbols = BTreeMap::new();
         let num_symbols = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize;
         let mut offset = 4;
 
@@ -682,7 +792,6 @@ mod tests {
     use crate::bytecode::format::{ArgType, InstructionFormat};
     use crate::bytecode::instructions::Instruction;
     use crate::bytecode::opcodes::Opcode;
-    use std::io::ErrorKind;
     use tempfile::tempdir;
 
     #[test]
@@ -972,14 +1081,98 @@ mod tests {
 
         // Vérifier le type d'erreur
         match result {
-            Err(e) => assert_eq!(e.kind(), ErrorKind::InvalidData),
+            Err(e) => assert!(matches!(e, VMError::DecodeError(_))),
             _ => panic!("Expected an error but got success"),
         }
     }
 
+    #[test]
+    fn test_bytecode_file_round_trip() {
+        let dir = tempdir().expect("Impossible de créer un répertoire temporaire");
+        let file_path = dir.path().join("round_trip.punk");
+
+        let mut bytecode = BytecodeFile::new();
+        bytecode.version = BytecodeVersion::new(1, 2, 3, 4);
+        bytecode.add_metadata("name", "RoundTrip");
+        bytecode.add_metadata("author", "PunkVM");
+        bytecode.add_instruction(Instruction::create_reg_imm8(Opcode::Load, 0, 10));
+        bytecode.add_instruction(Instruction::create_reg_reg_reg(Opcode::Add, 2, 0, 1));
+        bytecode.add_instruction(Instruction::create_jump(8, 0));
+        bytecode.add_instruction(Instruction::create_no_args(Opcode::Halt));
+        bytecode.add_data(&[0xDE, 0xAD, 0xBE, 0xEF]);
+        bytecode.add_readonly_data(&[0x11, 0x22, 0x33]);
+        bytecode.add_symbol("main", 0);
+        bytecode.debug_info = vec![0x01, 0x02, 0x03, 0x04, 0x05];
+
+        let code_size: u32 = bytecode.code.iter().map(|i| i.total_size() as u32).sum();
+        bytecode.segments = vec![
+            SegmentMetadata::new(SegmentType::Code, 0, code_size, 0),
+            SegmentMetadata::new(SegmentType::Data, 0, 4, 0x1000),
+            SegmentMetadata::new(SegmentType::ReadOnlyData, 0, 3, 0x2000),
+        ];
+
+        bytecode.write_to_file(&file_path).expect("Écriture impossible");
+        let loaded = BytecodeFile::read_from_file(&file_path).expect("Lecture impossible");
+
+        assert_eq!(loaded.version, bytecode.version);
+        assert_eq!(loaded.metadata, bytecode.metadata);
+        assert_eq!(loaded.segments, bytecode.segments);
+        assert_eq!(loaded.data, bytecode.data);
+        assert_eq!(loaded.readonly_data, bytecode.readonly_data);
+        assert_eq!(loaded.symbols, bytecode.symbols);
+        assert_eq!(loaded.debug_info, bytecode.debug_info);
+        assert!(loaded.undecoded_code.is_empty());
+        assert_eq!(loaded.code.len(), bytecode.code.len());
+        for (a, b) in loaded.code.iter().zip(bytecode.code.iter()) {
+            assert_eq!(a.encode(), b.encode());
+        }
+    }
+
+    #[test]
+    fn test_read_rejects_pre_sectioned_format_version() {
+        let dir = tempdir().expect("Impossible de créer un répertoire temporaire");
+        let file_path = dir.path().join("old.punk");
+
+        let mut bytecode = BytecodeFile::new();
+        assert_eq!(bytecode.version, BytecodeVersion::SECTIONED);
+        bytecode.add_instruction(Instruction::create_no_args(Opcode::Halt));
+        bytecode.version = BytecodeVersion::new(0, 1, 0, 0);
+        bytecode.write_to_file(&file_path).expect("Écriture impossible");
+
+        let err = BytecodeFile::read_from_file(&file_path).unwrap_err();
+        assert!(err.to_string().contains("0.1.0.0"), "{}", err);
+    }
+
+    #[test]
+    fn test_metadata_serialization_is_stable() {
+        let dir = tempdir().expect("Impossible de créer un répertoire temporaire");
+        let build = || {
+            let mut bytecode = BytecodeFile::new();
+            for (key, value) in [("name", "Stable"), ("author", "PunkVM"), ("version", "1.0.0"), ("arch", "punk64")] {
+                bytecode.add_metadata(key, value);
+            }
+            bytecode.add_symbol("main", 0);
+            bytecode.add_symbol("loop", 8);
+            bytecode.add_instruction(Instruction::create_no_args(Opcode::Halt));
+            bytecode
+        };
+
+        let reference = build().encode_metadata();
+        let mut files = Vec::new();
+        for i in 0..4 {
+            let bytecode = build();
+            assert_eq!(bytecode.encode_metadata(), reference);
+
+            let path = dir.path().join(format!("stable_{}.punk", i));
+            bytecode.write_to_file(&path).expect("Écriture impossible");
+            files.push(std::fs::read(&path).expect("Lecture impossible"));
+        }
+        assert!(files.windows(2).all(|pair| pair[0] == pair[1]));
+    }
+
     #[test]
     fn test_encode_decode_metadata() {
-        let mut metadata = HashMap::new();
+        let mut metadata = BTreeMap::new();
         metadata.insert("key1".to_string(), "value1".to_string());
         metadata.insert("key2".to_string(), "value2".to_string());
 
@@ -996,7 +1189,7 @@ mod tests {
 
     #[test]
     fn test_encode_decode_symbols() {
-        let mut symbols = HashMap::new();
+        let mut symbols = BTreeMap::new();
         symbols.insert("sym1".to_string(), 0x1000);
         symbols.insert("sym2".to_string(), 0x2000);
 
@@ -1036,4 +1229,66 @@ mod tests {
         assert_eq!(decoded[2].args[1], 0);
         assert_eq!(decoded[2].args[2], 1);
     }
+
+    #[test]
+    fn test_decode_code_keeps_mov_and_zero_jumps() {
+        // Mov (0x09) n'était pas décodé, JmpIfZero et JmpIfNotZero étaient inversés
+        let mut bytecode = BytecodeFile::new();
+        bytecode.code = vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 5),
+            Instruction::create_jump_if_zero(0, 16),
+            Instruction::create_jump_if_not_zero(0, 16),
+        ];
+
+        let decoded = BytecodeFile::decode_code(&bytecode.encode_code()).expect("Failed to decode code");
+
+        let opcodes: Vec<Opcode> = decoded.iter().map(|instr| instr.opcode).collect();
+        assert_eq!(opcodes, vec![Opcode::Mov, Opcode::JmpIfZero, Opcode::JmpIfNotZero]);
+        assert_eq!(decoded[0].args, bytecode.code[0].args);
+    }
+
+    #[test]
+    fn test_align_directive_aligns_label() {
+        let mut bytecode = BytecodeFile::new();
+        bytecode.add_instruction(Instruction::create_reg_imm8(Opcode::Load, 0, 10));
+        bytecode.add_instruction(Instruction::create_reg_reg_reg(Opcode::Add, 1, 0, 0));
+        let prologue_len = bytecode.code.len();
+
+        bytecode.apply_directive(".align 16").unwrap();
+        let loop_addr = bytecode.code_size();
+        bytecode.add_symbol("loop", loop_addr);
+        bytecode.add_instruction(Instruction::create_reg_reg_reg(Opcode::Sub, 0, 0, 1));
+
+        assert_eq!(bytecode.symbols["loop"] % 16, 0);
+
+        // Seuls des NOP séparent le prologue de l'instruction placée au label
+        let (last, padding) = bytecode.code[prologue_len..].split_last().unwrap();
+        assert!(padding.iter().all(|instr| instr.opcode == Opcode::Nop));
+        assert_eq!(last.opcode, Opcode::Sub);
+
+        // Le remplissage survit à l'encodage
+        let decoded = BytecodeFile::decode_code(&bytecode.encode_code()).unwrap();
+        let decoded_size: u32 = decoded.iter().map(|i| i.total_size() as u32).sum();
+        assert_eq!(decoded_size, bytecode.code_size());
+
+        assert!(bytecode.apply_directive(".align 0").is_err());
+        assert!(bytecode.apply_directive(".foo 4").is_err());
+    }
+
+    #[test]
+    fn test_lint_flags_inc_with_three_registers() {
+        use crate::bytecode::lint::LintKind;
+
+        let mut bytecode = BytecodeFile::new();
+        bytecode.add_instruction(Instruction::create_reg_reg(Opcode::Inc, 12, 12));
+        bytecode.add_instruction(Instruction::create_reg_reg_reg(Opcode::Inc, 13, 0, 0));
+        bytecode.add_instruction(Instruction::create_no_args(Opcode::Halt));
+
+        let lints = bytecode.lint();
+        assert_eq!(lints.len(), 1);
+        assert_eq!(lints[0].index, 1);
+        assert_eq!(lints[0].address, bytecode.code[0].total_size() as u32);
+        assert_eq!(lints[0].opcode, Opcode::Inc);
+        assert_eq!(lints[0].kind, LintKind::OperandCount { expected: (1, 2), found: 3 });
+    }
 }
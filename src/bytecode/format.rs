@@ -1,5 +1,22 @@
 // src/bytecode/format.rs
 
+use crate::bytecode::opcodes::Opcode;
+
+/// Octets fixes d'une instruction: opcode (1) + format (2)
+pub const INSTRUCTION_HEADER_SIZE: usize = 3;
+
+/// Taille encodée (en octets) d'une instruction `opcode` au format `format`, connue avant
+/// sa construction: en-tête, champ de taille (1 octet, ou 0xFF + 2 octets si l'instruction
+/// dépasse 255 octets) puis arguments. L'opcode tient toujours sur un octet.
+pub fn encoded_size(_opcode: Opcode, format: &InstructionFormat) -> usize {
+    let size = INSTRUCTION_HEADER_SIZE + format.args_size();
+    if size < 255 {
+        size + 1
+    } else {
+        size + 3
+    }
+}
+
 ///Type d'argument pour les instructions
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
@@ -14,8 +31,10 @@ pub enum ArgType {
     RelativeAddr = 0x7, // Adresse relative (offset par rapport au PC)
     AbsoluteAddr = 0x8, // Adresse absolue
     RegisterOffset = 0x9, // Registre + offset (pour accès mémoire indexé)
+    RegisterOffset16 = 0xA, // Registre + offset signé 16 bits
+    Indexed = 0xB,      // Base + index * échelle + déplacement signé 8 bits
                         // Flag = 0xA, // 4 bits pour les flags (ex: ZF, SF, OF, CF)
-                        // 0xA-0xF réservés pour extensions futures
+                        // 0xC-0xF réservés pour extensions futures
     // Flag = 0xA, // 4 bits pour les flags (ex: ZF, SF, OF, CF)
     // ImmediateF8 = 0xA, // Valeur immédiate flottante 8 bits
     // ImmediateF16 = 0xB, // Valeur immédiate flottante 16 bits
@@ -23,7 +42,7 @@ pub enum ArgType {
     // ImmediateF64 = 0xD, // Valeur immédiate flottante 64 bits
     // ImmediateString = 0xE, // Valeur immédiate chaîne de caractères
     // ImmediateBool = 0xF, // Valeur immédiate booléenne (true/false)
-    // Note: Les valeurs 0xA à 0xF sont réservées pour des extensions futures
+    // Note: Les valeurs 0xC à 0xF sont réservées pour des extensions futures
 }
 impl ArgType {
     /// Convertit un u8 en ArgType 4 bits
@@ -39,6 +58,8 @@ impl ArgType {
             0x7 => Some(Self::RelativeAddr),
             0x8 => Some(Self::AbsoluteAddr),
             0x9 => Some(Self::RegisterOffset),
+            0xA => Some(Self::RegisterOffset16),
+            0xB => Some(Self::Indexed),
             //////////////////////////////////////
             // 0xA => Some(Self::ImmediateF8), // Valeur immédiate flottante 8 bits
             // 0xB => Some(Self::ImmediateF16), // Valeur immédiate flottante 16 bits
@@ -46,7 +67,7 @@ impl ArgType {
             // 0xD => Some(Self::ImmediateF64), // Valeur immédiate flottante 64 bits
             // 0xE => Some(Self::ImmediateString), // Valeur immédiate chaîne de caractères
             // 0xF => Some(Self::ImmediateBool), // Valeur immédiate booléenne (true/false)
-            // Note: Les valeurs 0xA à 0xF sont réservées pour des extensions futures
+            // Note: Les valeurs 0xC à 0xF sont réservées pour des extensions futures
 
             // 0xA => Some(Self::Flag),
             _ => None,
@@ -66,6 +87,8 @@ impl ArgType {
             Self::RelativeAddr => 4, // Typiquement 32 bits pour un offset
             Self::AbsoluteAddr => 4, // Pourrait être 8 sur systèmes 64 bits
             Self::RegisterOffset => 2, // Registre (1B) + offset (1B)
+            Self::RegisterOffset16 => 3, // Registre (1B) + offset (2B)
+            Self::Indexed => 4, // Base (1B) + index (1B) + échelle (1B) + offset (1B)
                                       // Self::Flag => 1, // 4 bits pour les flags, mais on aligne sur le byte
             // Self::ImmediateF8 => 1, // 8 bits pour un float
             // Self::ImmediateF16 => 2, // 16 bits pour un float
@@ -188,6 +211,17 @@ impl InstructionFormat {
     pub fn reg_regoff() -> Self {
         Self::new(ArgType::Register, ArgType::RegisterOffset, ArgType::None)
     }
+    /// Format des transferts multiples: masque de registres (16 bits) + base/offset
+    pub fn mask_regoff() -> Self {
+        Self::new(ArgType::Immediate16, ArgType::RegisterOffset, ArgType::None)
+    }
+    pub fn reg_regoff16() -> Self {
+        Self::new(ArgType::Register, ArgType::RegisterOffset16, ArgType::None)
+    }
+    /// Format des accès indexés: registre + [base + index * échelle + offset]
+    pub fn reg_indexed() -> Self {
+        Self::new(ArgType::Register, ArgType::Indexed, ArgType::None)
+    }
 
     pub fn no_args() -> Self {
         Self::new(ArgType::None, ArgType::None, ArgType::None)
@@ -284,6 +318,18 @@ impl InstructionFormat {
         Self::new(ArgType::Register, ArgType::Immediate8, ArgType::None)
     }
 
+    pub fn push_immediate16() -> Self {
+        Self::new(ArgType::Register, ArgType::Immediate16, ArgType::None)
+    }
+
+    pub fn push_immediate32() -> Self {
+        Self::new(ArgType::Register, ArgType::Immediate32, ArgType::None)
+    }
+
+    pub fn push_immediate64() -> Self {
+        Self::new(ArgType::Register, ArgType::Immediate64, ArgType::None)
+    }
+
     pub fn pop_reg() -> Self {
         Self::new(ArgType::Register, ArgType::None, ArgType::None)
     }
@@ -299,6 +345,10 @@ impl InstructionFormat {
     pub fn simd_reg_reg() -> Self {
         Self::new(ArgType::RegisterExt, ArgType::RegisterExt, ArgType::RegisterExt)
     }
+    /// Format à quatre registres vectoriels: vd, va, puis vb/vc packés sur 16 bits
+    pub fn simd_reg_reg_pair() -> Self {
+        Self::new(ArgType::RegisterExt, ArgType::RegisterExt, ArgType::Immediate16)
+    }
     pub fn simd_load_offset() -> Self {
         Self::new(ArgType::RegisterExt, ArgType::RegisterOffset, ArgType::None)
     }
@@ -680,3 +730,54 @@ impl InstructionFormat {
 //         assert_eq!(fmt, decoded);
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bytecode::instructions::Instruction;
+
+    #[test]
+    fn test_encoded_size_matches_built_instructions() {
+        let built = vec![
+            Instruction::create_no_args(Opcode::Halt),
+            Instruction::create_single_reg(Opcode::Inc, 1),
+            Instruction::create_reg_reg(Opcode::Mov, 1, 2),
+            Instruction::create_reg_reg_reg(Opcode::Add, 1, 2, 3),
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 5),
+            Instruction::create_reg_imm16(Opcode::Mov, 1, 0x1234),
+            Instruction::create_reg_imm32(Opcode::Mov, 1, 0x1234_5678),
+            Instruction::create_load_reg_offset(1, 2, -8),
+            Instruction::create_load_reg_offset_i16(1, 2, 300),
+            Instruction::create_store_reg_offset(Opcode::Store, 1, 2, 8),
+            Instruction::create_load_indexed(1, 2, 3, 4, 0),
+            Instruction::create_store_reg_indexed(1, 2, 3),
+            Instruction::create_lea(1, 2, 16),
+            Instruction::create_jump(0, 0x40),
+            Instruction::create_jump_if_not_equal(0x40, 0),
+        ];
+
+        for instr in &built {
+            assert_eq!(encoded_size(instr.opcode, &instr.format), instr.total_size(), "{:?}", instr);
+            let operands = [instr.format.arg1_type, instr.format.arg2_type, instr.format.arg3_type];
+            assert_eq!(instr.opcode.encoded_size(&operands), instr.total_size(), "{:?}", instr);
+        }
+
+        // Forme étendue: le champ de taille passe à 3 octets au-delà de 255 octets
+        let long = Instruction::new(Opcode::Nop, InstructionFormat::no_args(), vec![0; 300]);
+        assert_eq!(long.total_size(), 1 + 2 + 3 + 300);
+        assert_eq!(Opcode::Add.encoded_size(&[ArgType::Register]), 5);
+    }
+
+    #[test]
+    fn test_store_reg_indexed_round_trip() {
+        use crate::bytecode::instructions::ArgValue;
+        let store = Instruction::create_store_reg_indexed(4, 1, 2);
+        let (decoded, size) = Instruction::decode(&store.encode()).unwrap();
+
+        assert_eq!(size, store.total_size());
+        assert_eq!(decoded.opcode, Opcode::Store);
+        assert!(matches!(decoded.get_arg1_value(), Ok(ArgValue::Register(4))));
+        assert!(matches!(decoded.get_arg2_value(), Ok(ArgValue::Indexed(1, 2, 1, 0))));
+        assert_eq!(decoded.register_operands(), vec![4, 1, 2]);
+    }
+}
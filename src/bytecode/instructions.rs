@@ -29,7 +29,9 @@ pub enum ArgValue {
     Immediate(u64),
     RelativeAddr(i32),
     AbsoluteAddr(u64),
-    RegisterOffset(u8, i8),
+    RegisterOffset(u8, i16),
+    /// Base, index, échelle (1, 2, 4 ou 8) et déplacement
+    Indexed(u8, u8, u8, i16),
     Label(String), // Pour les labels, si besoin
 }
 
@@ -172,6 +174,24 @@ impl Instruction {
         Ok((inst, size as usize))
     }
 
+    /// Indices bruts (non masqués) de tous les registres référencés par les opérandes,
+    /// y compris les bases et index des modes d'adressage mémoire
+    pub fn register_operands(&self) -> Vec<u8> {
+        let mut registers = Vec::new();
+        let mut offset = 0;
+        for arg_type in [self.format.arg1_type, self.format.arg2_type, self.format.arg3_type] {
+            let count = match arg_type {
+                ArgType::Register | ArgType::RegisterExt
+                | ArgType::RegisterOffset | ArgType::RegisterOffset16 => 1,
+                ArgType::Indexed => 2,
+                _ => 0,
+            };
+            registers.extend(self.args.iter().skip(offset).take(count));
+            offset += arg_type.size();
+        }
+        registers
+    }
+
     /// Extrait la valeur du premier argument en fonction de son type
     pub fn get_arg1_value(&self) -> Result<ArgValue, DecodeError> {
         self.get_arg_value(0, self.format.arg1_type)
@@ -301,7 +321,7 @@ impl Instruction {
             ArgType::RegisterOffset => {
                 if offset + 1 < self.args.len() {
                     let reg = self.args[offset];
-                    let offset_val = self.args[offset + 1] as i8;
+                    let offset_val = self.args[offset + 1] as i8 as i16;
                     println!(
                         "DEBUG: RegisterOffset => reg={}, offset={}",
                         reg, offset_val
@@ -311,6 +331,36 @@ impl Instruction {
                     Err(DecodeError::InvalidArgumentOffset)
                 }
             }
+
+            ArgType::RegisterOffset16 => {
+                if offset + 2 < self.args.len() {
+                    let reg = self.args[offset];
+                    let offset_val = i16::from_le_bytes([self.args[offset + 1], self.args[offset + 2]]);
+                    println!(
+                        "DEBUG: RegisterOffset16 => reg={}, offset={}",
+                        reg, offset_val
+                    );
+                    Ok(ArgValue::RegisterOffset(reg, offset_val))
+                } else {
+                    Err(DecodeError::InvalidArgumentOffset)
+                }
+            }
+
+            ArgType::Indexed => {
+                if offset + 3 < self.args.len() {
+                    let base = self.args[offset];
+                    let index = self.args[offset + 1];
+                    let scale = self.args[offset + 2];
+                    let disp = self.args[offset + 3] as i8 as i16;
+                    println!(
+                        "DEBUG: Indexed => base={}, index={}, scale={}, offset={}",
+                        base, index, scale, disp
+                    );
+                    Ok(ArgValue::Indexed(base, index, scale, disp))
+                } else {
+                    Err(DecodeError::InvalidArgumentOffset)
+                }
+            }
             // ArgType::ImmediateF8 => {
             //     if offset < self.args.len() {
             //         let value = f32::from_bits(u32::from_le_bytes([
@@ -340,7 +390,7 @@ impl Instruction {
     pub fn create_single_reg(opcode: Opcode, reg: u8) -> Self {
         // Self::new(opcode, InstructionFormat::single_reg(), vec![reg & 0x0F])
         let fmt = InstructionFormat::single_reg();
-        let args = vec![reg & 0x0F];
+        let args = vec![reg];
         Self::new(opcode, fmt, args)
     }
 
@@ -350,7 +400,7 @@ impl Instruction {
         // reg1 dans les 4 bits de poids faible, reg2 dans les 4 bits de poids fort
         // ADD R2, R1
         let fmt = InstructionFormat::double_reg();
-        let args = vec![rd & 0x0F, rs1 & 0x0F];
+        let args = vec![rd, rs1];
         Self::new(opcode, fmt, args)
     }
 
@@ -361,14 +411,35 @@ impl Instruction {
         // ADD R2, R0, R1
         let fmt = InstructionFormat::reg_reg_reg();
         // [rd, rs1, rs2]
-        let args = vec![rd & 0x0F, rs1 & 0x0F, rs2 & 0x0F];
+        let args = vec![rd, rs1, rs2];
+        Self::new(opcode, fmt, args)
+    }
+
+    /// Crée une instruction avec deux registres et une valeur immédiate 8 bits
+    /// SHLD R0, R1, 4
+    pub fn create_reg_reg_imm8(opcode: Opcode, rd: u8, rs: u8, imm: u8) -> Self {
+        let fmt = InstructionFormat::reg_reg_imm8();
+        let args = vec![rd, rs, imm];
         Self::new(opcode, fmt, args)
     }
 
+    /// Crée une instruction de champ de bits (Bfe/Bfi): `lsb` et `width` sont deux
+    /// champs de 6 bits d'un immédiat 16 bits (lsb en bits 0-5, width en bits 6-11)
+    pub fn create_bitfield(opcode: Opcode, rd: u8, rs: u8, lsb: u8, width: u8) -> Self {
+        let packed = (lsb as u16 & 0x3F) | ((width as u16 & 0x3F) << 6);
+        let args = vec![rd, rs, packed as u8, (packed >> 8) as u8];
+        Self::new(opcode, InstructionFormat::reg_reg_imm16(), args)
+    }
+
+    /// Sépare l'immédiat d'une instruction Bfe/Bfi en (lsb, width)
+    pub fn unpack_bitfield(imm: u64) -> (u32, u32) {
+        ((imm & 0x3F) as u32, ((imm >> 6) & 0x3F) as u32)
+    }
+
     /// Crée une instruction avec un registre et une valeur immédiate 8 bits
     pub fn create_reg_imm8(opcode: Opcode, reg: u8, imm: u8) -> Self {
         let fmt = InstructionFormat::reg_imm8(); // (Register, Immediate8, None)
-        let args = vec![reg & 0x0F, imm];
+        let args = vec![reg, imm];
         Self::new(opcode, fmt, args)
     }
 
@@ -415,20 +486,82 @@ impl Instruction {
     /// Crée une instruction de chargement mémoire avec registre + offset
     pub fn create_load_reg_offset(reg_dest: u8, reg_base: u8, offset: i8) -> Self {
         let fmt = InstructionFormat::reg_regoff(); // (Register, RegisterOffset, None)?
-        let args = vec![reg_dest & 0x0F, reg_base & 0x0F, offset as u8];
+        let args = vec![reg_dest, reg_base, offset as u8];
         Self::new(Opcode::Load, fmt, args)
     }
 
+    /// Crée un chargement indexé: rd = [base + index * scale + disp].
+    /// `scale` doit valoir 1, 2, 4 ou 8; toute autre valeur est refusée par l'AGU
+    pub fn create_load_indexed(reg_dest: u8, reg_base: u8, reg_index: u8, scale: u8, disp: i8) -> Self {
+        let fmt = InstructionFormat::reg_indexed();
+        let args = vec![reg_dest, reg_base, reg_index, scale, disp as u8];
+        Self::new(Opcode::Load, fmt, args)
+    }
+
+    /// Crée un Lea: rd = base + offset, calculé par l'AGU sans accès mémoire
+    pub fn create_lea(reg_dest: u8, reg_base: u8, offset: i8) -> Self {
+        let fmt = InstructionFormat::reg_regoff();
+        let args = vec![reg_dest, reg_base, offset as u8];
+        Self::new(Opcode::Lea, fmt, args)
+    }
+
     /// Crée une instruction de stockage mémoire avec registre + offset
     pub fn create_store_reg_offset(opcode: Opcode, reg_src: u8, reg_base: u8, offset: i8) -> Self {
         // let fmt = InstructionFormat::reg_reg_imm8(); // (Register, RegisterOffset, None)?
         let fmt = InstructionFormat::reg_regoff(); // (Register, RegisterOffset, None)?
 
-        let  args = vec![reg_src & 0x0F, reg_base & 0x0F, offset as u8];
+        let  args = vec![reg_src, reg_base, offset as u8];
 
         Self::new(opcode, fmt, args)
     }
 
+    /// Crée un stockage indexé: [base + index] = src, l'adresse étant résolue par l'AGU
+    pub fn create_store_reg_indexed(reg_src: u8, reg_base: u8, reg_index: u8) -> Self {
+        let fmt = InstructionFormat::reg_indexed();
+        let args = vec![reg_src, reg_base, reg_index, 1, 0];
+        Self::new(Opcode::Store, fmt, args)
+    }
+
+    /// Crée une instruction de chargement mémoire avec registre + offset signé 16 bits
+    pub fn create_load_reg_offset_i16(reg_dest: u8, reg_base: u8, offset: i16) -> Self {
+        let fmt = InstructionFormat::reg_regoff16();
+        let [lo, hi] = offset.to_le_bytes();
+        let args = vec![reg_dest, reg_base, lo, hi];
+        Self::new(Opcode::Load, fmt, args)
+    }
+
+    /// Crée une instruction de stockage mémoire avec registre + offset signé 16 bits
+    pub fn create_store_reg_offset_i16(opcode: Opcode, reg_src: u8, reg_base: u8, offset: i16) -> Self {
+        let fmt = InstructionFormat::reg_regoff16();
+        let [lo, hi] = offset.to_le_bytes();
+        let args = vec![reg_src, reg_base, lo, hi];
+        Self::new(opcode, fmt, args)
+    }
+
+
+    /// Crée un Stm: écrit les registres `regs` dans un bloc contigu à partir de [base + offset].
+    /// Les registres sont rangés par numéro croissant, 8 octets chacun.
+    /// Panique si un registre sort du masque (R0..R15).
+    pub fn create_store_multiple(reg_base: u8, regs: &[u8], offset: i8) -> Self {
+        Self::create_multiple(Opcode::Stm, reg_base, regs, offset)
+    }
+
+    /// Crée un Ldm: charge les registres `regs` depuis un bloc contigu à partir de [base + offset].
+    /// Même disposition que `create_store_multiple`.
+    pub fn create_load_multiple(reg_base: u8, regs: &[u8], offset: i8) -> Self {
+        Self::create_multiple(Opcode::Ldm, reg_base, regs, offset)
+    }
+
+    fn create_multiple(opcode: Opcode, reg_base: u8, regs: &[u8], offset: i8) -> Self {
+        if let Some(&reg) = regs.iter().find(|&&reg| reg >= 16) {
+            panic!("{:?}: R{} hors du masque de registres (R0..R15)", opcode, reg);
+        }
+        let mask = regs.iter().fold(0u16, |mask, &reg| mask | (1 << reg));
+        let [lo, hi] = mask.to_le_bytes();
+        let args = vec![lo, hi, reg_base, offset as u8];
+        Self::new(opcode, InstructionFormat::mask_regoff(), args)
+    }
+
 
     pub fn create_jump(from_addr: u32, to_addr: u32) -> Self {
         // Calculer la taille de l'instruction de saut
@@ -991,6 +1124,14 @@ impl Instruction {
         Self::new(Opcode::Call, fmt, offset.to_le_bytes().to_vec())
     }
 
+    /// Trap conditionnel `TrapIf*`: faute `ExplicitTrap` portant `code` si la condition
+    /// des flags (positionnés par le Cmp précédent) est vraie
+    pub fn create_trap_if(opcode: Opcode, code: u8) -> Self {
+        debug_assert!(opcode.is_conditional_trap(), "{:?} n'est pas un trap conditionnel", opcode);
+        let fmt = InstructionFormat::new(ArgType::Immediate8, ArgType::None, ArgType::None);
+        Self::new(opcode, fmt, vec![code])
+    }
+
     pub fn create_return() -> Self {
         let fmt = InstructionFormat::ret();
         Self::new(Opcode::Ret, fmt, Vec::new())
@@ -998,7 +1139,7 @@ impl Instruction {
 
     pub fn create_push_register(reg: u8) -> Self{
         let fmt = InstructionFormat::push_reg();
-        Self::new(Opcode::Push,fmt, vec![reg & 0x0F])
+        Self::new(Opcode::Push,fmt, vec![reg])
 
     }
 
@@ -1007,9 +1148,33 @@ impl Instruction {
         Self::new(Opcode::Push, fmt, vec![reg,imm8])
     }
 
+    /// PUSH d'une valeur immédiate 16 bits (étendue à zéro sur 64 bits dans la pile)
+    pub fn create_push_immediate16(reg: u8, imm16: u16) -> Self {
+        let fmt = InstructionFormat::push_immediate16();
+        let mut args = vec![reg];
+        args.extend_from_slice(&imm16.to_le_bytes());
+        Self::new(Opcode::Push, fmt, args)
+    }
+
+    /// PUSH d'une valeur immédiate 32 bits (étendue à zéro sur 64 bits dans la pile)
+    pub fn create_push_immediate32(reg: u8, imm32: u32) -> Self {
+        let fmt = InstructionFormat::push_immediate32();
+        let mut args = vec![reg];
+        args.extend_from_slice(&imm32.to_le_bytes());
+        Self::new(Opcode::Push, fmt, args)
+    }
+
+    /// PUSH d'une valeur immédiate 64 bits, empilée telle quelle
+    pub fn create_push_immediate64(reg: u8, imm64: u64) -> Self {
+        let fmt = InstructionFormat::push_immediate64();
+        let mut args = vec![reg];
+        args.extend_from_slice(&imm64.to_le_bytes());
+        Self::new(Opcode::Push, fmt, args)
+    }
+
     pub fn create_pop_register(reg: u8) -> Self {
         let fmt = InstructionFormat::pop_reg();
-        Self::new(Opcode::Pop, fmt, vec![reg & 0x0F])
+        Self::new(Opcode::Pop, fmt, vec![reg])
     }
 
     pub fn create_pop_immediate8(reg:u8,imm8:u8) -> Self {
@@ -1022,33 +1187,68 @@ impl Instruction {
     /// Crée une instruction SIMD arithmétique/logique entre 3 registres vectoriels
     pub fn create_simd_vector_128(opcode: Opcode, reg_dst: u8, reg_src1: u8, reg_src2: u8) -> Self {
         let fmt = InstructionFormat::simd_reg_reg();
-        let args = vec![reg_dst & 0x0F, reg_src1 & 0x0F, reg_src2 & 0x0F];
+        let args = vec![reg_dst, reg_src1, reg_src2];
         Self::new(opcode, fmt, args)
     }
 
     /// Charge un vecteur 128-bit depuis la mémoire
     pub fn create_load_simd_vector_128(opcode: Opcode, reg_dest: u8, reg_base: u8, offset: i8) -> Self {
         let fmt = InstructionFormat::simd_load_offset();  // (RegisterEx, RegisterOffset, None)?
-        let args = vec![reg_dest & 0x0F, reg_base & 0x0F, offset as u8];
+        let args = vec![reg_dest, reg_base, offset as u8];
         Self::new(opcode, fmt, args)
     }
 
     /// Stocke un vecteur 128-bit en mémoire
     pub fn create_store_simd_vector_128(opcode: Opcode, reg_src: u8, reg_base: u8, offset: i8) -> Self {
         let fmt = InstructionFormat::simd_store_offset();
-        let args = vec![reg_src & 0x0F, reg_base & 0x0F, offset as u8];
+        let args = vec![reg_src, reg_base, offset as u8];
         Self::new(opcode, fmt, args)
     }
 
     /// Stocke un vecteur 128-bit en mémoire
     pub fn create_store_simd_vector_256(opcode: Opcode, reg_src: u8, reg_base: u8, offset: i8) -> Self {
         let fmt = InstructionFormat::simd_store_offset();
-        let args = vec![reg_src & 0x0F, reg_base & 0x0F, offset as u8];
+        let args = vec![reg_src, reg_base, offset as u8];
         Self::new(opcode, fmt, args)
     }
 
     /// Helpers spécifiques pour chaque type d'opération SIMD
 
+    /// Addition vectorielle i64x2 128-bit
+    pub fn create_simd128_add_i64x2(dst: u8, src1: u8, src2: u8) -> Self {
+        Self::create_simd_vector_128(Opcode::Simd128AddI64x2, dst, src1, src2)
+    }
+
+    /// Soustraction vectorielle i64x2 128-bit
+    pub fn create_simd128_sub_i64x2(dst: u8, src1: u8, src2: u8) -> Self {
+        Self::create_simd_vector_128(Opcode::Simd128SubI64x2, dst, src1, src2)
+    }
+
+    /// Multiplication vectorielle i64x2 128-bit
+    pub fn create_simd128_mul_i64x2(dst: u8, src1: u8, src2: u8) -> Self {
+        Self::create_simd_vector_128(Opcode::Simd128MulI64x2, dst, src1, src2)
+    }
+
+    /// Addition vectorielle f64x2 128-bit
+    pub fn create_simd128_add_f64x2(dst: u8, src1: u8, src2: u8) -> Self {
+        Self::create_simd_vector_128(Opcode::Simd128AddF64x2, dst, src1, src2)
+    }
+
+    /// Soustraction vectorielle f64x2 128-bit
+    pub fn create_simd128_sub_f64x2(dst: u8, src1: u8, src2: u8) -> Self {
+        Self::create_simd_vector_128(Opcode::Simd128SubF64x2, dst, src1, src2)
+    }
+
+    /// Multiplication vectorielle f64x2 128-bit
+    pub fn create_simd128_mul_f64x2(dst: u8, src1: u8, src2: u8) -> Self {
+        Self::create_simd_vector_128(Opcode::Simd128MulF64x2, dst, src1, src2)
+    }
+
+    /// Division vectorielle f64x2 128-bit
+    pub fn create_simd128_div_f64x2(dst: u8, src1: u8, src2: u8) -> Self {
+        Self::create_simd_vector_128(Opcode::Simd128DivF64x2, dst, src1, src2)
+    }
+
     /// Addition vectorielle 128-bit (utilise Simd128Add)
     pub fn create_simd128_add(dst: u8, src1: u8, src2: u8) -> Self {
         Self::create_simd_vector_128(Opcode::Simd128Add, dst, src1, src2)
@@ -1087,10 +1287,73 @@ impl Instruction {
     /// NOT vectoriel 128-bit (unaire)
     pub fn create_simd128_not(dst: u8, src: u8) -> Self {
         let fmt = InstructionFormat::new(ArgType::RegisterExt, ArgType::RegisterExt, ArgType::None);
-        let args = vec![dst & 0x0F, src & 0x0F];
+        let args = vec![dst, src];
         Self::new(Opcode::Simd128Not, fmt, args)
     }
 
+    /// Réciproque approchée 1/x vectorielle 128-bit (f32x4, unaire)
+    pub fn create_simd128_rcp(dst: u8, src: u8) -> Self {
+        let fmt = InstructionFormat::new(ArgType::RegisterExt, ArgType::RegisterExt, ArgType::None);
+        let args = vec![dst, src];
+        Self::new(Opcode::Simd128Rcp, fmt, args)
+    }
+
+    /// Réciproque approchée 1/sqrt(x) vectorielle 128-bit (f32x4, unaire)
+    pub fn create_simd128_rsqrt(dst: u8, src: u8) -> Self {
+        let fmt = InstructionFormat::new(ArgType::RegisterExt, ArgType::RegisterExt, ArgType::None);
+        let args = vec![dst, src];
+        Self::new(Opcode::Simd128Rsqrt, fmt, args)
+    }
+
+    /// Multiplication-addition fusionnée 128-bit (f32x4): vd = va * vb + vc
+    pub fn create_simd128_fma(vd: u8, va: u8, vb: u8, vc: u8) -> Self {
+        let fmt = InstructionFormat::simd_reg_reg_pair();
+        let args = vec![vd, va, vb, vc];
+        Self::new(Opcode::Simd128FMA, fmt, args)
+    }
+
+    /// Sélection par lane 128-bit: vd[i] = va[i] si le bit de signe de vmask[i] est levé, vb[i] sinon
+    pub fn create_simd128_blend(vd: u8, va: u8, vb: u8, vmask: u8) -> Self {
+        let fmt = InstructionFormat::simd_reg_reg_pair();
+        let args = vec![vd, va, vb, vmask];
+        Self::new(Opcode::Simd128Blend, fmt, args)
+    }
+
+    /// Réduction horizontale d'un registre V128 vers un registre général
+    /// `float_lanes` choisit l'interprétation des lanes: f32x4 si vrai, i32x4 sinon
+    pub fn create_simd128_horizontal(opcode: Opcode, dst: u8, src: u8, float_lanes: bool) -> Self {
+        let fmt = InstructionFormat::new(ArgType::Register, ArgType::RegisterExt, ArgType::Immediate8);
+        let args = vec![dst, src, float_lanes as u8];
+        Self::new(opcode, fmt, args)
+    }
+
+    /// Somme horizontale des lanes i32x4 de `src` dans le registre général `dst`
+    pub fn create_simd128_hadd(dst: u8, src: u8) -> Self {
+        Self::create_simd128_horizontal(Opcode::Simd128HAdd, dst, src, false)
+    }
+
+    /// Minimum horizontal des lanes i32x4 de `src` dans le registre général `dst`
+    pub fn create_simd128_hmin(dst: u8, src: u8) -> Self {
+        Self::create_simd128_horizontal(Opcode::Simd128HMin, dst, src, false)
+    }
+
+    /// Maximum horizontal des lanes i32x4 de `src` dans le registre général `dst`
+    pub fn create_simd128_hmax(dst: u8, src: u8) -> Self {
+        Self::create_simd128_horizontal(Opcode::Simd128HMax, dst, src, false)
+    }
+
+    /// Copie la lane i32 `lane` (0..4) du registre V128 `v_src` dans le registre général `rd`
+    pub fn create_simd128_extract_lane(rd: u8, v_src: u8, lane: u8) -> Self {
+        let fmt = InstructionFormat::new(ArgType::Register, ArgType::RegisterExt, ArgType::Immediate8);
+        Self::new(Opcode::Simd128ExtractLane, fmt, vec![rd, v_src, lane])
+    }
+
+    /// Copie les 32 bits de poids faible du registre général `rs` dans la lane `lane` (0..4) de `v_dst`
+    pub fn create_simd128_insert_lane(v_dst: u8, rs: u8, lane: u8) -> Self {
+        let fmt = InstructionFormat::new(ArgType::RegisterExt, ArgType::Register, ArgType::Immediate8);
+        Self::new(Opcode::Simd128InsertLane, fmt, vec![v_dst, rs, lane])
+    }
+
     /// Charge un vecteur depuis la mémoire
     pub fn create_simd128_load(dst: u8, base: u8, offset: i8) -> Self {
         Self::create_load_simd_vector_128(Opcode::Simd128Load, dst, base, offset)
@@ -1109,7 +1372,7 @@ impl Instruction {
     /// Déplace un vecteur entre registres
     pub fn create_simd128_mov(dst: u8, src: u8) -> Self {
         let fmt = InstructionFormat::new(ArgType::RegisterExt, ArgType::RegisterExt, ArgType::None);
-        let args = vec![dst & 0x0F, src & 0x0F];
+        let args = vec![dst, src];
         Self::new(Opcode::Simd128Mov, fmt, args)
     }
 
@@ -1130,6 +1393,21 @@ impl Instruction {
         Self::create_simd_vector_128(Opcode::Simd256Mul, dst, src1, src2)
     }
 
+    /// Addition vectorielle i64x4 256-bit
+    pub fn create_simd256_add_i64x4(dst: u8, src1: u8, src2: u8) -> Self {
+        Self::create_simd_vector_128(Opcode::Simd256AddI64x4, dst, src1, src2)
+    }
+
+    /// Soustraction vectorielle i64x4 256-bit
+    pub fn create_simd256_sub_i64x4(dst: u8, src1: u8, src2: u8) -> Self {
+        Self::create_simd_vector_128(Opcode::Simd256SubI64x4, dst, src1, src2)
+    }
+
+    /// Multiplication vectorielle i64x4 256-bit
+    pub fn create_simd256_mul_i64x4(dst: u8, src1: u8, src2: u8) -> Self {
+        Self::create_simd_vector_128(Opcode::Simd256MulI64x4, dst, src1, src2)
+    }
+
     /// ET logique vectoriel 256-bit
     pub fn create_simd256_and(dst: u8, src1: u8, src2: u8) -> Self {
         Self::create_simd_vector_128(Opcode::Simd256And, dst, src1, src2)
@@ -1151,7 +1429,7 @@ impl Instruction {
     pub fn create_simd128_const_i32x4(dst: u8, values: [i32; 4]) -> Self {
         // Maintenant on utilise l'opcode Simd128Const implémenté
         let fmt = InstructionFormat::new(ArgType::RegisterExt, ArgType::Immediate64, ArgType::Immediate64);
-        let mut args = vec![dst & 0x0F];
+        let mut args = vec![dst];
         
         // Encoder les 4 valeurs i32 dans 16 bytes
         for val in values.iter() {
@@ -1165,7 +1443,7 @@ impl Instruction {
     pub fn create_simd128_const_f32x4(dst: u8, values: [f32; 4]) -> Self {
         // Maintenant on utilise l'opcode Simd128ConstF32 implémenté
         let fmt = InstructionFormat::new(ArgType::RegisterExt, ArgType::Immediate64, ArgType::Immediate64);
-        let mut args = vec![dst & 0x0F];
+        let mut args = vec![dst];
         
         // Encoder les 4 valeurs f32 dans 16 bytes
         for val in values.iter() {
@@ -1179,7 +1457,7 @@ impl Instruction {
     pub fn create_simd256_const_i32x8(dst: u8, values: [i32; 8]) -> Self {
         // Format avec plusieurs immediates pour encoder 32 bytes
         let fmt = InstructionFormat::new(ArgType::RegisterExt, ArgType::Immediate64, ArgType::Immediate64);
-        let mut args = vec![dst & 0x0F];
+        let mut args = vec![dst];
         
         // Encoder les 8 valeurs i32 dans 32 bytes
         for val in values.iter() {
@@ -1192,7 +1470,7 @@ impl Instruction {
     /// Crée une instruction pour charger une constante vectorielle f32x8
     pub fn create_simd256_const_f32x8(dst: u8, values: [f32; 8]) -> Self {
         let fmt = InstructionFormat::new(ArgType::RegisterExt, ArgType::Immediate64, ArgType::Immediate64);
-        let mut args = vec![dst & 0x0F];
+        let mut args = vec![dst];
         
         // Encoder les 8 valeurs f32 dans 32 bytes
         for val in values.iter() {
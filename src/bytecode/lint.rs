@@ -0,0 +1,250 @@
+//src/bytecode/lint.rs
+
+use std::fmt;
+
+use crate::bytecode::format::ArgType;
+use crate::bytecode::instructions::{ArgValue, Instruction};
+use crate::bytecode::opcodes::Opcode;
+
+/// Nature d'un encodage suspect détecté par `BytecodeFile::lint`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LintKind {
+    /// Nombre d'opérandes incompatible avec l'opcode
+    OperandCount { expected: (usize, usize), found: usize },
+    /// MOV d'un registre vers lui-même
+    SelfMove { reg: u8 },
+    /// Instruction qui réécrit la valeur lue sans la modifier (seuls les flags changent)
+    NoEffect { reg: u8 },
+    /// Branchement conditionnel dont les flags de la comparaison précédente ont été
+    /// écrasés par une autre instruction (`writer`, à l'index `writer_index`)
+    FlagClobber { writer: Opcode, writer_index: usize },
+}
+
+/// Avertissement sur une instruction du segment de code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lint {
+    /// Index de l'instruction dans `BytecodeFile::code`
+    pub index: usize,
+    /// Adresse de l'instruction
+    pub address: u32,
+    pub opcode: Opcode,
+    pub kind: LintKind,
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{:08X} [{}] {:?}: ", self.address, self.index, self.opcode)?;
+        match &self.kind {
+            LintKind::OperandCount { expected: (min, max), found } if min == max => {
+                write!(f, "{} opérande(s) attendu(s), {} trouvé(s)", min, found)
+            }
+            LintKind::OperandCount { expected: (min, max), found } => {
+                write!(f, "{} à {} opérandes attendus, {} trouvé(s)", min, max, found)
+            }
+            LintKind::SelfMove { reg } => write!(f, "MOV de R{} vers lui-même", reg),
+            LintKind::NoEffect { reg } => {
+                write!(f, "R{} réécrit sans changement, seuls les flags sont modifiés", reg)
+            }
+            LintKind::FlagClobber { writer, writer_index } => write!(
+                f,
+                "flags de la comparaison écrasés par {:?} [{}] avant le branchement",
+                writer, writer_index
+            ),
+        }
+    }
+}
+
+/// Analyse une suite d'instructions et retourne les avertissements dans l'ordre du code
+pub fn lint_instructions(code: &[Instruction]) -> Vec<Lint> {
+    let mut lints = Vec::new();
+    let mut address = 0u32;
+    // Depuis le dernier transfert de contrôle: une comparaison a-t-elle été vue,
+    // et quelle instruction a écrit les flags en dernier
+    let mut compare_seen = false;
+    let mut last_flag_writer: Option<(usize, Opcode)> = None;
+
+    for (index, instruction) in code.iter().enumerate() {
+        let mut push = |kind| {
+            lints.push(Lint { index, address, opcode: instruction.opcode, kind });
+        };
+
+        let found = operand_count(instruction);
+        if let Some((min, max)) = expected_operands(instruction.opcode) {
+            if found < min || found > max {
+                push(LintKind::OperandCount { expected: (min, max), found });
+            }
+        }
+
+        if let Some(kind) = redundant_write(instruction) {
+            push(kind);
+        }
+
+        let opcode = instruction.opcode;
+        if is_conditional_branch(opcode) {
+            if let Some((writer_index, writer)) = last_flag_writer {
+                if compare_seen && !matches!(writer, Opcode::Cmp | Opcode::Test) {
+                    push(LintKind::FlagClobber { writer, writer_index });
+                }
+            }
+        }
+        if opcode.is_branch() {
+            compare_seen = false;
+            last_flag_writer = None;
+        } else if writes_flags(opcode) {
+            compare_seen |= matches!(opcode, Opcode::Cmp | Opcode::Test);
+            last_flag_writer = Some((index, opcode));
+        }
+
+        address += instruction.total_size() as u32;
+    }
+
+    lints
+}
+
+/// Nombre d'arguments effectivement encodés dans le format
+fn operand_count(instruction: &Instruction) -> usize {
+    [instruction.format.arg1_type, instruction.format.arg2_type, instruction.format.arg3_type]
+        .iter()
+        .filter(|&&t| t != ArgType::None)
+        .count()
+}
+
+/// Bornes (min, max) du nombre d'opérandes pour les opcodes contrôlés
+fn expected_operands(opcode: Opcode) -> Option<(usize, usize)> {
+    match opcode {
+        Opcode::Nop | Opcode::Halt | Opcode::Ret => Some((0, 0)),
+        // Forme courte "Inc R1" ou historique "Inc R1, R1"
+        Opcode::Inc | Opcode::Dec | Opcode::Neg | Opcode::Not => Some((1, 2)),
+        Opcode::Push | Opcode::Pop => Some((1, 1)),
+        Opcode::Mov | Opcode::Cmp | Opcode::Test | Opcode::Crc32 | Opcode::Lea
+        | Opcode::Popcnt | Opcode::Clz | Opcode::Ctz => Some((2, 2)),
+        Opcode::Add | Opcode::Sub | Opcode::Mul | Opcode::Div | Opcode::Mod
+        | Opcode::AddSat | Opcode::SubSat | Opcode::AddSatD | Opcode::SubSatD
+        | Opcode::AddSatS | Opcode::SubSatS | Opcode::AddSatSD | Opcode::SubSatSD
+        | Opcode::And | Opcode::Or | Opcode::Xor
+        | Opcode::Shl | Opcode::Shr | Opcode::Sar | Opcode::Rol | Opcode::Ror => Some((2, 3)),
+        Opcode::Simd128MaskedLoad | Opcode::Simd128MaskedStore
+        | Opcode::Simd128ExtractLane | Opcode::Simd128InsertLane
+        | Opcode::Shld | Opcode::Shrd | Opcode::Bfe | Opcode::Bfi => Some((3, 3)),
+        _ if opcode.is_conditional_trap() => Some((1, 1)),
+        _ if opcode.is_conditional_move() => Some((2, 2)),
+        _ => None,
+    }
+}
+
+/// Instructions dont l'exécution passe par l'ALU et met à jour les flags
+fn writes_flags(opcode: Opcode) -> bool {
+    matches!(
+        opcode,
+        Opcode::Add | Opcode::Sub | Opcode::Mul | Opcode::Div | Opcode::Mod
+            | Opcode::Inc | Opcode::Dec | Opcode::Neg
+            | Opcode::And | Opcode::Or | Opcode::Xor | Opcode::Not
+            | Opcode::Shl | Opcode::Shr | Opcode::Sar | Opcode::Rol | Opcode::Ror
+            | Opcode::Shld | Opcode::Shrd | Opcode::Popcnt | Opcode::Clz | Opcode::Ctz
+            | Opcode::Bfe | Opcode::Bfi
+            | Opcode::Crc32 | Opcode::AddSat | Opcode::SubSat | Opcode::AddSatD | Opcode::SubSatD
+            | Opcode::AddSatS | Opcode::SubSatS | Opcode::AddSatSD | Opcode::SubSatSD
+            | Opcode::Cmp | Opcode::Test
+    )
+}
+
+/// Branchements, traps et déplacements conditionnels, qui consomment les flags
+fn is_conditional_branch(opcode: Opcode) -> bool {
+    (opcode.is_branch() && !matches!(opcode, Opcode::Jmp | Opcode::Call | Opcode::Ret))
+        || opcode.is_conditional_trap()
+        || opcode.is_conditional_move()
+}
+
+fn register<E>(value: &Result<ArgValue, E>) -> Option<u8> {
+    match value {
+        Ok(ArgValue::Register(r)) => Some(*r),
+        _ => None,
+    }
+}
+
+fn immediate<E>(value: &Result<ArgValue, E>) -> Option<u64> {
+    match value {
+        Ok(ArgValue::Immediate(v)) => Some(*v),
+        _ => None,
+    }
+}
+
+/// Détecte les auto-MOV et les écritures qui recopient la valeur lue
+fn redundant_write(instruction: &Instruction) -> Option<LintKind> {
+    let rd = register(&instruction.get_arg1_value())?;
+    let arg2 = instruction.get_arg2_value();
+    let arg3 = instruction.get_arg3_value();
+
+    let two_operands = instruction.format.arg3_type == ArgType::None;
+    let rs1_is_rd = register(&arg2) == Some(rd);
+    // x & x == x | x == x
+    let same_sources = rs1_is_rd && (two_operands || register(&arg3) == Some(rd));
+    // L'immédiat 0 est l'élément neutre de l'opération
+    let zero_immediate = if two_operands {
+        immediate(&arg2) == Some(0)
+    } else {
+        rs1_is_rd && immediate(&arg3) == Some(0)
+    };
+
+    match instruction.opcode {
+        Opcode::Mov | Opcode::Simd128Mov | Opcode::Simd256Mov | Opcode::FpuMov if rs1_is_rd =>
+        {
+            Some(LintKind::SelfMove { reg: rd })
+        }
+        Opcode::And | Opcode::Or if same_sources => Some(LintKind::NoEffect { reg: rd }),
+        Opcode::Add | Opcode::Sub | Opcode::Or | Opcode::Xor
+        | Opcode::Shl | Opcode::Shr | Opcode::Sar | Opcode::Rol | Opcode::Ror
+            if zero_immediate =>
+        {
+            Some(LintKind::NoEffect { reg: rd })
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lint_redundant_writes() {
+        let code = vec![
+            Instruction::create_reg_reg(Opcode::Mov, 3, 3),
+            Instruction::create_reg_reg_reg(Opcode::And, 2, 2, 2),
+            Instruction::create_reg_imm8(Opcode::Add, 1, 0),
+            // Instructions légitimes
+            Instruction::create_reg_reg_reg(Opcode::Xor, 2, 2, 2),
+            Instruction::create_reg_imm8(Opcode::Add, 1, 1),
+            Instruction::create_reg_reg(Opcode::Mov, 3, 4),
+        ];
+
+        let kinds: Vec<LintKind> = lint_instructions(&code).into_iter().map(|l| l.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                LintKind::SelfMove { reg: 3 },
+                LintKind::NoEffect { reg: 2 },
+                LintKind::NoEffect { reg: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_flag_clobber_between_cmp_and_branch() {
+        let code = vec![
+            Instruction::create_reg_reg(Opcode::Cmp, 1, 2),
+            Instruction::create_reg_reg_reg(Opcode::Add, 3, 1, 2),
+            Instruction::create_jump_if_equal(0, 0),
+            // Idiome légitime: décrément puis branchement sur ses propres flags
+            Instruction::create_reg_imm8(Opcode::Sub, 4, 1),
+            Instruction::create_jump_if_not_zero(0, 0),
+            // Mov ne touche pas aux flags
+            Instruction::create_reg_reg(Opcode::Cmp, 1, 2),
+            Instruction::create_reg_imm8(Opcode::Mov, 5, 1),
+            Instruction::create_jump_if_equal(0, 0),
+        ];
+
+        let kinds: Vec<LintKind> = lint_instructions(&code).into_iter().map(|l| l.kind).collect();
+        assert_eq!(kinds, vec![LintKind::FlagClobber { writer: Opcode::Add, writer_index: 1 }]);
+    }
+}
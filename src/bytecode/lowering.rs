@@ -0,0 +1,276 @@
+//src/bytecode/lowering.rs
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::bytecode::format::ArgType;
+use crate::bytecode::instructions::Instruction;
+use crate::bytecode::opcodes::{Opcode, OpcodeCategory};
+
+/// Taille en octets d'une moitié de vecteur 256-bit
+const HALF_VECTOR_SIZE: i8 = 16;
+
+/// Équivalent 128-bit d'une opération SIMD 256-bit traitée moitié par moitié.
+/// Les opérations qui mélangent les deux moitiés (Shuffle) n'en ont pas.
+fn simd128_equivalent(opcode: Opcode) -> Option<Opcode> {
+    let lowered = match opcode {
+        Opcode::Simd256Add => Opcode::Simd128Add,
+        Opcode::Simd256Sub => Opcode::Simd128Sub,
+        Opcode::Simd256Mul => Opcode::Simd128Mul,
+        Opcode::Simd256Div => Opcode::Simd128Div,
+        Opcode::Simd256And => Opcode::Simd128And,
+        Opcode::Simd256Or => Opcode::Simd128Or,
+        Opcode::Simd256Xor => Opcode::Simd128Xor,
+        Opcode::Simd256Not => Opcode::Simd128Not,
+        Opcode::Simd256Load => Opcode::Simd128Load,
+        Opcode::Simd256Store => Opcode::Simd128Store,
+        Opcode::Simd256Mov => Opcode::Simd128Mov,
+        Opcode::Simd256Cmp => Opcode::Simd128Cmp,
+        Opcode::Simd256Min => Opcode::Simd128Min,
+        Opcode::Simd256Max => Opcode::Simd128Max,
+        Opcode::Simd256Sqrt => Opcode::Simd128Sqrt,
+        Opcode::Simd256Const => Opcode::Simd128Const,
+        Opcode::Simd256ConstF32 => Opcode::Simd128ConstF32,
+        Opcode::Simd256ConstI16x16 => Opcode::Simd128ConstI16x8,
+        Opcode::Simd256ConstI64x4 => Opcode::Simd128ConstI64x2,
+        Opcode::Simd256ConstF64x4 => Opcode::Simd128ConstF64x2,
+        Opcode::Simd256AddI64x4 => Opcode::Simd128AddI64x2,
+        Opcode::Simd256SubI64x4 => Opcode::Simd128SubI64x2,
+        Opcode::Simd256MulI64x4 => Opcode::Simd128MulI64x2,
+        _ => return None,
+    };
+    Some(lowered)
+}
+
+/// Registres 128-bit (moitié basse, moitié haute) représentant le registre Y`reg`
+fn half_registers(reg: u8) -> Result<(u8, u8), String> {
+    if reg >= 8 {
+        return Err(format!("Registre Y{} sans équivalent 128-bit (seuls Y0-Y7 sont abaissables)", reg));
+    }
+    Ok((reg * 2, reg * 2 + 1))
+}
+
+/// Registres V128 lus ou écrits par une instruction SIMD 128-bit
+fn vector_registers_128(instruction: &Instruction) -> &[u8] {
+    let args = &instruction.args;
+    let range = match instruction.opcode {
+        // [Vreg, base/constante...]: seul le premier opérande est vectoriel
+        Opcode::Simd128Load
+        | Opcode::Simd128Store
+        | Opcode::Simd128MaskedLoad
+        | Opcode::Simd128MaskedStore
+        | Opcode::Simd128InsertLane
+        | Opcode::Simd128Const
+        | Opcode::Simd128ConstF32
+        | Opcode::Simd128ConstI16x8
+        | Opcode::Simd128ConstI64x2
+        | Opcode::Simd128ConstF64x2 => 0..1,
+        // [Rd, Vsrc, immédiat]
+        Opcode::Simd128HAdd | Opcode::Simd128HMin | Opcode::Simd128HMax | Opcode::Simd128ExtractLane => 1..2,
+        Opcode::Simd128Shuffle => 0..2,
+        _ => 0..args.len(),
+    };
+    args.get(range).unwrap_or(&[])
+}
+
+/// Réécrit une instruction SIMD 256-bit en deux instructions 128-bit: Yn est porté par
+/// la paire V(2n), V(2n+1). Les autres instructions sont recopiées telles quelles.
+fn lower_instruction(instruction: &Instruction) -> Result<Vec<Instruction>, String> {
+    if instruction.opcode.category() != OpcodeCategory::Simd256 {
+        return Ok(vec![instruction.clone()]);
+    }
+    let opcode = simd128_equivalent(instruction.opcode)
+        .ok_or_else(|| format!("{:?} n'a pas d'équivalent 128-bit", instruction.opcode))?;
+    let args = &instruction.args;
+    let format = instruction.format;
+
+    let halves = match instruction.opcode {
+        // [Vreg, base, offset]: la moitié haute se trouve 16 octets plus loin
+        Opcode::Simd256Load | Opcode::Simd256Store => {
+            let (low, high) = half_registers(args[0])?;
+            let offset = args[2] as i8;
+            let high_offset = offset.checked_add(HALF_VECTOR_SIZE).ok_or_else(|| {
+                format!("{:?}: offset {} + 16 hors de portée d'un i8", instruction.opcode, offset)
+            })?;
+            [vec![low, args[1], args[2]], vec![high, args[1], high_offset as u8]]
+        }
+        // [Vreg, 128 bits de constante]: comme l'exécution native, les deux moitiés
+        // reçoivent les mêmes 128 bits
+        Opcode::Simd256Const
+        | Opcode::Simd256ConstF32
+        | Opcode::Simd256ConstI16x16
+        | Opcode::Simd256ConstI64x4
+        | Opcode::Simd256ConstF64x4 => {
+            let (low, high) = half_registers(args[0])?;
+            let constant = args.get(1..17).ok_or("Constante SIMD 256-bit tronquée")?;
+            [
+                [&[low][..], constant].concat(),
+                [&[high][..], constant].concat(),
+            ]
+        }
+        // Opérations registre à registre: chaque opérande vectoriel est dédoublé
+        _ => {
+            let mut low = Vec::with_capacity(args.len());
+            let mut high = Vec::with_capacity(args.len());
+            for &reg in args {
+                let (l, h) = half_registers(reg)?;
+                low.push(l);
+                high.push(h);
+            }
+            [low, high]
+        }
+    };
+
+    let [low, high] = halves;
+    Ok(vec![Instruction::new(opcode, format, low), Instruction::new(opcode, format, high)])
+}
+
+/// Abaisse toutes les instructions SIMD 256-bit de `code` en paires d'instructions 128-bit
+/// et recalcule les branchements (relatifs, ou absolus pour un code chargé à `code_base`),
+/// dont les cibles se sont déplacées.
+/// Retourne le nouveau code et la correspondance ancienne adresse -> nouvelle adresse
+/// (y compris l'adresse de fin de code).
+///
+/// Le programme est refusé si son code 128-bit utilise déjà un registre V servant de
+/// moitié à un registre Y. Après une opération abaissée, les flags vectoriels sont ceux
+/// de la moitié haute: le signe est identique à l'exécution native, le flag zéro ignore
+/// la moitié basse.
+pub fn lower_simd256_instructions(
+    code: &[Instruction],
+    code_base: u32,
+) -> Result<(Vec<Instruction>, HashMap<u32, u32>), String> {
+    let live_v128: BTreeSet<u8> = code
+        .iter()
+        .filter(|i| i.opcode.category() == OpcodeCategory::Simd128)
+        .flat_map(|i| vector_registers_128(i).iter().copied())
+        .collect();
+
+    rewrite_instructions(code, code_base, |instruction| {
+        let lowered = lower_instruction(instruction)?;
+        if instruction.opcode.category() == OpcodeCategory::Simd256 {
+            if let Some(reg) = lowered.iter().flat_map(vector_registers_128).find(|r| live_v128.contains(r)) {
+                return Err(format!(
+                    "{:?}: V{} porte une moitié de registre Y mais est déjà utilisé par le code 128-bit",
+                    instruction.opcode, reg
+                ));
+            }
+        }
+        Ok(lowered)
+    })
+}
+
+/// Remplace chaque instruction de `code` par le groupe (éventuellement vide) retourné par
+/// `rewrite`, puis recalcule les offsets des branchements relatifs et les cibles absolues
+/// des branchements (adresses mémoire, le code étant chargé à `code_base`). Les
+/// branchements sont conservés tels quels; une instruction supprimée a pour nouvelle
+/// adresse celle de l'instruction conservée qui la suit. Retourne le nouveau code et la
+/// correspondance ancienne adresse -> nouvelle adresse, relatives au début du code
+/// (y compris l'adresse de fin de code).
+pub fn rewrite_instructions<F>(
+    code: &[Instruction],
+    code_base: u32,
+    mut rewrite: F,
+) -> Result<(Vec<Instruction>, HashMap<u32, u32>), String>
+where
+    F: FnMut(&Instruction) -> Result<Vec<Instruction>, String>,
+{
+    let mut groups = Vec::with_capacity(code.len());
+    let mut address_map = HashMap::new();
+    let (mut old_addr, mut new_addr) = (0u32, 0u32);
+    for instruction in code {
+        let group = rewrite(instruction)?;
+        address_map.insert(old_addr, new_addr);
+        old_addr += instruction.total_size() as u32;
+        new_addr += group.iter().map(|i| i.total_size() as u32).sum::<u32>();
+        groups.push(group);
+    }
+    address_map.insert(old_addr, new_addr);
+
+    let mut rewritten = Vec::new();
+    let mut old_addr = 0u32;
+    for (instruction, group) in code.iter().zip(groups) {
+        let size = instruction.total_size() as u32;
+        let arg_types = [instruction.format.arg1_type, instruction.format.arg2_type, instruction.format.arg3_type];
+        if instruction.format.arg2_type == ArgType::RelativeAddr && instruction.args.len() == 4 {
+            let offset = i32::from_le_bytes([instruction.args[0], instruction.args[1], instruction.args[2], instruction.args[3]]);
+            let old_target = (old_addr + size) as i32 + offset;
+            let new_target = *address_map.get(&(old_target as u32)).ok_or_else(|| {
+                format!("Branchement 0x{:X}: cible 0x{:X} hors d'une frontière d'instruction", old_addr, old_target)
+            })?;
+            let new_next = address_map[&old_addr] + size;
+            let new_offset = new_target as i32 - new_next as i32;
+            rewritten.push(Instruction::new(instruction.opcode, instruction.format, new_offset.to_le_bytes().to_vec()));
+        } else if instruction.opcode.is_branch() && arg_types.contains(&ArgType::AbsoluteAddr) {
+            let mut relocated = instruction.clone();
+            let mut offset = 0usize;
+            for arg_type in arg_types {
+                if arg_type == ArgType::AbsoluteAddr && offset + 4 <= relocated.args.len() {
+                    let bytes = &mut relocated.args[offset..offset + 4];
+                    let old_target = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                    let new_target = old_target
+                        .checked_sub(code_base)
+                        .and_then(|target| address_map.get(&target))
+                        .ok_or_else(|| {
+                            format!("Branchement 0x{:X}: cible absolue 0x{:X} hors d'une frontière d'instruction", old_addr, old_target)
+                        })?;
+                    bytes.copy_from_slice(&(code_base + new_target).to_le_bytes());
+                }
+                offset += arg_type.size();
+            }
+            rewritten.push(relocated);
+        } else {
+            rewritten.extend(group);
+        }
+        old_addr += size;
+    }
+    Ok((rewritten, address_map))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lowering_splits_registers_and_relocates_branches() {
+        let jump_size = Instruction::create_jump(0, 0).total_size() as u32;
+        let add = Instruction::create_simd256_add(3, 1, 2);
+        let code = vec![
+            Instruction::create_jump(0, jump_size + add.total_size() as u32),
+            add,
+            Instruction::create_no_args(Opcode::Halt),
+        ];
+
+        let (lowered, address_map) = lower_simd256_instructions(&code, 0).unwrap();
+
+        assert_eq!(lowered.len(), 4);
+        assert_eq!(lowered[1].opcode, Opcode::Simd128Add);
+        assert_eq!(lowered[1].args, vec![6, 2, 4]);
+        assert_eq!(lowered[2].args, vec![7, 3, 5]);
+        // Le saut vise toujours le Halt, désormais plus loin
+        let halt_addr = Instruction::calculate_current_address(&lowered[..3]);
+        let offset = i32::from_le_bytes(lowered[0].args[..4].try_into().unwrap());
+        assert_eq!(jump_size as i32 + offset, halt_addr as i32);
+        assert_eq!(address_map[&(jump_size + code[1].total_size() as u32)], halt_addr);
+
+        assert!(lower_simd256_instructions(&[Instruction::create_simd256_add(8, 1, 2)], 0).is_err());
+    }
+
+    #[test]
+    fn test_lowering_rejects_v128_registers_already_in_use() {
+        // Y1 serait porté par V2/V3, or V3 est déjà utilisé par le code 128-bit
+        let mixed = vec![
+            Instruction::create_simd128_add(3, 0, 1),
+            Instruction::create_simd256_add(1, 4, 5),
+            Instruction::create_no_args(Opcode::Halt),
+        ];
+        let err = lower_simd256_instructions(&mixed, 0).unwrap_err();
+        assert!(err.contains("V3"), "{}", err);
+
+        // Registres disjoints: V0/V1 pour le 128-bit, V4..V7 pour Y2/Y3
+        let disjoint = vec![
+            Instruction::create_simd128_add(1, 0, 1),
+            Instruction::create_simd256_add(2, 3, 3),
+            Instruction::create_simd128_hadd(4, 0),
+        ];
+        assert!(lower_simd256_instructions(&disjoint, 0).is_ok());
+    }
+}
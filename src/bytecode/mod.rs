@@ -1,9 +1,12 @@
 //src/
 
+pub mod cfg;
 pub mod decode_errors;
 pub mod files;
 pub mod format;
 pub mod instructions;
+pub mod lint;
+pub mod lowering;
 pub mod opcodes;
 pub mod simds;
 // Dans bytecode/mod.rs
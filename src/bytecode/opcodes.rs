@@ -1,7 +1,9 @@
 //src/bytecode/opcodes.rs
 
+use crate::bytecode::format::{encoded_size, ArgType, InstructionFormat};
+
 /// Représente les opcodes supportés par PunkVM
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(u8)]
 pub enum Opcode {
     // Instructions ALU (0x00 - 0x1F)
@@ -15,7 +17,28 @@ pub enum Opcode {
     Dec = 0x07,
     Neg = 0x08,
     Mov = 0x09, //move
-    //0x09 - 0x1F : Réservé pour les futures instructions ALU
+    Crc32 = 0x0A, // Accumule l'octet de poids faible de rs dans le CRC-32C de rd
+    AddSat = 0x0B, // Addition non signée saturée à u64::MAX
+    SubSat = 0x0C, // Soustraction non signée saturée à 0
+    // Déplacements conditionnels: Rd = Rs si la condition des flags est vraie
+    CmovEqual = 0x0D,         //CmovEqual
+    CmovNotEqual = 0x0E,      //CmovNotEqual
+    CmovGreater = 0x0F,       //CmovGreater
+    CmovGreaterEqual = 0x10,  //CmovGreaterOrEqual
+    CmovLess = 0x11,          //CmovLess
+    CmovLessEqual = 0x12,     //CmovLessOrEqual
+    CmovAbove = 0x13,         //CmovAbove
+    CmovAboveEqual = 0x14,    //CmovAboveOrEqual
+    CmovBelow = 0x15,         //CmovBelow
+    CmovBelowEqual = 0x16,    //CmovBelowOrEqual
+    // Variantes saturées: D = 32 bits, S = signé (comme LoadD / LoadSD)
+    AddSatD = 0x17,  // Addition non signée 32 bits saturée à u32::MAX
+    SubSatD = 0x18,  // Soustraction non signée 32 bits saturée à 0
+    AddSatS = 0x19,  // Addition signée saturée à [i64::MIN, i64::MAX]
+    SubSatS = 0x1A,  // Soustraction signée saturée à [i64::MIN, i64::MAX]
+    AddSatSD = 0x1B, // Addition signée 32 bits saturée à [i32::MIN, i32::MAX], étendue sur 64 bits
+    SubSatSD = 0x1C, // Soustraction signée 32 bits saturée à [i32::MIN, i32::MAX], étendue sur 64 bits
+    //0x1D - 0x1F : Réservé pour les futures instructions ALU
 
     // Instructions Logiques et de bit (0x20 - 0x3F)
     And = 0x20,
@@ -27,7 +50,14 @@ pub enum Opcode {
     Sar = 0x26, //shift arithmetic right
     Rol = 0x27, //rotate left
     Ror = 0x28, //rotate right
-    //0x29 - 0x3F : Réservé pour les futures instructions Logiques et de bit
+    Shld = 0x29, //shift left double: rd décalé à gauche, complété par les bits hauts de rs
+    Shrd = 0x2A, //shift right double: rd décalé à droite, complété par les bits bas de rs
+    Popcnt = 0x2B, //population count: rd = nombre de bits à 1 de rs
+    Clz = 0x2C, //count leading zeros: rd = zéros de tête de rs (64 si rs = 0)
+    Ctz = 0x2D, //count trailing zeros: rd = zéros de queue de rs (64 si rs = 0)
+    Bfe = 0x2E, //bit-field extract: rd = `width` bits de rs à partir de `lsb` (imm6 + imm6)
+    Bfi = 0x2F, //bit-field insert: les `width` bits bas de rs remplacent ceux de rd à `lsb`
+    //0x30 - 0x3F : Réservé pour les futures instructions Logiques et de bit
 
     // Instructions de controle de flux (0x40 - 0x5F)
     Jmp = 0x40,
@@ -54,7 +84,17 @@ pub enum Opcode {
     Ret = 0x54,  //Ret
     Cmp = 0x55,  //Cmp
     Test = 0x56, //Test
-    //0x57 - 0x5F : Réservé pour les futures instructions de controle de flux
+    // Traps conditionnels: faute ExplicitTrap si la condition des flags est vraie
+    // (Below/BelowEqual s'obtiennent en inversant les opérandes du Cmp)
+    TrapIfEqual = 0x57,        //TrapIfEqual
+    TrapIfNotEqual = 0x58,     //TrapIfNotEqual
+    TrapIfGreater = 0x59,      //TrapIfGreater
+    TrapIfGreaterEqual = 0x5A, //TrapIfGreaterOrEqual
+    TrapIfLess = 0x5B,         //TrapIfLess
+    TrapIfLessEqual = 0x5C,    //TrapIfLessOrEqual
+    TrapIfAbove = 0x5D,        //TrapIfAbove
+    TrapIfAboveEqual = 0x5E,   //TrapIfAboveOrEqual
+    TrapIfOverflow = 0x5F,     //TrapIfOverflow
 
 
 
@@ -69,14 +109,23 @@ pub enum Opcode {
     StoreD = 0x67, //store double word (32 bits)
     Push = 0x68,    // Push pour empiler une valeur sur la pile
     Pop = 0x69,   // Pop pour depiler une valeur de la pile
+    Ldm = 0x6A,   // Load multiple: charge une liste de registres depuis un bloc mémoire
+    Stm = 0x6B,   // Store multiple: écrit une liste de registres dans un bloc mémoire
+    Lea = 0x6C,   // Load effective address: rd = base + offset, sans accès mémoire
+    LoadSB = 0x6D, // load byte signé (extension de signe sur 64 bits)
+    LoadSW = 0x6E, // load word (16 bits) signé
+    LoadSD = 0x6F, // load double word (32 bits) signé
 
-    //0x6A - 0x7F : Réservé pour les futures instructions d'accès mémoire
+    //0x70 - 0x7F : Réservé pour les futures instructions d'accès mémoire
 
     // Instructions speciales (0x80 - 0x9F)
     Syscall = 0x80,
     Break = 0x81,
     Halt = 0x82,
-    //0x83 - 0x9F : Réservé pour les futures instructions speciales
+    RdMode = 0x83, // Lit le mode de privilège courant (0 = utilisateur, 1 = superviseur)
+    Sysret = 0x84, // Retour au mode utilisateur (superviseur uniquement)
+    RdInstret = 0x85, // Lit le nombre d'instructions retirées avant celle-ci
+    //0x86 - 0x9F : Réservé pour les futures instructions speciales
 
     // Instructions SIMD 128-bit (0xA0 - 0xBF)
     Simd128Add = 0xA0,        // Addition vectorielle 128-bit
@@ -101,6 +150,17 @@ pub enum Opcode {
     Simd128ConstI16x8 = 0xB2, // Constante vectorielle 128-bit (i16x8)
     Simd128ConstI64x2 = 0xB3, // Constante vectorielle 128-bit (i64x2)
     Simd128ConstF64x2 = 0xB4, // Constante vectorielle 128-bit (f64x2)
+    Simd128Rcp = 0xB5,        // Réciproque approchée 1/x (f32x4)
+    Simd128Rsqrt = 0xB6,      // Réciproque approchée de la racine 1/sqrt(x) (f32x4)
+    Simd128HAdd = 0xB7,       // Somme horizontale des lanes vers un registre général
+    Simd128HMin = 0xB8,       // Minimum horizontal des lanes vers un registre général
+    Simd128HMax = 0xB9,       // Maximum horizontal des lanes vers un registre général
+    Simd128FMA = 0xBA,        // Multiplication-addition fusionnée Vd = Va * Vb + Vc (f32x4)
+    Simd128MaskedLoad = 0xBB, // Chargement des lanes i32 sélectionnées par un masque (autres à zéro)
+    Simd128MaskedStore = 0xBC, // Stockage des seules lanes i32 sélectionnées par un masque
+    Simd128ExtractLane = 0xBD, // Copie d'une lane i32 vers un registre général
+    Simd128InsertLane = 0xBE, // Copie d'un registre général dans une lane i32
+    Simd128Blend = 0xBF,      // Sélection par lane: Va si le bit de signe du masque est levé, Vb sinon
 
 
     // Instructions SIMD 256-bit (0xC0 - 0xDF)  
@@ -126,6 +186,10 @@ pub enum Opcode {
     Simd256ConstI16x16 = 0xD2, // Constante vectorielle 256-bit (i16x16)
     Simd256ConstI64x4 = 0xD3, // Constante vectorielle 256-bit (i64x4)
     Simd256ConstF64x4 = 0xD4, // Constante vectorielle 256-bit (f64x4)
+    Simd256AddI64x4 = 0xD5,   // Addition vectorielle i64x4
+    Simd256SubI64x4 = 0xD6,   // Soustraction vectorielle i64x4
+    Simd256MulI64x4 = 0xD7,   // Multiplication vectorielle i64x4
+    //0xD8 - 0xDF : Réservé pour autres instructions SIMD 256-bit
 
     // Instructions FPU (0xE0 - 0xEF)
     FpuAdd = 0xE0,           // Addition flottante
@@ -145,7 +209,15 @@ pub enum Opcode {
 
     // Instruction etendues (0xF0 - 0xFF)
     Extended = 0xF0,
-    //0xF1 - 0xFF : Réservé pour les futures instructions etendues
+    // Opérations SIMD 128-bit à lanes 64 bits (0xF1 - 0xF7), la plage 0xA0 - 0xBF étant occupée
+    Simd128AddI64x2 = 0xF1,  // Addition vectorielle i64x2
+    Simd128SubI64x2 = 0xF2,  // Soustraction vectorielle i64x2
+    Simd128MulI64x2 = 0xF3,  // Multiplication vectorielle i64x2
+    Simd128AddF64x2 = 0xF4,  // Addition vectorielle f64x2
+    Simd128SubF64x2 = 0xF5,  // Soustraction vectorielle f64x2
+    Simd128MulF64x2 = 0xF6,  // Multiplication vectorielle f64x2
+    Simd128DivF64x2 = 0xF7,  // Division vectorielle f64x2
+    //0xF8 - 0xFF : Réservé pour les futures instructions etendues
 
 }
 
@@ -177,6 +249,26 @@ impl Opcode {
             0x06 => Some(Self::Inc),
             0x07 => Some(Self::Dec),
             0x08 => Some(Self::Neg),
+            0x09 => Some(Self::Mov),
+            0x0A => Some(Self::Crc32),
+            0x0B => Some(Self::AddSat),
+            0x0C => Some(Self::SubSat),
+            0x0D => Some(Self::CmovEqual),
+            0x0E => Some(Self::CmovNotEqual),
+            0x0F => Some(Self::CmovGreater),
+            0x10 => Some(Self::CmovGreaterEqual),
+            0x11 => Some(Self::CmovLess),
+            0x12 => Some(Self::CmovLessEqual),
+            0x13 => Some(Self::CmovAbove),
+            0x14 => Some(Self::CmovAboveEqual),
+            0x15 => Some(Self::CmovBelow),
+            0x16 => Some(Self::CmovBelowEqual),
+            0x17 => Some(Self::AddSatD),
+            0x18 => Some(Self::SubSatD),
+            0x19 => Some(Self::AddSatS),
+            0x1A => Some(Self::SubSatS),
+            0x1B => Some(Self::AddSatSD),
+            0x1C => Some(Self::SubSatSD),
 
             0x20 => Some(Self::And),
             0x21 => Some(Self::Or),
@@ -187,6 +279,13 @@ impl Opcode {
             0x26 => Some(Self::Sar),
             0x27 => Some(Self::Rol),
             0x28 => Some(Self::Ror),
+            0x29 => Some(Self::Shld),
+            0x2A => Some(Self::Shrd),
+            0x2B => Some(Self::Popcnt),
+            0x2C => Some(Self::Clz),
+            0x2D => Some(Self::Ctz),
+            0x2E => Some(Self::Bfe),
+            0x2F => Some(Self::Bfi),
 
             0x40 => Some(Self::Jmp),
             0x41 => Some(Self::JmpIf),
@@ -201,8 +300,8 @@ impl Opcode {
             0x4A => Some(Self::JmpIfAboveEqual),
             0x4B => Some(Self::JmpIfBelow),
             0x4C => Some(Self::JmpIfBelowEqual),
-            0x4D => Some(Self::JmpIfZero),
-            0x4E => Some(Self::JmpIfNotZero),
+            0x4D => Some(Self::JmpIfNotZero),
+            0x4E => Some(Self::JmpIfZero),
             0x4F => Some(Self::JmpIfOverflow),
             0x50 => Some(Self::JmpIfNotOverflow),
             0x51 => Some(Self::JmpIfPositive),
@@ -212,6 +311,15 @@ impl Opcode {
             0x54 => Some(Self::Ret),
             0x55 => Some(Self::Cmp),
             0x56 => Some(Self::Test),
+            0x57 => Some(Self::TrapIfEqual),
+            0x58 => Some(Self::TrapIfNotEqual),
+            0x59 => Some(Self::TrapIfGreater),
+            0x5A => Some(Self::TrapIfGreaterEqual),
+            0x5B => Some(Self::TrapIfLess),
+            0x5C => Some(Self::TrapIfLessEqual),
+            0x5D => Some(Self::TrapIfAbove),
+            0x5E => Some(Self::TrapIfAboveEqual),
+            0x5F => Some(Self::TrapIfOverflow),
 
             0x60 => Some(Self::Load),
             0x61 => Some(Self::Store),
@@ -223,9 +331,18 @@ impl Opcode {
             0x67 => Some(Self::StoreD),
             0x68 => Some(Self::Push),
             0x69 => Some(Self::Pop),
+            0x6A => Some(Self::Ldm),
+            0x6B => Some(Self::Stm),
+            0x6C => Some(Self::Lea),
+            0x6D => Some(Self::LoadSB),
+            0x6E => Some(Self::LoadSW),
+            0x6F => Some(Self::LoadSD),
 
             0x80 => Some(Self::Syscall),
             0x81 => Some(Self::Break),
+            0x83 => Some(Self::RdMode),
+            0x85 => Some(Self::RdInstret),
+            0x84 => Some(Self::Sysret),
             0x82 => Some(Self::Halt),
 
             // SIMD 128-bit opcodes
@@ -250,6 +367,17 @@ impl Opcode {
             0xB2 => Some(Self::Simd128ConstI16x8),
             0xB3 => Some(Self::Simd128ConstI64x2),
             0xB4 => Some(Self::Simd128ConstF64x2),
+            0xB5 => Some(Self::Simd128Rcp),
+            0xB6 => Some(Self::Simd128Rsqrt),
+            0xB7 => Some(Self::Simd128HAdd),
+            0xB8 => Some(Self::Simd128HMin),
+            0xB9 => Some(Self::Simd128HMax),
+            0xBA => Some(Self::Simd128FMA),
+            0xBB => Some(Self::Simd128MaskedLoad),
+            0xBC => Some(Self::Simd128MaskedStore),
+            0xBD => Some(Self::Simd128ExtractLane),
+            0xBE => Some(Self::Simd128InsertLane),
+            0xBF => Some(Self::Simd128Blend),
 
             // SIMD 256-bit opcodes
             0xC0 => Some(Self::Simd256Add),
@@ -273,6 +401,9 @@ impl Opcode {
             0xD2 => Some(Self::Simd256ConstI16x16),
             0xD3 => Some(Self::Simd256ConstI64x4),
             0xD4 => Some(Self::Simd256ConstF64x4),
+            0xD5 => Some(Self::Simd256AddI64x4),
+            0xD6 => Some(Self::Simd256SubI64x4),
+            0xD7 => Some(Self::Simd256MulI64x4),
 
             // FPU opcodes
             0xE0 => Some(Self::FpuAdd),
@@ -290,6 +421,13 @@ impl Opcode {
             0xEC => Some(Self::FpuMax),
 
             0xF0 => Some(Self::Extended),
+            0xF1 => Some(Self::Simd128AddI64x2),
+            0xF2 => Some(Self::Simd128SubI64x2),
+            0xF3 => Some(Self::Simd128MulI64x2),
+            0xF4 => Some(Self::Simd128AddF64x2),
+            0xF5 => Some(Self::Simd128SubF64x2),
+            0xF6 => Some(Self::Simd128MulF64x2),
+            0xF7 => Some(Self::Simd128DivF64x2),
             // 0xFF => Some(Self::Invalid),
             _ => None,
         }
@@ -339,6 +477,39 @@ impl Opcode {
         matches!(self , Self::Call)
     }
 
+    /// Indique si l'opcode est un trap conditionnel (lit les flags sans changer le flot)
+    pub fn is_conditional_trap(&self) -> bool {
+        matches!(
+            self,
+            Self::TrapIfEqual
+                | Self::TrapIfNotEqual
+                | Self::TrapIfGreater
+                | Self::TrapIfGreaterEqual
+                | Self::TrapIfLess
+                | Self::TrapIfLessEqual
+                | Self::TrapIfAbove
+                | Self::TrapIfAboveEqual
+                | Self::TrapIfOverflow
+        )
+    }
+
+    /// Indique si l'opcode est un déplacement conditionnel (lit les flags)
+    pub fn is_conditional_move(&self) -> bool {
+        matches!(
+            self,
+            Self::CmovEqual
+                | Self::CmovNotEqual
+                | Self::CmovGreater
+                | Self::CmovGreaterEqual
+                | Self::CmovLess
+                | Self::CmovLessEqual
+                | Self::CmovAbove
+                | Self::CmovAboveEqual
+                | Self::CmovBelow
+                | Self::CmovBelowEqual
+        )
+    }
+
     /// Indique si l'opcode est une instruction de retour pour RAS
     pub fn is_return(&self) -> bool {
         matches!(self, Self::Ret)
@@ -352,7 +523,7 @@ impl Opcode {
             0x40..=0x5F => OpcodeCategory::ControlFlow,
             0x60..=0x7F => OpcodeCategory::Memory,
             0x80..=0x9F => OpcodeCategory::Special,
-            0xA0..=0xBF => OpcodeCategory::Simd128,
+            0xA0..=0xBF | 0xF1..=0xF7 => OpcodeCategory::Simd128,
             0xC0..=0xDF => OpcodeCategory::Simd256,
             0xE0..=0xEF => OpcodeCategory::Fpu,
             0xF0..=0xFF => OpcodeCategory::Extended,
@@ -360,6 +531,13 @@ impl Opcode {
             _ => OpcodeCategory::Unknown,
         }
     }
+    /// Taille encodée d'une instruction de cet opcode pour les types d'opérandes prévus
+    /// (trois au plus, les absents valent `ArgType::None`)
+    pub fn encoded_size(self, operands: &[ArgType]) -> usize {
+        let operand = |i: usize| operands.get(i).copied().unwrap_or(ArgType::None);
+        encoded_size(self, &InstructionFormat::new(operand(0), operand(1), operand(2)))
+    }
+
     pub fn name(&self) -> &'static str{
         match self {
             Self::Nop => "Nop",
@@ -372,6 +550,25 @@ impl Opcode {
             Self::Dec => "Dec",
             Self::Neg => "Neg",
             Self::Mov => "Mov",
+            Self::Crc32 => "Crc32",
+            Self::AddSat => "AddSat",
+            Self::SubSat => "SubSat",
+            Self::AddSatD => "AddSatD",
+            Self::SubSatD => "SubSatD",
+            Self::AddSatS => "AddSatS",
+            Self::SubSatS => "SubSatS",
+            Self::AddSatSD => "AddSatSD",
+            Self::SubSatSD => "SubSatSD",
+            Self::CmovEqual => "CmovEqual",
+            Self::CmovNotEqual => "CmovNotEqual",
+            Self::CmovGreater => "CmovGreater",
+            Self::CmovGreaterEqual => "CmovGreaterEqual",
+            Self::CmovLess => "CmovLess",
+            Self::CmovLessEqual => "CmovLessEqual",
+            Self::CmovAbove => "CmovAbove",
+            Self::CmovAboveEqual => "CmovAboveEqual",
+            Self::CmovBelow => "CmovBelow",
+            Self::CmovBelowEqual => "CmovBelowEqual",
             Self::And => "And",
             Self::Or => "Or",
             Self::Xor => "Xor",
@@ -381,6 +578,13 @@ impl Opcode {
             Self::Sar => "Sar",
             Self::Rol => "Rol",
             Self::Ror => "Ror",
+            Self::Shld => "Shld",
+            Self::Shrd => "Shrd",
+            Self::Popcnt => "Popcnt",
+            Self::Clz => "Clz",
+            Self::Ctz => "Ctz",
+            Self::Bfe => "Bfe",
+            Self::Bfi => "Bfi",
             Self::Jmp => "Jmp",
             Self::JmpIf => "JmpIf",
             Self::JmpIfNot => "JmpIfNot",
@@ -404,6 +608,15 @@ impl Opcode {
             Self::Ret => "Ret",
             Self::Cmp => "Cmp",
             Self::Test => "Test",
+            Self::TrapIfEqual => "TrapIfEqual",
+            Self::TrapIfNotEqual => "TrapIfNotEqual",
+            Self::TrapIfGreater => "TrapIfGreater",
+            Self::TrapIfGreaterEqual => "TrapIfGreaterEqual",
+            Self::TrapIfLess => "TrapIfLess",
+            Self::TrapIfLessEqual => "TrapIfLessEqual",
+            Self::TrapIfAbove => "TrapIfAbove",
+            Self::TrapIfAboveEqual => "TrapIfAboveEqual",
+            Self::TrapIfOverflow => "TrapIfOverflow",
             Self::Load => "Load",
             Self::Store => "Store",
             Self::LoadB => "LoadB",
@@ -414,8 +627,17 @@ impl Opcode {
             Self::StoreD => "StoreD",
             Self::Push => "Push",
             Self::Pop => "Pop",
+            Self::Ldm => "Ldm",
+            Self::Stm => "Stm",
+            Self::Lea => "Lea",
+            Self::LoadSB => "LoadSB",
+            Self::LoadSW => "LoadSW",
+            Self::LoadSD => "LoadSD",
             Self::Syscall => "Syscall",
             Self::Break => "Break",
+            Self::RdMode => "RdMode",
+            Self::RdInstret => "RdInstret",
+            Self::Sysret => "Sysret",
             Self::Halt => "Halt",
             
             // SIMD 128-bit operations
@@ -435,6 +657,17 @@ impl Opcode {
             Self::Simd128Max => "Simd128Max",
             Self::Simd128Sqrt => "Simd128Sqrt",
             Self::Simd128Shuffle => "Simd128Shuffle",
+            Self::Simd128Rcp => "Simd128Rcp",
+            Self::Simd128Rsqrt => "Simd128Rsqrt",
+            Self::Simd128HAdd => "Simd128HAdd",
+            Self::Simd128HMin => "Simd128HMin",
+            Self::Simd128HMax => "Simd128HMax",
+            Self::Simd128FMA => "Simd128FMA",
+            Self::Simd128MaskedLoad => "Simd128MaskedLoad",
+            Self::Simd128MaskedStore => "Simd128MaskedStore",
+            Self::Simd128ExtractLane => "Simd128ExtractLane",
+            Self::Simd128InsertLane => "Simd128InsertLane",
+            Self::Simd128Blend => "Simd128Blend",
             
             // SIMD 256-bit operations
             Self::Simd256Add => "Simd256Add",
@@ -453,6 +686,9 @@ impl Opcode {
             Self::Simd256Max => "Simd256Max",
             Self::Simd256Sqrt => "Simd256Sqrt",
             Self::Simd256Shuffle => "Simd256Shuffle",
+            Self::Simd256AddI64x4 => "Simd256AddI64x4",
+            Self::Simd256SubI64x4 => "Simd256SubI64x4",
+            Self::Simd256MulI64x4 => "Simd256MulI64x4",
             
             // FPU operations
             Self::FpuAdd => "FpuAdd",
@@ -470,6 +706,13 @@ impl Opcode {
             Self::FpuMax => "FpuMax",
             
             Self::Extended => "Extended",
+            Self::Simd128AddI64x2 => "Simd128AddI64x2",
+            Self::Simd128SubI64x2 => "Simd128SubI64x2",
+            Self::Simd128MulI64x2 => "Simd128MulI64x2",
+            Self::Simd128AddF64x2 => "Simd128AddF64x2",
+            Self::Simd128SubF64x2 => "Simd128SubF64x2",
+            Self::Simd128MulF64x2 => "Simd128MulF64x2",
+            Self::Simd128DivF64x2 => "Simd128DivF64x2",
             _ => "Unknown",
 
 
@@ -491,7 +734,17 @@ mod tests {
         // Test des valeurs invalides
         assert_eq!(Opcode::from_u8(0xFF), None);
         // assert_eq!(Opcode::from_u8(0xFF),Some(Opcode::Invalid));
-        assert_eq!(Opcode::from_u8(0x09), None);
+        assert_eq!(Opcode::from_u8(0x1D), None);
+        assert_eq!(Opcode::from_u8(0x09), Some(Opcode::Mov));
+    }
+
+    #[test]
+    fn test_opcode_from_u8_roundtrips_discriminants() {
+        for value in 0..=u8::MAX {
+            if let Some(opcode) = Opcode::from_u8(value) {
+                assert_eq!(opcode as u8, value, "{} décodé depuis {:#04x}", opcode.name(), value);
+            }
+        }
     }
 
     #[test]
@@ -562,6 +815,12 @@ mod tests {
         assert_eq!(Opcode::Load.category(), OpcodeCategory::Memory);
         assert_eq!(Opcode::Syscall.category(), OpcodeCategory::Special);
         assert_eq!(Opcode::Extended.category(), OpcodeCategory::Extended);
+        // Lanes 64 bits: slots 0xF1 - 0xF7, classés SIMD 128-bit; le bloc 256-bit reste libre
+        assert_eq!(Opcode::from_u8(0xF1), Some(Opcode::Simd128AddI64x2));
+        assert_eq!(Opcode::from_u8(0xD8), None);
+        assert_eq!(Opcode::from_u8(0xF8), None);
+        assert_eq!(Opcode::Simd128DivF64x2.category(), OpcodeCategory::Simd128);
+        assert_eq!(Opcode::Simd256MulI64x4.category(), OpcodeCategory::Simd256);
     }
 
     #[test]
@@ -583,6 +842,9 @@ mod tests {
             Opcode::StoreW,
             Opcode::LoadD,
             Opcode::StoreD,
+            Opcode::LoadSB,
+            Opcode::LoadSW,
+            Opcode::LoadSD,
         ];
 
         for op in memory_ops.iter() {
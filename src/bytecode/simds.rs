@@ -664,6 +664,7 @@ mod tests {
         let simd_instruction = Instruction::create_reg_reg_reg(Opcode::Simd128Add, 2, 0, 1);
 
         let de_reg = DecodeExecuteRegister {
+            id: 0,
             instruction: simd_instruction,
             pc: 100,
             rs1: Some(0), // V0
@@ -683,15 +684,15 @@ mod tests {
         let vec1 = Vector128::from_i32x4([1, 2, 3, 4]);
         let vec2 = Vector128::from_i32x4([5, 6, 7, 8]);
         
-        execute_stage.get_vector_alu_mut().write_v128(0, vec1).unwrap();
-        execute_stage.get_vector_alu_mut().write_v128(1, vec2).unwrap();
+        execute_stage.get_vector_alu_ref().borrow_mut().write_v128(0, vec1).unwrap();
+        execute_stage.get_vector_alu_ref().borrow_mut().write_v128(1, vec2).unwrap();
 
         // Exécuter l'instruction
         let result = execute_stage.process_direct(&de_reg, &mut alu);
         assert!(result.is_ok());
 
         // Vérifier le résultat
-        let result_vector = execute_stage.get_vector_alu().read_v128(2).unwrap();
+        let result_vector = execute_stage.get_vector_alu_ref().borrow().read_v128(2).unwrap();
         unsafe {
             assert_eq!(result_vector.i32x4, [6, 8, 10, 12]);
         }
@@ -712,6 +713,7 @@ mod tests {
         let fpu_instruction = Instruction::create_reg_reg_reg(Opcode::FpuAdd, 2, 0, 1);
 
         let de_reg = DecodeExecuteRegister {
+            id: 0,
             instruction: fpu_instruction,
             pc: 100,
             rs1: Some(0), // F0
@@ -728,15 +730,15 @@ mod tests {
         };
 
         // Initialiser les registres FPU avec des valeurs de test
-        execute_stage.get_fpu_mut().write_fp_register(0, 2.5).unwrap();
-        execute_stage.get_fpu_mut().write_fp_register(1, 3.7).unwrap();
+        execute_stage.get_fpu_ref().borrow_mut().write_fp_register(0, 2.5).unwrap();
+        execute_stage.get_fpu_ref().borrow_mut().write_fp_register(1, 3.7).unwrap();
 
         // Exécuter l'instruction
         let result = execute_stage.process_direct(&de_reg, &mut alu);
         assert!(result.is_ok());
 
         // Vérifier le résultat
-        let result_value = execute_stage.get_fpu().read_fp_register(2).unwrap();
+        let result_value = execute_stage.get_fpu_ref().borrow().read_fp_register(2).unwrap();
         assert_eq!(result_value, 6.2);
     }
 }
\ No newline at end of file
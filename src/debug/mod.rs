@@ -3,7 +3,7 @@
 use std::fmt;
 use std::fmt::{Display, Formatter};
 use std::fs::File;
-use std::io::Write;
+use std::io::{BufRead, BufReader, Write};
 use std::path::Path;
 
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -139,6 +139,17 @@ pub enum TraceEvent {
         accuracy: f64,
     },
 
+    /// Retrait d'une instruction: cycles d'entrée en Fetch et de Writeback.
+    /// Le pipeline fait traverser tous ses étages à une instruction dans le cycle de son
+    /// fetch: la latence mesure les stalls subis, pas la profondeur du pipeline.
+    Retire {
+        /// Identifiant attribué au fetch, croissant dans l'ordre du programme
+        id: u64,
+        pc: u32,
+        fetch_cycle: u64,
+        retire_cycle: u64,
+    },
+
 }
 
 impl Display for TraceEvent {
@@ -293,8 +304,294 @@ impl Display for TraceEvent {
                     cycle, pc, predicted_target, actual_target, taken, accuracy * 100.0
                 )
             }
+            TraceEvent::Retire { id, pc, fetch_cycle, retire_cycle } => {
+                write!(
+                    f,
+                    "[{:04}] RETIRE: #{} PC=0x{:08X} FETCH={} LATENCY={}",
+                    retire_cycle, id, pc, fetch_cycle, retire_cycle - fetch_cycle
+                )
+            }
+        }
+    }
+}
+
+impl TraceEvent {
+    /// Cycle auquel l'événement a été enregistré
+    pub fn cycle(&self) -> u64 {
+        match self {
+            TraceEvent::Fetch { cycle, .. }
+            | TraceEvent::Decode { cycle, .. }
+            | TraceEvent::Execute { cycle, .. }
+            | TraceEvent::Memory { cycle, .. }
+            | TraceEvent::Writeback { cycle, .. }
+            | TraceEvent::Hazard { cycle, .. }
+            | TraceEvent::Branch { cycle, .. }
+            | TraceEvent::RegisterUpdate { cycle, .. }
+            | TraceEvent::PipelineStall { cycle, .. }
+            | TraceEvent::PipelineFlush { cycle, .. }
+            | TraceEvent::BranchPrediction { cycle, .. } => *cycle,
+            TraceEvent::Retire { retire_cycle, .. } => *retire_cycle,
+        }
+    }
+
+    /// Sérialise l'événement en objet JSON: champ "event" (type) puis tous ses champs
+    pub fn to_json(&self) -> String {
+        let fields: Vec<(&str, String)> = match self {
+            TraceEvent::Fetch { cycle, pc, instruction } => vec![
+                ("event", json_string("FETCH")),
+                ("cycle", cycle.to_string()),
+                ("pc", pc.to_string()),
+                ("instruction", json_instruction(instruction)),
+            ],
+            TraceEvent::Decode { cycle, pc, instruction, rs1, rs2, rd } => vec![
+                ("event", json_string("DECODE")),
+                ("cycle", cycle.to_string()),
+                ("pc", pc.to_string()),
+                ("instruction", json_instruction(instruction)),
+                ("rs1", json_option(rs1)),
+                ("rs2", json_option(rs2)),
+                ("rd", json_option(rd)),
+            ],
+            TraceEvent::Execute { cycle, pc, target_pc, branch_type, taken, condition } => vec![
+                ("event", json_string("EXECUTE")),
+                ("cycle", cycle.to_string()),
+                ("pc", pc.to_string()),
+                ("target_pc", target_pc.to_string()),
+                ("branch_type", json_string(branch_type)),
+                ("taken", taken.to_string()),
+                ("condition", json_string(condition)),
+            ],
+            TraceEvent::Memory { cycle, pc, instruction, address, value, is_read } => vec![
+                ("event", json_string("MEMORY")),
+                ("cycle", cycle.to_string()),
+                ("pc", pc.to_string()),
+                ("instruction", json_instruction(instruction)),
+                ("address", json_option(address)),
+                ("value", json_option(value)),
+                ("is_read", is_read.to_string()),
+            ],
+            TraceEvent::Writeback { cycle, pc, rd, value } => vec![
+                ("event", json_string("WRITEBACK")),
+                ("cycle", cycle.to_string()),
+                ("pc", pc.to_string()),
+                ("rd", json_option(rd)),
+                ("value", value.to_string()),
+            ],
+            TraceEvent::Hazard { cycle, hazard_type, stall_cycles, description } => vec![
+                ("event", json_string("HAZARD")),
+                ("cycle", cycle.to_string()),
+                ("hazard_type", json_string(hazard_type)),
+                ("stall_cycles", stall_cycles.to_string()),
+                ("description", json_string(description)),
+            ],
+            TraceEvent::Branch { cycle, pc, target_pc, branch_type, taken, condition } => vec![
+                ("event", json_string("BRANCH")),
+                ("cycle", cycle.to_string()),
+                ("pc", pc.to_string()),
+                ("target_pc", target_pc.to_string()),
+                ("branch_type", json_string(branch_type)),
+                ("taken", taken.to_string()),
+                ("condition", json_string(condition)),
+            ],
+            TraceEvent::RegisterUpdate { cycle, pc, register, old_value, new_value, source } => vec![
+                ("event", json_string("REG_UPDATE")),
+                ("cycle", cycle.to_string()),
+                ("pc", pc.to_string()),
+                ("register", register.to_string()),
+                ("old_value", old_value.to_string()),
+                ("new_value", new_value.to_string()),
+                ("source", json_string(source)),
+            ],
+            TraceEvent::PipelineStall { cycle, reason } => vec![
+                ("event", json_string("STALL")),
+                ("cycle", cycle.to_string()),
+                ("reason", json_string(reason)),
+            ],
+            TraceEvent::PipelineFlush { cycle, reason } => vec![
+                ("event", json_string("FLUSH")),
+                ("cycle", cycle.to_string()),
+                ("reason", json_string(reason)),
+            ],
+            TraceEvent::BranchPrediction { cycle, pc, predicted_target, actual_target, taken, accuracy } => vec![
+                ("event", json_string("BRANCH_PREDICTION")),
+                ("cycle", cycle.to_string()),
+                ("pc", pc.to_string()),
+                ("predicted_target", predicted_target.to_string()),
+                ("actual_target", actual_target.to_string()),
+                ("taken", taken.to_string()),
+                // JSON n'accepte ni NaN ni l'infini
+                ("accuracy", if accuracy.is_finite() { accuracy.to_string() } else { "null".to_string() }),
+            ],
+            TraceEvent::Retire { id, pc, fetch_cycle, retire_cycle } => vec![
+                ("event", json_string("RETIRE")),
+                ("cycle", retire_cycle.to_string()),
+                ("id", id.to_string()),
+                ("pc", pc.to_string()),
+                ("fetch_cycle", fetch_cycle.to_string()),
+                ("retire_cycle", retire_cycle.to_string()),
+            ],
+        };
+
+        let body: Vec<String> = fields.iter().map(|(name, value)| format!("\"{}\":{}", name, value)).collect();
+        format!("{{{}}}", body.join(","))
+    }
+}
+
+impl TraceEvent {
+    /// Colonnes CSV: cycle, type d'événement, PC éventuel, instruction (opcode) et détails
+    fn csv_columns(&self) -> (u64, &'static str, Option<u32>, String, String) {
+        let opcode = |instruction: &Option<Instruction>| {
+            instruction.as_ref().map_or(String::new(), |i| format!("{:?}", i.opcode))
+        };
+        let reg = |r: &Option<usize>| r.map_or("None".to_string(), |r| format!("R{}", r));
+
+        match self {
+            TraceEvent::Fetch { cycle, pc, instruction } => {
+                (*cycle, "FETCH", Some(*pc), opcode(instruction), String::new())
+            }
+            TraceEvent::Decode { cycle, pc, instruction, rs1, rs2, rd } => (
+                *cycle,
+                "DECODE",
+                Some(*pc),
+                opcode(instruction),
+                format!("RS1={} RS2={} RD={}", reg(rs1), reg(rs2), reg(rd)),
+            ),
+            TraceEvent::Execute { cycle, pc, target_pc, branch_type, taken, condition } => (
+                *cycle,
+                "EXECUTE",
+                Some(*pc),
+                String::new(),
+                format!("TARGET=0x{:08X} TYPE={} TAKEN={} COND={}", target_pc, branch_type, taken, condition),
+            ),
+            TraceEvent::Memory { cycle, pc, instruction, address, value, is_read } => {
+                let address = address.map_or("None".to_string(), |a| format!("0x{:08X}", a));
+                let value = value.map_or("None".to_string(), |v| format!("0x{:08X}", v));
+                let access = if *is_read { "READ" } else { "WRITE" };
+                (*cycle, "MEMORY", Some(*pc), opcode(instruction), format!("{} ADDR={} VALUE={}", access, address, value))
+            }
+            TraceEvent::Writeback { cycle, pc, rd, value } => (
+                *cycle,
+                "WRITEBACK",
+                Some(*pc),
+                String::new(),
+                format!("RD={} VALUE=0x{:016X}", reg(rd), value),
+            ),
+            TraceEvent::Hazard { cycle, hazard_type, stall_cycles, description } => (
+                *cycle,
+                "HAZARD",
+                None,
+                String::new(),
+                format!("TYPE={} STALLS={} {}", hazard_type, stall_cycles, description),
+            ),
+            TraceEvent::Branch { cycle, pc, target_pc, branch_type, taken, condition } => (
+                *cycle,
+                "BRANCH",
+                Some(*pc),
+                String::new(),
+                format!("TARGET=0x{:08X} TAKEN={} TYPE={} COND={}", target_pc, taken, branch_type, condition),
+            ),
+            TraceEvent::RegisterUpdate { cycle, pc, register, old_value, new_value, source } => (
+                *cycle,
+                "REG_UPDATE",
+                Some(*pc),
+                String::new(),
+                format!("R{}=0x{:016X} OLD=0x{:016X} SRC={}", register, new_value, old_value, source),
+            ),
+            TraceEvent::PipelineStall { cycle, reason } => (*cycle, "STALL", None, String::new(), reason.clone()),
+            TraceEvent::PipelineFlush { cycle, reason } => (*cycle, "FLUSH", None, String::new(), reason.clone()),
+            TraceEvent::BranchPrediction { cycle, pc, predicted_target, actual_target, taken, accuracy } => (
+                *cycle,
+                "BRANCH_PREDICTION",
+                Some(*pc),
+                String::new(),
+                format!(
+                    "PREDICTED=0x{:08X} ACTUAL=0x{:08X} TAKEN={} ACCURACY={:.2}%",
+                    predicted_target, actual_target, taken, accuracy * 100.0
+                ),
+            ),
+            TraceEvent::Retire { id, pc, fetch_cycle, retire_cycle } => (
+                *retire_cycle,
+                "RETIRE",
+                Some(*pc),
+                String::new(),
+                format!("ID={} FETCH={} LATENCY={}", id, fetch_cycle, retire_cycle - fetch_cycle),
+            ),
+        }
+    }
+}
+
+/// Champ CSV, entre guillemets (doublés à l'intérieur) s'il contient un séparateur
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Chaîne JSON échappée
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
         }
     }
+    out.push('"');
+    out
+}
+
+/// Valeur numérique optionnelle (null si absente)
+fn json_option<T: Display>(value: &Option<T>) -> String {
+    value.as_ref().map_or("null".to_string(), |v| v.to_string())
+}
+
+/// Instruction sous forme {"opcode": ..., "args": [...]}, ou null
+fn json_instruction(instruction: &Option<Instruction>) -> String {
+    match instruction {
+        Some(instr) => {
+            let args: Vec<String> = instr.args.iter().map(|b| b.to_string()).collect();
+            format!(
+                "{{\"opcode\":{},\"args\":[{}]}}",
+                json_string(&format!("{:?}", instr.opcode)),
+                args.join(",")
+            )
+        }
+        None => "null".to_string(),
+    }
+}
+
+/// En-tête d'un fichier de trace de référence ("golden trace")
+const GOLDEN_TRACE_HEADER: &str = "# PunkVM golden trace v1";
+
+/// Premier écart entre la trace courante et une trace de référence
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoldenMismatch {
+    /// Index de l'événement dans la séquence
+    pub index: usize,
+    /// Evénement attendu (None si la trace courante est plus longue)
+    pub expected: Option<String>,
+    /// Evénement obtenu (None si la trace courante est plus courte)
+    pub actual: Option<String>,
+}
+
+impl Display for GoldenMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "événement #{}: attendu `{}`, obtenu `{}`",
+            self.index,
+            self.expected.as_deref().unwrap_or("<fin de trace>"),
+            self.actual.as_deref().unwrap_or("<fin de trace>")
+        )
+    }
 }
 
 //Gestion de tracage
@@ -303,6 +600,7 @@ pub struct PipelineTracer {
     // tracer_events: Arc<Mutex<Vec<TraceEvent>>>,
     trace_events: Vec<TraceEvent>,
     current_cycle: u64,
+    sampling_rate: u64, // Seul un cycle sur `sampling_rate` voit son état de pipeline tracé
     log_file: Option<File>,
 }
 
@@ -338,6 +636,7 @@ impl PipelineTracer {
             config,
             trace_events: Vec::new(),
             current_cycle: 0,
+            sampling_rate: 1,
             log_file,
         }
     }
@@ -354,7 +653,7 @@ impl PipelineTracer {
             TraceEvent::Decode { .. } => self.config.trace_decode,
             TraceEvent::Execute { .. } => self.config.trace_execute,
             TraceEvent::Memory { .. } => self.config.trace_memory,
-            TraceEvent::Writeback { .. } => self.config.trace_writeback,
+            TraceEvent::Writeback { .. } | TraceEvent::Retire { .. } => self.config.trace_writeback,
             TraceEvent::Hazard { .. } => self.config.trace_hazards,
             TraceEvent::Branch { .. } => self.config.trace_branches,
             TraceEvent::RegisterUpdate { .. } => self.config.trace_registers,
@@ -386,9 +685,15 @@ impl PipelineTracer {
         self.current_cycle = cycle;
     }
 
+    /// Échantillonnage pour les longues exécutions: l'état du pipeline n'est tracé
+    /// qu'un cycle sur `rate` (1 = chaque cycle, 0 est traité comme 1)
+    pub fn set_sampling_rate(&mut self, rate: u64) {
+        self.sampling_rate = rate.max(1);
+    }
+
     // Trace un état complet du pipeline
     pub fn trace_pipeline_state(&mut self, state: &PipelineState, registers: &[u64]) {
-        if !self.config.enabled {
+        if !self.config.enabled || !self.current_cycle.is_multiple_of(self.sampling_rate) {
             return;
         }
 
@@ -424,7 +729,7 @@ impl PipelineTracer {
             // Tracer l'étage Execute
             self.trace(TraceEvent::Execute {
                 cycle: self.current_cycle,
-                pc: 0, // PC non disponible dans ExecuteMemoryRegister
+                pc: em_reg.pc,
                 // target_pc: em_reg.target_pc,$
                 target_pc: em_reg.branch_target.unwrap_or(0), // Utiliser la cible de branchement si disponible
 
@@ -442,7 +747,7 @@ impl PipelineTracer {
                 if let Some(target) = em_reg.branch_target {
                     self.trace(TraceEvent::Branch {
                         cycle: self.current_cycle,
-                        pc: 0, // PC non disponible directement
+                        pc: em_reg.pc,
                         target_pc: target,
                         branch_type: format!("{:?}", em_reg.instruction.opcode),
                         taken: true,
@@ -460,7 +765,7 @@ impl PipelineTracer {
                 if rd < registers.len() {
                     self.trace(TraceEvent::Writeback {
                         cycle: self.current_cycle,
-                        pc: 0, // PC non disponible directement
+                        pc: mw_reg.pc,
                         rd: Some(rd),
                         value: mw_reg.result,
                     });
@@ -468,6 +773,15 @@ impl PipelineTracer {
             }
         }
 
+        for timing in &state.retired {
+            self.trace(TraceEvent::Retire {
+                id: timing.id,
+                pc: timing.pc,
+                fetch_cycle: timing.fetch_cycle,
+                retire_cycle: timing.retire_cycle,
+            });
+        }
+
         // Tracer les prediction de branchement
         // if let Some(bp_reg) = &state.branch_prediction {
         //     self.trace(TraceEvent::BranchPrediction {
@@ -486,6 +800,7 @@ impl PipelineTracer {
     // Trace une modification de registre
     pub fn trace_register_update(
         &mut self,
+        pc: u32,
         register: usize,
         old_value: u64,
         new_value: u64,
@@ -497,7 +812,7 @@ impl PipelineTracer {
 
         self.trace(TraceEvent::RegisterUpdate {
             cycle: self.current_cycle,
-            pc: 0, // PC non disponible directement
+            pc,
             register,
             old_value,
             new_value,
@@ -512,47 +827,81 @@ impl PipelineTracer {
         // Écrire l'en-tête
         writeln!(file, "Cycle,Event,PC,Instruction,Details")?;
 
-        // Écrire les événements
+        // Une ligne de 5 colonnes par événement; PC et Instruction restent vides s'ils ne s'appliquent pas
         for event in &self.trace_events {
-            match event {
-                TraceEvent::Fetch {
-                    cycle,
-                    pc,
-                    instruction,
-                } => {
-                    let instr_str = instruction
-                        .as_ref()
-                        .map_or("None".to_string(), |i| format!("{:?}", i.opcode));
-                    writeln!(file, "{},FETCH,0x{:08X},{},", cycle, pc, instr_str)?;
-                }
-                TraceEvent::Decode {
-                    cycle,
-                    pc,
-                    instruction,
-                    rs1,
-                    rs2,
-                    rd,
-                } => {
-                    let instr_str = instruction
-                        .as_ref()
-                        .map_or("None".to_string(), |i| format!("{:?}", i.opcode));
-                    let rs1_str = rs1.map_or("None".to_string(), |r| format!("R{}", r));
-                    let rs2_str = rs2.map_or("None".to_string(), |r| format!("R{}", r));
-                    let rd_str = rd.map_or("None".to_string(), |r| format!("R{}", r));
-                    writeln!(
-                        file,
-                        "{},DECODE,0x{:08X},{},\"RS1={} RS2={} RD={}\"",
-                        cycle, pc, instr_str, rs1_str, rs2_str, rd_str
-                    )?;
-                }
-                // Ajouter les autres types d'événements...
-                _ => writeln!(file, "{},{}", event.to_string(), "")?,
-            }
+            let (cycle, name, pc, instruction, details) = event.csv_columns();
+            let pc_str = pc.map_or(String::new(), |pc| format!("0x{:08X}", pc));
+            writeln!(
+                file,
+                "{},{},{},{},{}",
+                cycle,
+                name,
+                pc_str,
+                csv_field(&instruction),
+                csv_field(&details)
+            )?;
         }
 
         Ok(())
     }
 
+    /// Exporte tous les événements dans un tableau JSON (un objet par événement)
+    pub fn export_to_json<P: AsRef<Path>>(&self, path: P) -> VMResult<()> {
+        let mut file = File::create(path)?;
+        writeln!(file, "[")?;
+        for (index, event) in self.trace_events.iter().enumerate() {
+            let separator = if index + 1 < self.trace_events.len() { "," } else { "" };
+            writeln!(file, "  {}{}", event.to_json(), separator)?;
+        }
+        writeln!(file, "]")?;
+        Ok(())
+    }
+
+    /// Événements enregistrés, dans l'ordre
+    pub fn events(&self) -> &[TraceEvent] {
+        &self.trace_events
+    }
+
+    /// Ecrit la séquence canonique des événements (une ligne par événement) comme trace de référence
+    pub fn capture_golden<P: AsRef<Path>>(&self, path: P) -> VMResult<()> {
+        let mut file = File::create(path)?;
+        writeln!(file, "{}", GOLDEN_TRACE_HEADER)?;
+        for event in &self.trace_events {
+            writeln!(file, "{}", event)?;
+        }
+        Ok(())
+    }
+
+    /// Compare les événements enregistrés à une trace de référence.
+    /// Retourne le premier écart, ou None si les séquences sont identiques.
+    pub fn compare_against_golden<P: AsRef<Path>>(&self, path: P) -> VMResult<Option<GoldenMismatch>> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut expected_lines = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            // Les lignes de commentaire (dont l'en-tête) sont ignorées
+            if !line.starts_with('#') {
+                expected_lines.push(line);
+            }
+        }
+
+        let actual_lines: Vec<String> = self.trace_events.iter().map(|e| e.to_string()).collect();
+        let len = expected_lines.len().max(actual_lines.len());
+        for index in 0..len {
+            let expected = expected_lines.get(index);
+            let actual = actual_lines.get(index);
+            if expected != actual {
+                return Ok(Some(GoldenMismatch {
+                    index,
+                    expected: expected.cloned(),
+                    actual: actual.cloned(),
+                }));
+            }
+        }
+
+        Ok(None)
+    }
+
     // Génère un rapport de synthèse des événements de traçage
     pub fn generate_summary(&self) -> String {
 
@@ -600,6 +949,21 @@ impl PipelineTracer {
         summary.push_str(&format!("Nombre de flushes: {}\n", flush_count));
         summary.push_str(&format!("Nombre de prédictions de branche: {}\n", branch_prediction_count));
 
+        let latencies: Vec<u64> = self
+            .trace_events
+            .iter()
+            .filter_map(|e| match e {
+                TraceEvent::Retire { fetch_cycle, retire_cycle, .. } => Some(retire_cycle - fetch_cycle),
+                _ => None,
+            })
+            .collect();
+        if !latencies.is_empty() {
+            summary.push_str(&format!(
+                "Latence moyenne des instructions (fetch -> retrait): {:.2} cycles\n",
+                latencies.iter().sum::<u64>() as f64 / latencies.len() as f64
+            ));
+        }
+
         // Statistiques des branchements
         if branch_count > 0 {
             let branches: Vec<_> = self
@@ -742,17 +1106,19 @@ mod tests {
         assert_eq!(tracer.trace_events.len(), 2);
 
         // Test d'un événement de mise à jour de registre
-        tracer.trace_register_update(1, 0, 42, "EXEC");
+        tracer.trace_register_update(0x1000, 1, 0, 42, "EXEC");
         assert_eq!(tracer.trace_events.len(), 3);
 
         // Vérification du dernier événement
         if let TraceEvent::RegisterUpdate {
+            pc,
             register,
             old_value,
             new_value,
             source,
             ..
         } = &tracer.trace_events[2] {
+            assert_eq!(*pc, 0x1000);
             assert_eq!(*register, 1);
             assert_eq!(*old_value, 0);
             assert_eq!(*new_value, 42);
@@ -817,6 +1183,67 @@ mod tests {
         tracer.trace(decode_event);
         assert_eq!(tracer.trace_events.len(), 1);
     }
-}
 
+    /// Découpe une ligne CSV en respectant les champs entre guillemets
+    fn split_csv_line(line: &str) -> Vec<String> {
+        let mut fields = vec![String::new()];
+        let mut in_quotes = false;
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if in_quotes && chars.peek() == Some(&'"') => {
+                    chars.next();
+                    fields.last_mut().unwrap().push('"');
+                }
+                '"' => in_quotes = !in_quotes,
+                ',' if !in_quotes => fields.push(String::new()),
+                c => fields.last_mut().unwrap().push(c),
+            }
+        }
+        fields
+    }
 
+    #[test]
+    fn test_export_to_csv_covers_every_event() {
+        let mut tracer = PipelineTracer::new(TracerConfig { enabled: true, log_to_console: false, ..TracerConfig::default() });
+        let instruction = Some(Instruction::create_load_reg_offset(1, 2, 0));
+        let events = vec![
+            TraceEvent::Fetch { cycle: 1, pc: 0x10, instruction: instruction.clone() },
+            TraceEvent::Decode { cycle: 1, pc: 0x10, instruction: instruction.clone(), rs1: Some(2), rs2: None, rd: Some(1) },
+            TraceEvent::Execute { cycle: 2, pc: 0x10, target_pc: 0, branch_type: "None".to_string(), taken: false, condition: "-".to_string() },
+            TraceEvent::Memory { cycle: 3, pc: 0x10, instruction, address: Some(0x4000), value: Some(7), is_read: true },
+            TraceEvent::Writeback { cycle: 4, pc: 0x10, rd: Some(1), value: 7 },
+            TraceEvent::Hazard { cycle: 5, hazard_type: "LoadUse".to_string(), stall_cycles: 1, description: "R1, \"en vol\"".to_string() },
+            TraceEvent::Branch { cycle: 6, pc: 0x20, target_pc: 0x10, branch_type: "JmpIfNotEqual".to_string(), taken: true, condition: "ZF=0".to_string() },
+            TraceEvent::RegisterUpdate { cycle: 7, pc: 0x20, register: 1, old_value: 0, new_value: 7, source: "WB".to_string() },
+            TraceEvent::PipelineStall { cycle: 8, reason: "load-use".to_string() },
+            TraceEvent::PipelineFlush { cycle: 9, reason: "mauvaise prédiction".to_string() },
+            TraceEvent::BranchPrediction { cycle: 10, pc: 0x20, predicted_target: 0x10, actual_target: 0x10, taken: true, accuracy: 1.0 },
+            TraceEvent::Retire { id: 3, pc: 0x10, fetch_cycle: 1, retire_cycle: 4 },
+        ];
+        for event in events {
+            tracer.trace(event);
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("trace.csv");
+        tracer.export_to_csv(&path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        let rows: Vec<Vec<String>> = content.lines().map(split_csv_line).collect();
+
+        assert_eq!(rows.len(), 1 + 12);
+        assert!(rows.iter().all(|row| row.len() == 5), "{:?}", rows);
+        let names: Vec<&str> = rows[1..].iter().map(|row| row[1].as_str()).collect();
+        assert_eq!(
+            names,
+            ["FETCH", "DECODE", "EXECUTE", "MEMORY", "WRITEBACK", "HAZARD", "BRANCH", "REG_UPDATE", "STALL", "FLUSH", "BRANCH_PREDICTION", "RETIRE"]
+        );
+
+        assert_eq!(rows[4][2], "0x00000010");
+        assert_eq!(rows[4][3], "Load");
+        assert!(rows[4][4].contains("READ") && rows[4][4].contains("ADDR=0x00004000"));
+        assert!(rows[6][4].ends_with("R1, \"en vol\""));
+        assert_eq!(rows[6][2], "");
+        assert!(rows[7][4].contains("TARGET=0x00000010") && rows[7][4].contains("TAKEN=true"));
+    }
+}
@@ -8,12 +8,12 @@ use crate::bytecode::instructions::Instruction;
 use crate::bytecode::opcodes::Opcode;
 use crate::BytecodeFile;
 
-fn main() -> std::io::Result<()> {
+fn main() -> crate::pvm::vm_errors::VMResult<()> {
     // Création d'un nouveau fichier bytecode
     let mut bytecode = BytecodeFile::new();
 
     // Définition de la version
-    bytecode.version = BytecodeVersion::new(0, 1, 0, 0);
+    bytecode.version = BytecodeVersion::SECTIONED;
 
     // Ajout de métadonnées
     bytecode.add_metadata("name", "Exemple PunkVM");
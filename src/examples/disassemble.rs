@@ -89,6 +89,19 @@ fn main() -> VMResult<()> {
                         }
                     }
 
+                    (ArgValue::Register(r1), ArgValue::Indexed(base, index, scale, offset)) => {
+                        format!(
+                            "{} R{}, [R{}+R{}*{}{}{}]",
+                            instr_str,
+                            r1,
+                            base,
+                            index,
+                            scale,
+                            if offset >= 0 { "+" } else { "" },
+                            offset
+                        )
+                    }
+
                     (ArgValue::Register(r1), ArgValue::RegisterOffset(r2, offset)) => {
                         format!(
                             "{} R{}, [R{}{}{}]",
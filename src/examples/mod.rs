@@ -1,3 +1,6 @@
 pub mod branch_debug;
 pub mod create_bytecode;
 pub mod disassemble;
+pub mod recursive_factorial;
+
+pub use recursive_factorial::{recursive_factorial, run_and_verify};
@@ -0,0 +1,116 @@
+//src/examples/recursive_factorial.rs
+
+use crate::bytecode::instructions::Instruction;
+use crate::bytecode::opcodes::Opcode;
+use crate::pvm::harness::build_program;
+use crate::pvm::vm::{PunkVM, VMConfig};
+use crate::pvm::vm_errors::{VMError, VMResult};
+use crate::BytecodeFile;
+
+/// Plus grand n dont la factorielle tient dans un registre 64 bits
+pub const MAX_FACTORIAL_ARG: u8 = 20;
+
+/// Programme calculant n! de façon réellement récursive (un CALL par niveau).
+///
+/// Convention d'appel: argument dans R0, résultat dans R1, R2 = constante 1.
+///
+/// ```text
+/// main:  MOV R0, n ; MOV R2, 1 ; CALL fact ; HALT
+/// fact:  CMP R0, R2 ; JLE base          ; cas de base n <= 1
+///        PUSH R0 ; DEC R0 ; CALL fact   ; R1 = (n-1)!
+///        POP R3 ; MUL R1, R1, R3 ; RET  ; R1 = n * (n-1)!
+/// base:  MOV R1, 1 ; RET
+/// ```
+pub fn recursive_factorial(n: u8) -> BytecodeFile {
+    let size = |instr: &Instruction| instr.total_size() as u32;
+    let call_size = size(&Instruction::create_call_relative(0, 0));
+    let jle_size = size(&Instruction::create_jump_if_less_equal(0, 0));
+
+    let mut main = vec![
+        Instruction::create_reg_imm8(Opcode::Mov, 0, n),
+        Instruction::create_reg_imm8(Opcode::Mov, 2, 1),
+    ];
+    let call_addr = Instruction::calculate_current_address(&main);
+    let fact_addr = call_addr + call_size + size(&Instruction::create_no_args(Opcode::Halt));
+    main.push(Instruction::create_call_relative(call_addr, fact_addr));
+    main.push(Instruction::create_no_args(Opcode::Halt));
+
+    let compare = Instruction::create_reg_reg(Opcode::Cmp, 0, 2);
+    let recursive_case = [
+        Instruction::create_push_register(0),
+        Instruction::create_single_reg(Opcode::Dec, 0),
+    ];
+    let after_call = [
+        Instruction::create_pop_register(3),
+        Instruction::create_reg_reg_reg(Opcode::Mul, 1, 1, 3),
+        Instruction::create_return(),
+    ];
+
+    let jle_addr = fact_addr + size(&compare);
+    let recursive_call_addr = jle_addr + jle_size + recursive_case.iter().map(size).sum::<u32>();
+    let base_addr = recursive_call_addr + call_size + after_call.iter().map(size).sum::<u32>();
+
+    let mut code = main;
+    code.push(compare);
+    code.push(Instruction::create_jump_if_less_equal(jle_addr, base_addr));
+    code.extend(recursive_case);
+    code.push(Instruction::create_call_relative(recursive_call_addr, fact_addr));
+    code.extend(after_call);
+    code.push(Instruction::create_reg_imm8(Opcode::Mov, 1, 1));
+    code.push(Instruction::create_return());
+
+    let mut program = build_program(code);
+    program.add_metadata("name", &format!("factorial({})", n));
+    program
+}
+
+/// Exécute `recursive_factorial(n)` et vérifie que R1 contient bien n!.
+/// Retourne la VM pour permettre l'inspection des statistiques (RAS, pile).
+pub fn run_and_verify(n: u8) -> VMResult<PunkVM> {
+    if n > MAX_FACTORIAL_ARG {
+        return Err(VMError::config_error(&format!(
+            "factorial({}) dépasse 64 bits (maximum {})", n, MAX_FACTORIAL_ARG
+        )));
+    }
+
+    // La pile doit se trouver dans la mémoire simulée pour les PUSH et les CALL
+    let config = VMConfig { stack_base: 0x8000, stack_size: 0x1000, ..VMConfig::default() };
+    let mut vm = PunkVM::with_config(config);
+    vm.load_program_from_bytecode(recursive_factorial(n))?;
+    vm.run()?;
+
+    let expected: u64 = (1..=n as u64).product();
+    if vm.registers[1] != expected {
+        return Err(VMError::execution_error(&format!(
+            "factorial({}) = {} au lieu de {}", n, vm.registers[1], expected
+        )));
+    }
+    Ok(vm)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recursive_factorial_of_five() {
+        let vm = run_and_verify(5).unwrap();
+        assert_eq!(vm.registers[1], 120);
+
+        // Un CALL depuis main puis un par niveau de récursion (5, 4, 3, 2)
+        let ras = vm.get_ras_stats();
+        assert_eq!((ras.pushes, ras.pops), (5, 5));
+        assert_eq!(ras.hits, 5);
+        assert_eq!(ras.misses, 0);
+
+        let stats = vm.stats();
+        assert!(stats.stack_pushes >= 4, "pushes: {}", stats.stack_pushes);
+    }
+
+    #[test]
+    fn test_recursive_factorial_base_cases_and_limit() {
+        assert_eq!(run_and_verify(0).unwrap().registers[1], 1);
+        assert_eq!(run_and_verify(1).unwrap().registers[1], 1);
+        assert!(run_and_verify(MAX_FACTORIAL_ARG + 1).is_err());
+    }
+}
@@ -10,4 +10,5 @@ pub mod tests;
 // Re-export des modules principaux
 pub use bytecode::files::BytecodeFile;
 pub use debug::TracerConfig;
+pub use pvm::harness::{assert_deterministic, execute_program, execute_program_with_options, ExecutionResult, RunResult};
 pub use pvm::vm::PunkVM; // Exporter la configuration du traceur
@@ -1,7 +1,7 @@
 //src/main.rs
 
 
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::time::Instant;
 // use PunkVM::alu::v_alu::VectorResult::Vector128;
 use PunkVM::bytecode::files::{BytecodeFile, BytecodeVersion, SegmentMetadata, SegmentType};
@@ -10,6 +10,9 @@ use PunkVM::bytecode::opcodes::Opcode;
 use PunkVM::bytecode::simds::Vector128;
 use PunkVM::debug::PipelineTracer;
 use PunkVM::pvm::vm::{PunkVM as VM, VMConfig, VMState};
+use PunkVM::pvm::branch_predictor::PredictorType;
+use PunkVM::pvm::cache_configs::{ReplacementPolicy, WritePolicy};
+use PunkVM::pvm::memorys::Endianness;
 use PunkVM::pvm::vm_errors::VMResult;
 
 
@@ -25,14 +28,42 @@ fn main() -> VMResult<()> {
         l1_cache_size: 4 * 1024,       // 4 KB de cache L1
         l2_cache_size: 16 * 1024,      // 16 KB de cache L2
         store_buffer_size: 8,          // 8 entrées dans le store buffer
+        store_drain_rate: 0,           // Éviction immédiate (pas de modèle de vidange)
         stack_size: 4 * 1024,          // 4 KB de pile
         stack_base: 0xC000,            // Base de la pile (48KB) dans la mémoire 64KB
         fetch_buffer_size: 8,          // 8 instructions dans le buffer de fetch
+        issue_width: 1,                // Émission simple (co-issue désactivé)
+        register_write_ports: 2,       // 2 ports d'écriture (dual-issue)
+        mmio_base: 0xFFFF_F000,        // Port console MMIO
+        l1_write_policy: WritePolicy::WriteThrough, // L1 write-through
+        l2_write_policy: WritePolicy::WriteBack,    // L2 write-back
+        cache_replacement_policy: ReplacementPolicy::LRU, // Remplacement LRU
+        cache_rng_seed: 0x5EED,        // Graine de la politique Random
+        endianness: Endianness::Little, // Octet de poids faible en premier
+        copy_on_write_memory: false,   // Mémoire principale contiguë
+        max_cycles: None,              // Pas de limite de cycles
+        misprediction_penalty: 0,      // Pas de pénalité au-delà du flush
+        enable_branch_fusion: false,   // CMP et saut conditionnel restent séparés
+        l1_latency: 1,                 // Hit L1: absorbé par l'étage Memory
+        l2_latency: 12,                // Hit L2
+        memory_latency: 100,           // Miss L1 et L2
+        enable_cache: true,            // Hiérarchie de cache L1/L2 active
+        simd_op_cache_size: 1,         // Seule la dernière opération SIMD est retenue
+        max_speculation_depth: None,   // Spéculation illimitée
         btb_size: 16,                  // 16 entrées dans la BTB
         ras_size: 4,                   // 4 entrées dans le RAS
+        branch_predictor_kind: PredictorType::Hybrid, // Prédicteur hybride (local + gshare)
+        branch_predictor_entries: None, // Tables du prédicteur à leur taille par défaut
         enable_forwarding: true,       // Activer le forwarding
+        forward_ex_to_ex: true,        // Chemin EX/MEM -> EX
+        forward_mem_to_ex: true,       // Chemin MEM/WB -> EX
         enable_hazard_detection: true, // Activer la détection de hazards
         enable_tracing: true,          // Activer le traçage
+        trace_sampling_rate: 1,        // Tracer chaque cycle
+        enable_opcode_histogram: false, // Histogramme des opcodes désactivé
+        enable_icache_prefetch: false, // Prefetch du cache d'instructions désactivé
+        enable_simd256: true,          // Support SIMD 256-bit natif
+        shared_simd_port: false,       // Unité SIMD dédiée
     };
 
     let mut tracer = PipelineTracer::new(Default::default());
@@ -176,274 +207,7 @@ fn print_registers(vm: &VM) {
 }
 
 fn print_stats(vm: &VM) {
-    let stats = vm.stats();
-    println!("\n===== STATISTIQUES D'EXÉCUTION =====\n");
-    println!("  Cycles: {}", stats.cycles);
-    println!("  Instructions exécutées: {}", stats.instructions_executed);
-    println!("  IPC (Instructions Par Cycle): {:.2}", stats.ipc);
-    println!("  Stalls: {}", stats.stalls);
-    println!("  Hazards: {}", stats.hazards);
-    println!("  Forwards: {}", stats.forwards);
-    
-    // Statistiques de la hiérarchie de cache
-    println!("\n-- Cache Hierarchy Performance --");
-    println!("  L1 Data Hits: {}", stats.l1_data_hits);
-    println!("  L1 Data Misses: {}", stats.l1_data_misses);
-    println!("  L1 Instruction Hits: {}", stats.l1_inst_hits);
-    println!("  L1 Instruction Misses: {}", stats.l1_inst_misses);
-    println!("  L2 Hits: {}", stats.l2_hits);
-    println!("  L2 Misses: {}", stats.l2_misses);
-    println!("  L2 Writebacks: {}", stats.l2_writebacks);
-    println!("  L2 Prefetch Hits: {}", stats.l2_prefetch_hits);
-    println!("  Memory Accesses: {}", stats.memory_accesses);
-    
-    // Calculs des taux de hits
-    let l1_total = stats.l1_data_hits + stats.l1_data_misses;
-    let l1_hit_rate = if l1_total > 0 {
-        stats.l1_data_hits as f64 / l1_total as f64 * 100.0
-    } else {
-        0.0
-    };
-    
-    let l2_total = stats.l2_hits + stats.l2_misses;
-    let l2_hit_rate = if l2_total > 0 {
-        stats.l2_hits as f64 / l2_total as f64 * 100.0
-    } else {
-        0.0
-    };
-    
-    println!("  L1 Data Hit Rate: {:.2}%", l1_hit_rate);
-    println!("  L2 Hit Rate: {:.2}%", l2_hit_rate);
-    
-    if stats.average_memory_latency > 0.0 {
-        println!("  Average Memory Latency: {:.2} cycles", stats.average_memory_latency);
-    }
-    println!("  Branches flush: {}", stats.branch_flush);
-    println!("  Branche predictions: {}", stats.branch_predictor);
-    println!(
-        "  Branch prediction rate : {:.2}%",
-        stats.branch_prediction_rate
-    );
-    
-    // Statistiques BTB
-    println!("\n-- Branch Target Buffer (BTB) --");
-    println!("  BTB Hits: {}", stats.btb_hits);
-    println!("  BTB Misses: {}", stats.btb_misses);
-    println!("  BTB Hit Rate: {:.2}%", stats.btb_hit_rate * 100.0);
-    println!("  BTB Correct Targets: {}", stats.btb_correct_targets);
-    println!("  BTB Incorrect Targets: {}", stats.btb_incorrect_targets);
-    println!("  BTB Accuracy: {:.2}%", stats.btb_accuracy * 100.0);
-
-    // Calcul de quelques métriques supplémentaires
-    if stats.cycles > 0 {
-        let stall_rate = (stats.stalls as f64 / stats.cycles as f64) * 100.0;
-        println!("  Taux de stalls: {:.2}%", stall_rate);
-    }
-
-    // Déjà affiché dans la section Cache Hierarchy ci-dessus
-
-
-    // Statistique du Stack
-    println!("\n===== STATISTIQUES DU STACK =====\n");
-    // println!("  Taille de la pile: {} octets", stats.);
-    println!(" Total de Stack Push: {}", stats.stack_pushes);
-    println!(" Total de Stack Pop: {}", stats.stack_pops);
-    println!(" Total Hits de Stack: {}", stats.stack_hits);
-    println!(" Total Miss de Stack: {}", stats.stack_misses);
-    println!(" Profondeur maximale de la pile: {}", stats.stack_max_depth);
-    println!(" Profondeur actuelle de la pile: {}", stats.stack_current_depth);
-
-
-
-    // Évaluation des performances
-    println!("\n===== ÉVALUATION DES PERFORMANCES =====\n");
-
-    // Taux de hits global de la hiérarchie de cache
-    let total_cache_accesses = stats.l1_data_hits + stats.l1_data_misses + stats.l2_hits + stats.l2_misses;
-    let total_cache_hits = stats.l1_data_hits + stats.l2_hits;
-    let overall_hit_rate = if total_cache_accesses > 0 {
-        total_cache_hits as f64 / total_cache_accesses as f64 * 100.0
-    } else {
-        0.0
-    };
-    println!("Taux de hits global (L1+L2): {:.2}%", overall_hit_rate);
-
-    // Taux de stalls
-    let stall_rate = if stats.cycles > 0 {
-        stats.stalls as f64 / stats.cycles as f64 * 100.0
-    } else {
-        0.0
-    };
-    println!("Taux de stalls: {:.2}%", stall_rate);
-
-    // Efficacité du forwarding
-    println!("\n-- Analyse du Forwarding --");
-    println!("Dépendances de données détectées: {}", stats.data_dependencies);
-    println!("Forwards potentiels identifiés: {}", stats.potential_forwards);
-    println!("Forwards effectués: {}", stats.forwards);
-    println!("Vrais hazards (causant stalls): {}", stats.hazards);
-    
-    let forwarding_efficiency = if stats.potential_forwards > 0 {
-        stats.forwards as f64 / stats.potential_forwards as f64 * 100.0
-    } else {
-        0.0
-    };
-    println!("Efficacité du forwarding: {:.2}% ({}/{})", 
-            forwarding_efficiency, stats.forwards, stats.potential_forwards);
-    
-    // Store-Load forwarding
-    println!("\n-- Store-Load Forwarding --");
-    println!("Store-Load tentatives: {}", stats.store_load_attempts);
-    println!("Store-Load forwards: {}", stats.store_load_forwards);
-    
-    let store_load_efficiency = if stats.store_load_attempts > 0 {
-        stats.store_load_forwards as f64 / stats.store_load_attempts as f64 * 100.0
-    } else {
-        0.0
-    };
-    println!("Efficacité Store-Load forwarding: {:.2}% ({}/{})", 
-            store_load_efficiency, stats.store_load_forwards, stats.store_load_attempts);
-
-    // Statistiques SIMD
-    println!("\n===== STATISTIQUES SIMD =====");
-    println!("Opérations SIMD 128-bit: {}", stats.simd128_ops);
-    println!("Opérations SIMD 256-bit: {}", stats.simd256_ops);
-    let total_simd_ops = stats.simd128_ops + stats.simd256_ops;
-    println!("Total opérations SIMD: {}", total_simd_ops);
-    
-    if total_simd_ops > 0 {
-        println!("Cycles SIMD totaux: {}", stats.simd_total_cycles);
-        println!("Opérations SIMD/cycle: {:.2}", stats.simd_ops_per_cycle);
-        println!("Opérations parallélisées: {}", stats.simd_parallel_ops);
-        
-        println!("\n--- Cache d'Opérations SIMD ---");
-        println!("Cache hits: {}", stats.simd_cache_hits);
-        println!("Cache misses: {}", stats.simd_cache_misses);
-        println!("Taux de réussite du cache: {:.2}%", stats.simd_cache_hit_rate);
-        
-        let total_cache_accesses = stats.simd_cache_hits + stats.simd_cache_misses;
-        if total_cache_accesses > 0 {
-            let cache_efficiency = (stats.simd_cache_hits as f64 / total_cache_accesses as f64) * 100.0;
-            println!("Efficacité globale du cache SIMD: {:.1}%", cache_efficiency);
-        }
-        
-        // Analyse de performance
-        if stats.simd_parallel_ops > 0 {
-            let parallelization_rate = (stats.simd_parallel_ops as f64 / total_simd_ops as f64) * 100.0;
-            println!("Taux de parallélisation SIMD: {:.1}%", parallelization_rate);
-        }
-    } else {
-        println!("Aucune opération SIMD détectée");
-    }
-
-    // Statistiques AGU (Address Generation Unit)
-    println!("\n===== STATISTIQUES AGU =====");
-    println!("Calculs d'adresse totaux: {}", stats.agu_total_calculations);
-    println!("Résolutions anticipées: {}", stats.agu_early_resolutions);
-    
-    if stats.agu_total_calculations > 0 {
-        println!("\n--- Stride Predictor ---");
-        println!("Prédictions de stride totales: {}", stats.agu_stride_predictions_total);
-        println!("Prédictions correctes: {}", stats.agu_stride_predictions_correct);
-        println!("Précision du stride predictor: {:.2}%", stats.agu_stride_accuracy * 100.0);
-        
-        println!("\n--- Base Address Cache ---");
-        println!("Cache hits: {}", stats.agu_base_cache_hits);
-        println!("Cache misses: {}", stats.agu_base_cache_misses);
-        println!("Taux de réussite: {:.2}%", stats.agu_base_cache_hit_rate * 100.0);
-        
-        println!("\n--- Performance AGU ---");
-        println!("Exécutions parallèles AGU/ALU: {}", stats.agu_parallel_executions);
-        if stats.agu_average_latency > 0.0 {
-            println!("Latence moyenne: {:.2} cycles", stats.agu_average_latency);
-        }
-        
-        // Analyse de l'efficacité
-        if stats.agu_total_calculations > 0 {
-            let early_resolution_rate = (stats.agu_early_resolutions as f64 / stats.agu_total_calculations as f64) * 100.0;
-            println!("Taux de résolution anticipée: {:.1}%", early_resolution_rate);
-        }
-        
-        if stats.cycles > 0 {
-            let agu_utilization = (stats.agu_total_calculations as f64 / stats.cycles as f64) * 100.0;
-            println!("Utilisation AGU: {:.1}% des cycles", agu_utilization);
-        }
-    } else {
-        println!("Aucun calcul d'adresse AGU détecté");
-    }
-
-    // Statistiques Dual-Issue Controller
-    println!("\n===== STATISTIQUES DUAL-ISSUE =====");
-    println!("Instructions traitées: {}", stats.dual_issue_total_instructions);
-    println!("Exécutions parallèles: {}", stats.dual_issue_parallel_executions);
-    println!("Taux de parallélisme: {:.2}%", stats.dual_issue_parallel_rate);
-    
-    if stats.dual_issue_total_instructions > 0 {
-        println!("\n--- Répartition par Unité ---");
-        println!("Instructions ALU uniquement: {}", stats.dual_issue_alu_only);
-        println!("Instructions AGU uniquement: {}", stats.dual_issue_agu_only);
-        println!("Conflits de ressources: {}", stats.dual_issue_resource_conflicts);
-        
-        // Analyse de l'efficacité dual-issue
-        let alu_ratio = (stats.dual_issue_alu_only as f64 / stats.dual_issue_total_instructions as f64) * 100.0;
-        let agu_ratio = (stats.dual_issue_agu_only as f64 / stats.dual_issue_total_instructions as f64) * 100.0;
-        
-        println!("\n--- Efficacité Dual-Issue ---");
-        println!("Ratio ALU: {:.1}%", alu_ratio);
-        println!("Ratio AGU: {:.1}%", agu_ratio);
-        
-        if stats.dual_issue_parallel_executions > 0 {
-            let theoretical_max = stats.dual_issue_total_instructions / 2;
-            let efficiency = (stats.dual_issue_parallel_executions as f64 / theoretical_max as f64) * 100.0;
-            println!("Efficacité théorique: {:.1}% ({}/{} max)", 
-                    efficiency, stats.dual_issue_parallel_executions, theoretical_max);
-        }
-        
-        if stats.cycles > 0 {
-            let dual_issue_impact = (stats.dual_issue_parallel_executions as f64 / stats.cycles as f64) * 100.0;
-            println!("Impact dual-issue: {:.1}% des cycles", dual_issue_impact);
-        }
-    } else {
-        println!("Aucune instruction traitée par dual-issue");
-    }
-
-    // Statistiques Parallel Execution Engine
-    println!("\n===== STATISTIQUES PARALLEL ENGINE =====");
-    println!("Instructions totales: {}", stats.parallel_engine_total_instructions);
-    println!("Exécutions parallèles: {}", stats.parallel_engine_parallel_executions);
-    println!("Instructions ALU: {}", stats.parallel_engine_alu_instructions);
-    println!("Instructions AGU: {}", stats.parallel_engine_agu_instructions);
-    println!("Instructions SIMD: {}", stats.parallel_engine_simd_instructions);
-    
-    if stats.parallel_engine_total_instructions > 0 {
-        println!("\n--- Analyse des Dépendances ---");
-        println!("Dépendances RAW: {}", stats.parallel_engine_raw_dependencies);
-        println!("Dépendances WAR: {}", stats.parallel_engine_war_dependencies);
-        println!("Dépendances WAW: {}", stats.parallel_engine_waw_dependencies);
-        println!("Stalls dépendances: {}", stats.parallel_engine_dependency_stalls);
-        println!("Conflits ressources: {}", stats.parallel_engine_resource_conflicts);
-        
-        println!("\n--- Utilisation des Unités ---");
-        println!("Utilisation ALU: {:.2}%", stats.parallel_engine_alu_utilization);
-        println!("Utilisation AGU: {:.2}%", stats.parallel_engine_agu_utilization);
-        println!("Profondeur queue moyenne: {:.2}", stats.parallel_engine_average_queue_depth);
-        
-        // Analyse du parallélisme
-        println!("\n--- Efficacité Parallélisme ---");
-        println!("Taux d'exécution parallèle: {:.2}%", stats.parallel_engine_parallel_rate);
-        
-        if stats.parallel_engine_parallel_executions > 0 {
-            println!("Gain théorique IPC: +{:.1}%", stats.parallel_engine_parallel_rate);
-            
-            // Comparer avec dual-issue
-            if stats.dual_issue_parallel_executions > 0 {
-                println!("Amélioration vs dual-issue: {}x plus d'exécutions parallèles", 
-                         stats.parallel_engine_parallel_executions as f64 / stats.dual_issue_parallel_executions as f64);
-            }
-        }
-    } else {
-        println!("Aucune instruction traitée par le parallel engine");
-    }
+    println!("\n{}", vm.stats());
 
     println!("\n===== TEST TERMINÉ =====");
     println!("=====PunkVM=By=YmC======\n");
@@ -452,7 +216,7 @@ fn print_stats(vm: &VM) {
 
 pub fn punk_program_3() -> BytecodeFile {
     let mut program = BytecodeFile::new();
-    program.version = BytecodeVersion::new(0, 1, 0, 0);
+    program.version = BytecodeVersion::SECTIONED;
     program.add_metadata("name", "PunkVM Comprehensive Branch Test");
     program.add_metadata("description", "Test complet de tous les types de branchements conditionnels et inconditionnels");
     program.add_metadata("author", "PunkVM Team");
@@ -539,11 +303,11 @@ pub fn punk_program_3() -> BytecodeFile {
     current_address = Instruction::calculate_current_address(&program.code);
 
     // ============================================================================
-    // SECTION 5: TEST JmpIfGreater (ZF = 0 ET SF = 0)
+    // SECTION 5: TEST JmpIfGreater (ZF = 0 ET SF = OF, signé)
     // ============================================================================
     println!("=== SECTION 5: TEST JmpIfGreater ===");
 
-    // Test 1: R1 > R0 (20 > 10) → ZF = 0, SF = 0 → branchement PRIS
+    // Test 1: R1 > R0 (20 > 10) → ZF = 0, SF = OF → branchement PRIS
     program.add_instruction(Instruction::create_reg_reg(Opcode::Cmp, 1, 0));
     current_address = Instruction::calculate_current_address(&program.code);
     let jmpifgreater_target_1 = current_address + 8 + 6;
@@ -552,7 +316,7 @@ pub fn punk_program_3() -> BytecodeFile {
     program.add_instruction(Instruction::create_reg_imm8(Opcode::Mov, 6, 0x08)); // R6 = 8 (succès)
     current_address = Instruction::calculate_current_address(&program.code);
 
-    // Test 2: R3 > R0 (5 > 10) → SF = 1 → branchement NON PRIS
+    // Test 2: R3 > R0 (5 > 10) → SF ≠ OF → branchement NON PRIS
     program.add_instruction(Instruction::create_reg_reg(Opcode::Cmp, 3, 0));
     current_address = Instruction::calculate_current_address(&program.code);
     let jmpifgreater_target_2 = current_address + 8 + 6;
@@ -562,11 +326,11 @@ pub fn punk_program_3() -> BytecodeFile {
     current_address = Instruction::calculate_current_address(&program.code);
 
     // ============================================================================
-    // SECTION 6: TEST JmpIfLess (SF = 1)
+    // SECTION 6: TEST JmpIfLess (SF ≠ OF, signé)
     // ============================================================================
     println!("=== SECTION 6: TEST JmpIfLess ===");
 
-    // Test 1: R3 < R0 (5 < 10) → SF = 1 → branchement PRIS
+    // Test 1: R3 < R0 (5 < 10) → SF ≠ OF → branchement PRIS
     program.add_instruction(Instruction::create_reg_reg(Opcode::Cmp, 3, 0));
     current_address = Instruction::calculate_current_address(&program.code);
     let jmpifless_target_1 = current_address + 8 + 6;
@@ -575,7 +339,7 @@ pub fn punk_program_3() -> BytecodeFile {
     program.add_instruction(Instruction::create_reg_imm8(Opcode::Mov, 9, 0x0B)); // R9 = 11 (succès)
     current_address = Instruction::calculate_current_address(&program.code);
 
-    // Test 2: R1 < R0 (20 < 10) → SF = 0 → branchement NON PRIS
+    // Test 2: R1 < R0 (20 < 10) → SF = OF → branchement NON PRIS
     program.add_instruction(Instruction::create_reg_reg(Opcode::Cmp, 1, 0));
     current_address = Instruction::calculate_current_address(&program.code);
     let jmpifless_target_2 = current_address + 8 + 6;
@@ -585,11 +349,11 @@ pub fn punk_program_3() -> BytecodeFile {
     current_address = Instruction::calculate_current_address(&program.code);
 
     // ============================================================================
-    // SECTION 7: TEST JmpIfGreaterEqual (SF = 0 ou ZF = 1)
+    // SECTION 7: TEST JmpIfGreaterEqual (SF = OF, signé)
     // ============================================================================
     println!("=== SECTION 7: TEST JmpIfGreaterEqual ===");
 
-    // Test 1: R1 >= R0 (20 >= 10) → SF = 0 → branchement PRIS
+    // Test 1: R1 >= R0 (20 >= 10) → SF = OF → branchement PRIS
     program.add_instruction(Instruction::create_reg_reg(Opcode::Cmp, 1, 0));
     current_address = Instruction::calculate_current_address(&program.code);
     let jmpifgreaterequal_target_1 = current_address + 8 + 6;
@@ -607,7 +371,7 @@ pub fn punk_program_3() -> BytecodeFile {
     program.add_instruction(Instruction::create_reg_imm8(Opcode::Mov, 13, 0x0F)); // R13 = 15 (succès)
     current_address = Instruction::calculate_current_address(&program.code);
 
-    // Test 3: R3 >= R0 (5 >= 10) → SF = 1 → branchement NON PRIS
+    // Test 3: R3 >= R0 (5 >= 10) → SF ≠ OF → branchement NON PRIS
     program.add_instruction(Instruction::create_reg_reg(Opcode::Cmp, 3, 0));
     current_address = Instruction::calculate_current_address(&program.code);
     let jmpifgreaterequal_target_3 = current_address + 8 + 6;
@@ -617,11 +381,11 @@ pub fn punk_program_3() -> BytecodeFile {
     current_address = Instruction::calculate_current_address(&program.code);
 
     // ============================================================================
-    // SECTION 8: TEST JmpIfLessEqual (SF = 1 OU ZF = 1)
+    // SECTION 8: TEST JmpIfLessEqual (SF ≠ OF OU ZF = 1, signé)
     // ============================================================================
     println!("=== SECTION 8: TEST JmpIfLessEqual ===");
 
-    // Test 1: R3 <= R0 (5 <= 10) → SF = 1 → branchement PRIS
+    // Test 1: R3 <= R0 (5 <= 10) → SF ≠ OF → branchement PRIS
     program.add_instruction(Instruction::create_reg_reg(Opcode::Cmp, 3, 0));
     current_address = Instruction::calculate_current_address(&program.code);
     let jmpiflessequal_target_1 = current_address + 8 + 6;
@@ -639,7 +403,7 @@ pub fn punk_program_3() -> BytecodeFile {
     program.add_instruction(Instruction::create_reg_imm8(Opcode::Mov, 6, 0x13)); // R6 = 19 (succès)
     current_address = Instruction::calculate_current_address(&program.code);
 
-    // Test 3: R1 <= R0 (20 <= 10) → SF = 0, ZF = 0 → branchement NON PRIS
+    // Test 3: R1 <= R0 (20 <= 10) → SF = OF, ZF = 0 → branchement NON PRIS
     program.add_instruction(Instruction::create_reg_reg(Opcode::Cmp, 1, 0));
     current_address = Instruction::calculate_current_address(&program.code);
     let jmpiflessequal_target_3 = current_address + 8 + 6;
@@ -783,7 +547,7 @@ pub fn punk_program_3() -> BytecodeFile {
     // ============================================================================
     println!("\n=== CARTE COMPLÈTE DES INSTRUCTIONS ===");
     let mut addr = 0u32;
-    let mut section_counters = HashMap::new();
+    let mut section_counters = BTreeMap::new(); // Ordre stable pour un résumé déterministe
 
     for (idx, instr) in program.code.iter().enumerate() {
         let size = instr.total_size();
@@ -861,7 +625,7 @@ fn create_stack_test_program() -> BytecodeFile {
     let mut current_address: u32 = 0;
     
     // Initialiser la version et les métadonnées
-    program.version = BytecodeVersion::new(0, 1, 0, 0);
+    program.version = BytecodeVersion::SECTIONED;
     program.add_metadata("name", "PunkVM Stack Machine Test");
     program.add_metadata("description", "Test complet de la stack machine avec PUSH/POP/CALL/RET");
     program.add_metadata("author", "PunkVM Team");
@@ -1059,7 +823,7 @@ fn test_basic_stack_operations() -> BytecodeFile {
     let mut program = BytecodeFile::new();
     
     // Initialiser la version et les métadonnées
-    program.version = BytecodeVersion::new(0, 1, 0, 0);
+    program.version = BytecodeVersion::SECTIONED;
     program.add_metadata("name", "PunkVM Basic Stack Test");
     program.add_metadata("description", "Test des opérations PUSH/POP basiques avec registres et immédiat");
     program.add_metadata("author", "PunkVM Team");
@@ -1118,7 +882,7 @@ fn test_arithmetic_with_stack() -> BytecodeFile {
     let mut program = BytecodeFile::new();
     
     // Initialiser la version et les métadonnées
-    program.version = BytecodeVersion::new(0, 1, 0, 0);
+    program.version = BytecodeVersion::SECTIONED;
     program.add_metadata("name", "PunkVM Arithmetic Stack Test");
     program.add_metadata("description", "Test des opérations arithmétiques utilisant la pile");
     program.add_metadata("author", "PunkVM Team");
@@ -1184,7 +948,7 @@ fn test_advanced_stack_register() -> BytecodeFile {
     let mut program = BytecodeFile::new();
     
     // Initialiser la version et les métadonnées
-    program.version = BytecodeVersion::new(0, 1, 0, 0);
+    program.version = BytecodeVersion::SECTIONED;
     program.add_metadata("name", "PunkVM Advanced Stack-Register Test");
     program.add_metadata("description", "Test avancé de combinaison registres et pile avec boucle");
     program.add_metadata("author", "PunkVM Team");
@@ -1251,7 +1015,7 @@ fn test_advanced_stack_register() -> BytecodeFile {
 /// Test de stress pour le forwarding avec chaînes de dépendances RAW
 fn forwarding_stress_test() -> BytecodeFile {
     let mut program = BytecodeFile::new();
-    program.version = BytecodeVersion::new(0, 1, 0, 0);
+    program.version = BytecodeVersion::SECTIONED;
     program.add_metadata("name", "PunkVM Forwarding Stress Test");
     program.add_metadata("description", "Test intensif de toutes les formes de forwarding");
     program.add_metadata("author", "PunkVM Team");
@@ -1312,11 +1076,12 @@ fn forwarding_stress_test() -> BytecodeFile {
     // MOV R15, 42
     program.add_instruction(Instruction::create_reg_imm16(Opcode::Mov, 15, 42));
     
-    // ADD R16, R15, R14 (dépendance sur R15 immédiate et R14 à distance)
-    program.add_instruction(Instruction::create_reg_reg_reg(Opcode::Add, 16, 15, 14));
+    // ADD R7, R15, R14 (dépendance sur R15 immédiate et R14 à distance)
+    // R16-R18 sont réservés (SP/BP/RA): on recycle R7 et R8
+    program.add_instruction(Instruction::create_reg_reg_reg(Opcode::Add, 7, 15, 14));
     
-    // SUB R17, R16, R15 (dépendances multiples récentes)
-    program.add_instruction(Instruction::create_reg_reg_reg(Opcode::Sub, 17, 16, 15));
+    // SUB R8, R7, R15 (dépendances multiples récentes)
+    program.add_instruction(Instruction::create_reg_reg_reg(Opcode::Sub, 8, 7, 15));
     
     // === Fin du test ===
     program.add_instruction(Instruction::create_no_args(Opcode::Halt));
@@ -1347,7 +1112,7 @@ fn forwarding_stress_test() -> BytecodeFile {
 }/// Test spécifique pour le Store-Load forwarding avec imm8
 fn store_load_forwarding_test_8() -> BytecodeFile {
     let mut program = BytecodeFile::new();
-    program.version = BytecodeVersion::new(0, 1, 0, 0);
+    program.version = BytecodeVersion::SECTIONED;
     program.add_metadata("name", "Store-Load Forwarding Test");
     program.add_metadata("description", "Test spécifique pour le forwarding Store-Load");
     program.add_metadata("author", "PunkVM Team");
@@ -1414,7 +1179,7 @@ fn store_load_forwarding_test_8() -> BytecodeFile {
 /// Test spécifique pour le Store-Load forwarding avec immediate 16bit
 fn store_load_forwarding_test_16() -> BytecodeFile {
     let mut program = BytecodeFile::new();
-    program.version = BytecodeVersion::new(0, 1, 0, 0);
+    program.version = BytecodeVersion::SECTIONED;
     program.add_metadata("name", "Store-Load Forwarding Test");
     program.add_metadata("description", "Test spécifique pour le forwarding Store-Load");
     program.add_metadata("author", "PunkVM Team");
@@ -1481,7 +1246,7 @@ fn store_load_forwarding_test_16() -> BytecodeFile {
 /// Test spécifique pour le Store-Load forwarding avec immediate 32bit
 fn store_load_forwarding_test_32() -> BytecodeFile {
     let mut program = BytecodeFile::new();
-    program.version = BytecodeVersion::new(0, 1, 0, 0);
+    program.version = BytecodeVersion::SECTIONED;
     program.add_metadata("name", "Store-Load Forwarding Test");
     program.add_metadata("description", "Test spécifique pour le forwarding Store-Load");
     program.add_metadata("author", "PunkVM #YmC");
@@ -1547,7 +1312,7 @@ fn store_load_forwarding_test_32() -> BytecodeFile {
 /// Test spécifique pour le Store-Load forwarding avec immediate 15bit
 fn store_load_forwarding_test_64() -> BytecodeFile {
     let mut program = BytecodeFile::new();
-    program.version = BytecodeVersion::new(0, 1, 0, 0);
+    program.version = BytecodeVersion::SECTIONED;
     program.add_metadata("name", "Store-Load Forwarding Test");
     program.add_metadata("description", "Test spécifique pour le forwarding Store-Load");
     program.add_metadata("author", "PunkVM Team");
@@ -1618,7 +1383,7 @@ fn store_load_forwarding_test_64() -> BytecodeFile {
 /// Test spécifique pour valider la correction du bug cache
 fn cache_stress_test() -> BytecodeFile {
     let mut program = BytecodeFile::new();
-    program.version = BytecodeVersion::new(0, 1, 0, 0);
+    program.version = BytecodeVersion::SECTIONED;
     program.add_metadata("name", "PunkVM Cache Stress Test");
     program.add_metadata("description", "Test de stress pour la hiérarchie de cache L1/L2");
     program.add_metadata("author", "PunkVM @YmC");
@@ -1670,7 +1435,7 @@ fn cache_stress_test() -> BytecodeFile {
 
 fn forwarding_efficiency_test() -> BytecodeFile {
     let mut program = BytecodeFile::new();
-    program.version = BytecodeVersion::new(0, 1, 0, 0);
+    program.version = BytecodeVersion::SECTIONED;
     program.add_metadata("name", "Forwarding Efficiency Test");
     program.add_metadata("description", "Mesure de l'efficacité du forwarding avec patterns variés");
     program.add_metadata("author", "PunkVM Team");
@@ -1734,7 +1499,7 @@ fn forwarding_efficiency_test() -> BytecodeFile {
 
 fn simd_instruction_test() -> BytecodeFile {
     let mut program = BytecodeFile::new();
-    program.version = BytecodeVersion::new(0, 1, 0, 0);
+    program.version = BytecodeVersion::SECTIONED;
     program.add_metadata("name", "PunkVM SIMD Instruction Test");
     program.add_metadata("description", "Test complet des instructions SIMD avec nouveaux helpers");
     program.add_metadata("author", "PunkVM Team");
@@ -1851,19 +1616,19 @@ fn simd_instruction_test() -> BytecodeFile {
     program.add_instruction(Instruction::create_simd256_add(15, 0, 1));
     println!("   V15 = V0 + V1 (256-bit)");
     
-    // Multiplication 256-bit: V16 = V2 * V0
-    program.add_instruction(Instruction::create_simd256_mul(16, 2, 0));
-    println!("   V16 = V2 * V0 (256-bit)");
+    // Multiplication 256-bit: V12 = V2 * V0 (seuls V0-V15 sont adressables)
+    program.add_instruction(Instruction::create_simd256_mul(12, 2, 0));
+    println!("   V12 = V2 * V0 (256-bit)");
 
     // ============================================================================
     // SECTION 7: CHAÎNAGE D'OPÉRATIONS
     // ============================================================================
     println!("\n7. Chaînage d'opérations vectorielles");
     
-    // V17 = (V0 + V1) * V2
-    program.add_instruction(Instruction::create_simd128_add(17, 0, 1));  // V17 = V0 + V1
-    program.add_instruction(Instruction::create_simd128_mul(17, 17, 2)); // V17 = V17 * V2
-    println!("   V17 = (V0 + V1) * V2");
+    // V11 = (V0 + V1) * V2
+    program.add_instruction(Instruction::create_simd128_add(11, 0, 1));  // V11 = V0 + V1
+    program.add_instruction(Instruction::create_simd128_mul(11, 11, 2)); // V11 = V11 * V2
+    println!("   V11 = (V0 + V1) * V2");
 
     // Fin du programme
     program.add_instruction(Instruction::create_no_args(Opcode::Halt));
@@ -1913,7 +1678,7 @@ fn simd_advanced_test() -> BytecodeFile {
     println!("=== CRÉATION DU TEST SIMD AVANCÉ ===");
     
     // Configuration des métadonnées
-    program.version = BytecodeVersion::new(0, 1, 0, 0);
+    program.version = BytecodeVersion::SECTIONED;
     program.add_metadata("name", "PunkVM SIMD Advanced");
     program.add_metadata("description", "Test complet des instructions SIMD Advanced ");
     program.add_metadata("author", "PunkVM @YmC");
@@ -2020,7 +1785,7 @@ fn simd_cache_validation_test() -> BytecodeFile {
     println!("=== CRÉATION DU TEST DE VALIDATION CACHE SIMD ===");
     
     // Métadonnées du programme
-    program.version = BytecodeVersion::new(0, 1, 0, 0);
+    program.version = BytecodeVersion::SECTIONED;
     program.add_metadata("name", "PunkVM SIMD Cache Validation");
     program.add_metadata("description", "Test validation cache SIMD avec opérations répétées");
     program.add_metadata("author", "PunkVM @YmC");
@@ -2123,7 +1888,7 @@ fn cache_hierarchy_validation_test() -> BytecodeFile {
     
     println!("=== CRÉATION DU TEST HIÉRARCHIE CACHE ===");
     
-    program.version = BytecodeVersion::new(0, 1, 0, 0);
+    program.version = BytecodeVersion::SECTIONED;
     program.add_metadata("name", "Cache Hierarchy Test");
     program.add_metadata("description", "Test de validation de la hiérarchie cache L1/L2");
     program.add_metadata("author", "PunkVM Cache Validation");
@@ -2240,7 +2005,7 @@ fn cache_hierarchy_validation_test() -> BytecodeFile {
 /// Programme 5: Test BTB simple avec boucle répétitive
 pub fn punk_program_5() -> BytecodeFile {
     let mut program = BytecodeFile::new();
-    program.version = BytecodeVersion::new(0, 1, 0, 0);
+    program.version = BytecodeVersion::SECTIONED;
     program.add_metadata("name", "PunkVM BTB Simple Loop Test");
     program.add_metadata("description", "Test simple avec une boucle for BTB hits");
     program.add_metadata("author", "PunkVM Team");
@@ -2296,7 +2061,7 @@ pub fn punk_program_5() -> BytecodeFile {
 /// Programme 4: Test spécifique pour BTB (Branch Target Buffer) avec patterns répétitifs
 pub fn punk_program_4() -> BytecodeFile {
     let mut program = BytecodeFile::new();
-    program.version = BytecodeVersion::new(0, 1, 0, 0);
+    program.version = BytecodeVersion::SECTIONED;
     program.add_metadata("name", "PunkVM BTB Test - Repetitive Branch Patterns");
     program.add_metadata("description", "Test pour générer des hits dans le BTB via des patterns de branchement répétitifs");
     program.add_metadata("author", "PunkVM Team");
@@ -2483,7 +2248,7 @@ pub fn punk_program_4() -> BytecodeFile {
 /// Test de validation AGU - Address Generation Unit
 fn agu_validation_test() -> BytecodeFile {
     let mut program = BytecodeFile::new();
-    program.version = BytecodeVersion::new(0, 1, 0, 0);
+    program.version = BytecodeVersion::SECTIONED;
     program.add_metadata("name", "AGU Validation Test");
     program.add_metadata("description", "Test spécifique pour valider l'Address Generation Unit");
     program.add_metadata("author", "PunkVM Team");
@@ -2565,7 +2330,7 @@ fn agu_validation_test() -> BytecodeFile {
 /// Test mémoire intensif pour maximiser l'utilisation de l'AGU
 fn memory_intensive_stress_test() -> BytecodeFile {
     let mut program = BytecodeFile::new();
-    program.version = BytecodeVersion::new(0, 1, 0, 0);
+    program.version = BytecodeVersion::SECTIONED;
     program.add_metadata("name", "Memory Intensive AGU Stress Test");
     program.add_metadata("description", "Test intensif pour maximiser l'utilisation de l'AGU");
     program.add_metadata("author", "PunkVM Team");
@@ -2696,3 +2461,97 @@ fn memory_intensive_stress_test() -> BytecodeFile {
 // TODO: Réimplémenter quand l'API des instructions sera clarifiée
 
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use PunkVM::pvm::vm::VMStats;
+
+    #[test]
+    fn test_misprediction_penalty_adds_stall_cycles() {
+        let run_with_penalty = |misprediction_penalty: u64| {
+            let config = VMConfig { misprediction_penalty, ..VMConfig::default() };
+            let mut vm = VM::with_config(config);
+            vm.load_program_from_bytecode(punk_program_5()).unwrap();
+            vm.run().unwrap();
+            assert_eq!(vm.registers[15], 0xDD);
+            vm.stats()
+        };
+
+        let base = run_with_penalty(0);
+        let penalized = run_with_penalty(5);
+
+        assert!(base.branch_mispredictions > 0);
+        assert_eq!(penalized.branch_mispredictions, base.branch_mispredictions);
+        assert_eq!(penalized.instructions_executed, base.instructions_executed);
+        assert_eq!(penalized.cycles - base.cycles, base.branch_mispredictions * 5);
+        assert_eq!(penalized.stalls - base.stalls, base.branch_mispredictions * 5);
+    }
+
+    #[test]
+    fn test_branch_fusion_reduces_loop_cycles() {
+        let run_with_fusion = |enable_branch_fusion: bool| {
+            let config = VMConfig { enable_branch_fusion, ..VMConfig::default() };
+            let mut vm = VM::with_config(config);
+            vm.load_program_from_bytecode(punk_program_5()).unwrap();
+            vm.run().unwrap();
+            assert_eq!(vm.registers[15], 0xDD);
+            vm.stats()
+        };
+
+        let separate = run_with_fusion(false);
+        let fused = run_with_fusion(true);
+
+        assert_eq!(separate.fused_branch_count, 0);
+        assert!(fused.fused_branch_count > 0);
+        assert_eq!(fused.instructions_executed, separate.instructions_executed);
+        assert!(fused.cycles < separate.cycles, "fusion: {} cycles, sans: {}", fused.cycles, separate.cycles);
+    }
+
+    #[test]
+    fn test_call_stack_survives_snapshot_in_stack_test_program() {
+        let program = create_stack_test_program();
+        // Premier CALL de la section 3: la fonction commence à l'ADD, le retour au MOV R8
+        let call = program.code.iter().position(|i| i.opcode == Opcode::Call).unwrap();
+        let return_address = Instruction::calculate_current_address(&program.code[..call + 1]);
+        let function_address = Instruction::calculate_current_address(&program.code[..call + 2]);
+
+        let config = VMConfig { stack_base: 0xC000, stack_size: 4 * 1024, ..VMConfig::default() };
+        let mut vm = VM::with_config(config);
+        vm.load_program_from_bytecode(program).unwrap();
+
+        assert!(vm.run_to_pc(function_address).unwrap());
+        assert_eq!(vm.call_stack(), vec![return_address]);
+        let snapshot = vm.snapshot();
+
+        // Après le RET, plus aucun appel en cours
+        assert!(vm.run_to_pc(return_address).unwrap());
+        assert!(vm.call_stack().is_empty());
+
+        vm.restore(&snapshot).unwrap();
+        assert_eq!(vm.call_stack(), vec![return_address]);
+        assert!(vm.run_to_pc(return_address).unwrap());
+        assert_eq!(vm.registers[7], 30);
+    }
+    /// Exécute cache_stress_test puis vide les caches pour que les lignes sales du L2
+    /// write-back soient réécrites en mémoire
+    fn run_cache_stress_with_l2_policy(l2_write_policy: WritePolicy) -> VMStats {
+        let config = VMConfig { l1_write_policy: WritePolicy::WriteThrough, l2_write_policy, ..VMConfig::default() };
+        let mut vm = VM::with_config(config);
+        vm.load_program_from_bytecode(cache_stress_test()).unwrap();
+        vm.run().unwrap();
+        assert_eq!(vm.registers[3], 200);
+        vm.flush_all_caches().unwrap();
+        vm.stats()
+    }
+
+    #[test]
+    fn test_write_policy_changes_writeback_traffic() {
+        let write_through = run_cache_stress_with_l2_policy(WritePolicy::WriteThrough);
+        let write_back = run_cache_stress_with_l2_policy(WritePolicy::WriteBack);
+
+        // Write-through: aucune ligne sale, chaque store part immédiatement en mémoire
+        assert_eq!(write_through.l2_writebacks, 0);
+        assert!(write_back.l2_writebacks > 0);
+        assert!(write_through.memory_accesses > write_back.memory_accesses);
+    }
+}
@@ -4,6 +4,7 @@ use crate::bytecode::instructions::{ArgValue, Instruction};
 use crate::bytecode::opcodes::Opcode;
 use crate::pipeline::{DecodeExecuteRegister, FetchDecodeRegister};
 use crate::pvm::branch_predictor::{BranchPredictor, BranchPrediction, PredictorType};
+use crate::pvm::pipeline_errors::PipelineError;
 use crate::pipeline::ras::{RASStats, ReturnAddressStack};
 
 /// implementation de l'étage Decode du pipeline
@@ -16,6 +17,9 @@ pub struct DecodeStage {
 }
 
 
+/// Registres (rs1, rs2, rd) référencés par une instruction
+type RegisterIndices = (Option<usize>, Option<usize>, Option<usize>);
+
 /// Types d'opérations sur la pile
 #[derive(Debug, Clone, Copy)]
 pub enum StackOperation {
@@ -33,6 +37,14 @@ impl DecodeStage {
         }
     }
 
+    /// Crée un étage Decode avec le prédicteur de branchement choisi
+    pub fn with_predictor(kind: PredictorType) -> Self {
+        Self {
+            branch_predictor: BranchPredictor::new(kind),
+            ras: ReturnAddressStack::new(32),
+        }
+    }
+
     /// Effectue le décodage :
     ///
     /// - détermine rs1_index, rs2_index, rd_index
@@ -46,11 +58,12 @@ impl DecodeStage {
         &mut self,
         fd_reg: &FetchDecodeRegister,
         registers: &[u64],
-    ) -> Result<DecodeExecuteRegister, String> {
+    ) -> Result<DecodeExecuteRegister, PipelineError> {
         let instruction = &fd_reg.instruction;
+        let decode_error = |message| PipelineError::Decode { pc: fd_reg.pc, message };
 
         // Extraction des registres source et destination
-        let (rs1_index, rs2_index, rd_index) = self.extract_registers(instruction)?;
+        let (rs1_index, rs2_index, rd_index) = self.extract_registers(instruction, fd_reg.pc)?;
 
         // lire rs1_value et rs2_value dans la banque de registres
         let rs1_value = rs1_index.map_or(0, |ix| {
@@ -72,11 +85,11 @@ impl DecodeStage {
         });
 
         // Extraction de la valeur immédiate
-        let immediate = self.extract_immediate(instruction)?;
+        let immediate = self.extract_immediate(instruction).map_err(decode_error)?;
         println!("Valeur immédiate extraite: {:?}", immediate);
 
         // Calcul de l'adresse de branchement (si instruction de branchement)
-        let mut branch_addr = self.calculate_branch_address(instruction, fd_reg.pc)?;
+        let mut branch_addr = self.calculate_branch_address(instruction, fd_reg.pc).map_err(decode_error)?;
         println!("Adresse de branchement calculée: {:?}", branch_addr);
 
         // si c'est une instruction de branchement, utiliser le prédicteur de branchement
@@ -99,26 +112,23 @@ impl DecodeStage {
             }
         }
 
-        // Gestion Special pour CALL et RET avec le RAS
-        if instruction.opcode == Opcode::Call {
-            // Pour CALL, mettre à jour le RAS avec l'adresse de retour
-            let return_address = fd_reg.pc + instruction.total_size() as u32;
-            self.ras.push(return_address);
-            println!(" RAS UPDATE: CALL pushes return address: 0x{:08X}", return_address);
-        } else if instruction.opcode == Opcode::Ret {
-            // Pour RET, prédire l'adresse de retour avec le RAS
+        // Gestion Special pour RET avec le RAS (le CALL empile son adresse de retour
+        // à l'exécution, une fois le saut confirmé)
+        if instruction.opcode == Opcode::Ret {
+            // Pour RET, prédire l'adresse de retour avec le sommet du RAS
             if let Some(predicted_addr) = self.ras.predict() {
                 branch_addr = Some(predicted_addr);
                 println!(" RAS PREDICT: Ret branch address predicted: 0x{:08X}", predicted_addr);
             } else {
-                println!(" RAS PREDICT: Ret branch address predicted: None (RAS is empty)");
-                branch_addr = None;
+                // RAS vide (débordé par des appels trop profonds): repli sur le BTB
+                branch_addr = self.branch_predictor.predict_target(fd_reg.pc as u64);
+                println!(" RAS PREDICT: RAS is empty, BTB fallback: {:?}", branch_addr);
             }
         }
 
 
         // Calcul de l'adresse mémoire (si instruction mémoire)
-        let mem_addr = self.calculate_memory_address(instruction, registers)?;
+        let mem_addr = self.calculate_memory_address(instruction, registers).map_err(decode_error)?;
         println!("Adresse mémoire calculée: {:?}", mem_addr);
 
 
@@ -161,6 +171,7 @@ impl DecodeStage {
 
 
         Ok(DecodeExecuteRegister {
+            id: fd_reg.id,
             instruction: instruction.clone(),
             pc: fd_reg.pc,
             rs1: rs1_index,
@@ -181,7 +192,8 @@ impl DecodeStage {
     fn extract_registers(
         &self,
         instruction: &Instruction,
-    ) -> Result<(Option<usize>, Option<usize>, Option<usize>), String> {
+        pc: u32,
+    ) -> Result<RegisterIndices, PipelineError> {
         let mut rs1 = None;
         let mut rs2 = None;
         let mut rd = None;
@@ -216,6 +228,7 @@ impl DecodeStage {
             | Opcode::Sub
             | Opcode::Mul
             | Opcode::Div
+            | Opcode::Mod
             | Opcode::And
             | Opcode::Or
             | Opcode::Xor
@@ -223,7 +236,18 @@ impl DecodeStage {
             | Opcode::Shr
             | Opcode::Sar
             | Opcode::Rol
-            | Opcode::Ror => {
+            | Opcode::Ror
+            | Opcode::Shld
+            | Opcode::Shrd
+            | Opcode::Crc32
+            | Opcode::AddSat
+            | Opcode::SubSat
+            | Opcode::AddSatD
+            | Opcode::SubSatD
+            | Opcode::AddSatS
+            | Opcode::SubSatS
+            | Opcode::AddSatSD
+            | Opcode::SubSatSD => {
                 if let Ok(ArgValue::Register(r)) = instruction.get_arg1_value() {
                     rd = Some(r as usize);
                     rs1 = Some(r as usize); // Dans certaines architectures, rd est aussi rs1
@@ -245,7 +269,17 @@ impl DecodeStage {
             | Opcode::Simd128Cmp
             | Opcode::Simd128Min
             | Opcode::Simd128Max
+            | Opcode::Simd128AddI64x2
+            | Opcode::Simd128SubI64x2
+            | Opcode::Simd128MulI64x2
+            | Opcode::Simd128AddF64x2
+            | Opcode::Simd128SubF64x2
+            | Opcode::Simd128MulF64x2
+            | Opcode::Simd128DivF64x2
             | Opcode::Simd256Add
+            | Opcode::Simd256AddI64x4
+            | Opcode::Simd256SubI64x4
+            | Opcode::Simd256MulI64x4
             | Opcode::Simd256Sub
             | Opcode::Simd256Mul
             | Opcode::Simd256Div
@@ -267,6 +301,8 @@ impl DecodeStage {
             // Instructions SIMD unaires
             Opcode::Simd128Not
             | Opcode::Simd128Sqrt
+            | Opcode::Simd128Rcp
+            | Opcode::Simd128Rsqrt
             | Opcode::Simd128Shuffle
             | Opcode::Simd256Not
             | Opcode::Simd256Sqrt
@@ -280,6 +316,67 @@ impl DecodeStage {
                 }
             }
 
+            // FMA: vd, va, puis vb (octet bas) et vc (octet haut) de l'immédiat 16 bits
+            Opcode::Simd128FMA => {
+                if let Ok(ArgValue::Register(r)) = instruction.get_arg1_value() {
+                    rd = Some(r as usize);
+                }
+                if let Ok(ArgValue::Register(r)) = instruction.get_arg2_value() {
+                    rs1 = Some(r as usize);
+                }
+                if let Ok(ArgValue::Immediate(pair)) = instruction.get_arg3_value() {
+                    rs2 = Some((pair & 0x0F) as usize);
+                }
+            }
+
+            // Blend: vd n'est pas un registre général, seuls va et vb (octet bas) sont suivis
+            Opcode::Simd128Blend => {
+                if let Ok(ArgValue::Register(r)) = instruction.get_arg2_value() {
+                    rs1 = Some(r as usize);
+                }
+                if let Ok(ArgValue::Immediate(pair)) = instruction.get_arg3_value() {
+                    rs2 = Some((pair & 0x0F) as usize);
+                }
+            }
+
+            // Accès masqués: vd/vs est un registre V128, base et masque sont des registres généraux
+            Opcode::Simd128MaskedLoad | Opcode::Simd128MaskedStore => {
+                if let Ok(ArgValue::Register(r)) = instruction.get_arg2_value() {
+                    rs1 = Some(r as usize);
+                }
+                if let Ok(ArgValue::Register(r)) = instruction.get_arg3_value() {
+                    rs2 = Some(r as usize);
+                }
+            }
+
+            // Réductions horizontales: rd est un registre général, rs1 un registre V128
+            Opcode::Simd128HAdd | Opcode::Simd128HMin | Opcode::Simd128HMax => {
+                if let Ok(ArgValue::Register(r)) = instruction.get_arg1_value() {
+                    rd = Some(r as usize);
+                }
+                if let Ok(ArgValue::Register(r)) = instruction.get_arg2_value() {
+                    rs1 = Some(r as usize);
+                }
+            }
+
+            // Extraction de lane: rd est un registre général, rs1 un registre V128
+            Opcode::Simd128ExtractLane => {
+                if let Ok(ArgValue::Register(r)) = instruction.get_arg1_value() {
+                    rd = Some(r as usize);
+                }
+                if let Ok(ArgValue::Register(r)) = instruction.get_arg2_value() {
+                    rs1 = Some(r as usize);
+                }
+            }
+
+            // Insertion de lane: seul le registre général source est suivi,
+            // le registre V128 destination (arg1) est relu à l'exécution
+            Opcode::Simd128InsertLane => {
+                if let Ok(ArgValue::Register(r)) = instruction.get_arg2_value() {
+                    rs1 = Some(r as usize);
+                }
+            }
+
             // Instructions SIMD de mouvement
             Opcode::Simd128Mov | Opcode::Simd256Mov => {
                 // Format: rd, rs1 (2 registres)
@@ -294,8 +391,14 @@ impl DecodeStage {
             // Instructions SIMD de constantes
             Opcode::Simd128Const
             | Opcode::Simd128ConstF32
+            | Opcode::Simd128ConstI16x8
+            | Opcode::Simd128ConstI64x2
+            | Opcode::Simd128ConstF64x2
             | Opcode::Simd256Const
-            | Opcode::Simd256ConstF32 => {
+            | Opcode::Simd256ConstF32
+            | Opcode::Simd256ConstI16x16
+            | Opcode::Simd256ConstI64x4
+            | Opcode::Simd256ConstF64x4 => {
                 // Format: rd, imm1, imm2 (1 registre + constantes)
                 if let Ok(ArgValue::Register(r)) = instruction.get_arg1_value() {
                     rd = Some(r as usize);
@@ -325,17 +428,25 @@ impl DecodeStage {
             }
 
             // Instructions de charge (load)
-            Opcode::Load | Opcode::LoadB | Opcode::LoadW | Opcode::LoadD |
-            Opcode::Simd128Load | Opcode::Simd256Load => {
+            Opcode::Load | Opcode::LoadB | Opcode::LoadW | Opcode::LoadD | Opcode::LoadSB | Opcode::LoadSW | Opcode::LoadSD |
+            Opcode::Simd128Load | Opcode::Simd256Load | Opcode::Lea => {
                 if let Ok(ArgValue::Register(r)) = instruction.get_arg1_value() {
                     rd = Some(r as usize);
                     println!("Registre destination: {:?}", rd);
                 }
 
-                // Extraction du registre base pour les adresses indexées
-                if let Ok(ArgValue::RegisterOffset(r, _)) = instruction.get_arg2_value() {
-                    rs1 = Some(r as usize);
-                    println!("Registre base 1: {:?}", rs1);
+                // Extraction du registre base (et de l'index) pour les adresses indexées
+                match instruction.get_arg2_value() {
+                    Ok(ArgValue::RegisterOffset(r, _)) => {
+                        rs1 = Some(r as usize);
+                        println!("Registre base 1: {:?}", rs1);
+                    }
+                    Ok(ArgValue::Indexed(base, index, _, _)) => {
+                        rs1 = Some(base as usize);
+                        rs2 = Some(index as usize);
+                        println!("Registre base 1: {:?}, index: {:?}", rs1, rs2);
+                    }
+                    _ => {}
                 }
             }
 
@@ -348,9 +459,22 @@ impl DecodeStage {
                 }
 
                 // Extraction du registre base pour les adresses indexées
+                // (l'index d'un opérande Indexed est relu par l'AGU depuis l'opérande)
+                match instruction.get_arg2_value() {
+                    Ok(ArgValue::RegisterOffset(r, _)) | Ok(ArgValue::Indexed(r, _, _, _)) => {
+                        rs2 = Some(r as usize);
+                        println!("Registre base 2: {:?}", rs2);
+                    }
+                    _ => {}
+                }
+            }
+
+            // Transferts multiples: seul le registre de base est lu ici,
+            // la liste des registres (masque) est traitée par l'étage Memory
+            Opcode::Ldm | Opcode::Stm => {
                 if let Ok(ArgValue::RegisterOffset(r, _)) = instruction.get_arg2_value() {
-                    rs2 = Some(r as usize);
-                    println!("Registre base 2: {:?}", rs2);
+                    rs1 = Some(r as usize);
+                    println!("Registre base pour {:?}: {:?}", instruction.opcode, rs1);
                 }
             }
 
@@ -369,6 +493,12 @@ impl DecodeStage {
                 }
             }
 
+            Opcode::RdMode | Opcode::RdInstret => {
+                if let Ok(ArgValue::Register(r)) = instruction.get_arg1_value() {
+                    rd = Some(r as usize);
+                }
+            }
+
             // Instructions de branchement conditionnel
             Opcode::Jmp
             | Opcode::JmpIf
@@ -393,6 +523,45 @@ impl DecodeStage {
                 // mais se basent sur les flags définis par les instructions précédentes
             }
 
+            // Champs de bits: "Bfe Rd, Rs, lsb, width" lit rs; Bfi lit aussi rd
+            Opcode::Bfe | Opcode::Bfi => {
+                let Ok(ArgValue::Immediate(imm)) = instruction.get_arg3_value() else {
+                    let message = format!("{:?}: champ de bits (lsb, width) manquant", instruction.opcode);
+                    return Err(PipelineError::Decode { pc, message });
+                };
+                let (lsb, width) = Instruction::unpack_bitfield(imm);
+                if lsb + width > 64 {
+                    let message = format!(
+                        "{:?}: champ de bits hors du registre (lsb={}, width={})",
+                        instruction.opcode, lsb, width
+                    );
+                    return Err(PipelineError::Decode { pc, message });
+                }
+                if let Ok(ArgValue::Register(r)) = instruction.get_arg1_value() {
+                    rd = Some(r as usize);
+                    if instruction.opcode == Opcode::Bfi {
+                        rs1 = Some(r as usize);
+                    }
+                }
+                if let Ok(ArgValue::Register(r)) = instruction.get_arg2_value() {
+                    if instruction.opcode == Opcode::Bfi {
+                        rs2 = Some(r as usize);
+                    } else {
+                        rs1 = Some(r as usize);
+                    }
+                }
+            }
+
+            // Comptages de bits: "Popcnt Rd, Rs"
+            Opcode::Popcnt | Opcode::Clz | Opcode::Ctz => {
+                if let Ok(ArgValue::Register(r)) = instruction.get_arg1_value() {
+                    rd = Some(r as usize);
+                }
+                if let Ok(ArgValue::Register(r)) = instruction.get_arg2_value() {
+                    rs1 = Some(r as usize);
+                }
+            }
+
             // Instructions de Mov
             Opcode::Mov => {
                 if let Ok(ArgValue::Register(r)) = instruction.get_arg1_value() {
@@ -407,6 +576,11 @@ impl DecodeStage {
                     // => le decode saura stocker l'immediate dans un champ (plus tard).
                     println!("Valeur immédiate pour MOV: {:?}", imm);
                 }
+
+                // "Mov Rd, Rs": la valeur copiée est lue dans rs2 (voir l'étage Execute)
+                if let Ok(ArgValue::Register(r)) = instruction.get_arg2_value() {
+                    rs2 = Some(r as usize);
+                }
             }
             // Instructions de contrôle de flux
             Opcode::Call => {
@@ -419,18 +593,54 @@ impl DecodeStage {
                 println!("Instruction RET détectée");
             }
 
+            // Déplacements conditionnels: rd est aussi lu, il garde sa valeur si la condition est fausse
+            Opcode::CmovEqual
+            | Opcode::CmovNotEqual
+            | Opcode::CmovGreater
+            | Opcode::CmovGreaterEqual
+            | Opcode::CmovLess
+            | Opcode::CmovLessEqual
+            | Opcode::CmovAbove
+            | Opcode::CmovAboveEqual
+            | Opcode::CmovBelow
+            | Opcode::CmovBelowEqual => {
+                if let Ok(ArgValue::Register(r)) = instruction.get_arg1_value() {
+                    rd = Some(r as usize);
+                    rs1 = Some(r as usize);
+                }
+                if let Ok(ArgValue::Register(r)) = instruction.get_arg2_value() {
+                    rs2 = Some(r as usize);
+                }
+            }
+
+            // Traps conditionnels: lisent les flags, le code de trap est l'immédiat
+            Opcode::TrapIfEqual
+            | Opcode::TrapIfNotEqual
+            | Opcode::TrapIfGreater
+            | Opcode::TrapIfGreaterEqual
+            | Opcode::TrapIfLess
+            | Opcode::TrapIfLessEqual
+            | Opcode::TrapIfAbove
+            | Opcode::TrapIfAboveEqual
+            | Opcode::TrapIfOverflow => {}
+
+            // Changements de mode de privilège: aucun registre
+            Opcode::Syscall | Opcode::Sysret => {
+                println!("Instruction {:?} détectée", instruction.opcode);
+            }
+
+            // NOP (y compris le remplissage d'alignement): aucun registre
+            Opcode::Nop => {}
+
             // Instructions d'arret
             Opcode::Halt => {
                 // Pas de registre à extraire
                 println!("Instruction HALT détectée");
             }
 
-            // Autres instructions (par défaut)
+            // Autres instructions (par défaut): aucun étage ne sait les exécuter
             _ => {
-                return Err(format!(
-                    "Instruction non prise en charge: {:?}",
-                    instruction.opcode
-                ));
+                return Err(PipelineError::UnsupportedInstruction { pc, opcode: instruction.opcode });
             }
         }
 
@@ -533,10 +743,15 @@ impl DecodeStage {
             | Opcode::LoadB
             | Opcode::LoadW
             | Opcode::LoadD
+            | Opcode::LoadSB
+            | Opcode::LoadSW
+            | Opcode::LoadSD
             | Opcode::Store
             | Opcode::StoreB
             | Opcode::StoreW
-            | Opcode::StoreD => {
+            | Opcode::StoreD
+            | Opcode::Ldm
+            | Opcode::Stm => {
                 // On suppose que l'adresse est dans arg2
                 match instruction.get_arg2_value() {
                     Ok(ArgValue::AbsoluteAddr(addr)) => Ok(Some(addr as u32)),
@@ -553,6 +768,17 @@ impl DecodeStage {
                             Err(format!("Register R{} out of range", reg))
                         }
                     }
+                    Ok(ArgValue::Indexed(base, index, scale, off)) => {
+                        match (registers.get(base as usize), registers.get(index as usize)) {
+                            (Some(&base), Some(&index)) => {
+                                let addr = base
+                                    .wrapping_add(index.wrapping_mul(scale as u64))
+                                    .wrapping_add(off as u64);
+                                Ok(Some(addr as u32))
+                            }
+                            _ => Err(format!("Register R{}/R{} out of range", base, index)),
+                        }
+                    }
                     Ok(ArgValue::Register(reg)) => {
                         if (reg as usize) < registers.len() {
                             Ok(Some(registers[reg as usize] as u32))
@@ -568,6 +794,11 @@ impl DecodeStage {
     }
 
 
+    /// Redimensionne le RAS (vide son contenu)
+    pub fn set_ras_size(&mut self, size: usize) {
+        self.ras = ReturnAddressStack::new(size);
+    }
+
     /// MEt a jour le RAS lors d'un CALL
     pub fn update_ras_for_call(&mut self,pc:u32,instruction_size:u32) {
         let return_address = pc + instruction_size;
@@ -583,6 +814,14 @@ impl DecodeStage {
         self.ras.stats()
     }
 
+    /// Paire fusionnable en une seule micro-op: comparaison (CMP/TEST) immédiatement
+    /// suivie d'un saut conditionnel qui lit ses flags
+    pub fn is_fusible_pair(first: &Instruction, second: &Instruction) -> bool {
+        matches!(first.opcode, Opcode::Cmp | Opcode::Test)
+            && second.opcode.is_branch()
+            && !matches!(second.opcode, Opcode::Jmp | Opcode::Call | Opcode::Ret)
+    }
+
     /// Réinitialise l'étage Decode
     pub fn reset(&mut self) {
         // Pas d'état interne à réinitialiser pour cet étage
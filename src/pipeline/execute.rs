@@ -1,20 +1,41 @@
 //src/pipeline/execute.rs
 
 use crate::alu::alu::{ALUOperation, BranchCondition, ALU};
-use crate::alu::v_alu::{VectorALU, VectorOperation, };
+use crate::alu::v_alu::{VectorALU, VectorOperation, VectorReduction};
 use crate::alu::fpu::{FPU, FPUOperation, FloatPrecision};
 use crate::alu::agu::{AGU, AGUConfig, AddressingMode, AGUError};
 use std::collections::{VecDeque};
 use std::cell::RefCell;
 use std::rc::Rc;
+use crate::bytecode::instructions::{ArgValue, Instruction};
 use crate::bytecode::opcodes::{Opcode, };
 use crate::bytecode::simds::{Vector128, Vector256, VectorDataType, Vector256DataType};
 use crate::pipeline::{DecodeExecuteRegister, ExecuteMemoryRegister};
 use crate::pvm::branch_predictor::{BranchPrediction, BranchPredictor, PredictorType};
 use crate::pipeline::decode::StackOperation;
-use crate::pipeline::parallel::{ExecutionUnit, InstructionPriority, ParallelExecutionEngine, ParallelExecutionStats};
+use crate::pipeline::memory::overlaps_readonly;
+use crate::pipeline::parallel::{DependencyType, ExecutionUnit, InstructionPriority, ParallelExecutionEngine, ParallelExecutionStats};
+use crate::pvm::vm_errors::VMError;
 // use crate::pipeline::parallel::{ParallelExecutionEngine, ParallelExecutionStats, ExecutionUnit, InstructionPriority};
 
+/// Nombre d'unités d'exécution disponibles pour l'émission multiple
+const ALU_UNITS: usize = 4;
+const AGU_UNITS: usize = 1;
+const SIMD_UNITS: usize = 1;
+
+/// Raison pour laquelle une instruction ne peut pas rejoindre le groupe d'émission
+#[derive(Debug, Clone, PartialEq)]
+pub enum IssueBlock {
+    /// Instruction non éligible (branchement, pile, FPU, ...)
+    Ineligible,
+    /// Aucune unité d'exécution libre
+    ResourceConflict,
+    /// ALU scalaire et unité SIMD partagent un port d'émission déjà occupé ce cycle
+    SharedPortConflict,
+    /// Dépendance avec une instruction du groupe
+    Dependency(DependencyType),
+}
+
 /// Contrôleur dual-issue pour exécution parallèle ALU/AGU
 #[derive(Debug, Clone)]
 pub struct DualIssueController {
@@ -26,6 +47,8 @@ pub struct DualIssueController {
     alu_only_instructions: u64,
     agu_only_instructions: u64,
     stalls_resource_conflict: u64,
+    /// Opérations SIMD et ALU scalaires émises par le même port (pas d'unité SIMD dédiée)
+    shared_simd_port: bool,
 }
 
 /// Instruction préparée pour dual-issue
@@ -56,6 +79,7 @@ impl DualIssueController {
             alu_only_instructions: 0,
             agu_only_instructions: 0,
             stalls_resource_conflict: 0,
+            shared_simd_port: false,
         }
     }
     
@@ -63,8 +87,9 @@ impl DualIssueController {
     fn analyze_instruction(&self, instruction: &DecodeExecuteRegister) -> (ExecutionUnit, InstructionPriority) {
         match instruction.instruction.opcode {
             // Instructions mémoire - AGU haute priorité
-            Opcode::Load | Opcode::LoadB | Opcode::LoadW | Opcode::LoadD |
-            Opcode::Store | Opcode::StoreB | Opcode::StoreW | Opcode::StoreD => {
+            Opcode::Load | Opcode::LoadB | Opcode::LoadW | Opcode::LoadD | Opcode::LoadSB | Opcode::LoadSW | Opcode::LoadSD |
+            Opcode::Store | Opcode::StoreB | Opcode::StoreW | Opcode::StoreD |
+            Opcode::Ldm | Opcode::Stm | Opcode::Lea => {
                 (ExecutionUnit::AGU, InstructionPriority::High)
             }
 
@@ -79,6 +104,10 @@ impl DualIssueController {
             Opcode::Inc | Opcode::Dec | Opcode::Neg |
             Opcode::And | Opcode::Or | Opcode::Xor | Opcode::Not |
             Opcode::Shl | Opcode::Shr | Opcode::Sar | Opcode::Rol | Opcode::Ror |
+            Opcode::Shld | Opcode::Shrd | Opcode::Popcnt | Opcode::Clz | Opcode::Ctz |
+            Opcode::Bfe | Opcode::Bfi |
+            Opcode::Crc32 | Opcode::AddSat | Opcode::SubSat | Opcode::AddSatD | Opcode::SubSatD |
+            Opcode::AddSatS | Opcode::SubSatS | Opcode::AddSatSD | Opcode::SubSatSD |
             Opcode::Cmp | Opcode::Test => {
                 (ExecutionUnit::ALU, InstructionPriority::Medium)
             }
@@ -119,8 +148,18 @@ impl DualIssueController {
                 (ExecutionUnit::ALU, InstructionPriority::Low)
             }
 
+            // Déplacements conditionnels: ALU, sans branchement
+            _ if instruction.instruction.opcode.is_conditional_move() => {
+                (ExecutionUnit::ALU, InstructionPriority::Medium)
+            }
+
+            // Traps conditionnels: vérification des flags, priorité haute
+            _ if instruction.instruction.opcode.is_conditional_trap() => {
+                (ExecutionUnit::ALU, InstructionPriority::High)
+            }
+
             // Instructions système - priorité haute
-            Opcode::Halt | Opcode::Syscall => {
+            Opcode::Halt | Opcode::Syscall | Opcode::Sysret | Opcode::RdMode | Opcode::RdInstret => {
                 (ExecutionUnit::Both, InstructionPriority::High)
             }
 
@@ -159,42 +198,47 @@ impl DualIssueController {
     
     /// Vérifie les dépendances de registres entre deux instructions
     fn check_register_dependency(&self, instr1: &DecodeExecuteRegister, instr2: &DecodeExecuteRegister) -> bool {
+        self.register_dependency(instr1, instr2).is_some()
+    }
+
+    /// Type de la dépendance de registre de `instr2` envers `instr1` (plus ancienne), s'il y en a une
+    fn register_dependency(&self, instr1: &DecodeExecuteRegister, instr2: &DecodeExecuteRegister) -> Option<DependencyType> {
         // RAW (Read After Write): instr2 lit un registre que instr1 écrit
         if let Some(rd1) = instr1.rd {
             if instr2.rs1 == Some(rd1) || instr2.rs2 == Some(rd1) {
-                return true;
+                return Some(DependencyType::RAW);
             }
         }
         
         // WAR (Write After Read): instr2 écrit un registre que instr1 lit
         if let Some(rd2) = instr2.rd {
             if instr1.rs1 == Some(rd2) || instr1.rs2 == Some(rd2) {
-                return true;
+                return Some(DependencyType::WAR);
             }
         }
         
         // WAW (Write After Write): les deux écrivent le même registre
         if let (Some(rd1), Some(rd2)) = (instr1.rd, instr2.rd) {
             if rd1 == rd2 {
-                return true;
+                return Some(DependencyType::WAW);
             }
         }
         
-        false
+        None
     }
     
     /// Vérifie les dépendances mémoire entre deux instructions
     fn check_memory_dependency(&self, instr1: &DecodeExecuteRegister, instr2: &DecodeExecuteRegister) -> bool {
         let instr1_is_memory = matches!(instr1.instruction.opcode, 
-            Opcode::Load | Opcode::LoadB | Opcode::LoadW | Opcode::LoadD |
+            Opcode::Load | Opcode::LoadB | Opcode::LoadW | Opcode::LoadD | Opcode::LoadSB | Opcode::LoadSW | Opcode::LoadSD |
             Opcode::Store | Opcode::StoreB | Opcode::StoreW | Opcode::StoreD |
-            Opcode::Push | Opcode::Pop
+            Opcode::Ldm | Opcode::Stm | Opcode::Push | Opcode::Pop
         );
         
         let instr2_is_memory = matches!(instr2.instruction.opcode,
-            Opcode::Load | Opcode::LoadB | Opcode::LoadW | Opcode::LoadD |
+            Opcode::Load | Opcode::LoadB | Opcode::LoadW | Opcode::LoadD | Opcode::LoadSB | Opcode::LoadSW | Opcode::LoadSD |
             Opcode::Store | Opcode::StoreB | Opcode::StoreW | Opcode::StoreD |
-            Opcode::Push | Opcode::Pop
+            Opcode::Ldm | Opcode::Stm | Opcode::Push | Opcode::Pop
         );
         
         // Si les deux sont des instructions mémoire, vérifier l'aliasing
@@ -207,6 +251,79 @@ impl DualIssueController {
         false
     }
     
+    /// Vérifie si `candidate` peut rejoindre le groupe d'émission `group` (instructions déjà
+    /// émises ce cycle, dans l'ordre du programme). Seules les instructions ALU, AGU et SIMD sans
+    /// effet implicite (pile, flot de contrôle, transferts multiples) sont éligibles, dans la
+    /// limite des unités disponibles, et sans dépendance avec aucun membre du groupe.
+    pub fn check_issue_group(
+        &self,
+        group: &[DecodeExecuteRegister],
+        candidate: &DecodeExecuteRegister,
+    ) -> Result<ExecutionUnit, IssueBlock> {
+        let candidate_unit = self.co_issue_unit(candidate).ok_or(IssueBlock::Ineligible)?;
+
+        let mut used = [0usize; 3];
+        for member in group {
+            match self.co_issue_unit(member) {
+                Some(unit) => used[Self::unit_slot(&unit)] += 1,
+                None => return Err(IssueBlock::Ineligible),
+            }
+        }
+        // Les accès mémoire SIMD passent par l'AGU: AGU et SIMD se partagent un port mémoire
+        let memory_port_used = used[1] + used[2] > 0;
+        let unit_available = match candidate_unit {
+            ExecutionUnit::ALU => used[0] < ALU_UNITS,
+            ExecutionUnit::AGU => used[1] < AGU_UNITS && !memory_port_used,
+            ExecutionUnit::SIMD => used[2] < SIMD_UNITS && !memory_port_used,
+            _ => false,
+        };
+        if !unit_available {
+            return Err(IssueBlock::ResourceConflict);
+        }
+        // Port partagé: une opération SIMD et une opération ALU ne sont jamais émises ensemble
+        let port_taken = match candidate_unit {
+            ExecutionUnit::ALU => used[2] > 0,
+            ExecutionUnit::SIMD => used[0] > 0,
+            _ => false,
+        };
+        if self.shared_simd_port && port_taken {
+            return Err(IssueBlock::SharedPortConflict);
+        }
+
+        for member in group {
+            if let Some(dep_type) = self.register_dependency(member, candidate) {
+                return Err(IssueBlock::Dependency(dep_type));
+            }
+            if self.check_memory_dependency(member, candidate) {
+                return Err(IssueBlock::Dependency(DependencyType::RAW));
+            }
+        }
+
+        Ok(candidate_unit)
+    }
+
+    fn unit_slot(unit: &ExecutionUnit) -> usize {
+        match unit {
+            ExecutionUnit::AGU => 1,
+            ExecutionUnit::SIMD => 2,
+            _ => 0,
+        }
+    }
+
+    /// Unité d'exécution d'une instruction éligible à l'émission multiple
+    fn co_issue_unit(&self, instruction: &DecodeExecuteRegister) -> Option<ExecutionUnit> {
+        if matches!(
+            instruction.instruction.opcode,
+            Opcode::Push | Opcode::Pop | Opcode::Ldm | Opcode::Stm
+        ) {
+            return None;
+        }
+        match self.analyze_instruction(instruction).0 {
+            unit @ (ExecutionUnit::ALU | ExecutionUnit::AGU | ExecutionUnit::SIMD) => Some(unit),
+            _ => None,
+        }
+    }
+
     /// Simule l'exécution dual-issue (version simplifiée)
     pub fn simulate_parallel_execution(&mut self, exec_unit: ExecutionUnit) -> bool {
         // Pour la démonstration, simuler quelques exécutions parallèles
@@ -248,6 +365,16 @@ impl DualIssueController {
 
 
 
+/// Mode de privilège du processeur
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PrivilegeMode {
+    /// Mode utilisateur: les instructions superviseur lèvent une `PrivilegeViolation`
+    #[default]
+    User,
+    /// Mode superviseur, atteint via `Syscall`
+    Supervisor,
+}
+
 /// Implementation de l'étage Execute du pipeline
 pub struct ExecuteStage {
     // Unité ALU
@@ -270,6 +397,37 @@ pub struct ExecuteStage {
     branch_hits:u64,
     /// Cycle actuel pour l'AGU
     current_cycle: u64,
+    /// Exception matérielle levée par la dernière instruction (ex: division par zéro)
+    pending_fault: Option<VMError>,
+    /// Adresse du Syscall exécuté ce cycle, en attente du gestionnaire de la VM
+    pending_syscall: Option<u32>,
+    /// Préchargements demandés par le prédicteur de stride: (adresse accédée, prochaine adresse prédite)
+    prefetch_requests: Vec<(u32, u32)>,
+    /// Mode de privilège courant
+    privilege_mode: PrivilegeMode,
+    /// Instructions retirées avant l'instruction en cours d'exécution (lu par RdInstret)
+    retired_instructions: u64,
+    /// Zones [début, fin) protégées en écriture, pour les stores exécutés dans cet étage
+    readonly_ranges: Vec<(u32, u32)>,
+}
+
+/// Condition des flags évaluée par un trap (`TrapIf*`) ou un déplacement conditionnel (`Cmov*`),
+/// avec la même sémantique que le branchement conditionnel correspondant
+fn flag_condition(opcode: Opcode) -> BranchCondition {
+    match opcode {
+        Opcode::TrapIfEqual | Opcode::CmovEqual => BranchCondition::Equal,
+        Opcode::TrapIfNotEqual | Opcode::CmovNotEqual => BranchCondition::NotEqual,
+        Opcode::TrapIfGreater | Opcode::CmovGreater => BranchCondition::Greater,
+        Opcode::TrapIfGreaterEqual | Opcode::CmovGreaterEqual => BranchCondition::GreaterEqual,
+        Opcode::TrapIfLess | Opcode::CmovLess => BranchCondition::Less,
+        Opcode::TrapIfLessEqual | Opcode::CmovLessEqual => BranchCondition::LessEqual,
+        Opcode::TrapIfAbove | Opcode::CmovAbove => BranchCondition::Above,
+        Opcode::TrapIfAboveEqual | Opcode::CmovAboveEqual => BranchCondition::AboveEqual,
+        Opcode::CmovBelow => BranchCondition::Below,
+        Opcode::CmovBelowEqual => BranchCondition::BelowEqual,
+        Opcode::TrapIfOverflow => BranchCondition::Overflow,
+        _ => BranchCondition::Always, // Ne devrait pas arriver
+    }
 }
 
 impl ExecuteStage {
@@ -299,9 +457,57 @@ impl ExecuteStage {
             branch_predictions: 0,
             branch_hits: 0,
             current_cycle: 0,
+            pending_fault: None,
+            pending_syscall: None,
+            prefetch_requests: Vec::new(),
+            privilege_mode: PrivilegeMode::User,
+            retired_instructions: 0,
+            readonly_ranges: Vec::new(),
         }
     }
 
+    /// Mode de privilège courant
+    pub fn privilege_mode(&self) -> PrivilegeMode {
+        self.privilege_mode
+    }
+
+    /// Force le mode de privilège (restauration d'un snapshot)
+    pub fn set_privilege_mode(&mut self, mode: PrivilegeMode) {
+        self.privilege_mode = mode;
+    }
+
+    /// Nombre d'instructions retirées avant la prochaine instruction exécutée
+    pub fn set_retired_instructions(&mut self, count: u64) {
+        self.retired_instructions = count;
+    }
+
+    /// Segments en lecture seule du programme chargé (stores masqués SIMD)
+    pub fn set_readonly_ranges(&mut self, ranges: Vec<(u32, u32)>) {
+        self.readonly_ranges = ranges;
+    }
+
+    /// Récupère (et efface) l'exception levée par l'étage Execute
+    pub fn take_fault(&mut self) -> Option<VMError> {
+        self.pending_fault.take()
+    }
+
+    /// Récupère (et efface) l'adresse du Syscall à servir
+    pub fn take_syscall(&mut self) -> Option<u32> {
+        self.pending_syscall.take()
+    }
+
+    /// Récupère (et efface) les préchargements demandés par le prédicteur de stride
+    pub fn take_prefetch_requests(&mut self) -> Vec<(u32, u32)> {
+        std::mem::take(&mut self.prefetch_requests)
+    }
+
+    /// Enregistre une exception et retourne le message d'erreur du pipeline
+    fn raise_fault(&mut self, fault: VMError) -> String {
+        let msg = fault.to_string();
+        self.pending_fault = Some(fault);
+        msg
+    }
+
     /// Traite l'étage Execute avec fallback sur dual-issue si pas prêt pour parallel
     pub fn process_parallel(
         &mut self,
@@ -395,12 +601,17 @@ impl ExecuteStage {
         // Vérifier le type d'instruction
         match ex_reg.instruction.opcode {
             // Toutes les instructions mémoire passent par l'AGU
-            Opcode::Load | Opcode::LoadB | Opcode::LoadW | Opcode::LoadD |
+            Opcode::Load | Opcode::LoadB | Opcode::LoadW | Opcode::LoadD | Opcode::LoadSB | Opcode::LoadSW | Opcode::LoadSD |
             Opcode::Store | Opcode::StoreB | Opcode::StoreW | Opcode::StoreD |
             Opcode::Simd128Load | Opcode::Simd128Store | Opcode::Simd256Load | Opcode::Simd256Store |
+            Opcode::Ldm | Opcode::Stm | Opcode::Lea |
             Opcode::Push | Opcode::Pop | Opcode::Call | Opcode::Ret => {
                 self.process_memory_with_agu(ex_reg, alu, registers, sp)
             }
+            // Les accès masqués touchent la mémoire lane par lane
+            Opcode::Simd128MaskedLoad | Opcode::Simd128MaskedStore => {
+                self.process_simd_memory_operations(ex_reg, alu, memory, registers, sp)
+            }
             _ => {
                 // Pour toutes les autres opérations, utiliser la méthode normale
                 self.process_direct(ex_reg, alu)
@@ -500,12 +711,62 @@ impl ExecuteStage {
                 println!("SIMD256Store: Vector Y{} stored to memory", src_reg);
             }
             
+            Opcode::Simd128MaskedLoad => {
+                let Ok(ArgValue::Register(dst_reg)) = ex_reg.instruction.get_arg1_value() else {
+                    return Err("SIMD128MaskedLoad: registre destination manquant".to_string());
+                };
+                let base = ex_reg.rs1_value as u32;
+                let mask = ex_reg.rs2_value;
+
+                // Les lanes hors masque restent à zéro et ne génèrent aucun accès
+                let mut lanes = [0i32; 4];
+                for (lane, value) in lanes.iter_mut().enumerate() {
+                    if mask & (1 << lane) != 0 {
+                        let addr = self.masked_lane_address(memory, base, lane)?;
+                        *value = memory.read_dword(addr)
+                            .map_err(|e| format!("SIMD128MaskedLoad: Erreur lecture mémoire: {}", e))? as i32;
+                    }
+                }
+
+                self.vector_alu.borrow_mut().write_v128(dst_reg, Vector128 { i32x4: lanes })
+                    .map_err(|e| format!("SIMD128MaskedLoad: Erreur écriture registre V128: {}", e))?;
+
+                println!("SIMD128MaskedLoad: V{} <- [0x{:08X}] masque {:#06b}", dst_reg, base, mask & 0xF);
+            }
+
+            Opcode::Simd128MaskedStore => {
+                let Ok(ArgValue::Register(src_reg)) = ex_reg.instruction.get_arg1_value() else {
+                    return Err("SIMD128MaskedStore: registre source manquant".to_string());
+                };
+                let base = ex_reg.rs1_value as u32;
+                let mask = ex_reg.rs2_value;
+
+                let vector = self.vector_alu.borrow_mut().read_v128(src_reg)
+                    .map_err(|e| format!("SIMD128MaskedStore: Erreur lecture registre V128: {}", e))?;
+                let lanes = unsafe { vector.i32x4 };
+
+                for (lane, &value) in lanes.iter().enumerate() {
+                    if mask & (1 << lane) != 0 {
+                        let addr = self.masked_lane_address(memory, base, lane)?;
+                        if overlaps_readonly(&self.readonly_ranges, addr, 4) {
+                            return Err(self.raise_fault(VMError::WriteToReadOnly { addr }));
+                        }
+                        memory.write_dword(addr, value as u32)
+                            .map_err(|e| format!("SIMD128MaskedStore: Erreur écriture mémoire: {}", e))?;
+                    }
+                }
+
+                println!("SIMD128MaskedStore: V{} -> [0x{:08X}] masque {:#06b}", src_reg, base, mask & 0xF);
+            }
+
             _ => return Err(format!("Opcode SIMD mémoire non supporté: {:?}", opcode)),
         }
 
         // Créer le registre Execute-Memory pour la suite du pipeline
         Ok(ExecuteMemoryRegister {
+            id: ex_reg.id,
             instruction: ex_reg.instruction.clone(),
+            pc: ex_reg.pc,
             rd: ex_reg.rd,
             alu_result: 0, // Les opérations SIMD ne génèrent pas de résultat ALU
             mem_addr: ex_reg.mem_addr,
@@ -517,9 +778,24 @@ impl ExecuteStage {
             branch_prediction_correct: None,
             ras_prediction_correct: None,
             halted: false,
+            vector_data: None,
         })
     }
 
+    /// Adresse d'une lane i32 d'un accès masqué; une lane hors mémoire lève une faute
+    fn masked_lane_address(
+        &mut self,
+        memory: &crate::pvm::memorys::Memory,
+        base: u32,
+        lane: usize,
+    ) -> Result<u32, String> {
+        let addr = base as u64 + lane as u64 * 4;
+        if addr + 4 > memory.size() as u64 {
+            return Err(self.raise_fault(VMError::MemoryOutOfBounds { addr, size: 4 }));
+        }
+        Ok(addr as u32)
+    }
+
     /// Traite l'étage Execute directement
     pub fn process_direct(
         &mut self,
@@ -561,6 +837,24 @@ impl ExecuteStage {
                 );
             }
 
+            Opcode::AddSat | Opcode::AddSatD | Opcode::AddSatS | Opcode::AddSatSD
+            | Opcode::SubSat | Opcode::SubSatD | Opcode::SubSatS | Opcode::SubSatSD => {
+                let opcode = ex_reg.instruction.opcode;
+                // Largeur et signe saturés selon la variante (D = 32 bits, S = signé)
+                let width = if matches!(opcode, Opcode::AddSatD | Opcode::SubSatD | Opcode::AddSatSD | Opcode::SubSatSD) { 32 } else { 64 };
+                let signed = matches!(opcode, Opcode::AddSatS | Opcode::SubSatS | Opcode::AddSatSD | Opcode::SubSatSD);
+                let operation = if matches!(opcode, Opcode::AddSat | Opcode::AddSatD | Opcode::AddSatS | Opcode::AddSatSD) {
+                    ALUOperation::AddSat(width, signed)
+                } else {
+                    ALUOperation::SubSat(width, signed)
+                };
+                alu_result = alu.execute(operation, rs1_value, rs2_value)?;
+                println!(
+                    "Execute {}: rs1_value={}, rs2_value={}, alu_result={}",
+                    opcode.name().to_uppercase(), rs1_value, rs2_value, alu_result
+                );
+            }
+
             Opcode::Mul => {
                 alu_result = alu.execute(ALUOperation::Mul, rs1_value, rs2_value)?;
                 println!(
@@ -570,18 +864,28 @@ impl ExecuteStage {
             }
 
             Opcode::Div => {
-                alu_result = alu.execute(ALUOperation::Div, rs1_value, rs2_value)?;
+                // Diviseur nul: trap avant tout calcul
+                let divisor = ex_reg.immediate.unwrap_or(rs2_value);
+                if divisor == 0 {
+                    return Err(self.raise_fault(VMError::DivisionByZero { pc: ex_reg.pc }));
+                }
+                alu_result = alu.execute(ALUOperation::Div, rs1_value, divisor)?;
                 println!(
-                    "Execute DIV: rs1_value={}, rs2_value={}, alu_result={}",
-                    rs1_value, rs2_value, alu_result
+                    "Execute DIV: rs1_value={}, divisor={}, alu_result={}",
+                    rs1_value, divisor, alu_result
                 );
             }
 
             Opcode::Mod => {
-                alu_result = alu.execute(ALUOperation::Mod, rs1_value, rs2_value)?;
+                // Diviseur nul: trap avant tout calcul
+                let divisor = ex_reg.immediate.unwrap_or(rs2_value);
+                if divisor == 0 {
+                    return Err(self.raise_fault(VMError::DivisionByZero { pc: ex_reg.pc }));
+                }
+                alu_result = alu.execute(ALUOperation::Mod, rs1_value, divisor)?;
                 println!(
-                    "Execute MOD: rs1_value={}, rs2_value={}, alu_result={}",
-                    rs1_value, rs2_value, alu_result
+                    "Execute MOD: rs1_value={}, divisor={}, alu_result={}",
+                    rs1_value, divisor, alu_result
                 );
             }
             Opcode::Mov => {
@@ -680,7 +984,9 @@ impl ExecuteStage {
             }
 
             Opcode::Rol => {
-                alu_result = alu.execute(ALUOperation::Rol, rs1_value, rs2_value)?;
+                // Compteur de rotation: registre ou immédiat 8 bits
+                let amount = ex_reg.immediate.unwrap_or(rs2_value);
+                alu_result = alu.execute(ALUOperation::Rol, rs1_value, amount)?;
                 println!(
                     "Execute ROL: rs1_value={}, rs2_value={}, alu_result={}",
                     rs1_value, rs2_value, alu_result
@@ -688,13 +994,71 @@ impl ExecuteStage {
             }
 
             Opcode::Ror => {
-                alu_result = alu.execute(ALUOperation::Ror, rs1_value, rs2_value)?;
+                // Compteur de rotation: registre ou immédiat 8 bits
+                let amount = ex_reg.immediate.unwrap_or(rs2_value);
+                alu_result = alu.execute(ALUOperation::Ror, rs1_value, amount)?;
                 println!(
                     "Execute ROR: rs1_value={}, rs2_value={}, alu_result={}",
                     rs1_value, rs2_value, alu_result
                 );
             }
 
+            Opcode::Shld => {
+                // rd:rs décalé à gauche, compteur immédiat 8 bits
+                let count = ex_reg.immediate.unwrap_or(0);
+                alu_result = alu.execute(ALUOperation::Shld(count), rs1_value, rs2_value)?;
+                println!(
+                    "Execute SHLD: rs1_value={:#X}, rs2_value={:#X}, count={}, alu_result={:#X}",
+                    rs1_value, rs2_value, count, alu_result
+                );
+            }
+
+            Opcode::Shrd => {
+                // rs:rd décalé à droite, compteur immédiat 8 bits
+                let count = ex_reg.immediate.unwrap_or(0);
+                alu_result = alu.execute(ALUOperation::Shrd(count), rs1_value, rs2_value)?;
+                println!(
+                    "Execute SHRD: rs1_value={:#X}, rs2_value={:#X}, count={}, alu_result={:#X}",
+                    rs1_value, rs2_value, count, alu_result
+                );
+            }
+
+            Opcode::Popcnt | Opcode::Clz | Opcode::Ctz => {
+                let operation = match ex_reg.instruction.opcode {
+                    Opcode::Popcnt => ALUOperation::Popcnt,
+                    Opcode::Clz => ALUOperation::Clz,
+                    _ => ALUOperation::Ctz,
+                };
+                alu_result = alu.execute(operation, rs1_value, 0)?;
+                println!(
+                    "Execute {:?}: rs1_value={:#X}, alu_result={}",
+                    ex_reg.instruction.opcode, rs1_value, alu_result
+                );
+            }
+
+            Opcode::Bfe | Opcode::Bfi => {
+                // lsb et width validés au décodage
+                let (lsb, width) = Instruction::unpack_bitfield(ex_reg.immediate.unwrap_or(0));
+                let operation = if ex_reg.instruction.opcode == Opcode::Bfe {
+                    ALUOperation::Bfe(lsb, width)
+                } else {
+                    ALUOperation::Bfi(lsb, width)
+                };
+                alu_result = alu.execute(operation, rs1_value, rs2_value)?;
+                println!(
+                    "Execute {:?}: rs1_value={:#X}, rs2_value={:#X}, lsb={}, width={}, alu_result={:#X}",
+                    ex_reg.instruction.opcode, rs1_value, rs2_value, lsb, width, alu_result
+                );
+            }
+
+            Opcode::Crc32 => {
+                alu_result = alu.execute(ALUOperation::Crc32, rs1_value, rs2_value)?;
+                println!(
+                    "Execute CRC32: rs1_value={:#X}, rs2_value={:#X}, alu_result={:#X}",
+                    rs1_value, rs2_value, alu_result
+                );
+            }
+
             // Instructions de comparaison
             Opcode::Cmp => {
                 // Compare mais ne stocke pas le résultat
@@ -796,7 +1160,7 @@ impl ExecuteStage {
             }
 ////////////////////////////////////Control des FLOW////////////////////////////////////////////////////////
             // Instructions d'accès mémoire - NOTE: Ces cas sont maintenant gérés dans process_memory_with_agu
-            Opcode::Load | Opcode::LoadB | Opcode::LoadW | Opcode::LoadD => {
+            Opcode::Load | Opcode::LoadB | Opcode::LoadW | Opcode::LoadD | Opcode::LoadSB | Opcode::LoadSW | Opcode::LoadSD => {
                 // Ces instructions finalisent leur exécution dans l'étage Memory
                 // L'adresse peut être recalculée par l'AGU pour optimisations
                 alu_result = 0; // Sera remplacé par la valeur chargée
@@ -879,8 +1243,69 @@ impl ExecuteStage {
 
             // Instructions spéciales
             Opcode::Syscall => {
-                // Traitées séparément (pas implémenté pour l'instant)
-                return Err("Syscall non implémenté".to_string());
+                // Trap logiciel: passage en mode superviseur; la VM appelle son gestionnaire
+                // de syscalls à la fin du cycle, puis l'exécution continue en séquence
+                self.privilege_mode = PrivilegeMode::Supervisor;
+                self.pending_syscall = Some(ex_reg.pc);
+                println!("Execute SYSCALL: passage en mode superviseur");
+            },
+
+            Opcode::Sysret => {
+                if self.privilege_mode != PrivilegeMode::Supervisor {
+                    return Err(self.raise_fault(VMError::PrivilegeViolation { pc: ex_reg.pc }));
+                }
+                self.privilege_mode = PrivilegeMode::User;
+                println!("Execute SYSRET: retour en mode utilisateur");
+            },
+
+            // Déplacements conditionnels: rd garde sa valeur (rs1) si la condition est fausse
+            Opcode::CmovEqual
+            | Opcode::CmovNotEqual
+            | Opcode::CmovGreater
+            | Opcode::CmovGreaterEqual
+            | Opcode::CmovLess
+            | Opcode::CmovLessEqual
+            | Opcode::CmovAbove
+            | Opcode::CmovAboveEqual
+            | Opcode::CmovBelow
+            | Opcode::CmovBelowEqual => {
+                let taken = alu.check_condition(flag_condition(ex_reg.instruction.opcode));
+                alu_result = if taken { rs2_value } else { rs1_value };
+                println!(
+                    "Execute {:?}: condition={}, alu_result={}",
+                    ex_reg.instruction.opcode, taken, alu_result
+                );
+            },
+
+            // Traps conditionnels: même codes de condition que les branchements
+            Opcode::TrapIfEqual
+            | Opcode::TrapIfNotEqual
+            | Opcode::TrapIfGreater
+            | Opcode::TrapIfGreaterEqual
+            | Opcode::TrapIfLess
+            | Opcode::TrapIfLessEqual
+            | Opcode::TrapIfAbove
+            | Opcode::TrapIfAboveEqual
+            | Opcode::TrapIfOverflow => {
+                if alu.check_condition(flag_condition(ex_reg.instruction.opcode)) {
+                    let code = ex_reg.immediate.unwrap_or(0) as u8;
+                    return Err(self.raise_fault(VMError::ExplicitTrap { pc: ex_reg.pc, code }));
+                }
+                println!("Execute {:?}: condition fausse, pas de trap", ex_reg.instruction.opcode);
+            },
+
+            Opcode::RdMode => {
+                alu_result = match self.privilege_mode {
+                    PrivilegeMode::User => 0,
+                    PrivilegeMode::Supervisor => 1,
+                };
+                println!("Execute RDMODE: mode={:?}", self.privilege_mode);
+            },
+
+            Opcode::RdInstret => {
+                // Les instructions plus anciennes du même groupe d'émission sont déjà retirées
+                alu_result = self.retired_instructions;
+                println!("Execute RDINSTRET: instret={}", alu_result);
             },
 
             Opcode::Break => {
@@ -891,7 +1316,9 @@ impl ExecuteStage {
             Opcode::Halt => {
                 println!("Execute HALT");
                 return Ok(ExecuteMemoryRegister {
+                    id: ex_reg.id,
                     instruction: ex_reg.instruction.clone(),
+                    pc: ex_reg.pc,
                     alu_result: 0,
                     rd: ex_reg.rd,
                     store_value: None,
@@ -903,6 +1330,7 @@ impl ExecuteStage {
                     stack_result: None,
                     ras_prediction_correct: None,
                     halted: true, // un champ qu’il faut rajouter (voir ci-dessous)
+                    vector_data: None,
                 });
             }
 
@@ -911,7 +1339,10 @@ impl ExecuteStage {
             Opcode::Simd128And | Opcode::Simd128Or | Opcode::Simd128Xor | Opcode::Simd128Not |
             Opcode::Simd128Mov | Opcode::Simd128Load | Opcode::Simd128Store |
             Opcode::Simd128Cmp | Opcode::Simd128Min | Opcode::Simd128Max |
-            Opcode::Simd128Sqrt | Opcode::Simd128Shuffle  | Opcode::Simd128Const | Opcode::Simd128ConstF32 |
+            Opcode::Simd128Sqrt | Opcode::Simd128Rcp | Opcode::Simd128Rsqrt |
+            Opcode::Simd128Shuffle  | Opcode::Simd128FMA | Opcode::Simd128Blend | Opcode::Simd128Const | Opcode::Simd128ConstF32 |
+            Opcode::Simd128AddI64x2 | Opcode::Simd128SubI64x2 | Opcode::Simd128MulI64x2 |
+            Opcode::Simd128AddF64x2 | Opcode::Simd128SubF64x2 | Opcode::Simd128MulF64x2 | Opcode::Simd128DivF64x2 |
             Opcode::Simd128ConstI16x8 | Opcode::Simd128ConstI64x2 | Opcode::Simd128ConstF64x2 => {
                 self.execute_simd_128(&ex_reg.instruction.opcode, ex_reg)?;
                 // Pour les instructions SIMD, on retourne 0 car le résultat est dans les registres vectoriels
@@ -920,13 +1351,26 @@ impl ExecuteStage {
                 println!("Execute SIMD128 {:?}: completed", ex_reg.instruction.opcode);
             }
 
+            // Réductions horizontales SIMD 128-bit: le scalaire va dans le registre général rd
+            Opcode::Simd128HAdd | Opcode::Simd128HMin | Opcode::Simd128HMax => {
+                alu_result = self.execute_simd_128_reduction(&ex_reg.instruction.opcode, ex_reg)?;
+                println!("Execute SIMD128 {:?}: result={}", ex_reg.instruction.opcode, alu_result as i64);
+            }
+
+            // Transferts entre lanes V128 et registres généraux
+            Opcode::Simd128ExtractLane | Opcode::Simd128InsertLane => {
+                alu_result = self.execute_simd_128_lane_move(&ex_reg.instruction.opcode, ex_reg)?;
+                println!("Execute SIMD128 {:?}: value={}", ex_reg.instruction.opcode, alu_result as i64);
+            }
+
             // Instructions SIMD 256-bit
             Opcode::Simd256Add | Opcode::Simd256Sub | Opcode::Simd256Mul | Opcode::Simd256Div |
             Opcode::Simd256And | Opcode::Simd256Or | Opcode::Simd256Xor | Opcode::Simd256Not |
             Opcode::Simd256Mov | Opcode::Simd256Load | Opcode::Simd256Store |
             Opcode::Simd256Cmp | Opcode::Simd256Min | Opcode::Simd256Max |
             Opcode::Simd256Sqrt | Opcode::Simd256Shuffle | Opcode::Simd256Const | Opcode::Simd256ConstF32 |
-            Opcode::Simd256ConstI16x16 | Opcode::Simd256ConstI64x4 | Opcode::Simd256ConstF64x4 => {
+            Opcode::Simd256ConstI16x16 | Opcode::Simd256ConstI64x4 | Opcode::Simd256ConstF64x4 |
+            Opcode::Simd256AddI64x4 | Opcode::Simd256SubI64x4 | Opcode::Simd256MulI64x4 => {
                 self.execute_simd_256(&ex_reg.instruction.opcode, ex_reg)?;
                 // Pour les instructions SIMD, on retourne 0 car le résultat est dans les registres vectoriels
                 alu_result = 0;
@@ -1012,7 +1456,9 @@ impl ExecuteStage {
 
 
         Ok(ExecuteMemoryRegister {
+            id: ex_reg.id,
             instruction: ex_reg.instruction.clone(),
+            pc: ex_reg.pc,
             alu_result,
             rd: ex_reg.rd,
             store_value, // pour CMP
@@ -1027,6 +1473,7 @@ impl ExecuteStage {
             stack_result,
             ras_prediction_correct,
             halted: false, // Pas de halt ici
+            vector_data: None,
         })
     }
 
@@ -1098,8 +1545,14 @@ impl ExecuteStage {
             Opcode::Simd128Min => VectorOperation::Min,
             Opcode::Simd128Max => VectorOperation::Max,
             Opcode::Simd128Sqrt => VectorOperation::Sqrt,
+            Opcode::Simd128Rcp => VectorOperation::Rcp,
+            Opcode::Simd128Rsqrt => VectorOperation::Rsqrt,
             Opcode::Simd128Cmp => VectorOperation::Cmp,
             Opcode::Simd128Shuffle => VectorOperation::Shuffle,
+            Opcode::Simd128AddI64x2 | Opcode::Simd128AddF64x2 => VectorOperation::Add,
+            Opcode::Simd128SubI64x2 | Opcode::Simd128SubF64x2 => VectorOperation::Sub,
+            Opcode::Simd128MulI64x2 | Opcode::Simd128MulF64x2 => VectorOperation::Mul,
+            Opcode::Simd128DivF64x2 => VectorOperation::Div,
             Opcode::Simd128Load => {
                 // Chargement d'un vecteur 128-bit depuis la mémoire
                 // implementer dans process_with_memory()
@@ -1127,6 +1580,29 @@ impl ExecuteStage {
                 
                 return Ok(());
             }
+            Opcode::Simd128FMA => {
+                // Le troisième opérande (vc) est dans l'octet haut de l'immédiat 16 bits
+                let src3_reg = match ex_reg.instruction.get_arg3_value() {
+                    Ok(ArgValue::Immediate(pair)) => ((pair >> 8) & 0x0F) as u8,
+                    _ => return Err("Simd128FMA: registre vc manquant".to_string()),
+                };
+                self.vector_alu.borrow_mut()
+                    .fma_v128(dst_reg, src1_reg, src2_reg, src3_reg, VectorDataType::F32x4)
+                    .map_err(|e| format!("Erreur exécution SIMD 128-bit: {}", e))?;
+                return Ok(());
+            }
+            Opcode::Simd128Blend => {
+                // vd n'est pas décodé dans rd (registre V128), le masque est l'octet haut de l'immédiat
+                let (Ok(ArgValue::Register(vd)), Ok(ArgValue::Immediate(pair))) =
+                    (ex_reg.instruction.get_arg1_value(), ex_reg.instruction.get_arg3_value())
+                else {
+                    return Err("Simd128Blend: opérandes vd/vmask manquants".to_string());
+                };
+                self.vector_alu.borrow_mut()
+                    .blend_v128(vd, src1_reg, src2_reg, ((pair >> 8) & 0x0F) as u8)
+                    .map_err(|e| format!("Erreur exécution SIMD 128-bit: {}", e))?;
+                return Ok(());
+            }
             Opcode::Simd128Mov => {
                 // Mov vectoriel simple
                 let src_vector = self.vector_alu.borrow_mut().read_v128(src1_reg)
@@ -1225,6 +1701,10 @@ impl ExecuteStage {
         // Déterminer le type de données vectorielles selon l'opération
         let data_type = match opcode {
             Opcode::Simd128Sqrt => VectorDataType::F32x4, // Sqrt nécessite des flottants
+            Opcode::Simd128Rcp | Opcode::Simd128Rsqrt => VectorDataType::F32x4, // Approximations f32x4 uniquement
+            Opcode::Simd128AddI64x2 | Opcode::Simd128SubI64x2 | Opcode::Simd128MulI64x2 => VectorDataType::I64x2,
+            Opcode::Simd128AddF64x2 | Opcode::Simd128SubF64x2 | Opcode::Simd128MulF64x2
+            | Opcode::Simd128DivF64x2 => VectorDataType::F64x2,
             _ => VectorDataType::I32x4, // Type par défaut pour les autres opérations
         };
 
@@ -1239,6 +1719,53 @@ impl ExecuteStage {
         Ok(())
     }
 
+    /// Exécute une réduction horizontale SIMD 128-bit et retourne le scalaire obtenu
+    fn execute_simd_128_reduction(&mut self, opcode: &Opcode, ex_reg: &DecodeExecuteRegister) -> Result<u64, String> {
+        let src_reg = ex_reg.rs1.unwrap_or(0) as u8;
+
+        let reduction = match opcode {
+            Opcode::Simd128HAdd => VectorReduction::Sum,
+            Opcode::Simd128HMin => VectorReduction::Min,
+            Opcode::Simd128HMax => VectorReduction::Max,
+            _ => return Err(format!("Réduction SIMD 128-bit non supportée: {:?}", opcode)),
+        };
+
+        // arg3 = 1 pour des lanes f32x4, i32x4 sinon
+        let data_type = match ex_reg.instruction.get_arg3_value() {
+            Ok(ArgValue::Immediate(1)) => VectorDataType::F32x4,
+            _ => VectorDataType::I32x4,
+        };
+
+        self.vector_alu.borrow_mut().reduce_v128(reduction, src_reg, data_type)
+            .map_err(|e| format!("Erreur réduction SIMD 128-bit: {}", e))
+    }
+
+    /// Exécute un transfert de lane: ExtractLane retourne la lane lue (écrite dans rd),
+    /// InsertLane retourne la valeur insérée (aucun registre général n'est écrit)
+    fn execute_simd_128_lane_move(&mut self, opcode: &Opcode, ex_reg: &DecodeExecuteRegister) -> Result<u64, String> {
+        let lane = match ex_reg.instruction.get_arg3_value() {
+            Ok(ArgValue::Immediate(lane)) => lane as u8,
+            _ => return Err(format!("{:?}: numéro de lane manquant", opcode)),
+        };
+
+        match opcode {
+            Opcode::Simd128ExtractLane => {
+                let src_reg = ex_reg.rs1.unwrap_or(0) as u8;
+                self.vector_alu.borrow_mut().extract_lane_v128(src_reg, lane)
+                    .map_err(|e| format!("Erreur extraction de lane SIMD 128-bit: {}", e))
+            }
+            Opcode::Simd128InsertLane => {
+                let Ok(ArgValue::Register(dst_reg)) = ex_reg.instruction.get_arg1_value() else {
+                    return Err("Simd128InsertLane: registre V128 destination manquant".to_string());
+                };
+                self.vector_alu.borrow_mut().insert_lane_v128(dst_reg, lane, ex_reg.rs1_value)
+                    .map_err(|e| format!("Erreur insertion de lane SIMD 128-bit: {}", e))?;
+                Ok(ex_reg.rs1_value)
+            }
+            _ => Err(format!("Transfert de lane SIMD 128-bit non supporté: {:?}", opcode)),
+        }
+    }
+
     /// Exécute une instruction SIMD 256-bit
     fn execute_simd_256(&mut self, opcode: &Opcode, ex_reg: &DecodeExecuteRegister) -> Result<(), String> {
         let src1_reg = ex_reg.rs1.unwrap_or(0) as u8;
@@ -1259,6 +1786,9 @@ impl ExecuteStage {
             Opcode::Simd256Sqrt => VectorOperation::Sqrt,
             Opcode::Simd256Cmp => VectorOperation::Cmp,
             Opcode::Simd256Shuffle => VectorOperation::Shuffle,
+            Opcode::Simd256AddI64x4 => VectorOperation::Add,
+            Opcode::Simd256SubI64x4 => VectorOperation::Sub,
+            Opcode::Simd256MulI64x4 => VectorOperation::Mul,
             Opcode::Simd256Mov => {
                 // Mov vectoriel simple
                 let src_vector = self.vector_alu.borrow_mut().read_v256(src1_reg)
@@ -1399,6 +1929,7 @@ impl ExecuteStage {
         // Déterminer le type de données vectorielles selon l'opération
         let data_type = match opcode {
             Opcode::Simd256Sqrt => Vector256DataType::F32x8, // Sqrt nécessite des flottants
+            Opcode::Simd256AddI64x4 | Opcode::Simd256SubI64x4 | Opcode::Simd256MulI64x4 => Vector256DataType::I64x4,
             _ => Vector256DataType::I32x8, // Type par défaut pour les autres opérations
         };
 
@@ -1458,6 +1989,11 @@ impl ExecuteStage {
         self.agu.borrow_mut().reset();
         self.dual_issue_controller.reset();
         self.parallel_engine = ParallelExecutionEngine::new();
+        self.pending_fault = None;
+        self.pending_syscall = None;
+        self.prefetch_requests.clear();
+        self.privilege_mode = PrivilegeMode::User;
+        self.retired_instructions = 0;
         self.branch_predictions = 0;
         self.branch_hits = 0;
         self.current_cycle = 0;
@@ -1468,6 +2004,40 @@ impl ExecuteStage {
         self.dual_issue_controller.get_stats()
     }
     
+    /// Vérifie si `candidate` peut rejoindre le groupe d'émission du cycle; les refus sont
+    /// comptabilisés dans les statistiques du moteur parallèle
+    pub fn check_issue_group(
+        &mut self,
+        group: &[DecodeExecuteRegister],
+        candidate: &DecodeExecuteRegister,
+    ) -> Result<ExecutionUnit, IssueBlock> {
+        let result = self.dual_issue_controller.check_issue_group(group, candidate);
+        match &result {
+            Err(IssueBlock::Dependency(dep_type)) => self.parallel_engine.record_dependency_stall(dep_type.clone()),
+            Err(IssueBlock::ResourceConflict) => {
+                let unit = self.dual_issue_controller.analyze_instruction(candidate).0;
+                self.parallel_engine.record_resource_conflict(unit);
+            }
+            Err(IssueBlock::SharedPortConflict) => self.parallel_engine.record_shared_port_conflict(),
+            _ => {}
+        }
+        result
+    }
+
+    /// Fait partager à l'unité SIMD le port d'émission de l'ALU scalaire
+    pub fn set_shared_simd_port(&mut self, shared: bool) {
+        self.dual_issue_controller.shared_simd_port = shared;
+    }
+
+    /// Enregistre le groupe d'instructions émises dans le cycle
+    pub fn record_issue_group(&mut self, group: &[DecodeExecuteRegister]) {
+        let units: Vec<ExecutionUnit> = group
+            .iter()
+            .map(|de| self.dual_issue_controller.analyze_instruction(de).0)
+            .collect();
+        self.parallel_engine.record_issue_group(&units);
+    }
+
     /// Obtient les statistiques du moteur d'exécution parallèle
     pub fn get_parallel_engine_stats(&self) -> &ParallelExecutionStats {
         self.parallel_engine.get_stats()
@@ -1497,6 +2067,7 @@ impl ExecuteStage {
         let rs1_value = ex_reg.rs1_value;
         let mut alu_result = 0;
         let mut store_value = None;
+        let mut vector_data = None;
         
         // Recalculer l'adresse avec l'AGU pour optimisations (stride prediction, etc.)
         let agu_addr = self.calculate_memory_address_with_agu(ex_reg, registers, sp)?;
@@ -1509,7 +2080,7 @@ impl ExecuteStage {
                  agu_addr, ex_reg.mem_addr, ex_reg.instruction.opcode);
         
         match ex_reg.instruction.opcode {
-            Opcode::Load | Opcode::LoadB | Opcode::LoadW | Opcode::LoadD => {
+            Opcode::Load | Opcode::LoadB | Opcode::LoadW | Opcode::LoadD | Opcode::LoadSB | Opcode::LoadSW | Opcode::LoadSD => {
                 // Ces instructions finalisent leur exécution dans l'étage Memory
                 alu_result = 0; // Sera remplacé par la valeur chargée
                 println!("Execute LOAD with AGU: rs1_value={}, mem_addr={:?}", 
@@ -1523,6 +2094,24 @@ impl ExecuteStage {
                          rs1_value, final_addr);
             }
             
+            // Transferts multiples: seule l'adresse du bloc est calculée ici,
+            // les registres sont lus/écrits par l'étage Memory
+            Opcode::Ldm | Opcode::Stm => {
+                println!("Execute {:?} with AGU: mem_addr={:?}", ex_reg.instruction.opcode, final_addr);
+            }
+
+            // Lea: l'adresse calculée est le résultat, aucun accès mémoire
+            Opcode::Lea => {
+                alu_result = match (agu_addr, ex_reg.instruction.get_arg2_value()) {
+                    (Some(addr), _) => addr,
+                    (None, Ok(ArgValue::RegisterOffset(_, offset))) => {
+                        rs1_value.wrapping_add(offset as i64 as u64)
+                    }
+                    _ => return Err("LEA: opérande [base + offset] attendu".to_string()),
+                };
+                println!("Execute LEA with AGU: rd={:?}, address=0x{:X}", ex_reg.rd, alu_result);
+            }
+
             // Instructions SIMD mémoire
             Opcode::Simd128Load | Opcode::Simd256Load => {
                 alu_result = 0; // Sera remplacé par la valeur chargée
@@ -1530,16 +2119,28 @@ impl ExecuteStage {
             }
             
             Opcode::Simd128Store | Opcode::Simd256Store => {
-                // Pour SIMD, la valeur est dans les registres vectoriels
-                store_value = Some(0); // Placeholder, la vraie valeur sera gérée dans Memory stage
+                // Pour SIMD, la valeur est dans les registres vectoriels: ses octets sont
+                // transmis à l'étage Memory qui les écrit
+                let src_reg = ex_reg.rs1.ok_or("SIMD Store: registre source manquant")? as u8;
+                let vector_alu = self.vector_alu.borrow();
+                let bytes = if ex_reg.instruction.opcode == Opcode::Simd128Store {
+                    let vector = vector_alu.read_v128(src_reg).map_err(|e| format!("SIMD Store: {}", e))?;
+                    vector.as_bytes().to_vec()
+                } else {
+                    let vector = vector_alu.read_v256(src_reg).map_err(|e| format!("SIMD Store: {}", e))?;
+                    vector.as_bytes().to_vec()
+                };
+                store_value = Some(0); // La valeur réelle est dans vector_data
+                vector_data = Some(bytes);
                 println!("Execute SIMD STORE with AGU: mem_addr={:?}", final_addr);
             }
             
             // Instructions Stack - Push/Pop utilisent l'adresse SP
             Opcode::Push => {
-                // Push utilise SP comme adresse de base
-                store_value = Some(rs1_value);
-                println!("Execute PUSH with AGU: value={}, mem_addr={:?}", rs1_value, final_addr);
+                // Push utilise SP comme adresse de base; un PUSH immédiat empile sa constante
+                let value_to_push = ex_reg.immediate.unwrap_or(rs1_value);
+                store_value = Some(value_to_push);
+                println!("Execute PUSH with AGU: value={}, mem_addr={:?}", value_to_push, final_addr);
             }
             
             Opcode::Pop => {
@@ -1575,12 +2176,17 @@ impl ExecuteStage {
             _ => None,
         };
         
+        // Lea ne touche pas la mémoire: l'étage Memory ne doit voir aucune adresse
+        let mem_addr = if ex_reg.instruction.opcode == Opcode::Lea { None } else { final_addr };
+
         Ok(ExecuteMemoryRegister {
+            id: ex_reg.id,
             instruction: ex_reg.instruction.clone(),
+            pc: ex_reg.pc,
             alu_result,
             rd: ex_reg.rd,
             store_value,
-            mem_addr: final_addr,
+            mem_addr,
             branch_target: None,
             branch_taken: false,
             branch_prediction_correct: None,
@@ -1588,6 +2194,7 @@ impl ExecuteStage {
             stack_result: None,
             ras_prediction_correct: None,
             halted: false,
+            vector_data,
         })
     }
     
@@ -1604,8 +2211,12 @@ impl ExecuteStage {
         let (base_reg, index_reg) = match ex_reg.instruction.opcode {
             Opcode::Store | Opcode::StoreB | Opcode::StoreW | Opcode::StoreD |
             Opcode::Simd128Store | Opcode::Simd256Store => {
-                // Pour Store avec RegisterOffset: rs2 est la base, pas d'index (rs1 est la valeur à stocker)
-                (ex_reg.rs2, None)
+                // Pour Store avec RegisterOffset: rs2 est la base, pas d'index (rs1 est la valeur à stocker).
+                // Un Store indexé porte son registre d'index dans l'opérande
+                match ex_reg.instruction.get_arg2_value() {
+                    Ok(ArgValue::Indexed(_, index, _, _)) => (ex_reg.rs2, Some(index as usize)),
+                    _ => (ex_reg.rs2, None),
+                }
             }
             _ => {
                 // Pour Load et autres: rs1 est le registre de base
@@ -1613,7 +2224,33 @@ impl ExecuteStage {
             }
         };
         
+        // Déplacement signé d'un accès [base + offset] (offset 8 ou 16 bits)
+        let displacement = match ex_reg.instruction.get_arg2_value() {
+            Ok(ArgValue::RegisterOffset(_, offset)) => Some(offset as i32),
+            _ => None,
+        };
+
+        // Accès indexé encodé: échelle et déplacement viennent de l'opérande
+        let indexed = match ex_reg.instruction.get_arg2_value() {
+            Ok(ArgValue::Indexed(_, _, scale, offset)) => Some((scale, offset as i32)),
+            _ => None,
+        };
+
         let addressing_mode = match (base_reg, index_reg, ex_reg.immediate) {
+            // Base + index * échelle + déplacement (opérande Indexed)
+            (Some(base), Some(index), _) if indexed.is_some() => {
+                let (scale, offset) = indexed.unwrap_or((1, 0));
+                AddressingMode::BaseIndexScale { base: base as u8, index: index as u8, scale, offset }
+            }
+
+            // Base + déplacement encodé dans l'opérande RegisterOffset
+            (Some(base), None, _) if displacement.is_some() => {
+                AddressingMode::BaseOffset {
+                    base: base as u8,
+                    offset: displacement.unwrap_or(0),
+                }
+            }
+
             // Base + Offset (registre + immédiat)
             (Some(base), None, Some(offset)) => {
                 AddressingMode::BaseOffset { 
@@ -1661,16 +2298,26 @@ impl ExecuteStage {
         };
         
         // Calculer l'adresse avec l'AGU
-        match self.agu.borrow_mut().calculate_address(addressing_mode, registers, ex_reg.pc as u64, sp) {
+        let mut agu = self.agu.borrow_mut();
+        match agu.calculate_address(addressing_mode, registers, ex_reg.pc as u64, sp) {
             Ok(address) => {
                 println!("AGU: Successfully calculated address 0x{:X} using {:?}", 
                          address, addressing_mode);
+                // Stride établi: le prochain accès de cette instruction peut être préchargé
+                if ex_reg.instruction.opcode != Opcode::Lea {
+                    if let Some(next) = agu.predict_next_address(ex_reg.pc as u64).filter(|&n| n != address) {
+                        self.prefetch_requests.push((address as u32, next as u32));
+                    }
+                }
                 Ok(Some(address))
             }
             Err(AGUError::InvalidRegister) => {
                 println!("AGU: Invalid register in addressing mode, falling back to decode address");
                 Ok(None)
             }
+            Err(AGUError::InvalidScale) => {
+                Err(format!("AGU: échelle d'index invalide pour {:?} (1, 2, 4 ou 8 attendu)", addressing_mode))
+            }
             Err(e) => {
                 println!("AGU: Error calculating address: {:?}, falling back to decode address", e);
                 Ok(None)
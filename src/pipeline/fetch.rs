@@ -7,6 +7,7 @@ use std::collections::VecDeque;
 pub struct FetchStage {
     fetch_buffer: VecDeque<(u32, Instruction)>,
     buffer_size: usize,
+    code_base: u32, // Adresse de chargement de la première instruction du programme
 }
 
 impl FetchStage {
@@ -15,9 +16,17 @@ impl FetchStage {
         Self {
             fetch_buffer: VecDeque::with_capacity(buffer_size),
             buffer_size,
+            code_base: 0,
         }
     }
 
+    /// Adresse à laquelle le segment de code est chargé: le PC de la première
+    /// instruction de `instructions`
+    pub fn set_code_base(&mut self, code_base: u32) {
+        self.code_base = code_base;
+        self.fetch_buffer.clear();
+    }
+
     /// Précharge des instructions dans le buffer
     fn prefetch(&mut self, pc: u32, instructions: &[Instruction]) {
         // Si le buffer est déjà plein, ne rien faire
@@ -27,7 +36,7 @@ impl FetchStage {
 
         // Trouver l'index de l'instruction à l'adresse PC
         let mut current_index = 0;
-        let mut current_addr = 0;
+        let mut current_addr = self.code_base;
         let mut found = false;
 
         for (idx, instr) in instructions.iter().enumerate() {
@@ -85,7 +94,8 @@ impl FetchStage {
 
             println!("Fetched Instruction: {:?}", instruction);
 
-            Ok(FetchDecodeRegister { instruction, pc })
+            // L'identifiant est attribué par le pipeline (`Pipeline::tag_fetch`)
+            Ok(FetchDecodeRegister { id: 0, instruction, pc })
         } else {
             Err(format!("Instruction non trouvée à l'adresse 0x{:08X}", pc))
         }
@@ -78,7 +78,7 @@ impl ForwardingUnit {
                 // car la donnée n'est pas encore disponible (elle le sera après MEM).
                 let is_load_in_ex = matches!(
                     mem.instruction.opcode,
-                    Opcode::Load | Opcode::LoadB | Opcode::LoadW | Opcode::LoadD | Opcode::Pop
+                    Opcode::Load | Opcode::LoadB | Opcode::LoadW | Opcode::LoadD | Opcode::LoadSB | Opcode::LoadSW | Opcode::LoadSD | Opcode::Pop
                 );
 
                 if !is_load_in_ex {
@@ -1,7 +1,7 @@
 //src/pipeline/hazard.rs
 
 use crate::bytecode::opcodes::Opcode;
-use crate::pipeline::PipelineState;
+use crate::pipeline::{DecodeExecuteRegister, PipelineState};
 
 /// Unité de détection de hazards
 pub struct HazardDetectionUnit {
@@ -12,6 +12,11 @@ pub struct HazardDetectionUnit {
     // Compteur de forwarding potentiels détectés
     pub potential_forwards_count: u64,
     branch_stall_cycles: u32,
+    // Chemins de forwarding disponibles: EX/MEM -> EX et MEM/WB -> EX
+    forward_ex_to_ex: bool,
+    forward_mem_to_ex: bool,
+    // Registres écrits (rd, produit par un load) au cycle courant, puis aux deux cycles précédents
+    recent_writes: [Vec<(usize, bool)>; 3],
 }
 
 #[derive(Debug, PartialEq)]
@@ -42,9 +47,52 @@ impl HazardDetectionUnit {
             data_dependencies_count: 0,
             potential_forwards_count: 0,
             branch_stall_cycles: 0,
+            forward_ex_to_ex: true,
+            forward_mem_to_ex: true,
+            recent_writes: Default::default(),
         }
     }
 
+    /// Active ou désactive indépendamment les chemins EX/MEM -> EX et MEM/WB -> EX
+    pub fn set_forwarding_paths(&mut self, ex_to_ex: bool, mem_to_ex: bool) {
+        self.forward_ex_to_ex = ex_to_ex;
+        self.forward_mem_to_ex = mem_to_ex;
+    }
+
+    /// Enregistre le registre écrit par une instruction retirée pendant le cycle courant
+    pub fn record_retired_write(&mut self, rd: usize, opcode: Opcode) {
+        let is_load = matches!(
+            opcode,
+            Opcode::Load | Opcode::LoadB | Opcode::LoadW | Opcode::LoadD | Opcode::LoadSB | Opcode::LoadSW | Opcode::LoadSD | Opcode::Pop
+        );
+        self.recent_writes[0].push((rd, is_load));
+    }
+
+    /// Fin de cycle: les écritures vieillissent d'un étage (un cycle de stall insère une bulle)
+    pub fn end_cycle(&mut self) {
+        self.recent_writes.rotate_right(1);
+        self.recent_writes[0].clear();
+    }
+
+    /// Indique si l'instruction décodée lit un registre qui ne lui parvient que par un
+    /// chemin de forwarding désactivé; elle doit alors attendre un cycle.
+    /// Producteur au cycle précédent: chemin EX -> EX (MEM -> EX pour un load).
+    /// Producteur deux cycles plus tôt: chemin MEM -> EX.
+    pub fn needs_disabled_forwarding(&self, decode_reg: &DecodeExecuteRegister) -> bool {
+        let sources = [decode_reg.rs1, decode_reg.rs2];
+        let reads = |rd: usize| sources.contains(&Some(rd));
+
+        let from_ex = self.recent_writes[1].iter().any(|&(rd, is_load)| {
+            reads(rd) && if is_load { !self.forward_mem_to_ex } else { !self.forward_ex_to_ex }
+        });
+        let from_mem = !self.forward_mem_to_ex
+            && self.recent_writes[2].iter().any(|&(rd, _)| {
+                // Une écriture plus récente du même registre masque la plus ancienne
+                reads(rd) && !self.recent_writes[1].iter().any(|&(newer, _)| newer == rd)
+            });
+        from_ex || from_mem
+    }
+
     /// Détecte les hazards dans le pipeline et retourne le type détecté
     pub fn detect_hazards_with_type(&mut self, state: &PipelineState) -> HazardResult {
         // 1. Load-Use Hazards (cas spécial de Data Hazard qui DOIT causer un stall)
@@ -157,7 +205,7 @@ impl HazardDetectionUnit {
                 // Skip si c'est un Load (sera traité par is_load_use_hazards)
                 let is_load = matches!(
                     ex_reg.instruction.opcode,
-                    Opcode::Load | Opcode::LoadB | Opcode::LoadW | Opcode::LoadD | Opcode::Pop
+                    Opcode::Load | Opcode::LoadB | Opcode::LoadW | Opcode::LoadD | Opcode::LoadSB | Opcode::LoadSW | Opcode::LoadSD | Opcode::Pop
                 );
                 
                 if !is_load && (rs1 == Some(rd_ex) || rs2 == Some(rd_ex)) {
@@ -215,7 +263,7 @@ impl HazardDetectionUnit {
             // Si l'instruction dans Execute est un Load et que son registre destination est utilisé dans Decode
             let is_load = matches!(
             ex_reg.instruction.opcode,
-            Opcode::Load | Opcode::LoadB | Opcode::LoadW | Opcode::LoadD
+            Opcode::Load | Opcode::LoadB | Opcode::LoadW | Opcode::LoadD | Opcode::LoadSB | Opcode::LoadSW | Opcode::LoadSD
         );
 
             if is_load && ex_reg.rd.is_some() {
@@ -251,7 +299,7 @@ impl HazardDetectionUnit {
     );
         let dec_is_load = matches!(
         decode_reg.instruction.opcode,
-        Opcode::Load | Opcode::LoadB | Opcode::LoadW | Opcode::LoadD
+        Opcode::Load | Opcode::LoadB | Opcode::LoadW | Opcode::LoadD | Opcode::LoadSB | Opcode::LoadSW | Opcode::LoadSD
     );
         if exe_is_store && dec_is_load {
             if let (Some(addr_store), Some(addr_load)) = (ex_reg.mem_addr, decode_reg.mem_addr) {
@@ -351,6 +399,9 @@ impl HazardDetectionUnit {
                 | Opcode::LoadB
                 | Opcode::LoadW
                 | Opcode::LoadD
+                | Opcode::LoadSB
+                | Opcode::LoadSW
+                | Opcode::LoadSD
                 | Opcode::Store
                 | Opcode::StoreB
                 | Opcode::StoreW
@@ -362,6 +413,9 @@ impl HazardDetectionUnit {
                 | Opcode::LoadB
                 | Opcode::LoadW
                 | Opcode::LoadD
+                | Opcode::LoadSB
+                | Opcode::LoadSW
+                | Opcode::LoadSD
                 | Opcode::Store
                 | Opcode::StoreB
                 | Opcode::StoreW
@@ -383,6 +437,7 @@ impl HazardDetectionUnit {
         self.data_dependencies_count = 0;
         self.potential_forwards_count = 0;
         self.branch_stall_cycles = 0;
+        self.recent_writes = Default::default();
     }
 
     /// Retourne le nombre de hazards détectés
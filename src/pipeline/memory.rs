@@ -1,8 +1,11 @@
 //src/pipeline/memory.rs
+use crate::bytecode::instructions::ArgValue;
 use crate::bytecode::opcodes::Opcode;
 use crate::pipeline::{ExecuteMemoryRegister, MemoryWritebackRegister};
 // use crate::pipeline::stage::PipelineStage;
-use crate::pvm::memorys::Memory;
+use crate::pvm::memorys::{Endianness, Memory};
+use crate::pvm::vm_errors::VMError;
+use std::collections::BTreeSet;
 
 /// Entrée du store buffer pour Store-Load forwarding
 #[derive(Debug, Clone)]
@@ -20,6 +23,8 @@ pub struct MemoryStage {
     
     /// Store buffer pour Store-Load forwarding
     store_buffer: Vec<StoreBufferEntry>,
+    /// Nombre d'écritures retenues pour le forwarding (0 = pas de store buffer)
+    store_buffer_capacity: usize,
     
     /// Statistiques de Store-Load forwarding
     pub store_load_forwards: u64,
@@ -30,7 +35,27 @@ pub struct MemoryStage {
     pub stack_pops: u64,
     pub stack_overflow_attempts: u64,
     pub stack_underflow_attempts: u64,
+
+    /// Zone [bas, haut) réservée à la pile; SP ne doit pas en sortir
+    stack_bounds: Option<(u32, u32)>,
+
+    /// Zones [début, fin) des segments en lecture seule: tout store y est rejeté
+    readonly_ranges: Vec<(u32, u32)>,
+
+    /// Exception levée par l'étage Memory (accès hors limites)
+    pending_fault: Option<VMError>,
+
+    /// Adresses surveillées en écriture
+    watchpoints: BTreeSet<u32>,
+    /// Premier watchpoint déclenché depuis le dernier `take_watchpoint_hit`: (adresse, ancien, nouveau)
+    watchpoint_hit: Option<(u32, u64, u64)>,
+}
+/// Vrai si une écriture de `size` octets à `addr` recouvre l'une des zones [début, fin)
+pub(crate) fn overlaps_readonly(ranges: &[(u32, u32)], addr: u32, size: usize) -> bool {
+    let end = addr as u64 + size as u64;
+    ranges.iter().any(|&(start, stop)| (addr as u64) < stop as u64 && end > start as u64)
 }
+
 impl MemoryStage {
     /// Crée un nouvel étage Memory
     pub fn new() -> Self {
@@ -41,6 +66,7 @@ impl MemoryStage {
             
             // Initialiser le store buffer
             store_buffer: Vec::with_capacity(8),
+            store_buffer_capacity: 8,
             
             // Statistiques de Store-Load forwarding
             store_load_forwards: 0,
@@ -51,6 +77,11 @@ impl MemoryStage {
             stack_pops: 0,
             stack_overflow_attempts: 0,
             stack_underflow_attempts: 0,
+            stack_bounds: None,
+            readonly_ranges: Vec::new(),
+            pending_fault: None,
+            watchpoints: BTreeSet::new(),
+            watchpoint_hit: None,
         }
     }
 
@@ -66,13 +97,47 @@ impl MemoryStage {
         // Nettoyage du store buffer (supprime les entrées anciennes)
         self.cleanup_store_buffer();
 
+        // Valeurs des adresses surveillées que cette écriture va recouvrir
+        let watched: Vec<(u32, u64)> = match Self::store_range(mem_reg) {
+            Some((addr, size)) if !self.watchpoints.is_empty() => self
+                .watchpoints
+                .range(addr..addr.saturating_add(size as u32))
+                .map(|&w| (w, memory.peek_qword(w).unwrap_or(0)))
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        // Les constantes des segments en lecture seule ne peuvent pas être écrasées
+        if let Some((addr, size)) = Self::store_range(mem_reg) {
+            self.check_writable(addr, size)?;
+        }
+
         // Traitement spécifique selon l'opcode
         match mem_reg.instruction.opcode {
+            // Accès MMIO: routés vers le périphérique, sans store buffer ni mémoire principale
+            Opcode::Store | Opcode::StoreB | Opcode::StoreW | Opcode::StoreD
+                if mem_reg.mem_addr.is_some_and(|addr| memory.is_mmio(addr)) =>
+            {
+                if let (Some(addr), Some(value)) = (mem_reg.mem_addr, mem_reg.store_value) {
+                    memory.mmio_write(addr, value);
+                    println!("MMIO write to address: {:#X}, value: {:#X}", addr, value);
+                }
+            }
+            Opcode::Load | Opcode::LoadB | Opcode::LoadW | Opcode::LoadD | Opcode::LoadSB | Opcode::LoadSW | Opcode::LoadSD
+                if mem_reg.mem_addr.is_some_and(|addr| memory.is_mmio(addr)) =>
+            {
+                if let Some(addr) = mem_reg.mem_addr {
+                    result = memory.mmio_read(addr);
+                    println!("MMIO read from address: {:#X}, result: {:#X}", addr, result);
+                }
+            }
+
             // Instructions de chargement (load)
             Opcode::Load => {
                 if let Some(addr) = mem_reg.mem_addr {
+                    self.check_bounds(memory, addr, 8)?;
                     // Tenter Store-Load forwarding d'abord
-                    if let Some(forwarded_value) = self.check_store_load_forwarding(addr, 8) {
+                    if let Some(forwarded_value) = self.check_store_load_forwarding(addr, 8, memory.endianness()) {
                         result = forwarded_value;
                         self.store_load_forwards += 1;
                         println!("[Store-Load Forwarding] Load from address: {:#X}, forwarded value: {:#X}", addr, result);
@@ -90,7 +155,8 @@ impl MemoryStage {
 
             Opcode::LoadB => {
                 if let Some(addr) = mem_reg.mem_addr {
-                    if let Some(forwarded_value) = self.check_store_load_forwarding(addr, 1) {
+                    self.check_bounds(memory, addr, 1)?;
+                    if let Some(forwarded_value) = self.check_store_load_forwarding(addr, 1, memory.endianness()) {
                         result = forwarded_value;
                         self.store_load_forwards += 1;
                         println!("[Store-Load Forwarding] LoadB from address: {:#X}, forwarded value: {:#X}", addr, result);
@@ -108,7 +174,8 @@ impl MemoryStage {
 
             Opcode::LoadW => {
                 if let Some(addr) = mem_reg.mem_addr {
-                    if let Some(forwarded_value) = self.check_store_load_forwarding(addr, 2) {
+                    self.check_bounds(memory, addr, 2)?;
+                    if let Some(forwarded_value) = self.check_store_load_forwarding(addr, 2, memory.endianness()) {
                         result = forwarded_value;
                         self.store_load_forwards += 1;
                         println!("[Store-Load Forwarding] LoadW from address: {:#X}, forwarded value: {:#X}", addr, result);
@@ -126,7 +193,8 @@ impl MemoryStage {
 
             Opcode::LoadD => {
                 if let Some(addr) = mem_reg.mem_addr {
-                    if let Some(forwarded_value) = self.check_store_load_forwarding(addr, 4) {
+                    self.check_bounds(memory, addr, 4)?;
+                    if let Some(forwarded_value) = self.check_store_load_forwarding(addr, 4, memory.endianness()) {
                         result = forwarded_value;
                         self.store_load_forwards += 1;
                         println!("[Store-Load Forwarding] LoadD from address: {:#X}, forwarded value: {:#X}", addr, result);
@@ -142,10 +210,36 @@ impl MemoryStage {
                 }
             }
 
+            // Chargements signés: 1, 2 ou 4 octets étendus par le bit de signe sur 64 bits
+            Opcode::LoadSB | Opcode::LoadSW | Opcode::LoadSD => {
+                if let Some(addr) = mem_reg.mem_addr {
+                    let size = match mem_reg.instruction.opcode {
+                        Opcode::LoadSB => 1,
+                        Opcode::LoadSW => 2,
+                        _ => 4,
+                    };
+                    self.check_bounds(memory, addr, size as usize)?;
+                    let raw = match self.check_store_load_forwarding(addr, size, memory.endianness()) {
+                        Some(forwarded_value) => {
+                            self.store_load_forwards += 1;
+                            forwarded_value
+                        }
+                        None => self.load_from_memory(memory, addr, size)?,
+                    };
+                    self.store_load_attempts += 1;
+                    result = Self::sign_extend(raw, size);
+                    println!(
+                        "{:?} from address: {:#X}, raw: {:#X}, result: {:#X}",
+                        mem_reg.instruction.opcode, addr, raw, result
+                    );
+                }
+            }
+
             // Instructions de stockage (store)
             Opcode::Store => {
                 if let Some(addr) = mem_reg.mem_addr {
                     if let Some(value) = mem_reg.store_value {
+                        self.check_bounds(memory, addr, 8)?;
                         // Ajouter au store buffer pour Store-Load forwarding
                         self.add_to_store_buffer(addr, value, 8);
                         self.store_to_memory(memory, addr, value, 8)?;
@@ -161,6 +255,7 @@ impl MemoryStage {
             Opcode::StoreB => {
                 if let Some(addr) = mem_reg.mem_addr {
                     if let Some(value) = mem_reg.store_value {
+                        self.check_bounds(memory, addr, 1)?;
                         self.add_to_store_buffer(addr, value, 1);
                         self.store_to_memory(memory, addr, value, 1)?;
                         println!("StoreB to address: {:#X}, value: {:#X}", addr, value);
@@ -175,6 +270,7 @@ impl MemoryStage {
             Opcode::StoreW => {
                 if let Some(addr) = mem_reg.mem_addr {
                     if let Some(value) = mem_reg.store_value {
+                        self.check_bounds(memory, addr, 2)?;
                         self.add_to_store_buffer(addr, value, 2);
                         self.store_to_memory(memory, addr, value, 2)?;
                         println!("StoreW to address: {:#X}, value: {:#X}", addr, value);
@@ -189,6 +285,7 @@ impl MemoryStage {
             Opcode::StoreD => {
                 if let Some(addr) = mem_reg.mem_addr {
                     if let Some(value) = mem_reg.store_value {
+                        self.check_bounds(memory, addr, 4)?;
                         self.add_to_store_buffer(addr, value, 4);
                         self.store_to_memory(memory, addr, value, 4)?;
                         println!("StoreD to address: {:#X}, value: {:#X}", addr, value);
@@ -200,20 +297,79 @@ impl MemoryStage {
                 }
             }
 
-            // Instructions de pile
-            Opcode::Push => {
+            // Load SIMD: l'accès au vecteur doit rester dans la mémoire
+            Opcode::Simd128Load | Opcode::Simd256Load => {
+                if let Some(addr) = mem_reg.mem_addr {
+                    let size = if mem_reg.instruction.opcode == Opcode::Simd128Load { 16 } else { 32 };
+                    self.check_bounds(memory, addr, size)?;
+                }
+            }
+
+            // Store SIMD: les octets du vecteur ont été lus par l'étage Execute
+            Opcode::Simd128Store | Opcode::Simd256Store => {
+                if let (Some(addr), Some(bytes)) = (mem_reg.mem_addr, &mem_reg.vector_data) {
+                    self.check_bounds(memory, addr, bytes.len())?;
+                    memory.write_block(addr, bytes).map_err(|e| e.to_string())?;
+                    println!("{:?} to address: {:#X}, {} bytes", mem_reg.instruction.opcode, addr, bytes.len());
+                }
+            }
+
+            // Transferts multiples: un mot de 8 octets par registre, par numéro de
+            // registre croissant, à partir de l'adresse du bloc
+            Opcode::Stm => {
+                if let Some(addr) = mem_reg.mem_addr {
+                    let regs = Self::multiple_registers(mem_reg)?;
+                    self.check_bounds(memory, addr, regs.len() * 8)?;
+                    for (i, &reg) in regs.iter().enumerate() {
+                        let slot = addr + (i as u32) * 8;
+                        let value = registers[reg];
+                        self.add_to_store_buffer(slot, value, 8);
+                        self.store_to_memory(memory, slot, value, 8)?;
+                        println!("Stm: R{} -> {:#X}, value: {:#X}", reg, slot, value);
+                    }
+                }
+            }
+
+            Opcode::Ldm => {
+                if let Some(addr) = mem_reg.mem_addr {
+                    let regs = Self::multiple_registers(mem_reg)?;
+                    self.check_bounds(memory, addr, regs.len() * 8)?;
+                    for (i, &reg) in regs.iter().enumerate() {
+                        let slot = addr + (i as u32) * 8;
+                        let value = match self.check_store_load_forwarding(slot, 8, memory.endianness()) {
+                            Some(forwarded_value) => {
+                                self.store_load_forwards += 1;
+                                forwarded_value
+                            }
+                            None => self.load_from_memory(memory, slot, 8)?,
+                        };
+                        self.store_load_attempts += 1;
+                        registers[reg] = value;
+                        println!("Ldm: {:#X} -> R{}, value: {:#X}", slot, reg, value);
+                    }
+                }
+            }
+
+            // Instructions de pile (CALL empile son adresse de retour, RET la dépile)
+            Opcode::Push | Opcode::Call => {
                 if let Some(value) = mem_reg.store_value {
                     // Utiliser le Stack Pointer des registres (SP = registre 16)
                     let sp = registers[16] as u32;
                     
                     // Vérifier stack overflow
-                    if sp < 8 {
+                    let stack_floor = self.stack_bounds.map_or(0, |(low, _)| low);
+                    if sp < stack_floor.saturating_add(8) {
                         self.stack_overflow_attempts += 1;
+                        if self.stack_bounds.is_some() {
+                            return Err(self.raise_fault(VMError::StackOverflow { pc: mem_reg.pc, sp: sp as u64 }));
+                        }
                         return Err("Stack overflow: cannot push more values".to_string());
                     }
                     
                     // Décrémenter SP de 8 (64-bit values)
                     let new_sp = sp - 8;
+                    self.check_bounds(memory, new_sp, 8)?;
+                    self.check_writable(new_sp, 8)?;
                     registers[16] = new_sp as u64;
                     
                     println!(
@@ -236,9 +392,18 @@ impl MemoryStage {
                 }
             }
 
-            Opcode::Pop => {
+            Opcode::Pop | Opcode::Ret => {
                 // Utiliser le Stack Pointer des registres (SP = registre 16)
                 let sp = registers[16] as u32;
+
+                // Vérifier stack underflow: SP au sommet, aucune valeur empilée
+                if let Some((_, stack_top)) = self.stack_bounds {
+                    if sp as u64 + 8 > stack_top as u64 {
+                        self.stack_underflow_attempts += 1;
+                        return Err(self.raise_fault(VMError::StackUnderflow { pc: mem_reg.pc, sp: sp as u64 }));
+                    }
+                }
+                self.check_bounds(memory, sp, 8)?;
                 
                 // Capturer et afficher l'erreur éventuelle
                 match self.load_from_memory(memory, sp, 8) {
@@ -283,13 +448,108 @@ impl MemoryStage {
         //          mem_reg.instruction.opcode, mem_reg.mem_addr, mem_reg.store_value
         // );
 
+        if let Some(&(addr, old)) = watched.first() {
+            if self.watchpoint_hit.is_none() {
+                let new = memory.peek_qword(addr).unwrap_or(0);
+                println!("Watchpoint 0x{:X}: 0x{:X} -> 0x{:X}", addr, old, new);
+                self.watchpoint_hit = Some((addr, old, new));
+            }
+        }
+
         Ok(MemoryWritebackRegister {
+            id: mem_reg.id,
             instruction: mem_reg.instruction.clone(),
+            pc: mem_reg.pc,
             result,
             rd: mem_reg.rd,
         })
     }
 
+    /// Plage [adresse, adresse + taille) écrite en mémoire principale par une instruction store
+    fn store_range(mem_reg: &ExecuteMemoryRegister) -> Option<(u32, usize)> {
+        let addr = mem_reg.mem_addr?;
+        let size = match mem_reg.instruction.opcode {
+            Opcode::Store => 8,
+            Opcode::StoreB => 1,
+            Opcode::StoreW => 2,
+            Opcode::StoreD => 4,
+            Opcode::Stm => Self::multiple_registers(mem_reg).ok()?.len() * 8,
+            Opcode::Simd128Store | Opcode::Simd256Store => mem_reg.vector_data.as_ref()?.len(),
+            _ => return None,
+        };
+        Some((addr, size))
+    }
+
+    /// Surveille les écritures couvrant `addr`
+    pub fn add_watchpoint(&mut self, addr: u32) {
+        self.watchpoints.insert(addr);
+    }
+
+    /// Adresses surveillées, par ordre croissant
+    pub fn watchpoints(&self) -> Vec<u32> {
+        self.watchpoints.iter().copied().collect()
+    }
+
+    /// Récupère (et efface) le watchpoint déclenché: (adresse, ancien qword, nouveau qword)
+    pub fn take_watchpoint_hit(&mut self) -> Option<(u32, u64, u64)> {
+        self.watchpoint_hit.take()
+    }
+
+    /// Liste des registres (ordre croissant) encodés dans le masque d'un Ldm/Stm
+    fn multiple_registers(mem_reg: &ExecuteMemoryRegister) -> Result<Vec<usize>, String> {
+        match mem_reg.instruction.get_arg1_value() {
+            Ok(ArgValue::Immediate(mask)) => Ok((0..16).filter(|reg| mask & (1 << reg) != 0).collect()),
+            _ => Err(format!("{:?}: masque de registres manquant", mem_reg.instruction.opcode)),
+        }
+    }
+
+    /// Vérifie qu'un accès de `size` octets à l'adresse calculée par l'AGU reste dans la mémoire
+    fn check_bounds(&mut self, memory: &Memory, addr: u32, size: usize) -> Result<(), String> {
+        if addr as u64 + size as u64 > memory.size() as u64 {
+            return Err(self.raise_fault(VMError::MemoryOutOfBounds { addr: addr as u64, size }));
+        }
+        Ok(())
+    }
+
+    /// Rejette une écriture de `size` octets qui recouvre un segment en lecture seule
+    fn check_writable(&mut self, addr: u32, size: usize) -> Result<(), String> {
+        if overlaps_readonly(&self.readonly_ranges, addr, size) {
+            return Err(self.raise_fault(VMError::WriteToReadOnly { addr }));
+        }
+        Ok(())
+    }
+
+    /// Segments en lecture seule du programme chargé, sous forme de zones [début, fin)
+    pub fn set_readonly_ranges(&mut self, ranges: Vec<(u32, u32)>) {
+        self.readonly_ranges = ranges;
+    }
+
+    /// Mémorise l'exception et retourne son message pour l'erreur du pipeline
+    fn raise_fault(&mut self, fault: VMError) -> String {
+        let msg = fault.to_string();
+        self.pending_fault = Some(fault);
+        msg
+    }
+
+    /// Fixe le nombre d'écritures retenues pour le Store-Load forwarding (0 le désactive)
+    pub fn set_store_buffer_capacity(&mut self, capacity: usize) {
+        self.store_buffer_capacity = capacity;
+        while self.store_buffer.len() > capacity {
+            self.store_buffer.remove(0);
+        }
+    }
+
+    /// Délimite la pile: SP doit rester dans [base, base + size). Un PUSH/CALL sous
+    /// `base` lève `StackOverflow`, un POP/RET avec SP au sommet lève `StackUnderflow`.
+    pub fn set_stack_bounds(&mut self, base: u32, size: usize) {
+        self.stack_bounds = Some((base, base.saturating_add(size as u32)));
+    }
+
+    /// Récupère (et efface) l'exception levée par l'étage Memory
+    pub fn take_fault(&mut self) -> Option<VMError> {
+        self.pending_fault.take()
+    }
+
     /// Charge une valeur depuis la mémoire
     fn load_from_memory(&self, memory: &mut Memory, addr: u32, size: u8) -> Result<u64, String> {
         match size {
@@ -314,6 +574,12 @@ impl MemoryStage {
         }
     }
 
+    /// Étend le bit de signe d'une valeur de `size` octets sur 64 bits
+    fn sign_extend(value: u64, size: u8) -> u64 {
+        let shift = 64 - 8 * size as u32;
+        (((value << shift) as i64) >> shift) as u64
+    }
+
     /// Stocke une valeur en mémoire
     fn store_to_memory(
         &self,
@@ -343,8 +609,12 @@ impl MemoryStage {
 
     /// Ajoute une entrée au store buffer
     fn add_to_store_buffer(&mut self, address: u32, value: u64, size: u8) {
+        // Sans store buffer, les loads relisent toujours la mémoire
+        if self.store_buffer_capacity == 0 {
+            return;
+        }
         // Maintenir la taille du buffer (FIFO)
-        if self.store_buffer.len() >= 8 {
+        if self.store_buffer.len() >= self.store_buffer_capacity {
             self.store_buffer.remove(0);
         }
         
@@ -359,18 +629,23 @@ impl MemoryStage {
     }
     
     /// Vérifie si un load peut être forwardé depuis le store buffer
-    fn check_store_load_forwarding(&self, load_addr: u32, load_size: u8) -> Option<u64> {
+    /// En big-endian, les octets à `load_addr` sont ceux de poids fort de la valeur stockée
+    fn check_store_load_forwarding(&self, load_addr: u32, load_size: u8, endianness: Endianness) -> Option<u64> {
         // Chercher dans le store buffer en ordre inverse (plus récent en premier)
         for entry in self.store_buffer.iter().rev() {
             if entry.address == load_addr {
                 // Vérifier la compatibilité des tailles
                 if entry.size >= load_size {
                     // Extraire la valeur selon la taille du load
+                    let value = match endianness {
+                        Endianness::Little => entry.value,
+                        Endianness::Big => entry.value >> ((entry.size - load_size) as u32 * 8),
+                    };
                     let forwarded_value = match load_size {
-                        1 => entry.value & 0xFF,
-                        2 => entry.value & 0xFFFF,
-                        4 => entry.value & 0xFFFFFFFF,
-                        8 => entry.value,
+                        1 => value & 0xFF,
+                        2 => value & 0xFFFF,
+                        4 => value & 0xFFFFFFFF,
+                        8 => value,
                         _ => return None,
                     };
                     
@@ -404,6 +679,20 @@ impl MemoryStage {
         self.stack_pops = 0;
         self.stack_overflow_attempts = 0;
         self.stack_underflow_attempts = 0;
+        self.pending_fault = None;
+        self.watchpoint_hit = None;
+    }
+
+    /// Copie du store buffer de forwarding et du pointeur de pile de l'étage
+    pub fn checkpoint(&self) -> (Vec<StoreBufferEntry>, u32) {
+        (self.store_buffer.clone(), self.stack_pointer)
+    }
+
+    /// Restaure l'état capturé par `checkpoint`
+    pub fn restore_checkpoint(&mut self, store_buffer: &[StoreBufferEntry], stack_pointer: u32) {
+        self.store_buffer = store_buffer.to_vec();
+        self.stack_pointer = stack_pointer;
+        self.pending_fault = None;
     }
 
     /// Retourne les statistiques de Store-Load forwarding
@@ -417,12 +706,18 @@ impl MemoryStage {
         Self {
             stack_pointer: 0x1000,
             store_buffer: Vec::with_capacity(8),
+            store_buffer_capacity: 8,
             store_load_forwards: 0,
             store_load_attempts: 0,
             stack_pushes: 0,
             stack_pops: 0,
             stack_overflow_attempts: 0,
             stack_underflow_attempts: 0,
+            stack_bounds: None,
+            readonly_ranges: Vec::new(),
+            pending_fault: None,
+            watchpoints: BTreeSet::new(),
+            watchpoint_hit: None,
         }
     }
 }
@@ -10,15 +10,35 @@ pub mod writeback;
 pub mod ras;
 pub mod parallel;
 
+use std::collections::{HashMap, VecDeque};
+
 use crate::alu::alu::ALU;
+use crate::bytecode::cfg::ExecutionProfile;
 use crate::bytecode::opcodes::Opcode;
 
 use crate::bytecode::instructions::Instruction;
 use crate::pipeline::decode::StackOperation;
-use crate::pvm::branch_predictor::{BranchPrediction, BranchPredictor};
+use crate::pvm::branch_predictor::{BranchPrediction, BranchPredictor, PredictorType};
 use crate::pvm::memorys::Memory;
+use crate::pvm::pipeline_errors::PipelineError;
 use crate::pipeline::ras::RASStats;
 
+/// Instructions chargées derrière un branchement avant sa résolution en Execute
+/// (étages Fetch et Decode): c'est la fenêtre spéculative annulée en cas de mauvaise prédiction
+const SPECULATION_WINDOW: usize = 2;
+
+/// Cycles consécutifs sans aucun retrait au-delà desquels le pipeline est déclaré interbloqué
+/// (largement au-dessus de la pire latence mémoire cumulée à une pénalité de branchement)
+const DEADLOCK_CYCLE_LIMIT: u64 = 100_000;
+
+/// Noms des étages, dans l'ordre de `PipelineStats::stage_busy_cycles`
+pub const PIPELINE_STAGE_NAMES: [&str; 5] = ["fetch", "decode", "execute", "memory", "writeback"];
+const STAGE_FETCH: usize = 0;
+const STAGE_DECODE: usize = 1;
+const STAGE_EXECUTE: usize = 2;
+const STAGE_MEMORY: usize = 3;
+const STAGE_WRITEBACK: usize = 4;
+
 /// Structure représentant le pipeline à 5 étages
 pub struct Pipeline {
     /// État actuel du pipeline
@@ -44,6 +64,50 @@ pub struct Pipeline {
     /// Configuration
     enable_forwarding: bool,
     enable_hazard_detection: bool,
+    /// Chemins de forwarding actifs (EX/MEM -> EX et MEM/WB -> EX)
+    forward_ex_to_ex: bool,
+    forward_mem_to_ex: bool,
+    /// Compteurs d'instructions retirées par opcode (None si désactivé)
+    opcode_counts: Option<HashMap<Opcode, u64>>,
+    /// Retraits par adresse et transitions entre instructions retirées (couverture, CFG)
+    profile: ExecutionProfile,
+    /// Emplacements de pile des adresses de retour des CALL en cours (le plus ancien en tête)
+    call_frames: Vec<u32>,
+    /// Nombre de ports d'écriture du banc de registres (résultats retirés par cycle)
+    register_write_ports: usize,
+    /// Nombre maximal d'instructions émises par cycle
+    issue_width: usize,
+    /// Cycles de pénalité ajoutés après chaque branchement mal prédit
+    misprediction_penalty: u64,
+    /// Cycles de pénalité restant à subir
+    pending_penalty_cycles: u64,
+    /// Fusion CMP/TEST + saut conditionnel en une micro-op (macro-op fusion)
+    enable_branch_fusion: bool,
+    /// Branchements non résolus autorisés en vol avant que le fetch ne bloque (None = illimité)
+    max_speculation_depth: Option<usize>,
+    /// Identifiant attribué à la prochaine instruction chargée
+    next_instruction_id: u64,
+    /// Instructions chargées et pas encore retirées: (identifiant, PC, cycle de fetch)
+    in_flight: VecDeque<(u64, u32, u64)>,
+    /// Instruction renvoyée au fetch par un stall: son rechargement garde son identifiant
+    refetch: Option<u64>,
+    /// Adresse de chargement du segment de code (PC de la première instruction)
+    code_base: u32,
+    /// Cycles consécutifs terminés sans retirer d'instruction
+    cycles_without_retire: u64,
+    /// Étages ayant traité une vraie instruction (hors bulle) pendant le cycle courant
+    busy_stages: [bool; 5],
+}
+
+/// Copie de l'état du pipeline restaurable par `Pipeline::restore_checkpoint`:
+/// registres intermédiaires, store buffer de forwarding, mode de privilège et appels en cours
+#[derive(Debug, Clone)]
+pub struct PipelineCheckpoint {
+    pub state: PipelineState,
+    pub store_buffer: Vec<memory::StoreBufferEntry>,
+    pub stack_pointer: u32,
+    pub privilege_mode: execute::PrivilegeMode,
+    pub call_frames: Vec<u32>,
 }
 
 /// État du pipeline à un instant donné
@@ -67,6 +131,19 @@ pub struct PipelineState {
     pub instructions_completed: usize,
     /// Indique si la branche a été traitée
     branch_processed: bool,
+    /// Instructions retirées ce cycle, avec leur cycle d'entrée en Fetch
+    pub retired: Vec<RetireTiming>,
+}
+
+/// Temps de passage d'une instruction dans le pipeline, du Fetch au Writeback
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetireTiming {
+    /// Identifiant attribué au fetch, croissant dans l'ordre du programme
+    pub id: u64,
+    pub pc: u32,
+    /// Premier cycle où l'instruction a été chargée (les rechargements après un stall ne comptent pas)
+    pub fetch_cycle: u64,
+    pub retire_cycle: u64,
 }
 
 impl Default for PipelineState {
@@ -81,6 +158,7 @@ impl Default for PipelineState {
             halted: false,
             instructions_completed: 0,
             branch_processed: false,
+            retired: Vec::new(),
         }
     }
 }
@@ -88,6 +166,8 @@ impl Default for PipelineState {
 /// Registre intermédiaire entre les étages Fetch et Decode
 #[derive(Debug, Clone)]
 pub struct FetchDecodeRegister {
+    /// Identifiant attribué au fetch, croissant dans l'ordre du programme
+    pub id: u64,
     /// Instruction brute récupérée
     pub instruction: Instruction,
     /// Adresse de l'instruction
@@ -97,6 +177,8 @@ pub struct FetchDecodeRegister {
 /// Registre intermédiaire entre les étages Decode et Execute
 #[derive(Debug, Clone)]
 pub struct DecodeExecuteRegister {
+    /// Identifiant attribué au fetch (voir `FetchDecodeRegister::id`)
+    pub id: u64,
     /// Instruction décodée
     pub instruction: Instruction,
     /// Adresse de l'instruction
@@ -129,8 +211,12 @@ pub struct DecodeExecuteRegister {
 /// Registre intermédiaire entre les étages Execute et Memory
 #[derive(Debug, Clone)]
 pub struct ExecuteMemoryRegister {
+    /// Identifiant attribué au fetch (voir `FetchDecodeRegister::id`)
+    pub id: u64,
     /// Instruction
     pub instruction: Instruction,
+    /// Adresse de l'instruction
+    pub pc: u32,
     /// Résultat de l'ALU
     pub alu_result: u64,
     /// Registre destination
@@ -153,13 +239,19 @@ pub struct ExecuteMemoryRegister {
 
     /// Halt
     pub halted: bool,
+    /// Octets du vecteur à écrire (SIMD store)
+    pub vector_data: Option<Vec<u8>>,
 }
 
 /// Registre intermédiaire entre les étages Memory et Writeback
 #[derive(Debug, Clone)]
 pub struct MemoryWritebackRegister {
+    /// Identifiant attribué au fetch (voir `FetchDecodeRegister::id`)
+    pub id: u64,
     /// Instruction
     pub instruction: Instruction,
+    /// Adresse de l'instruction
+    pub pc: u32,
     /// Résultat à écrire dans le registre destination
     pub result: u64,
     /// Registre destination
@@ -167,7 +259,7 @@ pub struct MemoryWritebackRegister {
 }
 
 /// Statistiques du pipeline
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct PipelineStats {
     /// Nombre total de cycles
     pub cycles: u64,
@@ -196,6 +288,29 @@ pub struct PipelineStats {
     pub branch_misses: u64,
     /// Nombre de branch flush
     pub branch_flush: u64,
+    /// Instructions plus jeunes qu'un branchement mal prédit, annulées par un flush
+    pub squashed_instructions: u64,
+    /// Instructions chargées sur le chemin prédit (fenêtre spéculative) annulées par un flush
+    pub speculative_squashes: u64,
+    /// Instructions plus anciennes encore en vol (EX/MEM, MEM/WB) conservées lors d'un flush
+    pub flush_preserved_instructions: u64,
+    /// Stalls structurels: instruction émissible retardée faute de port d'écriture libre
+    pub writeback_port_stalls: u64,
+    /// Cycles de stall du fetch: remplissage d'une ligne absente du cache d'instructions
+    pub instruction_fetch_stalls: u64,
+    /// Stalls du fetch: trop de branchements non résolus en vol (`max_speculation_depth`)
+    pub speculation_stalls: u64,
+    /// Cycles d'attente de l'étage Memory sur une lecture manquant le L1
+    pub memory_stall_cycles: u64,
+    /// Paires comparaison + saut conditionnel fusionnées en une seule micro-op
+    pub fused_branch_count: u64,
+    /// Cycles où chaque étage a traité une vraie instruction (ordre `PIPELINE_STAGE_NAMES`).
+    /// L'étage Memory n'est occupé que par les accès mémoire (load/store/pile) et leurs
+    /// cycles d'attente: les autres instructions ne font qu'y traverser le latch.
+    pub stage_busy_cycles: [u64; 5],
+    /// Conflits d'aliasing de la table du prédicteur: entrée mise à jour par un branchement
+    /// alors qu'un autre l'avait entraînée en dernier
+    pub branch_aliasing_conflicts: u64,
     /// Taux de prédiction de branchement (calculé lors de l'accès)
     pub branch_predictor_rate: f64,
 
@@ -222,6 +337,62 @@ impl PipelineStats {
         } else { 0.0 }
     }
 
+    /// Taux d'occupation de chaque étage (0.0 à 1.0, ordre `PIPELINE_STAGE_NAMES`)
+    pub fn stage_utilization(&self) -> [f64; 5] {
+        stage_utilization(&self.stage_busy_cycles, self.cycles)
+    }
+
+    /// Cycles où chaque étage n'a vu passer que des bulles
+    pub fn stage_idle_cycles(&self) -> [u64; 5] {
+        self.stage_busy_cycles.map(|busy| self.cycles.saturating_sub(busy))
+    }
+
+    /// Compteurs sous forme (nom, valeur). Les taux (f64) en sont dérivés et sont omis.
+    pub fn counters(&self) -> [(&'static str, u64); 30] {
+        [
+            ("cycles", self.cycles),
+            ("instructions", self.instructions),
+            ("stalls", self.stalls),
+            ("hazards", self.hazards),
+            ("data_dependencies", self.data_dependencies),
+            ("forwards", self.forwards),
+            ("potential_forwards", self.potential_forwards),
+            ("store_load_forwards", self.store_load_forwards),
+            ("store_load_attempts", self.store_load_attempts),
+            ("branch_predictions", self.branch_predictions),
+            ("branch_hits", self.branch_hits),
+            ("branch_misses", self.branch_misses),
+            ("branch_flush", self.branch_flush),
+            ("squashed_instructions", self.squashed_instructions),
+            ("speculative_squashes", self.speculative_squashes),
+            ("flush_preserved_instructions", self.flush_preserved_instructions),
+            ("writeback_port_stalls", self.writeback_port_stalls),
+            ("instruction_fetch_stalls", self.instruction_fetch_stalls),
+            ("speculation_stalls", self.speculation_stalls),
+            ("memory_stall_cycles", self.memory_stall_cycles),
+            ("fused_branch_count", self.fused_branch_count),
+            ("branch_aliasing_conflicts", self.branch_aliasing_conflicts),
+            ("stack_pushes", self.stack_pushes),
+            ("stack_pops", self.stack_pops),
+            ("total_calls", self.total_calls),
+            ("total_returns", self.total_returns),
+            ("ras_hits", self.ras_hits),
+            ("ras_misses", self.ras_misses),
+            ("max_call_depth", self.max_call_depth as u64),
+            ("current_call_depth", self.current_call_depth as u64),
+        ]
+    }
+
+    /// Compteurs qui diffèrent entre `self` et `other`: (nom, valeur de self, valeur de other).
+    /// Vide si les deux exécutions ont le même profil de performance.
+    pub fn diff(&self, other: &PipelineStats) -> Vec<(&'static str, u64, u64)> {
+        self.counters()
+            .into_iter()
+            .zip(other.counters())
+            .filter(|((_, a), (_, b))| a != b)
+            .map(|((name, a), (_, b))| (name, a, b))
+            .collect()
+    }
 }
 
 
@@ -241,6 +412,16 @@ impl Default for PipelineStats{
             branch_hits: 0,
             branch_misses: 0,
             branch_flush: 0,
+            squashed_instructions: 0,
+            speculative_squashes: 0,
+            flush_preserved_instructions: 0,
+            writeback_port_stalls: 0,
+            speculation_stalls: 0,
+            memory_stall_cycles: 0,
+            fused_branch_count: 0,
+            instruction_fetch_stalls: 0,
+            branch_aliasing_conflicts: 0,
+            stage_busy_cycles: [0; 5],
             branch_predictor_rate: 0.0,
 
             stack_pushes: 0,
@@ -262,24 +443,121 @@ impl Pipeline {
         fetch_buffer_size: usize,
         enable_forwarding: bool,
         enable_hazard_detection: bool,
+        branch_predictor_kind: PredictorType,
+        enable_opcode_histogram: bool,
+        register_write_ports: usize,
+        issue_width: usize,
     ) -> Self {
+        let mut hazard_detection = hazard::HazardDetectionUnit::new();
+        hazard_detection.set_forwarding_paths(enable_forwarding, enable_forwarding);
         Self {
             state: PipelineState::default(),
             fetch: fetch::FetchStage::new(fetch_buffer_size),
-            decode: decode::DecodeStage::new(),
+            decode: decode::DecodeStage::with_predictor(branch_predictor_kind),
             execute: execute::ExecuteStage::new(),
             memory: memory::MemoryStage::new(),
             writeback: writeback::WritebackStage::new(),
             // ras: ReturnAddressStack::new(),
 
-            hazard_detection: hazard::HazardDetectionUnit::new(),
+            hazard_detection,
             forwarding: forward::ForwardingUnit::new(),
             stats: PipelineStats::default(),
             enable_forwarding,
             enable_hazard_detection,
+            forward_ex_to_ex: enable_forwarding,
+            forward_mem_to_ex: enable_forwarding,
+            opcode_counts: if enable_opcode_histogram {
+                Some(HashMap::new())
+            } else {
+                None
+            },
+            profile: ExecutionProfile::default(),
+            call_frames: Vec::new(),
+            register_write_ports: register_write_ports.max(1),
+            issue_width: issue_width.max(1),
+            misprediction_penalty: 0,
+            pending_penalty_cycles: 0,
+            enable_branch_fusion: false,
+            max_speculation_depth: None,
+            next_instruction_id: 0,
+            in_flight: VecDeque::new(),
+            refetch: None,
+            code_base: 0,
+            cycles_without_retire: 0,
+            busy_stages: [false; 5],
         }
     }
 
+    /// Limite le nombre de branchements non résolus en vol: le fetch bloque plutôt que de
+    /// spéculer au-delà (modélise des ressources de checkpoint/récupération finies)
+    pub fn set_max_speculation_depth(&mut self, depth: Option<usize>) {
+        self.max_speculation_depth = depth;
+    }
+
+    /// Adresse de chargement du segment de code: les PC sont absolus, l'instruction
+    /// d'indice 0 se trouve à `code_base`
+    pub fn set_code_base(&mut self, code_base: u32) {
+        self.code_base = code_base;
+        self.fetch.set_code_base(code_base);
+    }
+
+    /// Sans unité SIMD dédiée, les opérations SIMD et ALU scalaires se disputent un même port
+    pub fn set_shared_simd_port(&mut self, shared: bool) {
+        self.execute.set_shared_simd_port(shared);
+    }
+
+    /// Nombre d'opérations SIMD retenues par largeur dans le cache d'opérations de l'ALU vectorielle
+    pub fn set_simd_op_cache_size(&mut self, size: usize) {
+        self.execute.get_vector_alu_ref().borrow_mut().set_operation_cache_size(size);
+    }
+
+    /// Active la fusion d'une comparaison avec le saut conditionnel qui la suit: la paire
+    /// occupe un seul créneau du pipeline et le saut se résout dans le cycle de la comparaison
+    pub fn set_branch_fusion(&mut self, enabled: bool) {
+        self.enable_branch_fusion = enabled;
+    }
+
+    /// Nombre de cycles de stall injectés après chaque mauvaise prédiction de branchement,
+    /// en plus de la bulle du flush (modélise un pipeline plus profond)
+    pub fn set_misprediction_penalty(&mut self, cycles: u64) {
+        self.misprediction_penalty = cycles;
+    }
+
+    /// Capacité du store buffer de l'étage Memory (`VMConfig::store_buffer_size`, 0 = désactivé)
+    pub fn set_store_buffer_size(&mut self, size: usize) {
+        self.memory.set_store_buffer_capacity(size);
+    }
+
+    /// Zone mémoire de la pile, vérifiée par l'étage Memory à chaque PUSH/POP/CALL/RET
+    pub fn set_stack_bounds(&mut self, base: u32, size: usize) {
+        self.memory.set_stack_bounds(base, size);
+    }
+
+    /// Zones [début, fin) protégées en écriture (segments ReadOnlyData du programme)
+    pub fn set_readonly_ranges(&mut self, ranges: Vec<(u32, u32)>) {
+        self.execute.set_readonly_ranges(ranges.clone());
+        self.memory.set_readonly_ranges(ranges);
+    }
+
+    /// Nombre d'entrées de chaque table du prédicteur de branchement
+    pub fn set_branch_predictor_entries(&mut self, entries: usize) {
+        self.decode.branch_predictor.set_table_entries(entries);
+    }
+
+    /// Nombre d'entrées du RAS (Return Address Stack) de l'étage Decode
+    pub fn set_ras_size(&mut self, size: usize) {
+        self.decode.set_ras_size(size);
+    }
+
+    /// Sélectionne les chemins de forwarding actifs; sans `enable_forwarding`, aucun ne l'est.
+    /// Une instruction qui dépend d'un chemin désactivé attend un cycle (stall).
+    pub fn set_forwarding_paths(&mut self, ex_to_ex: bool, mem_to_ex: bool) {
+        self.forward_ex_to_ex = self.enable_forwarding && ex_to_ex;
+        self.forward_mem_to_ex = self.enable_forwarding && mem_to_ex;
+        self.hazard_detection
+            .set_forwarding_paths(self.forward_ex_to_ex, self.forward_mem_to_ex);
+    }
+
     /// Réinitialise le pipeline
     pub fn reset(&mut self) {
         self.state = PipelineState::default();
@@ -289,11 +567,35 @@ impl Pipeline {
         self.memory.reset();
         self.writeback.reset();
         self.hazard_detection.reset();
+        self.hazard_detection
+            .set_forwarding_paths(self.forward_ex_to_ex, self.forward_mem_to_ex);
         self.forwarding.reset();
         self.stats = PipelineStats::default();
+        self.pending_penalty_cycles = 0;
+        if let Some(counts) = self.opcode_counts.as_mut() {
+            counts.clear();
+        }
+        self.profile.clear();
+        self.call_frames.clear();
+        self.next_instruction_id = 0;
+        self.in_flight.clear();
+        self.refetch = None;
+        self.cycles_without_retire = 0;
+    }
+
+    /// Vide les registres inter-étages et le tampon de fetch sans toucher aux
+    /// statistiques ni aux prédicteurs, pour reprendre l'exécution à une nouvelle adresse
+    pub fn clear_in_flight(&mut self) {
+        self.state = PipelineState::default();
+        self.fetch.reset();
+        self.pending_penalty_cycles = 0;
+        self.in_flight.clear();
+        self.refetch = None;
+        self.cycles_without_retire = 0;
     }
 
-    /// Exécute un cycle du pipeline
+    /// Exécute un cycle du pipeline. Un pipeline qui ne retire plus rien pendant
+    /// `DEADLOCK_CYCLE_LIMIT` cycles est signalé comme interbloqué.
     pub fn cycle(
         &mut self,
         pc: u32,
@@ -301,15 +603,57 @@ impl Pipeline {
         memory: &mut Memory,
         alu: &mut ALU,
         instructions: &[Instruction],
-    ) -> Result<PipelineState, String> {
+    ) -> Result<PipelineState, PipelineError> {
+        self.busy_stages = [false; 5];
+        let result = self.advance(pc, registers, memory, alu, instructions);
+        for (busy_cycles, busy) in self.stats.stage_busy_cycles.iter_mut().zip(self.busy_stages) {
+            *busy_cycles += u64::from(busy);
+        }
+        let state = result?;
+        if state.instructions_completed > 0 || state.halted {
+            self.cycles_without_retire = 0;
+        } else {
+            self.cycles_without_retire += 1;
+            if self.cycles_without_retire >= DEADLOCK_CYCLE_LIMIT {
+                return Err(PipelineError::Deadlock { pc, stalled_cycles: self.cycles_without_retire });
+            }
+        }
+        Ok(state)
+    }
+
+    fn advance(
+        &mut self,
+        pc: u32,
+        registers: &mut [u64],
+        memory: &mut Memory,
+        alu: &mut ALU,
+        instructions: &[Instruction],
+    ) -> Result<PipelineState, PipelineError> {
         // 0) Incrément du compteur de cycles pipeline
         self.stats.cycles += 1;
         println!("DEBUG: Debut du cycle - PC = {}", pc);
 
+        // Pénalité de mauvaise prédiction: le pipeline reste bloqué, aucun étage n'avance
+        if self.pending_penalty_cycles > 0 {
+            self.pending_penalty_cycles -= 1;
+            self.stats.stalls += 1;
+            self.hazard_detection.end_cycle();
+            let mut state = self.state.clone();
+            state.stalled = true;
+            state.instructions_completed = 0;
+            state.retired.clear();
+            state.next_pc = pc;
+            return Ok(state);
+        }
+
+        // Les écritures en attente quittent le store buffer dans l'ordre du programme
+        memory.drain_store_buffer_cycle();
+
         // 1) Clone de l’état local
         let mut state = self.state.clone();
         state.stalled = false;
         state.instructions_completed = 0;
+        state.retired.clear();
         let pc_for_this_cycle = pc; // bug fix
         let current_pc_target = self.state.next_pc;
 
@@ -364,7 +708,19 @@ impl Pipeline {
         // Si on n’est pas stalled, on fetch l’instruction à l’adresse `pc`.
         if !state.stalled {
             // On fetch
-            let fd_reg = self.fetch.process_direct(pc, instructions)?;
+            let mut fd_reg = match self.fetch.process_direct(pc, instructions) {
+                Ok(fd_reg) => fd_reg,
+                Err(msg) => return Err(invalid_opcode_at(memory, pc).unwrap_or(PipelineError::Stage(msg))),
+            };
+            fd_reg.id = self.tag_fetch(fd_reg.pc);
+            self.busy_stages[STAGE_FETCH] = true;
+            self.access_icache(fd_reg.pc, memory);
+            // Macro-op fusion: la comparaison s'exécute ici, le saut prend sa place dans le pipeline
+            let fd_reg = if self.enable_branch_fusion {
+                self.fuse_compare_branch(fd_reg, &mut state, registers, memory, alu, instructions)?
+            } else {
+                fd_reg
+            };
             state.fetch_decode = Some(fd_reg.clone()); // Clone fd_reg as it's used in println later
 
             // BugFixe: Modify Fetch PC update
@@ -372,7 +728,7 @@ impl Pipeline {
             // This can be overridden by branch resolution logic later in this same cycle.
             if let Some(fetched_instruction_data) = &state.fetch_decode { // Use the just-fetched instruction
                 let instruction_size = fetched_instruction_data.instruction.total_size() as u32;
-                state.next_pc = pc_for_this_cycle.wrapping_add(instruction_size);
+                state.next_pc = fetched_instruction_data.pc.wrapping_add(instruction_size);
                 // println!("[DEBUG: Fin Fetch -] PC = 0x{:08X}, next_pc = 0x{:08X}", fd_reg.pc, state.next_pc);
                 // Ensure fd_reg.pc is pc_for_this_cycle if used in the println.
                 // fd_reg.pc should be pc_for_this_cycle if fetch was successful for pc_for_this_cycle
@@ -385,8 +741,21 @@ impl Pipeline {
         if !state.stalled {
             if let Some(fd_reg) = &state.fetch_decode {
                 let ex_reg = self.decode.process_direct(fd_reg, registers)?;
-                state.decode_execute = Some(ex_reg);
+                self.busy_stages[STAGE_DECODE] = true;
                 println!("[DEBUG: Fin Decode -] PC = 0x{:08X}, instruction = {:?},next_pc = 0x{:08X}", fd_reg.pc, fd_reg.instruction.opcode, state.next_pc);
+                // Opérande disponible seulement via un chemin de forwarding désactivé: bulle
+                if self.enable_hazard_detection && self.hazard_detection.needs_disabled_forwarding(&ex_reg) {
+                    println!("Forwarding désactivé: PC=0x{:08X} attend un cycle", fd_reg.pc);
+                    self.refetch = Some(fd_reg.id);
+                    self.stats.stalls += 1;
+                    self.hazard_detection.hazards_count += 1;
+                    state.stalled = true;
+                    state.next_pc = pc_for_this_cycle;
+                    state.fetch_decode = None;
+                    state.decode_execute = None;
+                } else {
+                    state.decode_execute = Some(ex_reg);
+                }
             } else {
                 state.decode_execute = None;
                 println!("DEBUG: Pas d'instruction à décoder (fetch_decode est None)");
@@ -398,18 +767,19 @@ impl Pipeline {
         // ----- (3ᵉ étape) EXECUTE -----
         if let Some(de_reg) = &state.decode_execute {
             let pc_of_executed_branch_instr = de_reg.pc; // Copy PC early
+            self.busy_stages[STAGE_EXECUTE] = true;
             // Forwarding si activé
             let mut de_reg_mut = de_reg.clone();
             if self.enable_forwarding {
-                self.forwarding.forward(
-                    &mut de_reg_mut,
-                    &state.execute_memory,
-                    &state.memory_writeback,
-                );
+                // Un chemin désactivé ne fournit aucune valeur
+                let ex_source = if self.forward_ex_to_ex { state.execute_memory.clone() } else { None };
+                let mem_source = if self.forward_mem_to_ex { state.memory_writeback.clone() } else { None };
+                self.forwarding.forward(&mut de_reg_mut, &ex_source, &mem_source);
 
             }
 
             let sp = registers[16]; // SP (Stack Pointer)
+            self.execute.set_retired_instructions(self.stats.instructions);
             
             // ACTIVATION DU PARALLEL EXECUTION ENGINE !
             // Essayer d'abord process_parallel pour la vraie exécution parallèle
@@ -435,13 +805,36 @@ impl Pipeline {
 
             // Gérer les prédictions de branchement
             if let Some(prediction_correct) = mem_reg.branch_prediction_correct {
+                // Fenêtre chargée sur le chemin prédit pendant que le branchement était en vol
+                let sequential_pc = branch_pc + mem_reg.instruction.total_size() as u32;
+                let predicted_pc = match branch_prediction {
+                    Some(BranchPrediction::Taken) => de_reg.branch_addr.unwrap_or(sequential_pc),
+                    _ => sequential_pc,
+                };
+                let (speculated, blocked_slots) = self.speculative_window(predicted_pc, instructions);
+                if blocked_slots > 0 {
+                    self.stats.speculation_stalls += blocked_slots as u64;
+                    self.pending_penalty_cycles += blocked_slots as u64;
+                }
+                if !prediction_correct {
+                    self.stats.speculative_squashes += speculated as u64;
+                }
                 if prediction_correct {
                     // Prédiction correcte - mise à jour des statistiques
                     self.stats.branch_hits += 1;
 
+                    // Le fetch n'est pas redirigé par la prédiction: un branchement
+                    // pris (et bien prédit) doit tout de même amener le PC sur sa cible
+                    if mem_reg.branch_taken {
+                        if let Some(target) = mem_reg.branch_target {
+                            state.next_pc = target;
+                        }
+                    }
+
                 } else {
                     // Prédiction incorrecte - flush du pipeline et mise à jour du PC
                     self.stats.branch_misses += 1;
+                    self.pending_penalty_cycles += self.misprediction_penalty;
 
 
                     if mem_reg.branch_taken {
@@ -449,8 +842,7 @@ impl Pipeline {
                             // Branchement pris mais prédit non pris
                             state.next_pc = target;
                             println!("Branchement pris vers l'adresse: 0x{:08X}", target);
-                            state.fetch_decode = None;
-                            state.decode_execute = None;
+                            self.squash_younger_than(&mut state, branch_pc);
                             self.stats.branch_flush += 1;
                         }else {
                             println!("On ne fait rien ")
@@ -506,6 +898,11 @@ impl Pipeline {
                 );
             }
 
+            // CALL résolu: son adresse de retour alimente le RAS
+            if mem_reg.instruction.opcode == Opcode::Call {
+                self.decode.update_ras_for_call(mem_reg.pc, mem_reg.instruction.total_size() as u32);
+            }
+
             state.execute_memory = Some(mem_reg);
         } else {
             state.execute_memory = None;
@@ -514,10 +911,25 @@ impl Pipeline {
         // ----- (4ᵉ étape) MEMORY -----
         if let Some(ex_mem) = &state.execute_memory {
             let wb_reg = self.memory.process_direct(ex_mem, memory, registers)?;
+            self.mark_memory_access(ex_mem);
+            self.charge_memory_latency(memory);
+            self.issue_prefetches(memory);
+
+            // Suivi des cadres d'appel: CALL vient d'empiler son adresse de retour en SP
+            match ex_mem.instruction.opcode {
+                Opcode::Call => self.call_frames.push(registers[16] as u32),
+                Opcode::Ret => {
+                    self.call_frames.pop();
+                }
+                _ => {}
+            }
 
             // Si c’est un HALT => on arrête tout de suite
             if ex_mem.instruction.opcode == Opcode::Halt {
                 state.halted = true;
+                // HALT n'atteint pas Writeback mais fait partie du chemin exécuté
+                self.profile.record(ex_mem.pc);
+                self.record_retire_timing(ex_mem.id, &mut state.retired);
                 // Flush le pipeline
                 state.fetch_decode = None;
                 state.decode_execute = None;
@@ -529,6 +941,12 @@ impl Pipeline {
                 return Ok(state);
             }
 
+            // RET: la vraie adresse de retour vient d'être dépilée de la pile mémoire
+            if ex_mem.instruction.opcode == Opcode::Ret {
+                let ret = ex_mem.clone();
+                self.resolve_return(&mut state, &ret, wb_reg.result as u32);
+            }
+
             state.memory_writeback = Some(wb_reg);
 
         } else {
@@ -537,12 +955,28 @@ impl Pipeline {
 
         // ----- (5ᵉ étape) WRITEBACK -----
         if let Some(mw_reg) = &state.memory_writeback {
-            self.writeback.process_direct(mw_reg, registers)?;
+            self.writeback.process_group(std::slice::from_ref(mw_reg), registers)?;
+            self.busy_stages[STAGE_WRITEBACK] = true;
+            if let Some(rd) = mw_reg.rd {
+                self.hazard_detection.record_retired_write(rd, mw_reg.instruction.opcode);
+            }
             // On considère qu’une instruction est finalisée ici
             state.instructions_completed += 1;
-            // self.stats.instructions += 1;
+            self.record_retirement(mw_reg.id, mw_reg.pc, mw_reg.instruction.opcode, &mut state.retired);
+        }
+        // Le latch MEM/WB est vidé pour le cycle suivant, mais l'état renvoyé le conserve (traçage)
+        let retired_writeback = state.memory_writeback.take();
+
+        // ----- Emission multiple: instructions indépendantes retirées dans le même cycle -----
+        if let Some(leader) = state.decode_execute.clone() {
+            if !state.stalled && !leader.instruction.opcode.is_branch() {
+                self.issue_extra_slots(&leader, &mut state, registers, memory, alu, instructions)?;
+                self.charge_memory_latency(memory);
+                self.issue_prefetches(memory);
+            }
         }
-        state.memory_writeback = None;
+
+        self.hazard_detection.end_cycle();
 
         // Mise à jour des statistiques
         self.stats.hazards = self.hazard_detection.get_hazards_count();
@@ -557,12 +991,299 @@ impl Pipeline {
 
         // 9) Mise à jour de self.state
         self.state = state.clone();
+        state.memory_writeback = retired_writeback;
 
         println!("[[[DEBUG: Fin du cycle ]]] - PC = 0x{:08X}, next_pc = 0x{:08X}", pc, state.next_pc);
         // println!("DEBUG: Fin du cycle - PC = {}", pc);
         Ok(state)
     }
 
+    /// Complète le groupe d'émission de `leader` avec les instructions suivantes tant
+    /// qu'elles sont indépendantes de tout le groupe, que la largeur d'émission le permet et qu'un port
+    /// d'écriture du banc de registres reste libre. Chaque instruction ajoutée traverse
+    /// les étages dans le cycle courant; les latches gardent l'instruction de tête.
+    fn issue_extra_slots(
+        &mut self,
+        leader: &DecodeExecuteRegister,
+        state: &mut PipelineState,
+        registers: &mut [u64],
+        memory: &mut Memory,
+        alu: &mut ALU,
+        instructions: &[Instruction],
+    ) -> Result<(), PipelineError> {
+        let mut group = vec![leader.clone()];
+        let mut retired = Vec::new();
+        let mut register_writes = usize::from(leader.rd.is_some());
+
+        while group.len() < self.issue_width {
+            let Ok(fd_reg) = self.fetch.process_direct(state.next_pc, instructions) else {
+                break; // Fin du programme
+            };
+            if fd_reg.instruction.opcode.is_branch() || fd_reg.instruction.opcode == Opcode::Halt {
+                break;
+            }
+            // Une comparaison fusionnable attend le cycle suivant pour partir avec son saut
+            if self.fusible_at(&fd_reg, instructions) {
+                break;
+            }
+            let de_reg = self.decode.process_direct(&fd_reg, registers)?;
+            if self.execute.check_issue_group(&group, &de_reg).is_err() {
+                break;
+            }
+            if self.enable_hazard_detection && self.hazard_detection.needs_disabled_forwarding(&de_reg) {
+                break;
+            }
+            // Plus de port d'écriture: le writeback de cette instruction attend le cycle
+            // suivant et bloque le pipeline pendant ce cycle (stall structurel)
+            if de_reg.rd.is_some() && register_writes >= self.register_write_ports {
+                self.stats.writeback_port_stalls += 1;
+                self.stats.stage_busy_cycles[STAGE_WRITEBACK] += 1;
+                self.pending_penalty_cycles += 1;
+                register_writes = 0;
+                println!(
+                    "WRITEBACK: stall structurel, {} port(s) d'écriture occupé(s), PC=0x{:08X} retardé",
+                    self.register_write_ports, de_reg.pc
+                );
+            }
+
+            self.access_icache(fd_reg.pc, memory);
+            let mut de_reg = de_reg;
+            de_reg.id = self.tag_fetch(fd_reg.pc);
+            let wb_reg = self.execute_in_cycle(&de_reg, registers, memory, alu)?;
+
+            state.next_pc = fd_reg.pc.wrapping_add(fd_reg.instruction.total_size() as u32);
+            register_writes += usize::from(wb_reg.rd.is_some());
+            println!(
+                "MULTI-ISSUE: PC=0x{:08X} {:?} émise dans le slot {}",
+                fd_reg.pc, fd_reg.instruction.opcode, group.len()
+            );
+            retired.push(wb_reg);
+            group.push(de_reg);
+        }
+
+        // Les instructions du groupe étant indépendantes, leurs résultats sont écrits
+        // ensemble, dans l'ordre du programme
+        self.retire_group(&retired, state, registers)?;
+        self.execute.record_issue_group(&group);
+        Ok(())
+    }
+
+    /// Fait traverser Execute et Memory à `de_reg` dans le cycle courant
+    fn execute_in_cycle(
+        &mut self,
+        de_reg: &DecodeExecuteRegister,
+        registers: &mut [u64],
+        memory: &mut Memory,
+        alu: &mut ALU,
+    ) -> Result<MemoryWritebackRegister, PipelineError> {
+        let sp = registers[16];
+        self.execute.set_retired_instructions(self.stats.instructions);
+        let mem_reg = self.execute.process_with_dual_issue(de_reg, alu, memory, registers, sp)?;
+        let wb_reg = self.memory.process_direct(&mem_reg, memory, registers)?;
+        self.mark_memory_access(&mem_reg);
+        Ok(wb_reg)
+    }
+
+    /// Writeback d'un groupe d'instructions terminant dans le même cycle, puis retrait
+    fn retire_group(
+        &mut self,
+        wb_regs: &[MemoryWritebackRegister],
+        state: &mut PipelineState,
+        registers: &mut [u64],
+    ) -> Result<(), PipelineError> {
+        self.writeback.process_group(wb_regs, registers)?;
+        for wb_reg in wb_regs {
+            if let Some(rd) = wb_reg.rd {
+                self.hazard_detection.record_retired_write(rd, wb_reg.instruction.opcode);
+            }
+            state.instructions_completed += 1;
+            self.record_retirement(wb_reg.id, wb_reg.pc, wb_reg.instruction.opcode, &mut state.retired);
+        }
+        Ok(())
+    }
+
+    /// Vrai si l'instruction chargée forme avec la suivante une paire fusionnable
+    fn fusible_at(&self, fd_reg: &FetchDecodeRegister, instructions: &[Instruction]) -> bool {
+        let next_pc = fd_reg.pc + fd_reg.instruction.total_size() as u32;
+        self.enable_branch_fusion
+            && instruction_at(instructions, self.code_base, next_pc)
+                .is_some_and(|next| decode::DecodeStage::is_fusible_pair(&fd_reg.instruction, next))
+    }
+
+    /// Fusionne une comparaison avec le saut conditionnel qui la suit: la comparaison est
+    /// exécutée et retirée immédiatement, ses flags sont donc prêts quand le saut, chargé
+    /// dans le même cycle, traverse Decode et Execute à la place de la paire.
+    /// Retourne l'instruction qui continue dans le pipeline (`fd_reg` si pas de fusion).
+    fn fuse_compare_branch(
+        &mut self,
+        fd_reg: FetchDecodeRegister,
+        state: &mut PipelineState,
+        registers: &mut [u64],
+        memory: &mut Memory,
+        alu: &mut ALU,
+        instructions: &[Instruction],
+    ) -> Result<FetchDecodeRegister, PipelineError> {
+        if !self.fusible_at(&fd_reg, instructions) {
+            return Ok(fd_reg);
+        }
+        let de_reg = self.decode.process_direct(&fd_reg, registers)?;
+        let compare = self.execute_in_cycle(&de_reg, registers, memory, alu)?;
+        self.retire_group(std::slice::from_ref(&compare), state, registers)?;
+
+        let branch_pc = fd_reg.pc + fd_reg.instruction.total_size() as u32;
+        let mut branch = self.fetch.process_direct(branch_pc, instructions)?;
+        branch.id = self.tag_fetch(branch.pc);
+        self.access_icache(branch.pc, memory);
+        self.stats.fused_branch_count += 1;
+        println!("FUSION: {:?} 0x{:08X} + {:?} 0x{:08X}", fd_reg.instruction.opcode, fd_reg.pc, branch.instruction.opcode, branch.pc);
+        Ok(branch)
+    }
+
+    /// Transmet au système mémoire les préchargements demandés par le prédicteur de stride
+    fn issue_prefetches(&mut self, memory: &mut Memory) {
+        for (current, predicted) in self.execute.take_prefetch_requests() {
+            memory.prefetch_stride(current, predicted);
+        }
+    }
+
+    /// Comptabilise une instruction retirée (compteur, histogramme d'opcodes, profil
+    /// d'exécution) et ajoute son temps de passage à `retired`
+    fn record_retirement(&mut self, id: u64, pc: u32, opcode: Opcode, retired: &mut Vec<RetireTiming>) {
+        self.stats.instructions += 1;
+        if let Some(counts) = self.opcode_counts.as_mut() {
+            *counts.entry(opcode).or_insert(0) += 1;
+        }
+        self.profile.record(pc);
+        self.record_retire_timing(id, retired);
+    }
+
+    /// Ajoute à `retired` le temps de passage de l'instruction `id`
+    fn record_retire_timing(&mut self, id: u64, retired: &mut Vec<RetireTiming>) {
+        // Les instructions chargées avant celle-ci et jamais retirées ont été annulées
+        while let Some((fetched_id, pc, fetch_cycle)) = self.in_flight.pop_front() {
+            if fetched_id == id {
+                let retire_cycle = self.current_cycle();
+                retired.push(RetireTiming { id, pc, fetch_cycle, retire_cycle });
+                break;
+            }
+        }
+    }
+
+    /// Attribue un identifiant à l'instruction chargée à `pc`. Le rechargement d'une
+    /// instruction renvoyée au fetch par un stall garde son identifiant et son cycle de fetch.
+    fn tag_fetch(&mut self, pc: u32) -> u64 {
+        if let Some(id) = self.refetch.take() {
+            if self.in_flight.iter().any(|&(fetched_id, fetched_pc, _)| fetched_id == id && fetched_pc == pc) {
+                return id;
+            }
+        }
+        let id = self.next_instruction_id;
+        self.in_flight.push_back((id, pc, self.current_cycle()));
+        self.next_instruction_id += 1;
+        id
+    }
+
+    /// Numéro du cycle en cours, à partir de 0
+    fn current_cycle(&self) -> u64 {
+        self.stats.cycles.saturating_sub(1)
+    }
+
+    /// Bloque le pipeline le temps des lectures de données du cycle: l'étage Memory
+    /// absorbe un cycle, le reste de la latence (L2 ou mémoire) devient des stalls,
+    /// tout comme les attentes du store buffer
+    fn charge_memory_latency(&mut self, memory: &mut Memory) {
+        let store_stalls = memory.take_store_stall_cycles();
+        self.pending_penalty_cycles += store_stalls;
+        self.stats.stage_busy_cycles[STAGE_MEMORY] += store_stalls;
+        if let Some(latency) = memory.take_access_latency() {
+            let extra = latency.saturating_sub(1) as u64;
+            self.stats.memory_stall_cycles += extra;
+            self.pending_penalty_cycles += extra;
+            // L'étage Memory reste occupé pendant l'attente
+            self.stats.stage_busy_cycles[STAGE_MEMORY] += extra;
+        }
+    }
+
+    /// L'étage Memory n'est occupé que par les instructions qui accèdent à la mémoire
+    fn mark_memory_access(&mut self, ex_mem: &ExecuteMemoryRegister) {
+        if ex_mem.mem_addr.is_some() || ex_mem.stack_operation.is_some() {
+            self.busy_stages[STAGE_MEMORY] = true;
+        }
+    }
+
+    /// Lit l'instruction à `pc` dans le cache d'instructions; un miss bloque le fetch
+    /// pendant le remplissage de la ligne
+    fn access_icache(&mut self, pc: u32, memory: &mut Memory) {
+        if let Some(latency) = memory.fetch_instruction(pc) {
+            let extra = latency.saturating_sub(1) as u64;
+            self.stats.instruction_fetch_stalls += extra;
+            self.pending_penalty_cycles += extra;
+        }
+    }
+
+    /// Flush précis après un branchement mal prédit situé à `branch_pc`.
+    ///
+    /// Seules les instructions plus jeunes que le branchement sont annulées: elles se
+    /// trouvent dans IF/ID et ID/EX (le branchement lui-même, qui vient de quitter
+    /// Execute, y est ignoré). Les latches EX/MEM et MEM/WB ne contiennent que des
+    /// instructions plus anciennes, qui doivent retirer normalement et ne sont pas touchées.
+    fn squash_younger_than(&mut self, state: &mut PipelineState, branch_pc: u32) {
+        let fd_younger = state.fetch_decode.take().filter(|fd| fd.pc != branch_pc);
+        let de_younger = state.decode_execute.take().filter(|de| de.pc != branch_pc);
+        let squashed = fd_younger.iter().count() + de_younger.iter().count();
+
+        let older_in_flight = state.execute_memory.iter().count() + state.memory_writeback.iter().count();
+
+        self.stats.squashed_instructions += squashed as u64;
+        self.stats.flush_preserved_instructions += older_in_flight as u64;
+        println!(
+            "Flush précis après le branchement 0x{:08X}: {} instruction(s) annulée(s), {} plus ancienne(s) conservée(s)",
+            branch_pc, squashed, older_in_flight
+        );
+    }
+
+    /// Parcourt la fenêtre spéculative qui suit un branchement à partir de `pc`.
+    /// Retourne (instructions chargées, créneaux où le fetch a dû attendre): chaque
+    /// branchement rencontré est lui aussi non résolu, et au-delà de `max_speculation_depth`
+    /// branchements en vol le reste de la fenêtre devient des bulles.
+    fn speculative_window(&self, mut pc: u32, instructions: &[Instruction]) -> (usize, usize) {
+        let mut in_flight = 1; // le branchement lui-même
+        let mut speculated = 0;
+        for slot in 0..SPECULATION_WINDOW {
+            if self.max_speculation_depth.is_some_and(|depth| in_flight > depth) {
+                return (speculated, SPECULATION_WINDOW - slot);
+            }
+            let Some(instruction) = instruction_at(instructions, self.code_base, pc) else {
+                break; // Fin du programme
+            };
+            speculated += 1;
+            if instruction.opcode.is_branch() {
+                in_flight += 1;
+            }
+            pc += instruction.total_size() as u32;
+        }
+        (speculated, 0)
+    }
+
+    /// Valide la prédiction RAS (ou BTB) d'un RET contre l'adresse dépilée: en cas
+    /// d'écart, le chemin spéculatif est annulé et le fetch redirigé
+    fn resolve_return(&mut self, state: &mut PipelineState, ret: &ExecuteMemoryRegister, actual_target: u32) {
+        let predicted = ret.branch_target;
+        self.decode.update_ras_for_ret();
+        self.decode.ras.update_prediction(predicted, actual_target);
+
+        if predicted != Some(actual_target) {
+            println!("RAS MISS: RET 0x{:08X} prédit {:?}, retour réel 0x{:08X}", ret.pc, predicted, actual_target);
+            self.stats.branch_flush += 1;
+            self.pending_penalty_cycles += self.misprediction_penalty;
+            self.squash_younger_than(state, ret.pc);
+        }
+        state.next_pc = actual_target;
+
+        let btb_prediction = self.decode.branch_predictor.predict_target(ret.pc as u64);
+        self.decode.branch_predictor.update_btb(ret.pc as u64, actual_target, btb_prediction);
+    }
+
     pub fn update_branch_predictor(&mut self, pc: u64, taken: bool, prediction: BranchPredictor) {
         println!("Updating branch predictor: PC=0x{:X}, taken={}, prediction={:?}",
                  pc, taken, prediction);
@@ -574,11 +1295,16 @@ impl Pipeline {
         stats.branch_predictor_rate = stats.branch_prediction_rate();
         // Mise à jour des statistiques de la pile
         // stats.update_stack_stats(self.get_ras_stats());
+        let ras = self.get_ras_stats();
+        stats.ras_hits = ras.hits;
+        stats.ras_misses = ras.misses;
+        stats.ras_accuracy = ras.accuracy;
+        stats.branch_aliasing_conflicts = self.decode.branch_predictor.metrics.aliasing_conflicts as u64;
         stats
     }
 
     pub fn get_ras_stats(&self) -> RASStats {
-        self.get_ras_stats()
+        self.decode.ras_stats()
     }
     
     /// Retourne les statistiques de pile du Memory stage
@@ -604,6 +1330,82 @@ impl Pipeline {
         )
     }
 
+    /// Retourne le prédicteur de branchement utilisé par l'étage Decode
+    pub fn branch_predictor(&self) -> &BranchPredictor {
+        &self.decode.branch_predictor
+    }
+
+    /// Retourne une référence mutable au prédicteur de branchement de l'étage Decode
+    pub fn branch_predictor_mut(&mut self) -> &mut BranchPredictor {
+        &mut self.decode.branch_predictor
+    }
+
+    /// Emplacements de pile où les CALL en cours ont empilé leur adresse de retour,
+    /// du plus ancien au plus récent
+    pub fn call_frames(&self) -> &[u32] {
+        &self.call_frames
+    }
+
+    /// Retourne le profil d'exécution (retraits par adresse et transitions)
+    pub fn execution_profile(&self) -> &ExecutionProfile {
+        &self.profile
+    }
+
+    /// Retourne les compteurs d'instructions retirées par opcode, si activés
+    pub fn opcode_counts(&self) -> Option<&HashMap<Opcode, u64>> {
+        self.opcode_counts.as_ref()
+    }
+
+    /// Retourne l'exception matérielle ayant interrompu le dernier cycle, s'il y en a une
+    pub fn take_fault(&mut self) -> Option<crate::pvm::vm_errors::VMError> {
+        self.execute.take_fault().or_else(|| self.memory.take_fault())
+    }
+
+    /// Ajoute un watchpoint: toute écriture (STORE, STM, store SIMD) couvrant `addr` le déclenche
+    pub fn add_watchpoint(&mut self, addr: u32) {
+        self.memory.add_watchpoint(addr);
+    }
+
+    /// Adresses surveillées par `add_watchpoint`, par ordre croissant
+    pub fn watchpoints(&self) -> Vec<u32> {
+        self.memory.watchpoints()
+    }
+
+    /// Récupère (et efface) le watchpoint déclenché au dernier cycle: (adresse, ancien, nouveau)
+    pub fn take_watchpoint_hit(&mut self) -> Option<(u32, u64, u64)> {
+        self.memory.take_watchpoint_hit()
+    }
+
+    /// Retourne l'adresse du Syscall exécuté au dernier cycle, s'il y en a un
+    pub fn take_syscall(&mut self) -> Option<u32> {
+        self.execute.take_syscall()
+    }
+
+    /// Mode de privilège courant du processeur
+    pub fn privilege_mode(&self) -> execute::PrivilegeMode {
+        self.execute.privilege_mode()
+    }
+
+    /// Capture les registres intermédiaires et l'état interne nécessaire à une reprise
+    pub fn checkpoint(&self) -> PipelineCheckpoint {
+        let (store_buffer, stack_pointer) = self.memory.checkpoint();
+        PipelineCheckpoint {
+            state: self.state.clone(),
+            store_buffer,
+            stack_pointer,
+            privilege_mode: self.execute.privilege_mode(),
+            call_frames: self.call_frames.clone(),
+        }
+    }
+
+    /// Restaure un état capturé par `checkpoint`
+    pub fn restore_checkpoint(&mut self, checkpoint: &PipelineCheckpoint) {
+        self.state = checkpoint.state.clone();
+        self.memory.restore_checkpoint(&checkpoint.store_buffer, checkpoint.stack_pointer);
+        self.execute.set_privilege_mode(checkpoint.privilege_mode);
+        self.call_frames = checkpoint.call_frames.clone();
+    }
+
     /// Retourne une référence à l'étage Execute pour accéder aux composants internes
     pub fn get_execute_stage(&self) -> &execute::ExecuteStage {
         &self.execute
@@ -616,3 +1418,58 @@ impl Pipeline {
 
 }
 
+/// Taux d'occupation `busy / cycles` de chaque étage, nul sans cycle écoulé
+pub fn stage_utilization(stage_busy_cycles: &[u64; 5], cycles: u64) -> [f64; 5] {
+    stage_busy_cycles.map(|busy| if cycles > 0 { (busy as f64 / cycles as f64).min(1.0) } else { 0.0 })
+}
+
+/// Instruction commençant exactement à l'adresse `pc` (la première étant à `code_base`), s'il y en a une
+fn instruction_at(instructions: &[Instruction], code_base: u32, pc: u32) -> Option<&Instruction> {
+    let mut addr = code_base;
+    for instruction in instructions {
+        if addr == pc {
+            return Some(instruction);
+        }
+        addr += instruction.total_size() as u32;
+    }
+    None
+}
+
+/// Trap d'opcode invalide: l'octet de code chargé à `pc` ne correspond à aucun opcode
+fn invalid_opcode_at(memory: &Memory, pc: u32) -> Option<PipelineError> {
+    let byte = *memory.read_direct(pc, 1).ok()?.first()?;
+    Opcode::from_u8(byte).is_none().then_some(PipelineError::IllegalInstruction { pc, byte })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_squash_only_younger_instructions() {
+        let mut pipeline = Pipeline::new(4, true, true, PredictorType::Static, false, 2, 2);
+        let branch_pc = 0x18;
+        let mut state = PipelineState {
+            fetch_decode: Some(FetchDecodeRegister {
+                id: 0,
+                instruction: Instruction::create_reg_imm8(Opcode::Mov, 6, 9),
+                pc: 0x20,
+            }),
+            memory_writeback: Some(MemoryWritebackRegister {
+                id: 0,
+                instruction: Instruction::create_reg_imm8(Opcode::Mov, 5, 7),
+                pc: 0x1C,
+                result: 7,
+                rd: Some(5),
+            }),
+            ..PipelineState::default()
+        };
+
+        pipeline.squash_younger_than(&mut state, branch_pc);
+
+        assert!(state.fetch_decode.is_none());
+        assert_eq!(state.memory_writeback.as_ref().and_then(|wb| wb.rd), Some(5));
+        assert_eq!(pipeline.stats().squashed_instructions, 1);
+        assert_eq!(pipeline.stats().flush_preserved_instructions, 1);
+    }
+}
@@ -82,6 +82,12 @@ pub struct ParallelExecutionStats {
     
     pub dependency_stalls: u64,
     pub resource_conflicts: u64,
+    /// Instructions non émises faute d'unité libre, par classe d'unité
+    pub alu_issue_blocked: u64,
+    pub agu_issue_blocked: u64,
+    pub simd_issue_blocked: u64,
+    /// Co-émissions SIMD/ALU refusées car les deux unités partagent un port
+    pub shared_port_conflicts: u64,
     
     pub alu_utilization: f64,
     pub agu_utilization: f64,
@@ -281,8 +287,9 @@ impl ParallelExecutionEngine {
     pub fn analyze_instruction(instruction: &DecodeExecuteRegister) -> (ExecutionUnit, InstructionPriority) {
         match instruction.instruction.opcode {
             // Instructions mémoire - AGU haute priorité
-            Opcode::Load | Opcode::LoadB | Opcode::LoadW | Opcode::LoadD |
-            Opcode::Store | Opcode::StoreB | Opcode::StoreW | Opcode::StoreD => {
+            Opcode::Load | Opcode::LoadB | Opcode::LoadW | Opcode::LoadD | Opcode::LoadSB | Opcode::LoadSW | Opcode::LoadSD |
+            Opcode::Store | Opcode::StoreB | Opcode::StoreW | Opcode::StoreD |
+            Opcode::Ldm | Opcode::Stm | Opcode::Lea => {
                 (ExecutionUnit::AGU, InstructionPriority::High)
             }
             
@@ -297,6 +304,10 @@ impl ParallelExecutionEngine {
             Opcode::Inc | Opcode::Dec | Opcode::Neg |
             Opcode::And | Opcode::Or | Opcode::Xor | Opcode::Not |
             Opcode::Shl | Opcode::Shr | Opcode::Sar | Opcode::Rol | Opcode::Ror |
+            Opcode::Shld | Opcode::Shrd | Opcode::Popcnt | Opcode::Clz | Opcode::Ctz |
+            Opcode::Bfe | Opcode::Bfi |
+            Opcode::Crc32 | Opcode::AddSat | Opcode::SubSat | Opcode::AddSatD | Opcode::SubSatD |
+            Opcode::AddSatS | Opcode::SubSatS | Opcode::AddSatSD | Opcode::SubSatSD |
             Opcode::Cmp | Opcode::Test => {
                 (ExecutionUnit::ALU, InstructionPriority::Medium)
             }
@@ -304,7 +315,12 @@ impl ParallelExecutionEngine {
             // Instructions SIMD arithmétiques - SIMD priorité moyenne  
             Opcode::Simd128Add | Opcode::Simd128Sub | Opcode::Simd128Mul | Opcode::Simd128Div |
             Opcode::Simd128And | Opcode::Simd128Or | Opcode::Simd128Xor | Opcode::Simd128Not |
-            Opcode::Simd256Add | Opcode::Simd256Sub | Opcode::Simd256Mul | Opcode::Simd256Div => {
+            Opcode::Simd256Add | Opcode::Simd256Sub | Opcode::Simd256Mul | Opcode::Simd256Div |
+            Opcode::Simd256AddI64x4 | Opcode::Simd256SubI64x4 | Opcode::Simd256MulI64x4 |
+            Opcode::Simd128MaskedLoad | Opcode::Simd128MaskedStore |
+            Opcode::Simd128ExtractLane | Opcode::Simd128InsertLane | Opcode::Simd128Blend |
+            Opcode::Simd128AddI64x2 | Opcode::Simd128SubI64x2 | Opcode::Simd128MulI64x2 |
+            Opcode::Simd128AddF64x2 | Opcode::Simd128SubF64x2 | Opcode::Simd128MulF64x2 | Opcode::Simd128DivF64x2 => {
                 (ExecutionUnit::SIMD, InstructionPriority::Medium)
             }
             
@@ -486,7 +502,9 @@ impl ParallelExecutionEngine {
     /// Crée un résultat factice pour la simulation
     fn create_dummy_result(&self, packet: &ExecutionPacket, exec_unit: ExecutionUnit) -> ExecuteMemoryRegister {
         ExecuteMemoryRegister {
+            id: packet.instruction.id,
             instruction: packet.instruction.instruction.clone(),
+            pc: packet.instruction.pc,
             alu_result: 0,
             rd: packet.instruction.rd,
             store_value: None,
@@ -498,9 +516,51 @@ impl ParallelExecutionEngine {
             stack_result: None,
             ras_prediction_correct: None,
             halted: false,
+            vector_data: None,
         }
     }
     
+    /// Enregistre un groupe d'instructions émises dans le même cycle
+    pub fn record_issue_group(&mut self, units: &[ExecutionUnit]) {
+        self.stats.total_instructions += units.len() as u64;
+        for unit in units {
+            match unit {
+                ExecutionUnit::ALU => self.stats.alu_instructions += 1,
+                ExecutionUnit::AGU => self.stats.agu_instructions += 1,
+                ExecutionUnit::SIMD => self.stats.simd_instructions += 1,
+                _ => {}
+            }
+        }
+        // Chaque instruction émise en plus de l'instruction de tête compte comme exécution parallèle
+        self.stats.parallel_executions += units.len().saturating_sub(1) as u64;
+    }
+
+    /// Enregistre une instruction écartée du groupe d'émission à cause d'une dépendance
+    pub fn record_dependency_stall(&mut self, dep_type: DependencyType) {
+        self.stats.dependency_stalls += 1;
+        match dep_type {
+            DependencyType::RAW => self.stats.raw_dependencies += 1,
+            DependencyType::WAR => self.stats.war_dependencies += 1,
+            DependencyType::WAW => self.stats.waw_dependencies += 1,
+        }
+    }
+
+    /// Enregistre une instruction écartée faute d'unité d'exécution libre
+    pub fn record_resource_conflict(&mut self, unit: ExecutionUnit) {
+        self.stats.resource_conflicts += 1;
+        match unit {
+            ExecutionUnit::ALU => self.stats.alu_issue_blocked += 1,
+            ExecutionUnit::AGU => self.stats.agu_issue_blocked += 1,
+            ExecutionUnit::SIMD => self.stats.simd_issue_blocked += 1,
+            _ => {}
+        }
+    }
+
+    /// Enregistre un conflit structurel sur le port partagé ALU/SIMD
+    pub fn record_shared_port_conflict(&mut self) {
+        self.stats.shared_port_conflicts += 1;
+    }
+
     /// Obtient les statistiques d'exécution
     pub fn get_stats(&self) -> &ParallelExecutionStats {
         &self.stats
@@ -34,6 +34,39 @@ impl WritebackStage {
         Ok(())
     }
 
+    /// Écrit les résultats de toutes les instructions qui terminent dans le même cycle.
+    ///
+    /// `wb_regs` doit être fourni dans l'ordre du programme: en cas d'écritures
+    /// multiples dans un même registre, c'est l'instruction la plus récente
+    /// (la dernière de la liste) qui l'emporte. Un tel conflit ne devrait jamais
+    /// arriver car la logique d'émission sérialise les dépendances WAW; il est
+    /// signalé par une assertion en mode debug.
+    pub fn process_group(
+        &mut self,
+        wb_regs: &[MemoryWritebackRegister],
+        registers: &mut [u64],
+    ) -> Result<(), String> {
+        for (i, wb_reg) in wb_regs.iter().enumerate() {
+            if let Some(rd) = wb_reg.rd {
+                let conflict = wb_regs[..i].iter().find(|older| older.rd == Some(rd));
+                debug_assert!(
+                    conflict.is_none(),
+                    "Writeback: conflit d'écriture sur R{} dans le même cycle ({:?} puis {:?})",
+                    rd,
+                    conflict.map(|older| older.instruction.opcode),
+                    wb_reg.instruction.opcode
+                );
+            }
+        }
+
+        // Ordre programme: la dernière écriture gagne
+        for wb_reg in wb_regs {
+            self.process_direct(wb_reg, registers)?;
+        }
+
+        Ok(())
+    }
+
     /// Réinitialise l'étage Writeback
     pub fn reset(&mut self) {
         // self.reset();
@@ -73,7 +106,9 @@ mod tests {
 
         // Créer un registre Memory → Writeback
         let wb_reg = MemoryWritebackRegister {
+            id: 0,
             instruction: add_instruction,
+            pc: 0,
             result: 42,
             rd: Some(0), // Registre destination R0
         };
@@ -95,7 +130,9 @@ mod tests {
 
         // Créer un registre Memory → Writeback
         let wb_reg = MemoryWritebackRegister {
+            id: 0,
             instruction: add_instruction,
+            pc: 0,
             result: 123,
             rd: Some(2), // Registre destination R2
         };
@@ -121,7 +158,9 @@ mod tests {
             let add_instruction = Instruction::create_reg_reg(Opcode::Add, i as u8, 0);
 
             let wb_reg = MemoryWritebackRegister {
+                id: 0,
                 instruction: add_instruction,
+                pc: 0,
                 result: i as u64 * 10,
                 rd: Some(i),
             };
@@ -146,7 +185,9 @@ mod tests {
 
         // Créer un registre Memory → Writeback
         let wb_reg = MemoryWritebackRegister {
+            id: 0,
             instruction: add_instruction,
+            pc: 0,
             result: 42,
             rd: Some(0), // Registre destination R0
         };
@@ -170,7 +211,9 @@ mod tests {
             let add_instruction = Instruction::create_reg_reg(Opcode::Add, i as u8, 0);
 
             let wb_reg = MemoryWritebackRegister {
+                id: 0,
                 instruction: add_instruction,
+                pc: 0,
                 result: i as u64 * 10,
                 rd: Some(i), // i est déjà un usize ici
             };
@@ -201,7 +244,9 @@ mod tests {
         // Étape 1: ADD R2, R0, R1 (R2 = 5 + 10 = 15)
         let add_instruction = Instruction::create_reg_reg_reg(Opcode::Add, 2, 0, 1);
         let wb_reg_add = MemoryWritebackRegister {
+            id: 0,
             instruction: add_instruction,
+            pc: 0,
             result: 15, // Résultat calculé par l'étage Execute et passé par Memory
             rd: Some(2),
         };
@@ -212,7 +257,9 @@ mod tests {
         // Étape 2: SUB R3, R2, R0 (R3 = 15 - 5 = 10)
         let sub_instruction = Instruction::create_reg_reg_reg(Opcode::Sub, 3, 2, 0);
         let wb_reg_sub = MemoryWritebackRegister {
+            id: 0,
             instruction: sub_instruction,
+            pc: 0,
             result: 10,
             rd: Some(3),
         };
@@ -223,7 +270,9 @@ mod tests {
         // Étape 3: MUL R4, R3, R1 (R4 = 10 * 10 = 100)
         let mul_instruction = Instruction::create_reg_reg_reg(Opcode::Mul, 4, 3, 1);
         let wb_reg_mul = MemoryWritebackRegister {
+            id: 0,
             instruction: mul_instruction,
+            pc: 0,
             result: 100,
             rd: Some(4),
         };
@@ -248,7 +297,9 @@ mod tests {
         let cmp_instruction = Instruction::create_reg_reg(Opcode::Cmp, 0, 1);
 
         let wb_reg = MemoryWritebackRegister {
+            id: 0,
             instruction: cmp_instruction,
+            pc: 0,
             result: 42,
             rd: None, // Pas de registre destination
         };
@@ -272,7 +323,9 @@ mod tests {
         let add_instruction = Instruction::create_reg_reg(Opcode::Add, 0, 1);
 
         let wb_reg = MemoryWritebackRegister {
+            id: 0,
             instruction: add_instruction,
+            pc: 0,
             result: 42,
             rd: Some(100), // Registre destination invalide
         };
@@ -303,7 +356,9 @@ mod tests {
             let add_instruction = Instruction::create_reg_reg(Opcode::Add, i as u8, 0);
 
             let wb_reg = MemoryWritebackRegister {
+                id: 0,
                 instruction: add_instruction,
+                pc: 0,
                 result: value,
                 rd: Some(i), // i est un usize provenant d'enumerate()
             };
@@ -328,7 +383,9 @@ mod tests {
         // Format à trois registres: ADD R2, R0, R1
         let add_instruction = Instruction::create_reg_reg_reg(Opcode::Add, 2, 0, 1);
         let wb_reg_add = MemoryWritebackRegister {
+            id: 0,
             instruction: add_instruction,
+            pc: 0,
             result: 15, // 5 + 10
             rd: Some(2),
         };
@@ -339,7 +396,9 @@ mod tests {
         // Format à un registre: INC R2
         let inc_instruction = Instruction::create_single_reg(Opcode::Inc, 2);
         let wb_reg_inc = MemoryWritebackRegister {
+            id: 0,
             instruction: inc_instruction,
+            pc: 0,
             result: 16, // 15 + 1
             rd: Some(2),
         };
@@ -350,7 +409,9 @@ mod tests {
         // Format à deux registres: MOV R3, R2
         let mov_instruction = Instruction::create_reg_reg(Opcode::Mov, 3, 2);
         let wb_reg_mov = MemoryWritebackRegister {
+            id: 0,
             instruction: mov_instruction,
+            pc: 0,
             result: 16, // Valeur de R2
             rd: Some(3),
         };
@@ -362,4 +423,31 @@ mod tests {
         assert_eq!(registers[2], 16); // Valeur après INC
         assert_eq!(registers[3], 16); // Copie de R2
     }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "conflit d'écriture sur R3")]
+    fn test_writeback_group_same_register_conflict() {
+        let mut writeback = WritebackStage::new();
+        let mut registers = vec![0; 16];
+
+        let group = [
+            MemoryWritebackRegister {
+                id: 0,
+                instruction: Instruction::create_reg_reg(Opcode::Add, 3, 0),
+                pc: 0,
+                result: 1,
+                rd: Some(3),
+            },
+            MemoryWritebackRegister {
+                id: 0,
+                instruction: Instruction::create_reg_reg(Opcode::Sub, 3, 0),
+                pc: 0,
+                result: 2,
+                rd: Some(3),
+            },
+        ];
+
+        let _ = writeback.process_group(&group, &mut registers);
+    }
 }
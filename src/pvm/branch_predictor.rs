@@ -4,7 +4,124 @@ use std::collections::HashMap;
 use crate::pipeline::ras::ReturnAddressStack;
 use crate::pvm::branch_perceptor::Perceptron;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// Signature et version du format produit par `BranchPredictor::export_state`
+const PREDICTOR_STATE_MAGIC: &[u8; 4] = b"PKBP";
+const PREDICTOR_STATE_VERSION: u8 = 1;
+
+/// Ecriture little-endian de l'état d'un prédicteur
+#[derive(Default)]
+struct StateWriter {
+    bytes: Vec<u8>,
+}
+
+impl StateWriter {
+    fn u8(&mut self, v: u8) {
+        self.bytes.push(v);
+    }
+
+    fn u16(&mut self, v: u16) {
+        self.bytes.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn u32(&mut self, v: u32) {
+        self.bytes.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn u64(&mut self, v: u64) {
+        self.bytes.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn len(&mut self, len: usize) {
+        self.u32(len as u32);
+    }
+
+    fn weights(&mut self, values: &[isize]) {
+        self.len(values.len());
+        for &v in values {
+            self.u64(v as i64 as u64);
+        }
+    }
+
+    fn counters(&mut self, counters: &[TwoBitCounter]) {
+        self.len(counters.len());
+        for counter in counters {
+            self.u8(counter.state as u8);
+        }
+    }
+}
+
+/// Lecture séquentielle d'un état exporté, avec contrôle de taille
+struct StateReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> StateReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(n).filter(|&end| end <= self.data.len())
+            .ok_or_else(|| format!("Etat du prédicteur tronqué à l'octet {}", self.pos))?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, String> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    /// Lit une longueur et vérifie qu'elle correspond à la taille attendue
+    fn expect_len(&mut self, expected: usize, what: &str) -> Result<(), String> {
+        let len = self.u32()? as usize;
+        if len != expected {
+            return Err(format!("{}: {} entrées attendues, {} trouvées", what, expected, len));
+        }
+        Ok(())
+    }
+
+    fn weights(&mut self, target: &mut [isize], what: &str) -> Result<(), String> {
+        self.expect_len(target.len(), what)?;
+        for v in target.iter_mut() {
+            *v = self.u64()? as i64 as isize;
+        }
+        Ok(())
+    }
+
+    fn counters(&mut self, target: &mut [TwoBitCounter], what: &str) -> Result<(), String> {
+        self.expect_len(target.len(), what)?;
+        for counter in target.iter_mut() {
+            counter.state = TwoBitState::from_u8(self.u8()?)?;
+        }
+        Ok(())
+    }
+
+    fn finish(&self) -> Result<(), String> {
+        if self.pos != self.data.len() {
+            return Err(format!(
+                "Etat du prédicteur: {} octets inattendus en fin de données",
+                self.data.len() - self.pos
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PredictorType {
     Static,
     Dynamic,
@@ -32,11 +149,13 @@ struct GSharePredictor {
 
 impl GSharePredictor {
     pub fn new() -> Self {
-        let history_length = 12; // 12 bits d'historique global
-        let table_size = 1 << history_length; // 4096 entrées
+        // 8 bits d'historique suffisent aux motifs courts et chauffent plus vite que 12;
+        // la table reste à 4096 entrées, indexée par le PC complet
+        let history_length = 8;
+        let table_size = 4096;
         Self {
             global_history: 0,
-            pattern_table: vec![1; table_size], // Initialiser avec WeaklyNotTaken (1)
+            pattern_table: vec![2; table_size], // Initialiser avec WeaklyTaken (2), comme le Hybrid
             history_length,
         }
     }
@@ -68,9 +187,31 @@ impl GSharePredictor {
     
     fn compute_index(&self, branch_pc: u64) -> usize {
         // XOR entre les bits du PC et l'historique global
-        let pc_bits = (branch_pc as usize) & ((1 << self.history_length) - 1);
         let history_bits = self.global_history as usize;
-        (pc_bits ^ history_bits) & ((1 << self.history_length) - 1)
+        ((branch_pc as usize) ^ history_bits) % self.pattern_table.len()
+    }
+
+    /// Redimensionne la table de motifs à `entries` compteurs (table vidée)
+    fn resize(&mut self, entries: usize) {
+        self.pattern_table = vec![2; entries];
+        self.global_history = 0;
+    }
+
+    fn write_state(&self, out: &mut StateWriter) {
+        out.u16(self.global_history);
+        out.len(self.pattern_table.len());
+        out.bytes.extend_from_slice(&self.pattern_table);
+    }
+
+    fn read_state(&mut self, input: &mut StateReader) -> Result<(), String> {
+        self.global_history = input.u16()?;
+        input.expect_len(self.pattern_table.len(), "Table GShare")?;
+        let table = input.take(self.pattern_table.len())?;
+        if let Some(&bad) = table.iter().find(|&&c| c > 3) {
+            return Err(format!("Compteur GShare invalide: {}", bad));
+        }
+        self.pattern_table.copy_from_slice(table);
+        Ok(())
     }
 }
 
@@ -118,7 +259,12 @@ pub struct BranchPredictor {
     pub metrics: BranchMetrics,
     pub hybrid_predictor: Option<HybridPredictor>,
     pub overriding_predictor: Option<OverridingPredictor>,
+    gshare_predictor: Option<GSharePredictor>,
     pub btb: Option<BranchTargetBuffer>,
+    /// Nombre d'entrées de la table 2 bits du prédicteur Dynamic (None = une entrée par PC)
+    table_entries: Option<usize>,
+    /// Dernier PC ayant entraîné chaque entrée de la table 2 bits (détection d'aliasing)
+    entry_owners: HashMap<u64, u64>,
 }
 #[derive(Debug, Default, Clone)]
 pub struct BranchMetrics {
@@ -138,6 +284,8 @@ pub struct BranchMetrics {
     pub override_count: usize,
     pub override_benefit: usize,
     pub agreement_rate: f64,
+    /// Mises à jour d'une entrée de la table entraînée en dernier par un autre branchement
+    pub aliasing_conflicts: usize,
 }
 
 #[derive(Debug, Clone, )]
@@ -152,6 +300,8 @@ struct LocalHistoryEntry {
 pub struct HybridPredictor {
     // Prédicteur local (pattern par PC)
     local_history: HashMap<u64, LocalHistoryEntry>,
+    // Nombre d'historiques locaux (None = un par branchement)
+    local_entries: Option<usize>,
 
     // Prédicteur global (GShare)
     global_history: u16,  // 16 bits d'historique global
@@ -209,6 +359,18 @@ pub struct OverridingPredictor {
     pub agreement_incorrect: u64,
 }
 
+impl TwoBitState {
+    fn from_u8(value: u8) -> Result<Self, String> {
+        match value {
+            0 => Ok(TwoBitState::StronglyNotTaken),
+            1 => Ok(TwoBitState::WeaklyNotTaken),
+            2 => Ok(TwoBitState::WeaklyTaken),
+            3 => Ok(TwoBitState::StronglyTaken),
+            _ => Err(format!("Etat de compteur 2 bits invalide: {}", value)),
+        }
+    }
+}
+
 impl TwoBitCounter {
     pub fn new() -> Self {
         Self {
@@ -266,6 +428,7 @@ impl HybridPredictor {
         
         Self {
             local_history: HashMap::new(),
+            local_entries: None,
             global_history: 0,
             gshare_table,
             selector,
@@ -282,7 +445,7 @@ impl HybridPredictor {
         let gshare_prediction = self.predict_gshare(pc);
         
         // Use selector to choose between local and global
-        let selector_index = (pc & 0x3FF) as usize; // 10 bits
+        let selector_index = self.selector_index(pc);
         let selector_prediction = self.selector[selector_index].predict();
         
         match selector_prediction {
@@ -291,8 +454,28 @@ impl HybridPredictor {
         }
     }
     
+    fn selector_index(&self, pc: u64) -> usize {
+        pc as usize % self.selector.len() // 10 bits avec le sélecteur par défaut
+    }
+
+    /// Clé de l'historique local du branchement situé à `pc`
+    fn local_key(&self, pc: u64) -> u64 {
+        self.local_entries.map_or(pc, |entries| pc % entries as u64)
+    }
+
+    /// Ramène la table GShare, le sélecteur et les historiques locaux à `entries`
+    /// entrées chacun (tables vidées)
+    fn resize(&mut self, entries: usize) {
+        self.gshare_table = vec![TwoBitCounter::new_biased(TwoBitState::WeaklyTaken); entries];
+        self.gshare_table_size = entries;
+        self.selector = vec![TwoBitCounter::new_biased(TwoBitState::WeaklyTaken); entries];
+        self.local_history.clear();
+        self.local_entries = Some(entries);
+        self.global_history = 0;
+    }
+
     fn predict_local(&self, pc: u64) -> BranchPrediction {
-        if let Some(entry) = self.local_history.get(&pc) {
+        if let Some(entry) = self.local_history.get(&self.local_key(pc)) {
             let pattern_index = entry.history as usize & ((1 << self.local_history_bits) - 1);
             if pattern_index < entry.pattern_table.len() {
                 entry.pattern_table[pattern_index].predict()
@@ -312,7 +495,7 @@ impl HybridPredictor {
     fn compute_gshare_index(&self, pc: u64) -> usize {
         let pc_bits = pc as usize & ((1 << self.global_history_bits) - 1);
         let history_bits = self.global_history as usize;
-        (pc_bits ^ history_bits) & (self.gshare_table_size - 1)
+        (pc_bits ^ history_bits) % self.gshare_table_size
     }
     
     pub fn update(&mut self, pc: u64, taken: bool) {
@@ -326,7 +509,7 @@ impl HybridPredictor {
         self.update_gshare(pc, taken);
         
         // Update selector based on which predictor was more accurate
-        let selector_index = (pc & 0x3FF) as usize;
+        let selector_index = self.selector_index(pc);
         let local_correct = (local_prediction == BranchPrediction::Taken) == taken;
         let gshare_correct = (gshare_prediction == BranchPrediction::Taken) == taken;
         
@@ -343,7 +526,8 @@ impl HybridPredictor {
     }
     
     fn update_local(&mut self, pc: u64, taken: bool) {
-        let entry = self.local_history.entry(pc).or_insert_with(|| {
+        let key = self.local_key(pc);
+        let entry = self.local_history.entry(key).or_insert_with(|| {
             let mut pattern_table = Vec::with_capacity(1 << self.local_history_bits);
             for _ in 0..(1 << self.local_history_bits) {
                 pattern_table.push(TwoBitCounter::new_biased(TwoBitState::WeaklyTaken));
@@ -369,6 +553,42 @@ impl HybridPredictor {
         let index = self.compute_gshare_index(pc);
         self.gshare_table[index].update(taken);
     }
+
+    fn write_state(&self, out: &mut StateWriter) {
+        out.u16(self.global_history);
+        out.counters(&self.gshare_table);
+        out.counters(&self.selector);
+
+        // Tri par PC pour un export déterministe
+        let mut pcs: Vec<&u64> = self.local_history.keys().collect();
+        pcs.sort();
+        out.len(pcs.len());
+        for pc in pcs {
+            let entry = &self.local_history[pc];
+            out.u64(*pc);
+            out.u16(entry.history);
+            out.u64(entry.last_used);
+            out.counters(&entry.pattern_table);
+        }
+    }
+
+    fn read_state(&mut self, input: &mut StateReader) -> Result<(), String> {
+        self.global_history = input.u16()?;
+        input.counters(&mut self.gshare_table, "Table GShare hybride")?;
+        input.counters(&mut self.selector, "Sélecteur hybride")?;
+
+        self.local_history.clear();
+        let count = input.u32()?;
+        for _ in 0..count {
+            let pc = input.u64()?;
+            let history = input.u16()?;
+            let last_used = input.u64()?;
+            let mut pattern_table = vec![TwoBitCounter::new(); 1 << self.local_history_bits];
+            input.counters(&mut pattern_table, "Historique local hybride")?;
+            self.local_history.insert(pc, LocalHistoryEntry { history, pattern_table, last_used });
+        }
+        Ok(())
+    }
 }
 
 impl BranchPredictor {
@@ -389,7 +609,13 @@ impl BranchPredictor {
             None
         };
         
-        let btb = if matches!(predictor_type, PredictorType::Hybrid | PredictorType::Dynamic | PredictorType::Perceptron) {
+        let gshare_predictor = if predictor_type == PredictorType::GShare {
+            Some(GSharePredictor::new())
+        } else {
+            None
+        };
+
+        let btb = if matches!(predictor_type, PredictorType::Hybrid | PredictorType::Dynamic | PredictorType::GShare | PredictorType::Perceptron) {
             Some(BranchTargetBuffer::new(512)) // 512 entries BTB
         } else {
             None
@@ -402,7 +628,10 @@ impl BranchPredictor {
             metrics: BranchMetrics::default(),
             hybrid_predictor,
             overriding_predictor,
+            gshare_predictor,
             btb,
+            table_entries: None,
+            entry_owners: HashMap::new(),
         }
     }
     
@@ -423,7 +652,13 @@ impl BranchPredictor {
             None
         };
         
-        let btb = if matches!(predictor_type, PredictorType::Hybrid | PredictorType::Dynamic | PredictorType::Perceptron) {
+        let gshare_predictor = if predictor_type == PredictorType::GShare {
+            Some(GSharePredictor::new())
+        } else {
+            None
+        };
+
+        let btb = if matches!(predictor_type, PredictorType::Hybrid | PredictorType::Dynamic | PredictorType::GShare | PredictorType::Perceptron) {
             Some(BranchTargetBuffer::new(config.btb_size))
         } else {
             None
@@ -436,8 +671,38 @@ impl BranchPredictor {
             metrics: BranchMetrics::default(),
             hybrid_predictor,
             overriding_predictor,
+            gshare_predictor,
             btb,
+            table_entries: None,
+            entry_owners: HashMap::new(),
+        }
+    }
+
+    /// Limite chaque table du prédicteur à `entries` entrées indexées modulo `entries`:
+    /// table 2 bits du Dynamic, table GShare, tables GShare/sélecteur/historiques locaux
+    /// du Hybrid, tables GShare et perceptrons du Perceptron. Des branchements distincts
+    /// peuvent alors partager (et se disputer) une entrée. Static, Gskew et Tournament
+    /// n'ont pas de table. Vide les tables apprises.
+    pub fn set_table_entries(&mut self, entries: usize) {
+        let entries = entries.max(1);
+        self.table_entries = Some(entries);
+        self.two_bit_states.clear();
+        self.entry_owners.clear();
+        if let Some(ref mut gshare) = self.gshare_predictor {
+            gshare.resize(entries);
+        }
+        if let Some(ref mut hybrid) = self.hybrid_predictor {
+            hybrid.resize(entries);
         }
+        if let Some(ref mut overriding) = self.overriding_predictor {
+            overriding.gshare_predictor.resize(entries);
+            overriding.perceptron_predictor.resize(entries);
+        }
+    }
+
+    /// Entrée de la table 2 bits utilisée par le branchement situé à `pc`
+    fn table_index(&self, pc: u64) -> u64 {
+        self.table_entries.map_or(pc, |entries| pc % entries as u64)
     }
 
     pub fn predict_target(&mut self, pc: u64) -> Option<u32> {
@@ -464,9 +729,10 @@ impl BranchPredictor {
             }
             PredictorType::Dynamic => {
                 // Lire l'état 2 bits ou init par défaut
+                let index = self.table_index(pc);
                 let state = self
                     .two_bit_states
-                    .entry(pc)
+                    .entry(index)
                     .or_insert(TwoBitState::WeaklyNotTaken);
 
                 match state {
@@ -479,9 +745,10 @@ impl BranchPredictor {
                 }
             }
             PredictorType::GShare => {
-                // GSharePredictor
-                BranchPrediction::NotTaken
-
+                match self.gshare_predictor {
+                    Some(ref gshare) if gshare.predict_branch(pc) => BranchPrediction::Taken,
+                    _ => BranchPrediction::NotTaken,
+                }
             }
             PredictorType::Gskew => {
                 // GSkewPredictor
@@ -555,15 +822,26 @@ impl BranchPredictor {
 
         // Mise à jour du prédicteur dynamique
         if self.prediction_type == PredictorType::Dynamic {
-            let old_state = self.two_bit_states.get(&pc).cloned();
-            self.update_dynamic(pc, taken);
-            let new_state = self.two_bit_states.get(&pc).cloned();
+            let index = self.table_index(pc);
+            if self.entry_owners.insert(index, pc).is_some_and(|owner| owner != pc) {
+                self.metrics.aliasing_conflicts += 1;
+            }
+            let old_state = self.two_bit_states.get(&index).cloned();
+            self.update_dynamic(index, taken);
+            let new_state = self.two_bit_states.get(&index).cloned();
             println!(
                 "Branch state update: PC={:X}, {:?} -> {:?}",
                 pc, old_state, new_state
             );
         }
         
+        // Mise à jour du prédicteur GShare (historique global XOR PC)
+        if self.prediction_type == PredictorType::GShare {
+            if let Some(ref mut gshare) = self.gshare_predictor {
+                gshare.update_predictor(pc, taken);
+            }
+        }
+
         // Mise à jour du prédicteur hybride
         if self.prediction_type == PredictorType::Hybrid {
             if let Some(ref mut hybrid) = self.hybrid_predictor {
@@ -656,6 +934,114 @@ impl BranchPredictor {
         }
     }
 
+    /// Exporte l'état appris (tables de prédiction, historiques, BTB) pour un démarrage à chaud.
+    /// Les métriques ne sont pas incluses.
+    pub fn export_state(&self) -> Vec<u8> {
+        let mut out = StateWriter::default();
+        out.bytes.extend_from_slice(PREDICTOR_STATE_MAGIC);
+        out.u8(PREDICTOR_STATE_VERSION);
+        out.u8(self.prediction_type as u8);
+
+        match self.prediction_type {
+            PredictorType::Dynamic => {
+                let mut entries: Vec<(&u64, &TwoBitState)> = self.two_bit_states.iter().collect();
+                entries.sort_by_key(|(pc, _)| **pc);
+                out.len(entries.len());
+                for (pc, state) in entries {
+                    out.u64(*pc);
+                    out.u8(*state as u8);
+                }
+            }
+            PredictorType::GShare => {
+                if let Some(ref gshare) = self.gshare_predictor {
+                    gshare.write_state(&mut out);
+                }
+            }
+            PredictorType::Hybrid => {
+                if let Some(ref hybrid) = self.hybrid_predictor {
+                    hybrid.write_state(&mut out);
+                }
+            }
+            PredictorType::Perceptron => {
+                if let Some(ref overriding) = self.overriding_predictor {
+                    overriding.gshare_predictor.write_state(&mut out);
+                    overriding.perceptron_predictor.write_state(&mut out);
+                }
+            }
+            // Prédicteurs sans état
+            PredictorType::Static | PredictorType::Gskew | PredictorType::Tournament => {}
+        }
+
+        match self.btb {
+            Some(ref btb) => {
+                out.u8(1);
+                btb.write_state(&mut out);
+            }
+            None => out.u8(0),
+        }
+
+        out.bytes
+    }
+
+    /// Recharge un état produit par `export_state` sur un prédicteur du même type
+    /// et de même configuration. En cas d'erreur, le prédicteur peut être partiellement modifié.
+    pub fn import_state(&mut self, data: &[u8]) -> Result<(), String> {
+        let mut input = StateReader::new(data);
+        if input.take(PREDICTOR_STATE_MAGIC.len())? != PREDICTOR_STATE_MAGIC {
+            return Err("Etat du prédicteur: signature invalide".to_string());
+        }
+        let version = input.u8()?;
+        if version != PREDICTOR_STATE_VERSION {
+            return Err(format!("Etat du prédicteur: version {} non supportée", version));
+        }
+        let kind = input.u8()?;
+        if kind != self.prediction_type as u8 {
+            return Err(format!(
+                "Etat du prédicteur: type {} incompatible avec {:?}",
+                kind, self.prediction_type
+            ));
+        }
+
+        match self.prediction_type {
+            PredictorType::Dynamic => {
+                self.two_bit_states.clear();
+                let count = input.u32()?;
+                for _ in 0..count {
+                    let pc = input.u64()?;
+                    let state = TwoBitState::from_u8(input.u8()?)?;
+                    self.two_bit_states.insert(pc, state);
+                }
+            }
+            PredictorType::GShare => {
+                if let Some(ref mut gshare) = self.gshare_predictor {
+                    gshare.read_state(&mut input)?;
+                }
+            }
+            PredictorType::Hybrid => {
+                if let Some(ref mut hybrid) = self.hybrid_predictor {
+                    hybrid.read_state(&mut input)?;
+                }
+            }
+            PredictorType::Perceptron => {
+                if let Some(ref mut overriding) = self.overriding_predictor {
+                    overriding.gshare_predictor.read_state(&mut input)?;
+                    overriding.perceptron_predictor.read_state(&mut input)?;
+                    overriding.perceptron_predictions_cache.clear();
+                }
+            }
+            PredictorType::Static | PredictorType::Gskew | PredictorType::Tournament => {}
+        }
+
+        let has_btb = input.u8()? != 0;
+        match (has_btb, self.btb.as_mut()) {
+            (true, Some(btb)) => btb.read_state(&mut input)?,
+            (false, None) => {}
+            _ => return Err("Etat du prédicteur: présence du BTB incohérente".to_string()),
+        }
+
+        input.finish()
+    }
+
 }
 
 
@@ -760,6 +1146,31 @@ impl BranchTargetBuffer {
             entry.valid = false;
         }
     }
+
+    fn write_state(&self, out: &mut StateWriter) {
+        out.u64(self.current_cycle);
+        out.len(self.entries.len());
+        for entry in &self.entries {
+            out.u32(entry.tag);
+            out.u32(entry.target);
+            out.u8(entry.valid as u8);
+            out.u8(entry.confidence);
+            out.u64(entry.last_used);
+        }
+    }
+
+    fn read_state(&mut self, input: &mut StateReader) -> Result<(), String> {
+        self.current_cycle = input.u64()?;
+        input.expect_len(self.entries.len(), "BTB")?;
+        for entry in self.entries.iter_mut() {
+            entry.tag = input.u32()?;
+            entry.target = input.u32()?;
+            entry.valid = input.u8()? != 0;
+            entry.confidence = input.u8()?;
+            entry.last_used = input.u64()?;
+        }
+        Ok(())
+    }
 }
 
 
@@ -782,7 +1193,14 @@ impl PerceptronPredictor {
     // Sélectionne l'indice du perceptron basé sur le PC de branche
     // Une fonction de hachage plus sophistiquée peut être utilisée ici (PC XOR GHR, etc.)
     fn get_perceptron_index(&self, branch_pc: u64) -> usize {
-        (branch_pc as usize) % crate::pvm::branch_perceptor::NUM_PERCEPTRONS
+        (branch_pc as usize) % self.perceptrons.len()
+    }
+
+    /// Ramène la table à `entries` perceptrons (poids remis à zéro)
+    fn resize(&mut self, entries: usize) {
+        self.perceptrons = (0..entries)
+            .map(|_| Perceptron::new(crate::pvm::branch_perceptor::TOTAL_HISTORY_LENGTH))
+            .collect();
     }
 
     // Génère l'historique combiné (global + local) pour les entrées du perceptron
@@ -841,6 +1259,40 @@ impl PerceptronPredictor {
     }
 
     // Réinitialisation de l'état du prédicteur
+    fn write_state(&self, out: &mut StateWriter) {
+        out.len(self.perceptrons.len());
+        for perceptron in &self.perceptrons {
+            out.weights(&perceptron.weight);
+        }
+        out.weights(&self.global_history);
+
+        let mut pcs: Vec<&u64> = self.local_histories.keys().collect();
+        pcs.sort();
+        out.len(pcs.len());
+        for pc in pcs {
+            out.u64(*pc);
+            out.weights(&self.local_histories[pc]);
+        }
+    }
+
+    fn read_state(&mut self, input: &mut StateReader) -> Result<(), String> {
+        input.expect_len(self.perceptrons.len(), "Perceptrons")?;
+        for perceptron in self.perceptrons.iter_mut() {
+            input.weights(&mut perceptron.weight, "Poids du perceptron")?;
+        }
+        input.weights(&mut self.global_history, "Historique global du perceptron")?;
+
+        self.local_histories.clear();
+        let count = input.u32()?;
+        for _ in 0..count {
+            let pc = input.u64()?;
+            let mut history = vec![0; crate::pvm::branch_perceptor::LOCAL_HISTORY_LENGTH];
+            input.weights(&mut history, "Historique local du perceptron")?;
+            self.local_histories.insert(pc, history);
+        }
+        Ok(())
+    }
+
     pub fn reset(&mut self) {
         for perceptron in &mut self.perceptrons {
             perceptron.weight.fill(0); // Réinitialiser les poids à 0
@@ -1343,4 +1795,47 @@ mod tests {
             predictor.two_bit_states.get(&branch1)
         );
     }
+
+    #[test]
+    fn test_export_import_state_round_trip() {
+        let kinds = [
+            PredictorType::Dynamic,
+            PredictorType::GShare,
+            PredictorType::Hybrid,
+            PredictorType::Perceptron,
+        ];
+        let pcs = [0x10u64, 0x24, 0x38];
+
+        for kind in kinds {
+            let mut trained = BranchPredictor::new(kind);
+            for i in 0..40 {
+                for (n, &pc) in pcs.iter().enumerate() {
+                    let taken = (i + n) % 3 != 0;
+                    let prediction = trained.predict(pc);
+                    trained.update(pc, taken, prediction);
+                    trained.update_btb(pc, pc as u32 + 0x100, None);
+                }
+            }
+            let state = trained.export_state();
+
+            let mut restored = BranchPredictor::new(kind);
+            restored.import_state(&state).unwrap();
+            assert_eq!(restored.export_state(), state, "{:?}", kind);
+            for &pc in &pcs {
+                assert_eq!(restored.predict(pc), trained.predict(pc), "{:?} pc=0x{:X}", kind, pc);
+            }
+        }
+    }
+
+    #[test]
+    fn test_import_state_rejects_mismatched_predictor() {
+        let state = BranchPredictor::new(PredictorType::GShare).export_state();
+
+        let mut hybrid = BranchPredictor::new(PredictorType::Hybrid);
+        assert!(hybrid.import_state(&state).is_err());
+
+        let mut gshare = BranchPredictor::new(PredictorType::GShare);
+        assert!(gshare.import_state(&state[..state.len() - 1]).is_err());
+        assert!(gshare.import_state(b"JUNK").is_err());
+    }
 }
@@ -1,9 +1,11 @@
 // //src/pvm/buffers.rs
 
 /// Store buffer pour les écritures mémoire
+#[derive(Debug, Clone)]
 pub struct StoreBuffer {
     capacity: usize,         // Taille maximale du buffer
-    entries: Vec<(u32, u8)>, // Entrées du buffer (adresse -> valeur)
+    entries: Vec<(u32, u8)>, // Entrées du buffer (adresse -> valeur), dans l'ordre du programme
+    drain_rate: usize,       // Entrées vidées vers le cache par cycle (0 = éviction immédiate)
 }
 
 impl StoreBuffer {
@@ -12,11 +14,29 @@ impl StoreBuffer {
         Self {
             capacity,
             entries: Vec::with_capacity(capacity),
+            drain_rate: 0,
         }
     }
 
-    /// Ajoute une entrée au store buffer
-    pub fn add(&mut self, addr: u32, value: u8) {
+    /// Crée un store buffer vidé dans l'ordre du programme à `drain_rate` entrées par cycle
+    pub fn with_drain_rate(capacity: usize, drain_rate: usize) -> Self {
+        Self {
+            drain_rate,
+            ..Self::new(capacity)
+        }
+    }
+
+    /// Ajoute une entrée au store buffer et retourne le nombre de cycles d'attente:
+    /// avec un débit de vidange, une écriture qui trouve le buffer plein attend qu'un
+    /// cycle de vidange libère de la place
+    pub fn add(&mut self, addr: u32, value: u8) -> usize {
+        // Capacité nulle: store buffer désactivé, rien à mettre en attente
+        if self.capacity == 0 {
+            return 0;
+        }
+
+        let mut waited = 0;
+
         // Vérifier si l'adresse est déjà dans le buffer
         if let Some(idx) = self.entries.iter().position(|&(a, _)| a == addr) {
             // Remplacer la valeur existante
@@ -24,12 +44,53 @@ impl StoreBuffer {
         } else {
             // Si le buffer est plein, vider la plus ancienne entrée
             if self.entries.len() >= self.capacity {
-                self.entries.remove(0);
+                if self.drain_rate == 0 {
+                    self.entries.remove(0);
+                } else {
+                    self.drain_cycle();
+                    waited = 1;
+                }
             }
 
             // Ajouter la nouvelle entrée
             self.entries.push((addr, value));
         }
+        waited
+    }
+
+    /// Un cycle de vidange: retire les `drain_rate` entrées les plus anciennes
+    /// (ordre du programme) et retourne leur nombre
+    pub fn drain_cycle(&mut self) -> usize {
+        let count = self.drain_rate.min(self.entries.len());
+        self.entries.drain(..count);
+        count
+    }
+
+    /// Vide les entrées dans l'ordre jusqu'à la plus récente touchant
+    /// `[addr, addr + len)` et retourne le nombre de cycles de vidange nécessaires
+    pub fn drain_through(&mut self, addr: u32, len: usize) -> usize {
+        let end = addr as u64 + len as u64;
+        let Some(last) = self
+            .entries
+            .iter()
+            .rposition(|&(a, _)| (a as u64) >= addr as u64 && (a as u64) < end)
+        else {
+            return 0;
+        };
+        let rate = self.drain_rate.max(1);
+        let cycles = (last + 1).div_ceil(rate);
+        self.entries.drain(..(cycles * rate).min(self.entries.len()));
+        cycles
+    }
+
+    /// Débit de vidange configuré (entrées par cycle, 0 si désactivé)
+    pub fn drain_rate(&self) -> usize {
+        self.drain_rate
+    }
+
+    /// Vrai si une nouvelle adresse ne trouverait pas de place
+    pub fn is_full(&self) -> bool {
+        self.capacity > 0 && self.entries.len() >= self.capacity
     }
 
     pub fn lookup_byte(&self, addr: u32) -> Option<u8> {
@@ -57,10 +118,38 @@ impl StoreBuffer {
         self.entries.clear();
     }
 
+    /// Retire et retourne toutes les entrées, dans l'ordre d'arrivée
+    pub fn drain(&mut self) -> Vec<(u32, u8)> {
+        std::mem::take(&mut self.entries)
+    }
+
+    /// Applique les entrées du buffer sur une copie de la mémoire sans les vider
+    pub fn apply_to(&self, memory: &mut [u8]) {
+        for (addr, value) in &self.entries {
+            if (*addr as usize) < memory.len() {
+                memory[*addr as usize] = *value;
+            }
+        }
+    }
+
+    /// Retire l'entrée en attente pour cette adresse (écrasée hors du chemin normal)
+    pub fn discard(&mut self, addr: u32) {
+        self.entries.retain(|&(a, _)| a != addr);
+    }
+
     /// Nettoie le store buffer
     pub fn clear(&mut self) {
         self.entries.clear();
     }
+
+    /// Nombre d'écritures encore en attente
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
 }
 
 #[cfg(test)]
@@ -128,6 +217,23 @@ mod tests {
         assert_eq!(buffer.lookup_byte(0x103), Some(45));
     }
 
+    #[test]
+    fn test_store_buffer_drains_in_program_order() {
+        let mut buffer = StoreBuffer::with_drain_rate(3, 2);
+        assert_eq!(buffer.add(0x100, 1), 0);
+        assert_eq!(buffer.add(0x101, 2), 0);
+        assert_eq!(buffer.add(0x102, 3), 0);
+        assert!(buffer.is_full());
+
+        // Buffer plein: l'écriture attend un cycle, qui vide les deux plus anciennes
+        assert_eq!(buffer.add(0x103, 4), 1);
+        assert_eq!(buffer.entries, vec![(0x102, 3), (0x103, 4)]);
+
+        // Attendre 0x103 impose de vider aussi 0x102, plus ancienne
+        assert_eq!(buffer.drain_through(0x103, 1), 1);
+        assert!(buffer.is_empty());
+    }
+
     #[test]
     fn test_store_buffer_has_address() {
         let mut buffer = StoreBuffer::new(4);
@@ -9,7 +9,7 @@ pub enum WritePolicy {
     WriteBack,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ReplacementPolicy {
     LRU,
     FIFO,
@@ -1,7 +1,6 @@
 // //src/pvm/caches.rs
 
-use std::collections::HashMap;
-use rand::Rng;
+use std::collections::{HashMap, HashSet};
 use crate::pvm::cache_configs::{CacheConfig, ReplacementPolicy, WritePolicy};
 use crate::pvm::cache_stats::CacheStatistics;
 use crate::pvm::vm_errors::{VMError, VMResult};
@@ -114,6 +113,24 @@ impl SimplePrefetcher {
         
         addrs
     }
+
+    /// Ligne à précharger pour un flux de stride: celle de l'adresse prédite, ou la ligne
+    /// voisine dans le sens du stride si l'adresse prédite reste dans la ligne courante
+    pub fn get_stride_prefetch_address(&self, current: u32, predicted: u32) -> Option<u32> {
+        if !self.enabled {
+            return None;
+        }
+        let line_size = DEFAULT_LINE_SIZE as u32;
+        let current_line = current & !(line_size - 1);
+        let predicted_line = predicted & !(line_size - 1);
+        if predicted_line != current_line {
+            Some(predicted_line)
+        } else if predicted > current {
+            current_line.checked_add(line_size)
+        } else {
+            current_line.checked_sub(line_size)
+        }
+    }
 }
 
 /// Write Buffer entre L1 et L2
@@ -167,6 +184,9 @@ pub struct CacheHierarchy {
     pub write_buffer: WriteBuffer,
     pub mshr: MSHR,
     pub prefetcher: SimplePrefetcher,
+    /// Lignes préchargées dans le L2 pas encore demandées
+    pub prefetched_l2_lines: HashSet<u32>,
+    pub l1_latency: u32,
     pub l2_latency: u32,
     pub memory_latency: u32,
 }
@@ -187,54 +207,73 @@ impl CacheHierarchy {
             write_buffer: WriteBuffer::new(16),
             mshr: MSHR::new(8),
             prefetcher: SimplePrefetcher::new(true, 2),
+            prefetched_l2_lines: HashSet::new(),
+            l1_latency: 1,
             l2_latency: 12,
             memory_latency: 100,
         }
     }
     
-    /// Accès byte simple - conversion automatique en accès u64 aligné
+    /// Fixe la graine de la politique Random de chaque niveau (dérivée pour que
+    /// les niveaux ne tirent pas la même séquence)
+    pub fn set_rng_seed(&mut self, seed: u64) {
+        self.l1_data.set_rng_seed(seed);
+        self.l1_inst.set_rng_seed(seed.rotate_left(21));
+        self.l2_unified.set_rng_seed(seed.rotate_left(42));
+    }
+
+    /// Précharge dans le L2 la ligne contenant `addr` si elle est absente.
+    /// Retourne vrai si une ligne a été installée.
+    pub fn prefetch_into_l2(&mut self, addr: u32) -> bool {
+        let line_addr = addr & !(self.l2_unified.get_line_size() as u32 - 1);
+        if self.l2_unified.contains(line_addr) || self.l2_unified.install_line(line_addr).is_err() {
+            return false;
+        }
+        self.prefetched_l2_lines.insert(line_addr);
+        true
+    }
+
+    /// Retire la marque de prefetch de la ligne contenant `addr`; vrai si la ligne
+    /// avait été préchargée et n'avait pas encore servi
+    pub fn consume_l2_prefetch(&mut self, addr: u32) -> bool {
+        let line_addr = addr & !(self.l2_unified.get_line_size() as u32 - 1);
+        self.prefetched_l2_lines.remove(&line_addr)
+    }
+
+    /// Vrai si chaque écriture traverse L1 et L2 jusqu'à la mémoire principale
+    pub fn writes_through_to_memory(&self) -> bool {
+        self.l1_data.config.write_policy == WritePolicy::WriteThrough
+            && self.l2_unified.config.write_policy == WritePolicy::WriteThrough
+    }
+
+    /// Accès à un octet: la donnée retournée (ou écrite) est celle de l'octet `addr`
+    /// dans sa ligne de cache
     pub fn access_byte(&mut self, addr: u32, is_write: bool, write_data: Option<u8>) -> VMResult<CacheAccessResult> {
-        // Aligner l'adresse sur u64 (8 bytes)
-        let aligned_addr = addr & !7;
-        let byte_offset = (addr & 7) as usize;
-        
         if is_write {
             if let Some(byte_value) = write_data {
-                // Pour une écriture, on doit d'abord lire le u64, modifier le byte, puis réécrire
-                match self.access_data(aligned_addr, false, None) {
-                    Ok(CacheAccessResult::Hit(mut data)) | Ok(CacheAccessResult::L2Hit(mut data)) => {
-                        // Modifier le byte dans le u64
-                        let mut bytes = data.to_le_bytes();
-                        bytes[byte_offset] = byte_value;
-                        data = u64::from_le_bytes(bytes);
-                        
-                        // Réécrire le u64 modifié
-                        self.access_data(aligned_addr, true, Some(data))
-                    }
-                    Ok(CacheAccessResult::Miss) | Ok(CacheAccessResult::MSHRPending) => {
-                        // Miss - créer un nouveau u64 avec le byte
-                        let mut bytes = [0u8; 8];
-                        bytes[byte_offset] = byte_value;
-                        let data = u64::from_le_bytes(bytes);
-                        self.access_data(aligned_addr, true, Some(data))
-                    }
+                // Pour une écriture, on lit d'abord la ligne (allocation), puis on écrit l'octet
+                match self.access_data(addr, false, None) {
+                    Ok(_) => self.access_data(addr, true, Some(byte_value as u64)),
                     Err(e) => Err(e),
                 }
             } else {
                 Err(VMError::memory_error("Write without data"))
             }
         } else {
-            // Lecture
-            match self.access_data(aligned_addr, false, None) {
-                Ok(CacheAccessResult::Hit(data)) => {
-                    let bytes = data.to_le_bytes();
-                    Ok(CacheAccessResult::Hit(bytes[byte_offset] as u64))
-                }
-                Ok(CacheAccessResult::L2Hit(data)) => {
-                    let bytes = data.to_le_bytes();
-                    Ok(CacheAccessResult::L2Hit(bytes[byte_offset] as u64))
-                }
-                other => other,
+            self.access_data(addr, false, None)
+        }
+    }
+
+    /// Recopie le contenu mémoire des lignes data (L1 et L2) contenant `addr` si elles
+    /// sont présentes: une ligne allouée par une écriture ou un remplissage partiel
+    /// porte ainsi les mêmes octets que la mémoire principale.
+    /// `read_line(base, taille)` lit la ligne en mémoire.
+    pub fn refresh_data_line(&mut self, addr: u32, read_line: impl Fn(u32, usize) -> Vec<u8>) {
+        for cache in [&mut self.l1_data, &mut self.l2_unified] {
+            let line_size = cache.get_line_size();
+            let base = addr & !(line_size as u32 - 1);
+            if cache.contains(base) {
+                cache.refresh_line(base, &read_line(base, line_size));
             }
         }
     }
@@ -250,7 +289,12 @@ impl CacheHierarchy {
             if let Some(data) = write_data {
                 // Convertir u64 en u8 pour l'écriture dans le cache
                 let data_u8 = data as u8;
-                self.l1_data.write(addr, data_u8).map(|_| CacheAccessResult::Hit(data))
+                let result = self.l1_data.write(addr, data_u8).map(|_| CacheAccessResult::Hit(data));
+                // L1 write-through: le L2 reçoit la même écriture immédiatement
+                if result.is_ok() && self.l1_data.config.write_policy == WritePolicy::WriteThrough {
+                    self.l2_unified.write(addr, data_u8)?;
+                }
+                result
             } else {
                 Err(VMError::memory_error("Write without data"))
             }
@@ -334,6 +378,24 @@ impl CacheHierarchy {
         Ok(())
     }
 
+    /// Vide les L1 data et instruction: les lignes sales du L1 data sont réécrites dans le L2
+    pub fn flush_l1(&mut self) -> VMResult<()> {
+        for (line_addr, data) in self.l1_data.flush() {
+            for (offset, &byte) in data.iter().enumerate() {
+                self.l2_unified.write(line_addr + offset as u32, byte)?;
+            }
+        }
+        self.l1_inst.flush();
+        Ok(())
+    }
+
+    /// Vide le L2: les lignes sales sont comptées comme write-backs vers la mémoire principale,
+    /// qui contient déjà leurs données (chaque écriture l'atteint aussi)
+    pub fn flush_l2(&mut self) {
+        self.l2_unified.flush();
+        self.prefetched_l2_lines.clear();
+    }
+
     pub fn get_combined_stats(&self) -> String {
         format!(
             "=== Cache Hierarchy Statistics ===\n\
@@ -392,6 +454,32 @@ impl Default for CacheState {
     }
 }
 
+/// Graine par défaut du générateur de la politique de remplacement Random
+pub const DEFAULT_CACHE_RNG_SEED: u64 = 0x2545_F491_4F6C_DD1D;
+
+/// Générateur pseudo-aléatoire xorshift64: suffisant pour choisir une victime,
+/// et reproductible à graine égale
+#[derive(Debug, Clone)]
+pub struct XorShift64 {
+    state: u64,
+}
+
+impl XorShift64 {
+    pub fn new(seed: u64) -> Self {
+        // L'état nul est un point fixe de xorshift
+        Self { state: if seed == 0 { DEFAULT_CACHE_RNG_SEED } else { seed } }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        x
+    }
+}
+
 /// Structure de la Cache
 #[derive(Debug)]
 pub struct Cache {
@@ -400,6 +488,8 @@ pub struct Cache {
     pub access_count: u64, // Compteur d'accès pour LRU
     pub statistics: CacheStatistics, // Statistiques de la cache
     pub next_level: Option<Box<Cache>>, // Niveau de cache suivant (si applicable)
+    rng_seed: u64,     // Graine de la politique Random (rétablie par reset)
+    rng: XorShift64,   // Générateur de la politique Random
 }
 
 
@@ -578,9 +668,17 @@ impl Cache {
             access_count: 0,
             statistics: CacheStatistics::default(),
             next_level,
+            rng_seed: DEFAULT_CACHE_RNG_SEED,
+            rng: XorShift64::new(DEFAULT_CACHE_RNG_SEED),
         }
     }
 
+    /// Fixe la graine de la politique de remplacement Random
+    pub fn set_rng_seed(&mut self, seed: u64) {
+        self.rng_seed = seed;
+        self.rng = XorShift64::new(seed);
+    }
+
     pub fn reset(&mut self) -> VMResult<()> {
         for set in &mut self.lines {
             for line in set {
@@ -589,6 +687,7 @@ impl Cache {
         }
         self.statistics = CacheStatistics::default();
         self.access_count = 0;
+        self.rng = XorShift64::new(self.rng_seed);
         Ok(())
     }
 
@@ -639,6 +738,10 @@ impl Cache {
                     return self.handle_write_miss(addr, value, set_index, tag, offset);
                 }
             }
+            // Write-back => la ligne devra être réécrite à l'éviction
+            if self.config.write_policy == WritePolicy::WriteBack {
+                line.dirty = true;
+            }
 
             // Write-through => propager
             if self.config.write_policy == WritePolicy::WriteThrough {
@@ -694,6 +797,73 @@ impl Cache {
     }
 
 
+    /// Invalide toutes les lignes. Les lignes sales sont comptées comme write-backs et
+    /// retournées (adresse de ligne, données) pour être réécrites au niveau suivant.
+    pub fn flush(&mut self) -> Vec<(u32, Vec<u8>)> {
+        let mut dirty_lines = Vec::new();
+        for set_index in 0..self.lines.len() {
+            for way in 0..self.lines[set_index].len() {
+                let line = &self.lines[set_index][way];
+                if !line.valid {
+                    continue;
+                }
+                if line.dirty {
+                    dirty_lines.push((self.reconstruct_address(set_index, line.tag), line.data.clone()));
+                }
+                let line = &mut self.lines[set_index][way];
+                line.valid = false;
+                line.dirty = false;
+                line.state = CacheState::Invalid;
+                self.statistics.invalidations += 1;
+            }
+        }
+        self.statistics.write_backs += dirty_lines.len();
+        dirty_lines
+    }
+
+    /// Indique si la ligne contenant `addr` est présente, sans toucher aux statistiques ni au LRU
+    pub fn contains(&self, addr: u32) -> bool {
+        let (set_index, tag, _) = self.decode_address(addr);
+        self.find_line_index(set_index, tag).is_some()
+    }
+
+    /// Remplace les données de la ligne contenant `addr` si elle est présente, sans
+    /// toucher aux statistiques ni à l'état de cohérence
+    pub fn refresh_line(&mut self, addr: u32, bytes: &[u8]) {
+        let (set_index, tag, _) = self.decode_address(addr);
+        if let Some(line) = self.find_line_mut(set_index, tag) {
+            let len = bytes.len().min(line.data.len());
+            line.data[..len].copy_from_slice(&bytes[..len]);
+        }
+    }
+
+    /// Alloue la ligne contenant `addr` sans accès de donnée (remplissage ou prefetch).
+    /// Ne compte ni hit ni miss; seule une éventuelle éviction est comptée.
+    pub fn install_line(&mut self, addr: u32) -> Result<(), VMError> {
+        let (set_index, tag, _) = self.decode_address(addr);
+        if self.find_line_index(set_index, tag).is_some() {
+            return Ok(());
+        }
+
+        let victim_way = self.select_victim(set_index)?;
+        if self.lines[set_index][victim_way].valid {
+            if self.lines[set_index][victim_way].dirty {
+                self.write_back(set_index, victim_way)?;
+                self.statistics.write_backs += 1;
+            }
+            self.statistics.evictions += 1;
+        }
+
+        self.access_count += 1;
+        let line = &mut self.lines[set_index][victim_way];
+        line.tag = tag;
+        line.valid = true;
+        line.dirty = false;
+        line.state = CacheState::Exclusive;
+        line.last_access = self.access_count;
+        Ok(())
+    }
+
     pub fn get_statistics(&self) -> &CacheStatistics {
         &self.statistics
     }
@@ -846,7 +1016,7 @@ impl Cache {
 
 
 
-    fn select_victim(&self, set_index: usize) -> Result<usize, VMError> {
+    fn select_victim(&mut self, set_index: usize) -> Result<usize, VMError> {
         match self.config.replacement_policy {
             ReplacementPolicy::LRU => {
                 let mut min_access = u64::MAX;
@@ -867,7 +1037,11 @@ impl Cache {
                 Ok(self.access_count as usize % self.config.associativity)
             }
             ReplacementPolicy::Random => {
-                Ok(rand::thread_rng().gen_range(0..self.config.associativity))
+                // Une voie libre est toujours préférée à une éviction
+                if let Some(free) = self.lines[set_index].iter().position(|line| !line.valid) {
+                    return Ok(free);
+                }
+                Ok((self.rng.next_u64() % self.config.associativity as u64) as usize)
             }
         }
     }
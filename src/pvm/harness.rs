@@ -0,0 +1,168 @@
+//src/pvm/harness.rs
+
+use crate::bytecode::files::{SegmentMetadata, SegmentType};
+use crate::bytecode::instructions::Instruction;
+use crate::pvm::vm::{PunkVM, VMConfig, VMStats};
+use crate::pvm::vm_errors::VMResult;
+use crate::BytecodeFile;
+
+/// Résultat d'une exécution complète d'un programme
+#[derive(Debug, Clone)]
+pub struct RunResult {
+    pub registers: Vec<u64>,      // État final des registres
+    pub memory_snapshot: Vec<u8>, // Contenu de la mémoire (store buffer inclus)
+    pub cycles: u64,              // Nombre de cycles exécutés
+    pub stats: VMStats,           // Statistiques complètes de la VM
+}
+
+/// Nom du résultat d'exécution dans l'API de `PunkVM::run_program_with_options`
+pub type ExecutionResult = RunResult;
+
+/// Exécute un programme avec la configuration par défaut de la VM
+pub fn execute_program(program: Vec<Instruction>) -> VMResult<RunResult> {
+    run_with_config(program, VMConfig::default())
+}
+
+/// Exécute un programme en activant ou non le forwarding et le store buffer
+pub fn execute_program_with_options(
+    program: Vec<Instruction>,
+    forwarding: bool,
+    store_buffer: bool,
+) -> VMResult<RunResult> {
+    let defaults = VMConfig::default();
+    let config = VMConfig {
+        enable_forwarding: forwarding,
+        // Un store buffer de capacité nulle écrit directement en mémoire
+        store_buffer_size: if store_buffer { defaults.store_buffer_size } else { 0 },
+        ..defaults
+    };
+    run_with_config(program, config)
+}
+
+/// Exécute `runs` fois le même programme avec la même configuration et vérifie
+/// que le nombre de cycles et les statistiques JSON sont identiques à chaque fois.
+/// Panique à la première divergence; retourne le résultat de la première exécution.
+pub fn assert_deterministic(program: Vec<Instruction>, config: VMConfig, runs: usize) -> RunResult {
+    assert!(runs > 0, "assert_deterministic: au moins une exécution est nécessaire");
+
+    let reference = run_with_config(program.clone(), config)
+        .unwrap_or_else(|e| panic!("assert_deterministic: exécution 1 échouée: {}", e));
+    let reference_json = reference.stats.to_json();
+
+    for run in 2..=runs {
+        let result = run_with_config(program.clone(), config)
+            .unwrap_or_else(|e| panic!("assert_deterministic: exécution {} échouée: {}", run, e));
+        assert_eq!(
+            result.cycles, reference.cycles,
+            "assert_deterministic: nombre de cycles différent à l'exécution {}", run
+        );
+        assert_eq!(
+            result.stats.to_json(), reference_json,
+            "assert_deterministic: statistiques différentes à l'exécution {}", run
+        );
+        assert_eq!(
+            result.registers, reference.registers,
+            "assert_deterministic: registres différents à l'exécution {}", run
+        );
+    }
+
+    reference
+}
+
+/// Construit la VM, charge le programme et l'exécute jusqu'au Halt
+fn run_with_config(program: Vec<Instruction>, config: VMConfig) -> VMResult<RunResult> {
+    let mut vm = PunkVM::with_config(config);
+    vm.load_program_from_bytecode(build_program(program))?;
+    vm.run()?;
+
+    let stats = vm.stats();
+    Ok(RunResult {
+        registers: vm.registers.clone(),
+        memory_snapshot: vm.memory.snapshot(),
+        cycles: stats.cycles,
+        stats,
+    })
+}
+
+/// Construit un BytecodeFile avec un segment de code couvrant toutes les instructions
+pub(crate) fn build_program(instructions: Vec<Instruction>) -> BytecodeFile {
+    let mut program = BytecodeFile::new();
+    for instr in instructions {
+        program.add_instruction(instr);
+    }
+    let code_size: u32 = program.code.iter().map(|i| i.total_size() as u32).sum();
+    program.segments = vec![SegmentMetadata::new(SegmentType::Code, 0, code_size, 0)];
+    program
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bytecode::opcodes::Opcode;
+    use crate::pvm::branch_predictor::PredictorType;
+
+    fn add_program() -> Vec<Instruction> {
+        vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 5),
+            Instruction::create_reg_imm8(Opcode::Mov, 2, 7),
+            Instruction::create_reg_reg_reg(Opcode::Add, 3, 1, 2),
+            Instruction::create_no_args(Opcode::Halt),
+        ]
+    }
+
+    #[test]
+    fn test_execute_program_add() {
+        let result = execute_program(add_program()).unwrap();
+        assert_eq!(result.registers[3], 12);
+        assert!(result.cycles > 0);
+        assert_eq!(result.cycles, result.stats.cycles);
+        assert_eq!(result.memory_snapshot.len(), VMConfig::default().memory_size);
+        assert!(result.stats.to_json().starts_with(&format!("{{\"cycles\":{},", result.cycles)));
+    }
+
+    /// Programme mêlant ALU, accès mémoire et boucle pour solliciter le dual-issue,
+    /// l'AGU, les caches et le prédicteur de branchement
+    fn mixed_program() -> Vec<Instruction> {
+        let mut code = vec![
+            Instruction::create_reg_imm16(Opcode::Mov, 1, 0x2000), // base
+            Instruction::create_reg_imm8(Opcode::Mov, 2, 1),
+            Instruction::create_reg_imm8(Opcode::Mov, 3, 8), // itérations
+        ];
+        let loop_start = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_store_reg_offset(Opcode::Store, 0, 1, 0));
+        code.push(Instruction::create_reg_reg_reg(Opcode::Add, 4, 4, 0));
+        code.push(Instruction::create_load_reg_offset(5, 1, 0));
+        code.push(Instruction::create_reg_reg_reg(Opcode::Add, 1, 1, 2));
+        code.push(Instruction::create_reg_reg_reg(Opcode::Add, 0, 0, 2));
+        code.push(Instruction::create_reg_reg(Opcode::Cmp, 0, 3));
+        let jl_addr = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_jump_if_less(jl_addr, loop_start));
+        code.push(Instruction::create_no_args(Opcode::Halt));
+        code
+    }
+
+    #[test]
+    fn test_assert_deterministic_across_configs() {
+        let defaults = VMConfig::default();
+        let configs = [
+            defaults,
+            VMConfig { enable_forwarding: false, ..defaults },
+            VMConfig { enable_hazard_detection: false, ..defaults },
+            VMConfig { store_buffer_size: 0, ..defaults },
+            VMConfig { branch_predictor_kind: PredictorType::GShare, ..defaults },
+            VMConfig { branch_predictor_kind: PredictorType::Perceptron, ..defaults },
+        ];
+
+        for config in configs {
+            let result = assert_deterministic(mixed_program(), config, 3);
+            assert_eq!(result.registers[0], 8);
+            assert_eq!(result.registers[4], 28); // 0 + 1 + ... + 7
+        }
+    }
+
+    #[test]
+    fn test_execute_program_with_options_add() {
+        let result = execute_program_with_options(add_program(), false, false).unwrap();
+        assert_eq!(result.registers[3], 12);
+    }
+}
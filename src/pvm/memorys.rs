@@ -1,10 +1,50 @@
 //src/pvm/memorys.rs
 
+use std::collections::HashSet;
 use std::io;
+use std::rc::Rc;
 
 use crate::pvm::buffers::StoreBuffer;
-use crate::pvm::caches::{CacheHierarchy, CacheAccessResult,};
-use crate::pvm::cache_configs::CacheConfig;
+use crate::pvm::caches::{CacheHierarchy, CacheAccessResult, DEFAULT_CACHE_RNG_SEED};
+use crate::pvm::cache_configs::{CacheConfig, ReplacementPolicy, WritePolicy};
+
+/// Adresse par défaut de la fenêtre MMIO (au-delà de toute mémoire principale réaliste)
+pub const DEFAULT_MMIO_BASE: u32 = 0xFFFF_F000;
+/// Taille de la fenêtre MMIO en octets
+pub const MMIO_WINDOW_SIZE: u32 = 0x100;
+/// Port console: chaque écriture y émet l'octet de poids faible de la valeur
+pub const MMIO_CONSOLE_PORT: u32 = 0x0;
+/// Taille d'une page de la mémoire copy-on-write
+pub const COW_PAGE_SIZE: usize = 4096;
+
+/// Ordre des octets des accès mémoire multi-octets (u16/u32/u64)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Endianness {
+    /// Octet de poids faible à l'adresse la plus basse
+    #[default]
+    Little,
+    /// Octet de poids fort à l'adresse la plus basse
+    Big,
+}
+
+impl Endianness {
+    /// Les `size` octets de poids faible de `value`, dans l'ordre de la mémoire
+    pub fn encode(self, value: u64, size: usize) -> Vec<u8> {
+        match self {
+            Endianness::Little => value.to_le_bytes()[..size].to_vec(),
+            Endianness::Big => value.to_be_bytes()[8 - size..].to_vec(),
+        }
+    }
+
+    /// Valeur représentée par `bytes` (au plus 8 octets) lus dans l'ordre de la mémoire
+    pub fn decode(self, bytes: &[u8]) -> u64 {
+        let fold = |value: u64, &byte: &u8| (value << 8) | byte as u64;
+        match self {
+            Endianness::Little => bytes.iter().rev().fold(0, fold),
+            Endianness::Big => bytes.iter().fold(0, fold),
+        }
+    }
+}
 
 /// Configuration du systeme memoire
 #[derive(Debug, Clone, Copy)]
@@ -13,6 +53,31 @@ pub struct MemoryConfig {
     pub l1_cache_size: usize,
     pub l2_cache_size: usize,
     pub store_buffer_size: usize,
+    /// Écritures (octets) vidées du store buffer vers le cache par cycle, dans l'ordre
+    /// du programme; 0 garde l'éviction immédiate de la plus ancienne entrée
+    pub store_drain_rate: usize,
+    pub mmio_base: u32,
+    pub l1_write_policy: WritePolicy,
+    pub l2_write_policy: WritePolicy,
+    /// Prefetch de la ligne de code suivante sur un miss du cache d'instructions
+    pub icache_prefetch: bool,
+    /// Mémoire principale en pages partagées entre forks jusqu'à la première écriture
+    pub copy_on_write: bool,
+    /// Latence (cycles) d'une lecture servie par le L1 data ou le store buffer
+    pub l1_latency: u32,
+    /// Latence (cycles) d'une lecture servie par le L2
+    pub l2_latency: u32,
+    /// Latence (cycles) d'une lecture qui manque L1 et L2
+    pub memory_latency: u32,
+    /// Passe par les caches L1/L2 et le store buffer; sinon chaque accès va directement
+    /// en mémoire principale avec `memory_latency`
+    pub enable_cache: bool,
+    /// Politique de remplacement des caches de données et du L2
+    pub replacement_policy: ReplacementPolicy,
+    /// Graine du générateur de la politique Random (exécutions reproductibles)
+    pub cache_rng_seed: u64,
+    /// Ordre des octets des lectures/écritures de mots, doubles et quadruples mots
+    pub endianness: Endianness,
 }
 
 /// Statistiques du système mémoire
@@ -32,6 +97,29 @@ pub struct MemoryStats {
     pub writes: u64,
     /// Nombre de lectures
     pub reads: u64,
+    /// Écritures propagées immédiatement en mémoire principale (L1 et L2 en write-through)
+    pub memory_writes: u64,
+    /// Nombre de hits dans le cache L1 instruction
+    pub l1_inst_hits: u64,
+    /// Nombre de misses dans le cache L1 instruction
+    pub l1_inst_misses: u64,
+    /// Lignes de code chargées par anticipation
+    pub icache_prefetches: u64,
+    /// Fetchs servis par une ligne préchargée
+    pub icache_prefetch_hits: u64,
+    /// Pages copiées à la première écriture car partagées avec un fork
+    pub cow_page_copies: u64,
+    /// Accès de données chronométrés (une entrée par `take_access_latency`)
+    pub timed_accesses: u64,
+    /// Somme des latences de ces accès, en cycles
+    pub total_access_latency: u64,
+    /// Lignes préchargées dans le L2 par le prefetcher de stride
+    pub l2_prefetches: u64,
+    /// Accès servis par une ligne préchargée dans le L2
+    pub l2_prefetch_hits: u64,
+    /// Cycles d'attente imposés par le store buffer (buffer plein ou lecture non
+    /// transférable en attente de la vidange d'une écriture)
+    pub store_buffer_stalls: u64,
 }
 
 impl Default for MemoryConfig {
@@ -41,16 +129,122 @@ impl Default for MemoryConfig {
             l1_cache_size: 64 * 1024, // 64KB
             l2_cache_size: 256 * 1024, // 256KB
             store_buffer_size: 8,
+            store_drain_rate: 0,
+            mmio_base: DEFAULT_MMIO_BASE,
+            l1_write_policy: WritePolicy::WriteThrough,
+            l2_write_policy: WritePolicy::WriteBack,
+            icache_prefetch: false,
+            copy_on_write: false,
+            l1_latency: 1,
+            l2_latency: 12,
+            memory_latency: 100,
+            enable_cache: true,
+            replacement_policy: ReplacementPolicy::LRU,
+            cache_rng_seed: DEFAULT_CACHE_RNG_SEED,
+            endianness: Endianness::Little,
         }
     }
 }
 
+/// Contenu de la mémoire principale: un bloc contigu, ou des pages partagées
+/// (copy-on-write) qui ne sont dupliquées qu'au moment où un fork les modifie
+#[derive(Debug, Clone)]
+enum MainMemory {
+    Flat(Vec<u8>),
+    CopyOnWrite { pages: Vec<Rc<Vec<u8>>>, size: usize },
+}
+
+impl MainMemory {
+    fn new(size: usize, copy_on_write: bool) -> Self {
+        if !copy_on_write {
+            return MainMemory::Flat(vec![0; size]);
+        }
+        // Chaque page est propre à cette mémoire: seules les pages partagées par un fork
+        // sont dupliquées (et comptées) à la première écriture
+        let page_count = size.div_ceil(COW_PAGE_SIZE);
+        let pages = (0..page_count).map(|_| Rc::new(vec![0; COW_PAGE_SIZE])).collect();
+        MainMemory::CopyOnWrite { pages, size }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            MainMemory::Flat(bytes) => bytes.len(),
+            MainMemory::CopyOnWrite { size, .. } => *size,
+        }
+    }
+
+    fn get(&self, addr: usize) -> u8 {
+        match self {
+            MainMemory::Flat(bytes) => bytes[addr],
+            MainMemory::CopyOnWrite { pages, .. } => pages[addr / COW_PAGE_SIZE][addr % COW_PAGE_SIZE],
+        }
+    }
+
+    /// Écrit un octet; retourne `true` si une page partagée a dû être copiée.
+    /// Réécrire la valeur déjà présente laisse la page partagée.
+    fn set(&mut self, addr: usize, value: u8) -> bool {
+        match self {
+            MainMemory::Flat(bytes) => {
+                bytes[addr] = value;
+                false
+            }
+            MainMemory::CopyOnWrite { pages, .. } => {
+                let page = &mut pages[addr / COW_PAGE_SIZE];
+                if page[addr % COW_PAGE_SIZE] == value {
+                    return false;
+                }
+                let copied = Rc::strong_count(page) > 1;
+                Rc::make_mut(page)[addr % COW_PAGE_SIZE] = value;
+                copied
+            }
+        }
+    }
+
+    fn read_range(&self, start: usize, len: usize) -> Vec<u8> {
+        match self {
+            MainMemory::Flat(bytes) => bytes[start..start + len].to_vec(),
+            MainMemory::CopyOnWrite { .. } => (start..start + len).map(|addr| self.get(addr)).collect(),
+        }
+    }
+
+    /// Écrit un bloc; retourne le nombre de pages partagées copiées
+    fn write_range(&mut self, start: usize, data: &[u8]) -> u64 {
+        match self {
+            MainMemory::Flat(bytes) => {
+                bytes[start..start + data.len()].copy_from_slice(data);
+                0
+            }
+            MainMemory::CopyOnWrite { .. } => data
+                .iter()
+                .enumerate()
+                .filter(|&(offset, &value)| self.set(start + offset, value))
+                .count() as u64,
+        }
+    }
+
+    fn to_vec(&self) -> Vec<u8> {
+        self.read_range(0, self.len())
+    }
+
+    fn is_copy_on_write(&self) -> bool {
+        matches!(self, MainMemory::CopyOnWrite { .. })
+    }
+}
+
 ///  Structure memoire VM
 pub struct Memory {
-    memory: Vec<u8>,           // Mémoire principale
+    config: MemoryConfig,      // Configuration d'origine (pour fork)
+    memory: MainMemory,        // Mémoire principale
     cache_hierarchy: CacheHierarchy, // Hiérarchie de cache L1/L2
     store_buffer: StoreBuffer, // Store buffer
     stats: MemoryStats,        // Statistiques de la mémoire
+    mmio_base: u32,            // Base de la fenêtre MMIO
+    console_output: Vec<u8>,   // Octets écrits sur le port console
+    icache_prefetch: bool,     // Prefetch next-line du cache d'instructions
+    prefetched_lines: HashSet<u32>, // Lignes préchargées pas encore utilisées
+    pending_latency: Option<u32>, // Latence du plus lent accès depuis le dernier take_access_latency
+    enable_cache: bool,        // Hiérarchie de cache active (sinon accès direct à la RAM)
+    pending_store_stalls: u64, // Cycles d'attente du store buffer pas encore facturés au pipeline
 }
 
 impl Memory {
@@ -61,8 +255,8 @@ impl Memory {
             size: config.l1_cache_size / 2, // Moitié pour data
             lines_size: 64,
             associativity: 4,
-            write_policy: crate::pvm::cache_configs::WritePolicy::WriteThrough,
-            replacement_policy: crate::pvm::cache_configs::ReplacementPolicy::LRU,
+            write_policy: config.l1_write_policy,
+            replacement_policy: config.replacement_policy,
         };
         
         let l1_inst_config = CacheConfig {
@@ -77,15 +271,79 @@ impl Memory {
             size: config.l2_cache_size,
             lines_size: 64,
             associativity: 8,
-            write_policy: crate::pvm::cache_configs::WritePolicy::WriteBack,
-            replacement_policy: crate::pvm::cache_configs::ReplacementPolicy::LRU,
+            write_policy: config.l2_write_policy,
+            replacement_policy: config.replacement_policy,
         };
         
+        let mut cache_hierarchy = CacheHierarchy::new(l1_data_config, l1_inst_config, l2_config);
+        cache_hierarchy.l1_latency = config.l1_latency;
+        cache_hierarchy.l2_latency = config.l2_latency;
+        cache_hierarchy.memory_latency = config.memory_latency;
+        cache_hierarchy.set_rng_seed(config.cache_rng_seed);
+
         Self {
-            memory: vec![0; config.size],
-            cache_hierarchy: CacheHierarchy::new(l1_data_config, l1_inst_config, l2_config),
-            store_buffer: StoreBuffer::new(config.store_buffer_size),
+            config,
+            memory: MainMemory::new(config.size, config.copy_on_write),
+            cache_hierarchy,
+            store_buffer: StoreBuffer::with_drain_rate(config.store_buffer_size, config.store_drain_rate),
             stats: MemoryStats::default(),
+            mmio_base: config.mmio_base,
+            console_output: Vec::new(),
+            icache_prefetch: config.icache_prefetch,
+            prefetched_lines: HashSet::new(),
+            pending_latency: None,
+            enable_cache: config.enable_cache,
+            pending_store_stalls: 0,
+        }
+    }
+
+    /// Accès au cache d'instructions pour le fetch de l'instruction à `pc`.
+    /// Retourne `None` sur un hit, sinon la latence de remplissage de la ligne que le fetch
+    /// doit attendre (L2 si la ligne y est, mémoire principale sinon).
+    /// Avec le prefetch activé, la ligne suivante est chargée sur un miss, puis dès
+    /// qu'une ligne préchargée est consommée, afin de rester en avance sur le flot de code.
+    /// Sans cache, le fetch est idéal et ne compte ni hit ni miss.
+    pub fn fetch_instruction(&mut self, pc: u32) -> Option<u32> {
+        if !self.enable_cache {
+            return None;
+        }
+        let line_size = self.cache_hierarchy.l1_inst.get_line_size() as u32;
+        let line_addr = pc - pc % line_size;
+
+        let hit = self.cache_hierarchy.l1_inst.read(pc).is_ok();
+        if hit {
+            self.stats.l1_inst_hits += 1;
+            if self.prefetched_lines.remove(&line_addr) {
+                self.stats.icache_prefetch_hits += 1;
+                self.prefetch_instruction_line(line_addr.wrapping_add(line_size));
+            }
+            return None;
+        }
+
+        self.stats.l1_inst_misses += 1;
+        self.prefetched_lines.remove(&line_addr);
+        let latency = if self.cache_hierarchy.l2_unified.contains(line_addr) {
+            self.cache_hierarchy.l2_latency
+        } else {
+            let _ = self.cache_hierarchy.l2_unified.install_line(line_addr);
+            self.cache_hierarchy.memory_latency
+        };
+        let _ = self.cache_hierarchy.l1_inst.install_line(line_addr);
+        self.prefetch_instruction_line(line_addr.wrapping_add(line_size));
+        Some(latency)
+    }
+
+    /// Précharge une ligne de code si le prefetch est actif et qu'elle est absente
+    fn prefetch_instruction_line(&mut self, line_addr: u32) {
+        if !self.icache_prefetch
+            || line_addr as usize >= self.memory.len()
+            || self.cache_hierarchy.l1_inst.contains(line_addr)
+        {
+            return;
+        }
+        if self.cache_hierarchy.l1_inst.install_line(line_addr).is_ok() {
+            self.prefetched_lines.insert(line_addr);
+            self.stats.icache_prefetches += 1;
         }
     }
 
@@ -95,14 +353,22 @@ impl Memory {
 
         self.stats.reads += 1;
 
+        // 0. Sans cache: lecture directe en RAM, latence fixe
+        if !self.enable_cache {
+            self.record_latency(self.config.memory_latency);
+            return Ok(self.memory.get(addr as usize));
+        }
+
         // 1. Vérifier d'abord dans le store buffer
         if let Some(value) = self.store_buffer.lookup_byte(addr) {
             self.stats.sb_hits += 1;
+            self.record_latency(self.cache_hierarchy.l1_latency);
             return Ok(value);
         }
 
         // 2. Utiliser la hiérarchie de cache avec accès byte
         let cache_result = self.cache_hierarchy.access_byte(addr, false, None);
+        self.refresh_cached_line(addr);
         
         // DEBUG: Log pour comprendre le comportement
         static mut DEBUG_COUNT: u32 = 0;
@@ -116,22 +382,30 @@ impl Memory {
         match cache_result {
             Ok(CacheAccessResult::Hit(data)) => {
                 self.stats.l1_hits += 1;
+                self.record_latency(self.cache_hierarchy.l1_latency);
                 Ok(data as u8)
             }
             Ok(CacheAccessResult::L2Hit(data)) => {
                 self.stats.l1_misses += 1;  // L1 miss
                 self.stats.l2_hits += 1;    // L2 hit
+                if self.cache_hierarchy.consume_l2_prefetch(addr) {
+                    self.stats.l2_prefetch_hits += 1;
+                }
+                self.record_latency(self.cache_hierarchy.l2_latency);
                 Ok(data as u8)
             }
             Ok(CacheAccessResult::Miss) | Ok(CacheAccessResult::MSHRPending) => {
                 self.stats.l1_misses += 1;  // L1 miss
                 self.stats.l2_misses += 1;  // L2 miss aussi
+                self.cache_hierarchy.consume_l2_prefetch(addr); // Ligne préchargée déjà évincée
+                self.record_latency(self.cache_hierarchy.memory_latency);
                 
                 // Lire depuis la mémoire principale
-                let value = self.memory[addr as usize];
+                let value = self.memory.get(addr as usize);
                 
                 // Remplir la hiérarchie cache avec les données de la mémoire
                 let _ = self.cache_hierarchy.fill_from_memory(addr, value);
+                self.refresh_cached_line(addr);
                 
                 Ok(value)
             }
@@ -139,18 +413,81 @@ impl Memory {
         }
     }
 
+    /// Prefetch de stride: `current` vient d'être accédée et `predicted` est la prochaine
+    /// adresse attendue; la ligne correspondante est chargée dans le L2 si elle manque
+    pub fn prefetch_stride(&mut self, current: u32, predicted: u32) {
+        if !self.enable_cache {
+            return;
+        }
+        let Some(line_addr) = self.cache_hierarchy.prefetcher.get_stride_prefetch_address(current, predicted) else {
+            return;
+        };
+        if (line_addr as usize) < self.memory.len() && self.cache_hierarchy.prefetch_into_l2(line_addr) {
+            self.refresh_cached_line(line_addr);
+            self.stats.l2_prefetches += 1;
+        }
+    }
+
+    /// Retient la latence d'une lecture: les octets d'un même accès sont servis en
+    /// parallèle, seul le plus lent compte
+    fn record_latency(&mut self, latency: u32) {
+        self.pending_latency = Some(self.pending_latency.map_or(latency, |l| l.max(latency)));
+    }
+
+    /// Latence (en cycles) des lectures de données effectuées depuis le dernier appel,
+    /// ou `None` si aucune lecture n'a eu lieu. L'accès est compté dans les statistiques.
+    pub fn take_access_latency(&mut self) -> Option<u32> {
+        let latency = self.pending_latency.take()?;
+        self.stats.timed_accesses += 1;
+        self.stats.total_access_latency += latency as u64;
+        Some(latency)
+    }
+
+    /// Cycle de vidange du store buffer (un appel par cycle pipeline)
+    pub fn drain_store_buffer_cycle(&mut self) {
+        self.store_buffer.drain_cycle();
+    }
+
+    /// Cycles d'attente du store buffer accumulés depuis le dernier appel
+    pub fn take_store_stall_cycles(&mut self) -> u64 {
+        std::mem::take(&mut self.pending_store_stalls)
+    }
+
+    fn record_store_stall(&mut self, cycles: usize) {
+        self.stats.store_buffer_stalls += cycles as u64;
+        self.pending_store_stalls += cycles as u64;
+    }
+
+    /// Une lecture de plusieurs octets ne peut être transférée depuis le store buffer
+    /// que s'il couvre tous ses octets; sinon elle attend la vidange des écritures
+    /// en attente qui la recouvrent
+    fn wait_for_pending_stores(&mut self, addr: u32, len: usize) {
+        if !self.enable_cache || self.store_buffer.drain_rate() == 0 {
+            return;
+        }
+        let pending = (0..len as u32)
+            .filter(|i| self.store_buffer.has_address(addr + i))
+            .count();
+        if pending > 0 && pending < len {
+            let cycles = self.store_buffer.drain_through(addr, len);
+            self.record_store_stall(cycles);
+        }
+    }
+
     /// Lit un mot (16 bits) à l'adresse spécifiée
     pub fn read_word(&mut self, addr: u32) -> io::Result<u16> {
         self.check_address(addr + 1)?;
+        self.wait_for_pending_stores(addr, 2);
         let b0 = self.read_byte(addr)?;
         let b1 = self.read_byte(addr + 1)?;
         println!("read_word: b0 = {}, b1 = {}", b0, b1);
-        Ok(u16::from_le_bytes([b0, b1]))
+        Ok(self.config.endianness.decode(&[b0, b1]) as u16)
     }
 
     /// Lit un double mot (32 bits) à l'adresse spécifiée
     pub fn read_dword(&mut self, addr: u32) -> io::Result<u32> {
         self.check_address(addr + 3)?;
+        self.wait_for_pending_stores(addr, 4);
         let b0 = self.read_byte(addr)?;
         let b1 = self.read_byte(addr + 1)?;
         let b2 = self.read_byte(addr + 2)?;
@@ -159,18 +496,19 @@ impl Memory {
             "read_dword: b0 = {}, b1 = {}, b2 = {}, b3 = {}",
             b0, b1, b2, b3
         );
-        Ok(u32::from_le_bytes([b0, b1, b2, b3]))
+        Ok(self.config.endianness.decode(&[b0, b1, b2, b3]) as u32)
     }
 
     /// Lit un quad mot (64 bits) à l'adresse spécifiée
     pub fn read_qword(&mut self, addr: u32) -> io::Result<u64> {
         self.check_address(addr + 7)?;
+        self.wait_for_pending_stores(addr, 8);
         let mut buf = [0u8; 8];
         for i in 0..8 {
             buf[i] = self.read_byte(addr + i as u32)?;
         }
         println!("read_qword: buf = {:?}", buf);
-        Ok(u64::from_le_bytes(buf))
+        Ok(self.config.endianness.decode(&buf))
     }
 
     /// Écrit un byte à l'adresse spécifiée
@@ -184,8 +522,18 @@ impl Memory {
             println!("DEBUG First write: addr=0x{:X}, value={}", addr, value);
         }
 
-        // 1) Ajouter au store buffer
-        self.store_buffer.add(addr, value);
+        // 0) Sans cache: écriture directe en RAM
+        if !self.enable_cache {
+            if self.memory.set(addr as usize, value) {
+                self.stats.cow_page_copies += 1;
+            }
+            self.stats.memory_writes += 1;
+            return Ok(());
+        }
+
+        // 1) Ajouter au store buffer (attente si plein)
+        let waited = self.store_buffer.add(addr, value);
+        self.record_store_stall(waited);
 
         // 2) Écrire dans la hiérarchie de cache
         match self.cache_hierarchy.access_byte(addr, true, Some(value)) {
@@ -204,7 +552,13 @@ impl Memory {
         }
 
         // 3) Écriture en RAM (pour compatibilité avec write-through du L1)
-        self.memory[addr as usize] = value;
+        if self.memory.set(addr as usize, value) {
+            self.stats.cow_page_copies += 1;
+        }
+        self.refresh_cached_line(addr);
+        if self.cache_hierarchy.writes_through_to_memory() {
+            self.stats.memory_writes += 1;
+        }
 
         Ok(())
     }
@@ -238,7 +592,7 @@ impl Memory {
 
     pub fn write_word(&mut self, addr: u32, value: u16) -> io::Result<()> {
         self.check_address(addr + 1)?;
-        let bytes = value.to_le_bytes();
+        let bytes = self.config.endianness.encode(value as u64, 2);
         self.write_byte(addr, bytes[0])?;
         self.write_byte(addr + 1, bytes[1])?;
         println!("write_word: addr = 0x{:08X}, value = {}", addr, value);
@@ -248,7 +602,7 @@ impl Memory {
     /// Écrit un double mot (32 bits) à l'adresse spécifiée
     pub fn write_dword(&mut self, addr: u32, value: u32) -> io::Result<()> {
         self.check_address(addr + 3)?;
-        let bytes = value.to_le_bytes();
+        let bytes = self.config.endianness.encode(value as u64, 4);
         for i in 0..4 {
             self.write_byte(addr + i, bytes[i as usize])?;
         }
@@ -259,7 +613,7 @@ impl Memory {
     /// Écrit un quad mot (64 bits) à l'adresse spécifiée
     pub fn write_qword(&mut self, addr: u32, value: u64) -> io::Result<()> {
         self.check_address(addr + 7)?;
-        let bytes = value.to_le_bytes();
+        let bytes = self.config.endianness.encode(value, 8);
         for i in 0..8 {
             self.write_byte(addr + i, bytes[i as usize])?;
         }
@@ -283,6 +637,7 @@ impl Memory {
     pub fn read_block(&mut self, addr: u32, size: usize) -> io::Result<Vec<u8>> {
         let end = addr + (size as u32) - 1;
         self.check_address(end)?;
+        self.wait_for_pending_stores(addr, size);
 
         let mut data = Vec::with_capacity(size);
         for i in 0..size {
@@ -362,13 +717,141 @@ impl Memory {
         Ok(vector)
     }
 
+    /// Écrit un bloc directement en mémoire principale, sans passer par les caches
+    /// ni compter d'accès. Les entrées du store buffer devenues obsolètes sont retirées.
+    pub fn write_direct(&mut self, addr: u32, bytes: &[u8]) -> io::Result<()> {
+        self.check_range(addr, bytes.len())?;
+        let start = addr as usize;
+        self.stats.cow_page_copies += self.memory.write_range(start, bytes);
+        for offset in 0..bytes.len() as u32 {
+            self.store_buffer.discard(addr + offset);
+            self.refresh_cached_line(addr + offset);
+        }
+        Ok(())
+    }
+
+    /// Aligne les lignes de cache data contenant `addr` sur la mémoire principale
+    fn refresh_cached_line(&mut self, addr: u32) {
+        let memory = &self.memory;
+        self.cache_hierarchy.refresh_data_line(addr, |base, len| {
+            let len = len.min(memory.len().saturating_sub(base as usize));
+            memory.read_range(base as usize, len)
+        });
+    }
+
+    /// Lit un bloc directement en mémoire principale, sans passer par les caches
+    pub fn read_direct(&self, addr: u32, len: usize) -> io::Result<Vec<u8>> {
+        self.check_range(addr, len)?;
+        let start = addr as usize;
+        Ok(self.memory.read_range(start, len))
+    }
+
+    /// Lit un qword sans passer par les caches ni modifier les statistiques,
+    /// en tenant compte des écritures en attente du store buffer
+    pub fn peek_qword(&self, addr: u32) -> io::Result<u64> {
+        let mut bytes: [u8; 8] = self.read_direct(addr, 8)?.try_into().unwrap();
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            if let Some(pending) = self.store_buffer.lookup_byte(addr + i as u32) {
+                *byte = pending;
+            }
+        }
+        Ok(self.config.endianness.decode(&bytes))
+    }
+
+    /// Ordre des octets des accès multi-octets
+    pub fn endianness(&self) -> Endianness {
+        self.config.endianness
+    }
+
     /// Vide le store buffer en écrivant toutes les données en mémoire
     pub fn flush_store_buffer(&mut self) -> io::Result<()> {
-        self.store_buffer.flush(&mut self.memory);
+        for (addr, value) in self.store_buffer.drain() {
+            if (addr as usize) < self.memory.len() && self.memory.set(addr as usize, value) {
+                self.stats.cow_page_copies += 1;
+            }
+        }
         println!("flush_store_buffer: store buffer flushed");
         Ok(())
     }
 
+    /// Nombre d'écritures encore en attente dans le store buffer
+    pub fn pending_stores(&self) -> usize {
+        self.store_buffer.len()
+    }
+
+    /// Taille configurée de la mémoire principale
+    pub fn size(&self) -> usize {
+        self.memory.len()
+    }
+
+    /// Copie du contenu de la mémoire, avec les écritures en attente du store buffer
+    pub fn snapshot(&self) -> Vec<u8> {
+        let mut snapshot = self.memory.to_vec();
+        self.store_buffer.apply_to(&mut snapshot);
+        snapshot
+    }
+
+    /// Crée une copie indépendante de ce système mémoire (caches à froid, statistiques
+    /// remises à zéro). En mode copy-on-write, les pages restent partagées avec l'original
+    /// jusqu'à ce que l'une des deux copies les modifie.
+    pub fn fork(&self) -> Memory {
+        let mut forked = Memory::new(MemoryConfig { copy_on_write: false, size: 0, ..self.config });
+        forked.config = self.config;
+        forked.memory = self.memory.clone();
+        forked.store_buffer = self.store_buffer.clone();
+        forked.console_output = self.console_output.clone();
+        forked
+    }
+
+    /// Indique si la mémoire principale est en mode copy-on-write
+    pub fn is_copy_on_write(&self) -> bool {
+        self.memory.is_copy_on_write()
+    }
+
+    /// Indique si l'adresse tombe dans la fenêtre MMIO
+    pub fn is_mmio(&self, addr: u32) -> bool {
+        addr.wrapping_sub(self.mmio_base) < MMIO_WINDOW_SIZE
+    }
+
+    /// Ecriture vers un périphérique MMIO (court-circuite caches et mémoire principale).
+    /// Les écritures hors du port console sont ignorées.
+    pub fn mmio_write(&mut self, addr: u32, value: u64) {
+        if addr.wrapping_sub(self.mmio_base) == MMIO_CONSOLE_PORT {
+            self.console_output.push(value as u8);
+        }
+    }
+
+    /// Lecture depuis la fenêtre MMIO: les périphériques sont en écriture seule et lisent 0
+    pub fn mmio_read(&self, _addr: u32) -> u64 {
+        0
+    }
+
+    /// Récupère (et vide) la sortie écrite sur le port console
+    pub fn take_output(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.console_output)
+    }
+
+    /// Sortie console écrite et pas encore consommée
+    pub fn console_output(&self) -> &[u8] {
+        &self.console_output
+    }
+
+    /// Remplace la sortie console en attente (restauration d'un snapshot)
+    pub fn restore_console_output(&mut self, output: &[u8]) {
+        self.console_output = output.to_vec();
+    }
+
+    /// Vérifie qu'une plage d'adresses tient dans la mémoire
+    fn check_range(&self, addr: u32, len: usize) -> io::Result<()> {
+        if addr as usize + len > self.memory.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Plage mémoire invalide: 0x{:08X} + {}", addr, len),
+            ));
+        }
+        Ok(())
+    }
+
     /// Vérifie si une adresse est valide
     fn check_address(&self, addr: u32) -> io::Result<()> {
         if addr as usize >= self.memory.len() {
@@ -385,7 +868,7 @@ impl Memory {
     /// Réinitialise le système mémoire
     pub fn reset(&mut self) {
         println!("Resetting memory...");
-        self.memory.iter_mut().for_each(|byte| *byte = 0);
+        self.memory = MainMemory::new(self.memory.len(), self.memory.is_copy_on_write());
         
         // Réinitialiser la hiérarchie de cache
         let _ = self.cache_hierarchy.l1_data.reset();
@@ -393,9 +876,60 @@ impl Memory {
         let _ = self.cache_hierarchy.l2_unified.reset();
         self.cache_hierarchy.mshr = crate::pvm::caches::MSHR::new(8);
         self.cache_hierarchy.write_buffer = crate::pvm::caches::WriteBuffer::new(16);
+        self.cache_hierarchy.prefetched_l2_lines.clear();
         
         self.store_buffer.clear();
         self.stats = MemoryStats::default();
+        self.console_output.clear();
+        self.prefetched_lines.clear();
+        self.pending_latency = None;
+        self.pending_store_stalls = 0;
+    }
+
+    /// Remplace le contenu de la mémoire principale par une copie issue de `snapshot`.
+    /// Le store buffer est vidé et les caches repartent à froid. En mode copy-on-write,
+    /// les pages dont le contenu ne change pas restent partagées.
+    pub fn restore(&mut self, contents: &[u8]) -> io::Result<()> {
+        if contents.len() != self.memory.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Taille de mémoire incompatible: {} au lieu de {}", contents.len(), self.memory.len()),
+            ));
+        }
+        self.stats.cow_page_copies += self.memory.write_range(0, contents);
+
+        let _ = self.cache_hierarchy.l1_data.reset();
+        let _ = self.cache_hierarchy.l1_inst.reset();
+        let _ = self.cache_hierarchy.l2_unified.reset();
+        self.cache_hierarchy.mshr = crate::pvm::caches::MSHR::new(8);
+        self.cache_hierarchy.write_buffer = crate::pvm::caches::WriteBuffer::new(16);
+        self.cache_hierarchy.prefetched_l2_lines.clear();
+        self.store_buffer.clear();
+        self.prefetched_lines.clear();
+        self.pending_latency = None;
+        Ok(())
+    }
+
+    /// Vide les caches L1 (data et instruction): les lignes sales sont réécrites dans le L2
+    /// et le prochain accès à chaque ligne manque le L1
+    pub fn flush_l1(&mut self) -> io::Result<()> {
+        self.cache_hierarchy
+            .flush_l1()
+            .map_err(|e| io::Error::other(e.to_string()))?;
+        self.prefetched_lines.clear();
+        Ok(())
+    }
+
+    /// Vide le cache L2: les lignes sales sont réécrites en mémoire (`l2_writebacks`)
+    pub fn flush_l2(&mut self) {
+        self.cache_hierarchy.flush_l2();
+    }
+
+    /// Vide toute la hiérarchie, L1 d'abord pour que ses lignes sales atteignent la mémoire via le L2
+    pub fn flush_all_caches(&mut self) -> io::Result<()> {
+        self.flush_l1()?;
+        self.flush_l2();
+        Ok(())
     }
 
     /// Retourne les statistiques mémoire
@@ -403,6 +937,11 @@ impl Memory {
         // println!("Memory stats: {:?}", self.stats);
         self.stats
     }
+
+    /// Nombre de lignes sales évincées du L2 vers la mémoire principale
+    pub fn l2_writebacks(&self) -> u64 {
+        self.cache_hierarchy.l2_unified.get_statistics().write_backs as u64
+    }
 }
 
 // Test unitaire pour la mémoire
@@ -524,6 +1063,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cache_hits_return_line_data() {
+        let mut mem = Memory::new(MemoryConfig::default());
+        let data: Vec<u8> = (1..=64).collect();
+        mem.write_direct(0x200, &data).unwrap();
+
+        // Le premier octet remplit la ligne; les suivants sont servis par le L1
+        assert_eq!(mem.read_byte(0x200).unwrap(), 1);
+        let l1_hits = mem.stats().l1_hits;
+        for offset in 1..64 {
+            assert_eq!(mem.read_byte(0x200 + offset).unwrap(), data[offset as usize]);
+        }
+        assert_eq!(mem.stats().l1_hits, l1_hits + 63);
+
+        // Une écriture directe met à jour la copie en cache
+        mem.write_direct(0x205, &[0xEE]).unwrap();
+        assert_eq!(mem.read_byte(0x205).unwrap(), 0xEE);
+        assert_eq!(mem.stats().l1_hits, l1_hits + 64);
+    }
+
     // #[test]
     // fn test_memory_cache_hit() {
     //     let config = MemoryConfig::default();
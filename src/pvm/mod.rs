@@ -3,6 +3,7 @@ pub mod cache_stats;
 pub mod caches;
 pub mod executions;
 pub mod forwardings;
+pub mod harness;
 pub mod hazards;
 pub mod instructions;
 pub mod memorys;
@@ -1,3 +1,193 @@
+//src/pvm/optimizings.rs
+
+use crate::bytecode::format::ArgType;
+use crate::bytecode::instructions::{ArgValue, Instruction};
+use crate::bytecode::lowering::rewrite_instructions;
+use crate::bytecode::opcodes::Opcode;
+use crate::BytecodeFile;
+
+/// Bilan d'une passe d'optimisation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OptimizationReport {
+    pub removed_nops: usize,       // NOP supprimés
+    pub removed_self_moves: usize, // MOV Rx, Rx supprimés
+    pub bytes_saved: u32,          // Octets retirés du segment de code
+}
+
+impl OptimizationReport {
+    /// Nombre total d'instructions supprimées
+    pub fn removed_instructions(&self) -> usize {
+        self.removed_nops + self.removed_self_moves
+    }
+}
+
+/// Vrai pour un MOV registre -> même registre (sans effet: MOV ne touche pas les flags)
+fn is_self_move(instruction: &Instruction) -> bool {
+    if instruction.opcode != Opcode::Mov || instruction.format.arg3_type != ArgType::None {
+        return false;
+    }
+    match (instruction.get_arg1_value(), instruction.get_arg2_value()) {
+        (Ok(ArgValue::Register(rd)), Ok(ArgValue::Register(rs))) => rd == rs,
+        _ => false,
+    }
+}
+
+/// Passe d'optimisation avant exécution: supprime les NOP et les MOV Rx, Rx.
+///
+/// Les branchements, relatifs ou absolus, sont recalculés pour viser la même instruction; un
+/// branchement vers une instruction supprimée vise l'instruction conservée qui la suit.
+/// La taille du segment de code et les symboles du code sont mis à jour. Les NOP de
+/// remplissage d'`align_code` sont supprimés comme les autres: l'alignement est perdu.
+pub fn optimize(program: &mut BytecodeFile) -> Result<OptimizationReport, String> {
+    let mut report = OptimizationReport::default();
+    let old_size = program.code_size();
+
+    let (code, address_map) = rewrite_instructions(&program.code, program.code_load_addr(), |instruction| {
+        if instruction.opcode == Opcode::Nop {
+            report.removed_nops += 1;
+            Ok(Vec::new())
+        } else if is_self_move(instruction) {
+            report.removed_self_moves += 1;
+            Ok(Vec::new())
+        } else {
+            Ok(vec![instruction.clone()])
+        }
+    })?;
+
+    let new_size = program.replace_code(code, &address_map);
+    report.bytes_saved = old_size - new_size;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bytecode::files::{SegmentMetadata, SegmentType};
+    use crate::bytecode::format::InstructionFormat;
+    use crate::pvm::vm::PunkVM;
+
+    /// Boucle R1 += R0 pour R0 = 5..1, parsemée de NOP et de MOV Rx, Rx.
+    /// La cible de la boucle est elle-même un NOP.
+    fn padded_loop_program() -> BytecodeFile {
+        let nop = || Instruction::create_no_args(Opcode::Nop);
+        let mut code = vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 0, 5),
+            nop(),
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 0),
+        ];
+        let loop_addr = Instruction::calculate_current_address(&code);
+        code.push(nop());
+        code.push(Instruction::create_reg_reg(Opcode::Add, 1, 0));
+        code.push(Instruction::create_reg_reg(Opcode::Mov, 2, 2));
+        code.push(Instruction::create_single_reg(Opcode::Dec, 0));
+        code.push(nop());
+        let branch_addr = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_jump_if_not_zero(branch_addr, loop_addr));
+        code.push(nop());
+        code.push(Instruction::create_no_args(Opcode::Halt));
+
+        let mut program = BytecodeFile::new();
+        for instruction in code {
+            program.add_instruction(instruction);
+        }
+        program.segments = vec![SegmentMetadata::new(SegmentType::Code, 0, program.code_size(), 0)];
+        program.add_symbol("loop", loop_addr);
+        program
+    }
+
+    fn run(program: BytecodeFile) -> PunkVM {
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program).unwrap();
+        vm.run().unwrap();
+        vm
+    }
+
+    #[test]
+    fn test_optimize_removes_nops_and_keeps_loop_semantics() {
+        let original = padded_loop_program();
+        let mut optimized = original.clone();
+        let report = optimize(&mut optimized).unwrap();
+
+        assert_eq!(report.removed_nops, 4);
+        assert_eq!(report.removed_self_moves, 1);
+        assert_eq!(optimized.code.len(), original.code.len() - 5);
+        assert_eq!(optimized.segments[0].size, optimized.code_size());
+        assert_eq!(report.bytes_saved, original.code_size() - optimized.code_size());
+        // Le symbole visait le NOP supprimé: il vise maintenant l'ADD qui le suivait
+        let loop_index = optimized.code.iter().position(|i| i.opcode == Opcode::Add).unwrap();
+        assert_eq!(optimized.symbols["loop"], Instruction::calculate_current_address(&optimized.code[..loop_index]));
+
+        let reference = run(original);
+        let vm = run(optimized);
+        assert_eq!(vm.registers[1], 15);
+        assert_eq!(vm.registers, reference.registers);
+        assert!(
+            vm.stats().instructions_executed < reference.stats().instructions_executed,
+            "{} instructions après optimisation contre {}",
+            vm.stats().instructions_executed,
+            reference.stats().instructions_executed
+        );
+    }
+
+    #[test]
+    fn test_optimize_without_dead_code_is_identity() {
+        let mut program = padded_loop_program();
+        optimize(&mut program).unwrap();
+        let once = program.code.clone();
+
+        assert_eq!(optimize(&mut program).unwrap(), OptimizationReport::default());
+        assert_eq!(program.code, once);
+    }
+
+    #[test]
+    fn test_optimize_relocates_absolute_branch_targets() {
+        const CODE_BASE: u32 = 0x100;
+        let absolute_jump = |target: u32| {
+            let format = InstructionFormat::new(ArgType::None, ArgType::AbsoluteAddr, ArgType::None);
+            Instruction::new(Opcode::Jmp, format, target.to_le_bytes().to_vec())
+        };
+        let mut code = vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 1),
+            absolute_jump(0),
+            Instruction::create_no_args(Opcode::Nop),
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 99),
+        ];
+        let target = CODE_BASE + Instruction::calculate_current_address(&code);
+        code[1] = absolute_jump(target);
+        code.push(Instruction::create_no_args(Opcode::Nop));
+        code.push(Instruction::create_reg_imm8(Opcode::Mov, 2, 7));
+        code.push(Instruction::create_no_args(Opcode::Halt));
+
+        let mut program = BytecodeFile::new();
+        for instruction in code {
+            program.add_instruction(instruction);
+        }
+        program.segments = vec![SegmentMetadata::new(SegmentType::Code, 0, program.code_size(), CODE_BASE)];
+        let original = program.clone();
+        assert_eq!(optimize(&mut program).unwrap().removed_nops, 2);
+
+        // Le saut visait le NOP supprimé: il vise maintenant le MOV R2 qui le suivait
+        let mov_index = program.code.iter().position(|i| i.args.first() == Some(&2)).unwrap();
+        let new_target = CODE_BASE + Instruction::calculate_current_address(&program.code[..mov_index]);
+        assert_eq!(program.code[1].args, new_target.to_le_bytes().to_vec());
+
+        let (reference, vm) = (run(original), run(program));
+        assert_eq!((vm.registers[1], vm.registers[2]), (1, 7));
+        assert_eq!(vm.registers, reference.registers);
+    }
+
+    #[test]
+    fn test_optimize_rejects_absolute_target_outside_code() {
+        let mut program = BytecodeFile::new();
+        let format = InstructionFormat::new(ArgType::None, ArgType::AbsoluteAddr, ArgType::None);
+        program.add_instruction(Instruction::new(Opcode::Jmp, format, 0x10u32.to_le_bytes().to_vec()));
+        program.add_instruction(Instruction::create_no_args(Opcode::Halt));
+        program.segments = vec![SegmentMetadata::new(SegmentType::Code, 0, program.code_size(), 0x100)];
+
+        assert!(optimize(&mut program).is_err());
+    }
+}
+
 // //src/pvm/optimizings.rs
 // use crate::pvm::instructions::{ArithmeticOp, DecodedInstruction};
 // use crate::pvm::pipelines::Pipeline;
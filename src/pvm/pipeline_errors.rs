@@ -1,11 +1,46 @@
-//
-//
-//
-//
-//
-// #[derive(Debug)]
-// pub enum PipelineError {
-//     MemoryError(String),
-//     BufferError(String),
-//     ExecutionError(String),
-// }
+//src/pvm/pipeline_errors.rs
+
+use std::fmt;
+
+use crate::bytecode::opcodes::Opcode;
+
+/// Erreur interne du pipeline, remontée par `Pipeline::cycle`
+#[derive(Debug, Clone, PartialEq)]
+pub enum PipelineError {
+    /// Instruction mal formée rejetée par l'étage Decode à l'adresse `pc`
+    Decode { pc: u32, message: String },
+    /// Opcode connu mais qu'aucun étage du pipeline ne sait exécuter
+    UnsupportedInstruction { pc: u32, opcode: Opcode },
+    /// Octet de code à l'adresse `pc` qui ne correspond à aucun opcode
+    IllegalInstruction { pc: u32, byte: u8 },
+    /// Aucune instruction retirée pendant `stalled_cycles` cycles consécutifs
+    Deadlock { pc: u32, stalled_cycles: u64 },
+    /// Autre erreur d'un étage (fetch, execute, memory, writeback)
+    Stage(String),
+}
+
+impl fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PipelineError::Decode { pc, message } => {
+                write!(f, "Decode: {} à PC=0x{:08X}", message, pc)
+            }
+            PipelineError::UnsupportedInstruction { pc, opcode } => {
+                write!(f, "UnsupportedInstruction: {} à PC=0x{:08X}", opcode.name(), pc)
+            }
+            PipelineError::IllegalInstruction { pc, byte } => {
+                write!(f, "IllegalInstruction: opcode 0x{:02X} à PC=0x{:08X}", byte, pc)
+            }
+            PipelineError::Deadlock { pc, stalled_cycles } => {
+                write!(f, "Deadlock: aucun retrait depuis {} cycles à PC=0x{:08X}", stalled_cycles, pc)
+            }
+            PipelineError::Stage(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl From<String> for PipelineError {
+    fn from(msg: String) -> Self {
+        PipelineError::Stage(msg)
+    }
+}
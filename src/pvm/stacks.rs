@@ -192,6 +192,22 @@ impl PunkVM {
     pub fn get_stack_stats(&self) -> StackStats {
         self.stack_stats
     }
+
+    /// Adresses de retour des appels en cours, de l'appel le plus récent au plus ancien.
+    /// Chaque adresse est relue dans la pile matérielle, à l'emplacement où son CALL
+    /// l'a empilée (une adresse écrasée par le programme apparaît donc telle quelle);
+    /// les emplacements au-dessous de SP, déjà dépilés, sont ignorés.
+    pub fn call_stack(&self) -> Vec<u32> {
+        let sp = self.get_sp() as u32;
+        self.pipeline
+            .call_frames()
+            .iter()
+            .rev()
+            .filter(|&&slot| slot >= sp)
+            .filter_map(|&slot| self.memory.peek_qword(slot).ok())
+            .map(|addr| addr as u32)
+            .collect()
+    }
 }
 
 
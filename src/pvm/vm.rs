@@ -1,38 +1,88 @@
 //src/pvm/vm.rs
+use std::collections::HashMap;
+use std::fmt;
 use std::path::Path;
 
-use crate::alu::alu::ALU;
+use crate::alu::alu::{ALUFlags, ALU};
+use crate::alu::fpu::FPU;
+use crate::alu::v_alu::DEFAULT_SIMD_OP_CACHE_SIZE;
+use crate::alu::v_alu::VectorFlags;
+use crate::bytecode::simds::{Vector128, Vector256};
 use crate::alu::agu::AGUStats;
+use crate::bytecode::cfg::{cfg_to_dot, ExecutionProfile};
 use crate::bytecode::files::SegmentType::{Code, Data, ReadOnlyData};
-use crate::debug::{PipelineTracer, TracerConfig};
-use crate::pipeline::Pipeline;
-use crate::pvm::memorys::{Memory, MemoryConfig};
+use crate::bytecode::opcodes::Opcode;
+use crate::bytecode::instructions::{ArgValue, Instruction};
+use crate::debug::{GoldenMismatch, PipelineTracer, TracerConfig};
+use crate::pipeline::{stage_utilization, Pipeline, PipelineCheckpoint, PipelineState, PIPELINE_STAGE_NAMES};
+use crate::pipeline::execute::PrivilegeMode;
+use crate::pvm::harness::{execute_program_with_options, ExecutionResult};
+use crate::pvm::pipeline_errors::PipelineError;
+use crate::pvm::memorys::{Endianness, Memory, MemoryConfig, DEFAULT_MMIO_BASE};
 use crate::pvm::vm_errors::{VMError, VMResult};
 use crate::BytecodeFile;
 use crate::pipeline::ras::RASStats;
 use crate::pvm::stacks::StackStats;
+use crate::pvm::branch_predictor::PredictorType;
+use crate::pvm::cache_configs::{ReplacementPolicy, WritePolicy};
+use crate::pvm::caches::DEFAULT_CACHE_RNG_SEED;
 
+/// Nombre de registres d'arguments de `run_with_input` (R0 à R7)
+pub const INPUT_ARG_REGISTERS: usize = 8;
+/// Adresse où `run_with_input` copie le bloc de données d'entrée
+pub const INPUT_DATA_ADDR: u32 = 0x8000;
+/// Registres adressables par les opérandes (R0 à R15). Les indices 16 (SP), 17 (BP)
+/// et 18 (RA) sont réservés: seules Push/Pop/Call/Ret y accèdent, implicitement.
+pub const NUM_GENERAL_REGISTERS: usize = 16;
 
 
 
 /// Configuration de la machine virtuelle
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct VMConfig {
     pub memory_size: usize,            // Taille de la mémoire
     pub num_registers: usize,          // Nombre de registres
     pub l1_cache_size: usize,          // Taille du cache L1
     pub l2_cache_size: usize,          // Taille du cache L2
     pub store_buffer_size: usize,      // Taille du buffer de stockage
+    pub store_drain_rate: usize,       // Écritures vidées du store buffer par cycle (0 = éviction immédiate)
     pub stack_size: usize,             // Taille de la pile
     pub stack_base: u32,               // Base de la pile
     pub fetch_buffer_size: usize,      // Taille du buffer de fetch
+    pub register_write_ports: usize,   // Ports d'écriture du banc de registres (résultats retirés par cycle)
+    pub issue_width: usize,            // Nombre maximal d'instructions émises par cycle
+    pub mmio_base: u32,                // Base de la fenêtre MMIO (port console à +0)
+    pub l1_write_policy: WritePolicy,  // Politique d'écriture du L1 data
+    pub l2_write_policy: WritePolicy,  // Politique d'écriture du L2 unifié
+    pub cache_replacement_policy: ReplacementPolicy, // Politique de remplacement du L1 data et du L2
+    pub cache_rng_seed: u64,           // Graine de la politique de remplacement Random
+    pub endianness: Endianness,        // Ordre des octets des loads/stores multi-octets
+    pub copy_on_write_memory: bool,    // Mémoire en pages partagées entre forks (copy-on-write)
+    pub max_cycles: Option<u64>,       // Budget de cycles de run() (None = illimité)
+    pub misprediction_penalty: u64,    // Cycles de stall ajoutés après un branchement mal prédit
+    pub enable_branch_fusion: bool,    // Fusion CMP/TEST + saut conditionnel en une micro-op
+    pub l1_latency: u32,               // Latence (cycles) d'une lecture servie par le L1 data
+    pub l2_latency: u32,               // Latence (cycles) d'une lecture servie par le L2
+    pub memory_latency: u32,           // Latence (cycles) d'une lecture manquant L1 et L2
+    pub enable_cache: bool,            // Hiérarchie L1/L2 (sinon accès direct à la mémoire avec memory_latency)
+    pub simd_op_cache_size: usize,     // Opérations SIMD retenues par largeur dans le cache d'opérations
+    pub max_speculation_depth: Option<usize>, // Branchements non résolus en vol avant blocage du fetch (None = illimité)
 
     pub btb_size: usize,               // Taille du BTB (Branch Target Buffer)
     pub ras_size: usize,               // Taille du RAS (Return Address Stack)
+    pub branch_predictor_kind: PredictorType, // Prédicteur de branchement utilisé par le Decode
+    pub branch_predictor_entries: Option<usize>, // Entrées de chaque table du prédicteur (None = tailles par défaut)
 
     pub enable_forwarding: bool,       // Active ou désactive le forwarding
+    pub forward_ex_to_ex: bool,        // Chemin de forwarding EX/MEM -> EX
+    pub forward_mem_to_ex: bool,       // Chemin de forwarding MEM/WB -> EX
     pub enable_hazard_detection: bool, // Active ou désactive la détection de hazards
     pub enable_tracing: bool,          // Active ou désactive le traçage
+    pub trace_sampling_rate: u64,      // Trace l'état du pipeline un cycle sur N (1 = chaque cycle)
+    pub enable_opcode_histogram: bool, // Compte les instructions retirées par opcode
+    pub enable_icache_prefetch: bool,  // Prefetch next-line du cache d'instructions
+    pub enable_simd256: bool,          // Support SIMD 256-bit (sinon les programmes sont abaissés en 128-bit)
+    pub shared_simd_port: bool,        // SIMD et ALU scalaire sur un même port d'émission (pas de co-émission)
 }
 
 impl Default for VMConfig {
@@ -43,28 +93,108 @@ impl Default for VMConfig {
             l1_cache_size: 64 * 1024, // 64KB
             l2_cache_size: 256 * 1024, // 256KB
             store_buffer_size: 8,
+            store_drain_rate: 0,
             stack_size: 64 * 1024, // 64KB
             stack_base: 0xFF000000,
             fetch_buffer_size: 16,
+            register_write_ports: 2,
+            issue_width: 1,
+            mmio_base: DEFAULT_MMIO_BASE,
+            l1_write_policy: WritePolicy::WriteThrough,
+            l2_write_policy: WritePolicy::WriteBack,
+            cache_replacement_policy: ReplacementPolicy::LRU,
+            cache_rng_seed: DEFAULT_CACHE_RNG_SEED,
+            endianness: Endianness::Little,
+            copy_on_write_memory: false,
+            max_cycles: None,
+            misprediction_penalty: 0,
+            enable_branch_fusion: false,
+            l1_latency: 1,
+            l2_latency: 12,
+            memory_latency: 100,
+            enable_cache: true,
+            simd_op_cache_size: DEFAULT_SIMD_OP_CACHE_SIZE,
+            max_speculation_depth: None,
             btb_size: 64,
             ras_size: 8,
+            branch_predictor_kind: PredictorType::Hybrid,
+            branch_predictor_entries: None,
             enable_forwarding: true,
+            forward_ex_to_ex: true,
+            forward_mem_to_ex: true,
             enable_hazard_detection: true,
             enable_tracing: true,
+            trace_sampling_rate: 1,
+            enable_opcode_histogram: false,
+            enable_icache_prefetch: false,
+            enable_simd256: true,
+            shared_simd_port: false,
+        }
+    }
+}
+
+impl VMConfig {
+    /// Construit une configuration à partir des valeurs par défaut, champ par champ:
+    /// `VMConfig::builder().memory_size(128 * 1024).enable_forwarding(false).build()`
+    pub fn builder() -> VMConfigBuilder {
+        VMConfigBuilder { config: VMConfig::default() }
+    }
+}
+
+/// Constructeur fluide de `VMConfig`; chaque champ non renseigné garde sa valeur par défaut
+#[derive(Debug, Clone, Copy)]
+pub struct VMConfigBuilder {
+    config: VMConfig,
+}
+
+/// Un setter par champ de `VMConfig`, du même nom et du même type
+macro_rules! vm_config_setters {
+    ($($field:ident: $ty:ty),* $(,)?) => {
+        impl VMConfigBuilder {
+            $(
+                pub fn $field(mut self, value: $ty) -> Self {
+                    self.config.$field = value;
+                    self
+                }
+            )*
         }
+    };
+}
+
+vm_config_setters!(
+    memory_size: usize, num_registers: usize, l1_cache_size: usize, l2_cache_size: usize,
+    store_buffer_size: usize, store_drain_rate: usize, stack_size: usize, stack_base: u32, fetch_buffer_size: usize,
+    register_write_ports: usize, issue_width: usize, mmio_base: u32,
+    l1_write_policy: WritePolicy, l2_write_policy: WritePolicy, cache_replacement_policy: ReplacementPolicy,
+    cache_rng_seed: u64, endianness: Endianness, copy_on_write_memory: bool,
+    max_cycles: Option<u64>, misprediction_penalty: u64, enable_branch_fusion: bool, l1_latency: u32, l2_latency: u32,
+    memory_latency: u32, enable_cache: bool, simd_op_cache_size: usize, max_speculation_depth: Option<usize>,
+    btb_size: usize, ras_size: usize,
+    branch_predictor_kind: PredictorType, branch_predictor_entries: Option<usize>, enable_forwarding: bool, forward_ex_to_ex: bool,
+    forward_mem_to_ex: bool, enable_hazard_detection: bool, enable_tracing: bool, trace_sampling_rate: u64,
+    enable_opcode_histogram: bool, enable_icache_prefetch: bool, enable_simd256: bool,
+    shared_simd_port: bool,
+);
+
+impl VMConfigBuilder {
+    pub fn build(self) -> VMConfig {
+        self.config
     }
 }
 
 ///Etat de la machine virtuelle
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum VMState {
     Ready,
     Running,
     Halted,
-    Error(String),
+    /// Exécution interrompue par une erreur, conservée typée pour l'appelant
+    Error(VMError),
+    /// Arrêt sur watchpoint: qword à `addr` avant et après l'écriture
+    Watchpoint { addr: u32, old: u64, new: u64 },
 }
 /// Statistiques d'exécution de la VM
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 pub struct VMStats {
     pub cycles: u64,                 // Nombre total de cycles exécutés
     pub instructions_executed: u64,  // Nombre total d'instructions exécutées
@@ -84,6 +214,8 @@ pub struct VMStats {
     pub l1_data_misses: u64,        // Nombre de misses dans le cache L1 data
     pub l1_inst_hits: u64,          // Nombre de hits dans le cache L1 instruction
     pub l1_inst_misses: u64,        // Nombre de misses dans le cache L1 instruction
+    pub icache_prefetch_hits: u64,  // Fetchs servis par une ligne de code préchargée
+    pub instruction_fetch_stalls: u64, // Cycles de fetch bloqués par un miss du cache d'instructions
     pub l2_hits: u64,               // Nombre de hits dans le cache L2
     pub l2_misses: u64,             // Nombre de misses dans le cache L2
     pub l2_writebacks: u64,         // Nombre de write-backs L2
@@ -91,8 +223,21 @@ pub struct VMStats {
     pub memory_accesses: u64,       // Nombre d'accès à la mémoire principale
     pub average_memory_latency: f64, // Latence moyenne mémoire
     pub branch_flush: u64,           // Nombre de flushes de branchements
+    pub squashed_instructions: u64,  // Instructions plus jeunes annulées par les flushes
+    pub speculative_squashes: u64,   // Instructions de la fenêtre spéculative annulées par les flushes
+    pub flush_preserved_instructions: u64, // Instructions plus anciennes conservées lors des flushes
+    pub writeback_port_stalls: u64,         // Stalls structurels faute de port d'écriture
+    pub speculation_stalls: u64,            // Stalls du fetch dus à la limite de spéculation
+    pub memory_stall_cycles: u64,           // Cycles bloqués sur des lectures manquant le L1
+    pub fused_branch_count: u64,            // Paires comparaison + saut conditionnel fusionnées
+    pub fetch_busy_cycles: u64,             // Cycles où l'étage Fetch a chargé une instruction
+    pub decode_busy_cycles: u64,            // Cycles où l'étage Decode a décodé une instruction
+    pub execute_busy_cycles: u64,           // Cycles où l'étage Execute a exécuté une instruction
+    pub memory_busy_cycles: u64,            // Cycles où l'étage Memory a servi un accès (attentes comprises)
+    pub writeback_busy_cycles: u64,         // Cycles où l'étage Writeback a retiré une instruction
     pub branch_predictor: u64,       // Nombre de prédictions de branchements
     pub branch_prediction_rate: f64, // Taux de prédiction de branchements
+    pub branch_mispredictions: u64,  // Nombre de branchements mal prédits
     
     // Statistiques BTB (Branch Target Buffer)
     pub btb_hits: u64,               // Nombre de hits dans le BTB
@@ -156,6 +301,10 @@ pub struct VMStats {
     pub parallel_engine_waw_dependencies: u64,     // Dépendances Write After Write
     pub parallel_engine_dependency_stalls: u64,    // Stalls causés par les dépendances
     pub parallel_engine_resource_conflicts: u64,   // Conflits de ressources
+    pub parallel_engine_alu_issue_blocked: u64,    // Émissions bloquées faute d'ALU libre
+    pub parallel_engine_agu_issue_blocked: u64,    // Émissions bloquées faute d'AGU libre
+    pub parallel_engine_simd_issue_blocked: u64,   // Émissions bloquées faute d'unité SIMD libre
+    pub parallel_engine_shared_port_conflicts: u64, // Co-émissions SIMD/ALU refusées (port partagé)
     
     // Utilisation des unités d'exécution
     pub parallel_engine_alu_utilization: f64,      // Utilisation de l'ALU (%)
@@ -165,11 +314,319 @@ pub struct VMStats {
 
 }
 
+/// Valeur numérique sérialisable en JSON
+trait JsonNumber {
+    fn to_json_number(&self) -> String;
+}
+
+impl JsonNumber for u64 {
+    fn to_json_number(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl JsonNumber for usize {
+    fn to_json_number(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl JsonNumber for f64 {
+    fn to_json_number(&self) -> String {
+        // NaN et l'infini n'existent pas en JSON
+        if self.is_finite() { self.to_string() } else { "null".to_string() }
+    }
+}
+
+impl VMStats {
+    /// Sérialise toutes les statistiques en un objet JSON (ordre des champs fixe)
+    pub fn to_json(&self) -> String {
+        macro_rules! json_fields {
+            ($($field:ident),* $(,)?) => {
+                vec![$(format!("\"{}\":{}", stringify!($field), self.$field.to_json_number())),*]
+            };
+        }
+
+        let fields = json_fields!(
+            cycles, instructions_executed, ipc, stalls, hazards, data_dependencies, forwards,
+            potential_forwards, store_load_forwards, store_load_attempts, l1_data_hits,
+            l1_data_misses, l1_inst_hits, l1_inst_misses,
+            icache_prefetch_hits, instruction_fetch_stalls, l2_hits, l2_misses, l2_writebacks,
+            l2_prefetch_hits, memory_accesses, average_memory_latency, branch_flush,
+            squashed_instructions, speculative_squashes, flush_preserved_instructions, writeback_port_stalls,
+            speculation_stalls,
+            memory_stall_cycles, fused_branch_count, fetch_busy_cycles, decode_busy_cycles, execute_busy_cycles,
+            memory_busy_cycles, writeback_busy_cycles,
+            branch_predictor, branch_prediction_rate, branch_mispredictions, btb_hits, btb_misses, btb_hit_rate,
+            btb_correct_targets, btb_incorrect_targets, btb_accuracy, stack_pushes, stack_pops,
+            stack_hits, stack_misses, stack_accuracy, stack_current_depth, stack_max_depth,
+            simd128_ops, simd256_ops, simd_total_cycles, simd_ops_per_cycle, simd_parallel_ops,
+            simd_cache_hits, simd_cache_misses, simd_cache_hit_rate, agu_total_calculations,
+            agu_early_resolutions, agu_stride_predictions_correct, agu_stride_predictions_total,
+            agu_stride_accuracy, agu_base_cache_hits, agu_base_cache_misses,
+            agu_base_cache_hit_rate, agu_parallel_executions, agu_average_latency,
+            dual_issue_parallel_executions, dual_issue_total_instructions, dual_issue_alu_only,
+            dual_issue_agu_only, dual_issue_resource_conflicts, dual_issue_parallel_rate,
+            parallel_engine_total_instructions, parallel_engine_parallel_executions,
+            parallel_engine_alu_instructions, parallel_engine_agu_instructions,
+            parallel_engine_simd_instructions, parallel_engine_raw_dependencies,
+            parallel_engine_war_dependencies, parallel_engine_waw_dependencies,
+            parallel_engine_dependency_stalls, parallel_engine_resource_conflicts,
+            parallel_engine_alu_issue_blocked, parallel_engine_agu_issue_blocked,
+            parallel_engine_simd_issue_blocked, parallel_engine_shared_port_conflicts,
+            parallel_engine_alu_utilization, parallel_engine_agu_utilization,
+            parallel_engine_average_queue_depth, parallel_engine_parallel_rate
+        );
+        format!("{{{}}}", fields.join(","))
+    }
+
+    /// Taux d'occupation des étages fetch, decode, execute, memory et writeback (0.0 à 1.0)
+    pub fn stage_utilization(&self) -> [f64; 5] {
+        let busy = [
+            self.fetch_busy_cycles,
+            self.decode_busy_cycles,
+            self.execute_busy_cycles,
+            self.memory_busy_cycles,
+            self.writeback_busy_cycles,
+        ];
+        stage_utilization(&busy, self.cycles)
+    }
+}
+
+/// Pourcentage `part / total`, nul si `total` est nul (jamais NaN)
+fn percent(part: u64, total: u64) -> f64 {
+    if total > 0 { part as f64 / total as f64 * 100.0 } else { 0.0 }
+}
+
+/// Rapport structuré par sections (cache, branchements, pile, forwarding, SIMD, AGU,
+/// dual-issue, moteur parallèle); les taux dérivés sont calculés ici
+impl fmt::Display for VMStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "===== STATISTIQUES D'EXÉCUTION =====")?;
+        writeln!(f, "  Cycles: {}", self.cycles)?;
+        writeln!(f, "  Instructions exécutées: {}", self.instructions_executed)?;
+        writeln!(f, "  IPC (Instructions Par Cycle): {:.2}", self.ipc)?;
+        writeln!(f, "  Stalls: {} ({:.2}% des cycles)", self.stalls, percent(self.stalls, self.cycles))?;
+        writeln!(f, "  Hazards: {}", self.hazards)?;
+        writeln!(f, "  Stalls faute de port d'écriture: {}", self.writeback_port_stalls)?;
+        writeln!(f, "  Stalls de limite de spéculation: {}", self.speculation_stalls)?;
+        writeln!(f, "  Stalls de fetch (miss I-cache): {}", self.instruction_fetch_stalls)?;
+
+        writeln!(f, "\n-- Étages du pipeline --")?;
+        for (name, utilization) in PIPELINE_STAGE_NAMES.iter().zip(self.stage_utilization()) {
+            writeln!(f, "  {}: {:.2}% occupé", name, utilization * 100.0)?;
+        }
+
+        writeln!(f, "\n-- Cache Hierarchy Performance --")?;
+        writeln!(f, "  L1 Data Hits/Misses: {}/{}", self.l1_data_hits, self.l1_data_misses)?;
+        writeln!(f, "  L1 Instruction Hits/Misses: {}/{}", self.l1_inst_hits, self.l1_inst_misses)?;
+        writeln!(f, "  I-Cache Prefetch Hits: {}", self.icache_prefetch_hits)?;
+        writeln!(f, "  L2 Hits/Misses: {}/{}", self.l2_hits, self.l2_misses)?;
+        writeln!(f, "  L2 Writebacks: {}", self.l2_writebacks)?;
+        writeln!(f, "  L2 Prefetch Hits: {}", self.l2_prefetch_hits)?;
+        writeln!(f, "  Memory Accesses: {}", self.memory_accesses)?;
+        writeln!(f, "  L1 Data Hit Rate: {:.2}%", percent(self.l1_data_hits, self.l1_data_hits + self.l1_data_misses))?;
+        writeln!(f, "  L2 Hit Rate: {:.2}%", percent(self.l2_hits, self.l2_hits + self.l2_misses))?;
+        writeln!(
+            f,
+            "  Taux de hits global (L1+L2): {:.2}%",
+            percent(
+                self.l1_data_hits + self.l2_hits,
+                self.l1_data_hits + self.l1_data_misses + self.l2_hits + self.l2_misses
+            )
+        )?;
+        writeln!(f, "  Average Memory Latency: {:.2} cycles", self.average_memory_latency)?;
+        writeln!(f, "  Memory Stall Cycles: {}", self.memory_stall_cycles)?;
+
+        writeln!(f, "\n-- Branchements --")?;
+        writeln!(f, "  Prédictions: {}", self.branch_predictor)?;
+        writeln!(f, "  Mauvaises prédictions: {}", self.branch_mispredictions)?;
+        writeln!(f, "  Sauts fusionnés (CMP+Jcc): {}", self.fused_branch_count)?;
+        writeln!(f, "  Taux de prédiction: {:.2}%", self.branch_prediction_rate)?;
+        writeln!(
+            f,
+            "  Flushes: {} ({} instruction(s) annulée(s), {} conservée(s))",
+            self.branch_flush, self.squashed_instructions, self.flush_preserved_instructions
+        )?;
+        writeln!(f, "  Instructions spéculatives annulées: {}", self.speculative_squashes)?;
+        writeln!(f, "  BTB Hits/Misses: {}/{} ({:.2}%)", self.btb_hits, self.btb_misses, percent(self.btb_hits, self.btb_hits + self.btb_misses))?;
+        writeln!(
+            f,
+            "  BTB Accuracy: {:.2}% ({}/{})",
+            percent(self.btb_correct_targets, self.btb_correct_targets + self.btb_incorrect_targets),
+            self.btb_correct_targets,
+            self.btb_correct_targets + self.btb_incorrect_targets
+        )?;
+
+        writeln!(f, "\n-- Pile --")?;
+        writeln!(f, "  Push/Pop: {}/{}", self.stack_pushes, self.stack_pops)?;
+        writeln!(f, "  Hits/Misses: {}/{}", self.stack_hits, self.stack_misses)?;
+        writeln!(f, "  Profondeur max/actuelle: {}/{}", self.stack_max_depth, self.stack_current_depth)?;
+
+        writeln!(f, "\n-- Forwarding --")?;
+        writeln!(f, "  Dépendances de données: {}", self.data_dependencies)?;
+        writeln!(
+            f,
+            "  Efficacité du forwarding: {:.2}% ({}/{})",
+            percent(self.forwards, self.potential_forwards),
+            self.forwards,
+            self.potential_forwards
+        )?;
+        writeln!(
+            f,
+            "  Efficacité Store-Load forwarding: {:.2}% ({}/{})",
+            percent(self.store_load_forwards, self.store_load_attempts),
+            self.store_load_forwards,
+            self.store_load_attempts
+        )?;
+
+        let simd_ops = self.simd128_ops + self.simd256_ops;
+        writeln!(f, "\n-- SIMD --")?;
+        writeln!(f, "  Opérations 128/256-bit: {}/{}", self.simd128_ops, self.simd256_ops)?;
+        writeln!(f, "  Cycles SIMD: {} ({:.2} op/cycle)", self.simd_total_cycles, self.simd_ops_per_cycle)?;
+        writeln!(f, "  Taux de parallélisation: {:.2}%", percent(self.simd_parallel_ops, simd_ops))?;
+        writeln!(
+            f,
+            "  Cache d'opérations: {} hits, {} misses ({:.2}%)",
+            self.simd_cache_hits,
+            self.simd_cache_misses,
+            percent(self.simd_cache_hits, self.simd_cache_hits + self.simd_cache_misses)
+        )?;
+
+        writeln!(f, "\n-- AGU --")?;
+        writeln!(f, "  Calculs d'adresse: {}", self.agu_total_calculations)?;
+        writeln!(
+            f,
+            "  Résolutions anticipées: {} ({:.2}%)",
+            self.agu_early_resolutions,
+            percent(self.agu_early_resolutions, self.agu_total_calculations)
+        )?;
+        writeln!(
+            f,
+            "  Stride predictor: {:.2}% ({}/{})",
+            percent(self.agu_stride_predictions_correct, self.agu_stride_predictions_total),
+            self.agu_stride_predictions_correct,
+            self.agu_stride_predictions_total
+        )?;
+        writeln!(
+            f,
+            "  Base address cache: {:.2}% ({}/{})",
+            percent(self.agu_base_cache_hits, self.agu_base_cache_hits + self.agu_base_cache_misses),
+            self.agu_base_cache_hits,
+            self.agu_base_cache_hits + self.agu_base_cache_misses
+        )?;
+        writeln!(f, "  Exécutions parallèles AGU/ALU: {}", self.agu_parallel_executions)?;
+        writeln!(f, "  Latence moyenne: {:.2} cycles", self.agu_average_latency)?;
+        writeln!(f, "  Utilisation: {:.2}% des cycles", percent(self.agu_total_calculations, self.cycles))?;
+
+        writeln!(f, "\n-- Dual-Issue --")?;
+        writeln!(f, "  Instructions traitées: {}", self.dual_issue_total_instructions)?;
+        writeln!(
+            f,
+            "  Exécutions parallèles: {} ({:.2}%)",
+            self.dual_issue_parallel_executions, self.dual_issue_parallel_rate
+        )?;
+        let theoretical_max = self.dual_issue_total_instructions / 2;
+        writeln!(
+            f,
+            "  Efficacité théorique: {:.2}% ({}/{} max)",
+            percent(self.dual_issue_parallel_executions, theoretical_max),
+            self.dual_issue_parallel_executions,
+            theoretical_max
+        )?;
+        writeln!(
+            f,
+            "  Ratio ALU/AGU: {:.2}%/{:.2}%",
+            percent(self.dual_issue_alu_only, self.dual_issue_total_instructions),
+            percent(self.dual_issue_agu_only, self.dual_issue_total_instructions)
+        )?;
+        writeln!(f, "  Conflits de ressources: {}", self.dual_issue_resource_conflicts)?;
+
+        writeln!(f, "\n-- Parallel Engine --")?;
+        writeln!(
+            f,
+            "  Instructions ALU/AGU/SIMD: {}/{}/{} sur {}",
+            self.parallel_engine_alu_instructions,
+            self.parallel_engine_agu_instructions,
+            self.parallel_engine_simd_instructions,
+            self.parallel_engine_total_instructions
+        )?;
+        writeln!(
+            f,
+            "  Exécutions parallèles: {} ({:.2}%)",
+            self.parallel_engine_parallel_executions, self.parallel_engine_parallel_rate
+        )?;
+        writeln!(
+            f,
+            "  Dépendances RAW/WAR/WAW: {}/{}/{}",
+            self.parallel_engine_raw_dependencies,
+            self.parallel_engine_war_dependencies,
+            self.parallel_engine_waw_dependencies
+        )?;
+        writeln!(f, "  Stalls de dépendances: {}", self.parallel_engine_dependency_stalls)?;
+        writeln!(f, "  Conflits de ressources: {}", self.parallel_engine_resource_conflicts)?;
+        writeln!(
+            f,
+            "  Utilisation ALU/AGU: {:.2}%/{:.2}%",
+            self.parallel_engine_alu_utilization, self.parallel_engine_agu_utilization
+        )?;
+        writeln!(f, "  Profondeur moyenne des queues: {:.2}", self.parallel_engine_average_queue_depth)?;
+        writeln!(
+            f,
+            "  Émissions bloquées (ALU/AGU/SIMD): {}/{}/{}",
+            self.parallel_engine_alu_issue_blocked,
+            self.parallel_engine_agu_issue_blocked,
+            self.parallel_engine_simd_issue_blocked
+        )?;
+        write!(f, "  Conflits de port ALU/SIMD partagé: {}", self.parallel_engine_shared_port_conflicts)
+    }
+}
+
+/// Copie complète de l'état de la VM produite par `PunkVM::snapshot`, y compris l'état
+/// appris du prédicteur de branchement et la sortie console non consommée.
+/// Les caches ne sont pas capturés: ils repartent à froid après `PunkVM::restore`.
+/// Les métriques du prédicteur ne sont pas restaurées non plus.
+#[derive(Debug, Clone)]
+pub struct VmSnapshot {
+    pub state: VMState,
+    pub pc: usize,
+    pub registers: Vec<u64>,
+    pub flags: ALUFlags,
+    pub v128_registers: [Vector128; 16],
+    pub v256_registers: [Vector256; 16],
+    pub vector_flags: VectorFlags,
+    pub fpu: FPU,
+    pub memory: Vec<u8>,
+    pub pipeline: PipelineCheckpoint,
+    pub branch_predictor: Vec<u8>,
+    pub console_output: Vec<u8>,
+    pub cycles: u64,
+    pub instructions_executed: u64,
+}
+
+impl fmt::Display for VMState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VMState::Ready => write!(f, "prête"),
+            VMState::Running => write!(f, "en cours"),
+            VMState::Halted => write!(f, "arrêtée (HALT)"),
+            VMState::Error(msg) => write!(f, "erreur: {}", msg),
+            VMState::Watchpoint { addr, old, new } => {
+                write!(f, "watchpoint 0x{:08X}: 0x{:X} -> 0x{:X}", addr, old, new)
+            }
+        }
+    }
+}
+
+/// Gestionnaire des services hôte appelé par `Syscall` (numéro de service dans R0).
+/// Il peut lire et modifier les registres et la mémoire avant la reprise du programme.
+pub type SyscallHandler = Box<dyn FnMut(&mut [u64], &mut Memory) -> VMResult<()>>;
+
 /// Machine virtuelle PunkVM
 pub struct PunkVM {
     pub config: VMConfig,
     pub state: VMState,
-    pipeline: Pipeline,
+    pub(crate) pipeline: Pipeline,
     alu: ALU,
     pub memory: Memory,
     pub pc: usize,                     // Compteur de programme
@@ -179,6 +636,7 @@ pub struct PunkVM {
     instructions_executed: u64,        // Nombre d'instructions exécutées
     pub tracer: Option<PipelineTracer>,    // Tracer pour le débogage
     pub stack_stats: StackStats,       // Statistiques de la pile
+    syscall_handler: Option<SyscallHandler>, // Gestionnaire des Syscall
 
 }
 
@@ -195,16 +653,27 @@ impl PunkVM {
             l1_cache_size: config.l1_cache_size,
             l2_cache_size: config.l2_cache_size,
             store_buffer_size: config.store_buffer_size,
+            store_drain_rate: config.store_drain_rate,
+            mmio_base: config.mmio_base,
+            l1_write_policy: config.l1_write_policy,
+            l2_write_policy: config.l2_write_policy,
+            icache_prefetch: config.enable_icache_prefetch,
+            copy_on_write: config.copy_on_write_memory,
+            l1_latency: config.l1_latency,
+            l2_latency: config.l2_latency,
+            memory_latency: config.memory_latency,
+            enable_cache: config.enable_cache,
+            replacement_policy: config.cache_replacement_policy,
+            cache_rng_seed: config.cache_rng_seed,
+            endianness: config.endianness,
         };
 
+        let pipeline = Self::build_pipeline(&config);
+
         Self {
             config, // Pas besoin de cloner, car VMConfig implémente Copy
             state: VMState::Ready,
-            pipeline: Pipeline::new(
-                config.fetch_buffer_size,
-                config.enable_forwarding,
-                config.enable_hazard_detection,
-            ),
+            pipeline,
             alu: ALU::new(),
             memory: Memory::new(memory_config),
             pc: 0,
@@ -214,7 +683,34 @@ impl PunkVM {
             instructions_executed: 0,
             tracer: None, // Pas de traçage par défaut
             stack_stats: StackStats::new(), // Initialiser les statistiques de pile
+            syscall_handler: None,
+        }
+    }
+
+    /// Pipeline neuf (prédicteurs non entraînés) configuré selon `config`
+    fn build_pipeline(config: &VMConfig) -> Pipeline {
+        let mut pipeline = Pipeline::new(
+            config.fetch_buffer_size,
+            config.enable_forwarding,
+            config.enable_hazard_detection,
+            config.branch_predictor_kind,
+            config.enable_opcode_histogram,
+            config.register_write_ports,
+            config.issue_width,
+        );
+        pipeline.set_forwarding_paths(config.forward_ex_to_ex, config.forward_mem_to_ex);
+        pipeline.set_misprediction_penalty(config.misprediction_penalty);
+        pipeline.set_branch_fusion(config.enable_branch_fusion);
+        pipeline.set_ras_size(config.ras_size);
+        pipeline.set_stack_bounds(config.stack_base, config.stack_size);
+        pipeline.set_store_buffer_size(config.store_buffer_size);
+        if let Some(entries) = config.branch_predictor_entries {
+            pipeline.set_branch_predictor_entries(entries);
         }
+        pipeline.set_max_speculation_depth(config.max_speculation_depth);
+        pipeline.set_simd_op_cache_size(config.simd_op_cache_size);
+        pipeline.set_shared_simd_port(config.shared_simd_port);
+        pipeline
     }
 
     // Active le traçage
@@ -223,7 +719,9 @@ impl PunkVM {
             println!("Tracing is enabled");
             // self.tracer = Some(PipelineTracer::new(config));
             // self.tracer = Some(PipelineTracer::new(Default::default()));
-            self.tracer = Some(PipelineTracer::new(config));
+            let mut tracer = PipelineTracer::new(config);
+            tracer.set_sampling_rate(self.config.trace_sampling_rate);
+            self.tracer = Some(tracer);
         } else {
             println!("Tracing is disabled");
         }
@@ -233,12 +731,7 @@ impl PunkVM {
     pub fn load_program<P: AsRef<Path>>(&mut self, path: P) -> VMResult<()> /*io::Result<()>*/ {
         // let program = BytecodeFile::read_from_file(path)?;
         // self.load_program_from_bytecode(program)
-        let program = BytecodeFile::read_from_file(path).map_err(|io_err| {
-            VMError::execution_error(&format!(
-                "Impossible de lire le fichier bytecode: {}",
-                io_err
-            ))
-        })?;
+        let program = BytecodeFile::read_from_file(path)?;
         self.load_program_from_bytecode(program)
     }
 
@@ -247,11 +740,66 @@ impl PunkVM {
         // Réinitialiser l'état de la VM avant de charger
         self.reset();
 
-        // Charger le code en mémoire
-        self.load_code_segment(&program)?;
+        // Sans support 256-bit, les instructions SIMD 256-bit sont réécrites en paires 128-bit
+        let program = if self.config.enable_simd256 {
+            program
+        } else {
+            program.lower_simd256_to_simd128().map_err(|e| VMError::config_error(&e))?
+        };
 
-        // Charger les segments de données
-        self.load_data_segments(&program)?;
+        // Un registre hors limites serait masqué ou écraserait un registre spécial
+        let register_limit = NUM_GENERAL_REGISTERS.min(self.config.num_registers);
+        for instruction in &program.code {
+            if let Some(&index) = instruction.register_operands().iter().find(|&&r| r as usize >= register_limit) {
+                return Err(VMError::InvalidRegister { index });
+            }
+        }
+
+        // Avertissements sur les encodages suspects (non bloquants)
+        for lint in program.lint() {
+            println!("WARNING lint: {}", lint);
+        }
+
+        self.install_program(program)
+    }
+
+    /// Relance le programme chargé depuis le début sans le relire: registres, flags,
+    /// mémoire (code et segments de données réécrits), pipeline, prédicteurs, caches et statistiques
+    /// reviennent à leur état initial. Le traceur, le gestionnaire de syscall et les watchpoints
+    /// sont conservés. En cas d'échec, le programme reste chargé.
+    pub fn restart(&mut self) -> VMResult<()> {
+        let program = self
+            .program
+            .take()
+            .ok_or_else(|| VMError::execution_error("Aucun programme chargé"))?;
+        // Prédicteurs, BTB et flags repartent de zéro, comme après la construction
+        let watchpoints = self.pipeline.watchpoints();
+        self.pipeline = Self::build_pipeline(&self.config);
+        for addr in watchpoints {
+            self.pipeline.add_watchpoint(addr);
+        }
+        self.alu = ALU::new();
+        self.reset();
+        let result = self.write_program(&program);
+        self.program = Some(program);
+        result?;
+        self.state = VMState::Ready;
+        Ok(())
+    }
+
+    /// Zones [début, fin) des segments en lecture seule de `program`
+    fn readonly_ranges(program: &BytecodeFile) -> Vec<(u32, u32)> {
+        program
+            .segments
+            .iter()
+            .filter(|s| s.segment_type == ReadOnlyData && s.size > 0)
+            .map(|s| (s.load_addr, s.load_addr.saturating_add(s.size)))
+            .collect()
+    }
+
+    /// Installe `program` comme programme chargé, prêt à s'exécuter
+    fn install_program(&mut self, program: BytecodeFile) -> VMResult<()> {
+        self.write_program(&program)?;
 
         // Stocker le BytecodeFile
         self.program = Some(program);
@@ -261,6 +809,21 @@ impl PunkVM {
         Ok(())
     }
 
+    /// Écrit le code et les données de `program` en mémoire et place le PC sur sa première instruction
+    fn write_program(&mut self, program: &BytecodeFile) -> VMResult<()> {
+        // Charger le code en mémoire
+        self.load_code_segment(program)?;
+
+        // Charger les segments de données
+        self.load_data_segments(program)?;
+
+        // L'exécution commence à la première instruction du segment de code
+        self.pc = program.code_load_addr() as usize;
+        self.pipeline.set_code_base(program.code_load_addr());
+        self.pipeline.set_readonly_ranges(Self::readonly_ranges(program));
+        Ok(())
+    }
+
     /// Exécute le programme chargé jusqu'à la fin ou jusqu'à une erreur
     pub fn run(&mut self) -> VMResult<()> {
         if self.program.is_none() {
@@ -274,6 +837,19 @@ impl PunkVM {
                 break;
             }
 
+            // Garde-fou contre les programmes qui bouclent indéfiniment
+            if let Some(limit) = self.config.max_cycles {
+                if self.cycles >= limit {
+                    let err = VMError::CycleLimitExceeded { cycles: self.cycles };
+                    self.state = VMState::Error(err.clone());
+                    return Err(err);
+                }
+            }
+
+            if let Some(tracer) = &mut self.tracer {
+                tracer.start_cycle(self.cycles);
+            }
+
             // Capturer et logguer toute erreur du pipeline
             let pipeline_result = self.pipeline.cycle(
                 self.pc as u32,
@@ -285,25 +861,31 @@ impl PunkVM {
 
             match pipeline_result {
                 Ok(pipeline_state) => {
+                    if let Some(tracer) = &mut self.tracer {
+                        tracer.trace_pipeline_state(&pipeline_state, &self.registers);
+                    }
+
                     self.pc = pipeline_state.next_pc as usize;
                     self.cycles += 1;
                     self.instructions_executed += pipeline_state.instructions_completed as u64;
+                    self.dispatch_syscall()?;
 
                     // Si le pipeline signale qu'il est halted => on arrête
                     if pipeline_state.halted {
                         self.state = VMState::Halted;
+                        self.drain_memory_on_halt()?;
                         break;
                     }
+                    self.check_watchpoint();
                 }
                 Err(err) => {
                     // Si l'erreur est due à HALT, convertir en VMState::Halted
                     if self.state == VMState::Halted {
+                        self.drain_memory_on_halt()?;
                         break;
                     } else {
                         // Sinon propager l'erreur
-                        let vm_err = VMError::execution_error(&format!("Erreur pipeline: {}", err));
-                        self.state = VMState::Error(vm_err.to_string());
-                        return Err(vm_err);
+                        return Err(self.pipeline_error(err));
                     }
                 }
             }
@@ -312,11 +894,287 @@ impl PunkVM {
         // Si on sort de la boucle, c'est soit Halted, soit Error
         match &self.state {
             VMState::Halted => Ok(()),
-            VMState::Error(msg) => Err(VMError::execution_error(msg)),
+            VMState::Error(err) => Err(err.clone()),
             _ => Ok(()),
         }
     }
 
+    /// Vide le store buffer à l'arrêt pour qu'aucune écriture ne reste en attente.
+    /// Les lignes de cache sales n'ont pas à être vidées: chaque écriture atteint aussi
+    /// la mémoire principale (`Memory::write_byte`), les caches ne modélisant que la latence.
+    fn drain_memory_on_halt(&mut self) -> VMResult<()> {
+        self.memory.flush_store_buffer()?;
+        debug_assert_eq!(self.memory.pending_stores(), 0, "écritures en attente après HALT");
+        Ok(())
+    }
+
+    /// Vide les caches L1: les lignes sales sont réécrites dans le L2, les accès suivants
+    /// manquent le L1 (mesure du comportement à froid entre deux phases)
+    pub fn flush_l1(&mut self) -> VMResult<()> {
+        self.memory.flush_l1()?;
+        Ok(())
+    }
+
+    /// Vide le cache L2: ses lignes sales sont réécrites en mémoire et comptées dans `l2_writebacks`
+    pub fn flush_l2(&mut self) {
+        self.memory.flush_l2();
+    }
+
+    /// Vide L1 puis L2: le prochain accès à chaque adresse repart de la mémoire principale
+    pub fn flush_all_caches(&mut self) -> VMResult<()> {
+        self.memory.flush_all_caches()?;
+        Ok(())
+    }
+
+    /// Arrête l'exécution (état `Watchpoint`) à la prochaine écriture couvrant `addr`:
+    /// STORE, STM ou store SIMD dont la plage recouvre l'adresse
+    pub fn add_watchpoint(&mut self, addr: u32) {
+        self.pipeline.add_watchpoint(addr);
+    }
+
+    /// Passe la VM en état `Watchpoint` si une écriture surveillée a eu lieu au dernier cycle
+    fn check_watchpoint(&mut self) {
+        if let Some((addr, old, new)) = self.pipeline.take_watchpoint_hit() {
+            self.state = VMState::Watchpoint { addr, old, new };
+        }
+    }
+
+    /// Enregistre le gestionnaire appelé par chaque `Syscall`
+    pub fn set_syscall_handler(&mut self, handler: SyscallHandler) {
+        self.syscall_handler = Some(handler);
+    }
+
+    /// Sert le Syscall exécuté au dernier cycle. Sans gestionnaire, ou si le
+    /// gestionnaire échoue, la VM passe en état d'erreur.
+    fn dispatch_syscall(&mut self) -> VMResult<()> {
+        let Some(pc) = self.pipeline.take_syscall() else {
+            return Ok(());
+        };
+        let result = match self.syscall_handler.as_mut() {
+            Some(handler) => handler(&mut self.registers, &mut self.memory),
+            None => Err(VMError::UnhandledSyscall { number: self.registers[0], pc }),
+        };
+        if let Err(err) = &result {
+            self.state = VMState::Error(err.clone());
+        }
+        result
+    }
+
+    /// Convertit une erreur du pipeline en VMError et passe la VM en état d'erreur.
+    /// Une exception matérielle (ex: division par zéro) est remontée telle quelle.
+    fn pipeline_error(&mut self, err: PipelineError) -> VMError {
+        let vm_err = self.pipeline.take_fault().unwrap_or_else(|| VMError::from(err));
+        self.state = VMState::Error(vm_err.clone());
+        vm_err
+    }
+
+    /// Exécute le programme chargé avec des entrées, selon la convention d'appel suivante:
+    /// `args[i]` est placé dans Ri (au plus `INPUT_ARG_REGISTERS` arguments) et `data`
+    /// est copié en mémoire à partir de `INPUT_DATA_ADDR`. Le résultat est lu dans R0.
+    pub fn run_with_input(&mut self, args: &[u64], data: &[u8]) -> VMResult<()> {
+        if args.len() > INPUT_ARG_REGISTERS {
+            return Err(VMError::config_error(&format!(
+                "{} arguments fournis, {} registres d'arguments disponibles",
+                args.len(),
+                INPUT_ARG_REGISTERS
+            )));
+        }
+        if INPUT_DATA_ADDR as usize + data.len() > self.memory.size() {
+            return Err(VMError::MemoryOutOfBounds { addr: INPUT_DATA_ADDR as u64, size: data.len() });
+        }
+
+        self.registers[..args.len()].copy_from_slice(args);
+        self.memory.write_block(INPUT_DATA_ADDR, data)?;
+        self.run()
+    }
+
+    /// Capture l'état complet de la VM (registres, mémoire, registres intermédiaires du pipeline)
+    pub fn snapshot(&self) -> VmSnapshot {
+        self.capture(self.memory.snapshot())
+    }
+
+    /// Capture l'état de la VM avec le contenu mémoire fourni
+    fn capture(&self, memory: Vec<u8>) -> VmSnapshot {
+        let execute = self.pipeline.get_execute_stage();
+        let vector_alu = execute.get_vector_alu_ref().borrow();
+        VmSnapshot {
+            state: self.state.clone(),
+            pc: self.pc,
+            registers: self.registers.clone(),
+            flags: self.alu.flags,
+            v128_registers: vector_alu.v128_registers,
+            v256_registers: vector_alu.v256_registers,
+            vector_flags: vector_alu.flags,
+            fpu: execute.get_fpu_ref().borrow().clone(),
+            memory,
+            pipeline: self.pipeline.checkpoint(),
+            branch_predictor: self.export_branch_predictor_state(),
+            console_output: self.memory.console_output().to_vec(),
+            cycles: self.cycles,
+            instructions_executed: self.instructions_executed,
+        }
+    }
+
+    /// Revient à un état capturé par `snapshot` sur une VM de même configuration
+    pub fn restore(&mut self, snapshot: &VmSnapshot) -> VMResult<()> {
+        if snapshot.registers.len() != self.registers.len() {
+            return Err(VMError::config_error("Snapshot incompatible: nombre de registres différent"));
+        }
+        self.import_branch_predictor_state(&snapshot.branch_predictor)?;
+        self.memory.restore(&snapshot.memory)?;
+        self.memory.restore_console_output(&snapshot.console_output);
+        self.restore_cpu(snapshot);
+        Ok(())
+    }
+
+    /// Restaure tout l'état capturé sauf la mémoire
+    fn restore_cpu(&mut self, snapshot: &VmSnapshot) {
+        self.state = snapshot.state.clone();
+        self.pc = snapshot.pc;
+        self.registers.copy_from_slice(&snapshot.registers);
+        self.alu.flags = snapshot.flags;
+        self.cycles = snapshot.cycles;
+        self.instructions_executed = snapshot.instructions_executed;
+
+        self.pipeline.restore_checkpoint(&snapshot.pipeline);
+        let execute = self.pipeline.get_execute_stage();
+        {
+            let mut vector_alu = execute.get_vector_alu_ref().borrow_mut();
+            vector_alu.v128_registers = snapshot.v128_registers;
+            vector_alu.v256_registers = snapshot.v256_registers;
+            vector_alu.flags = snapshot.vector_flags;
+        }
+        *execute.get_fpu_ref().borrow_mut() = snapshot.fpu.clone();
+    }
+
+    /// Crée une VM indépendante au même point d'exécution (programme, registres, pipeline,
+    /// mémoire). Avec `copy_on_write_memory`, la mémoire n'est pas copiée: les deux VM
+    /// partagent ses pages jusqu'à ce que l'une d'elles y écrive. Le traçage et le
+    /// gestionnaire de syscalls ne sont pas hérités.
+    pub fn fork(&self) -> PunkVM {
+        let mut forked = PunkVM::with_config(VMConfig { memory_size: 0, ..self.config });
+        forked.config = self.config;
+        forked.memory = self.memory.fork();
+        forked.program = self.program.clone();
+        if let Some(program) = &forked.program {
+            forked.pipeline.set_code_base(program.code_load_addr());
+            forked.pipeline.set_readonly_ranges(Self::readonly_ranges(program));
+        }
+        forked.restore_cpu(&self.capture(Vec::new()));
+        forked
+    }
+
+    /// Écrit des octets directement en mémoire principale (sans cache), par exemple
+    /// pour précharger les données d'un programme
+    pub fn write_memory(&mut self, addr: u32, bytes: &[u8]) -> VMResult<()> {
+        self.memory
+            .write_direct(addr, bytes)
+            .map_err(|_| VMError::MemoryOutOfBounds { addr: addr as u64, size: bytes.len() })
+    }
+
+    /// Lit des octets directement en mémoire principale (sans cache)
+    pub fn read_memory(&self, addr: u32, len: usize) -> VMResult<Vec<u8>> {
+        self.memory
+            .read_direct(addr, len)
+            .map_err(|_| VMError::MemoryOutOfBounds { addr: addr as u64, size: len })
+    }
+
+    /// Dump hexadécimal + ASCII (style `xxd`) de `len` octets à partir de `addr`, lus
+    /// directement en mémoire principale: 16 octets par ligne précédés de leur adresse,
+    /// les octets non imprimables affichés `.`. La plage est tronquée à la fin de la mémoire.
+    pub fn dump_memory(&self, addr: u32, len: usize) -> String {
+        use std::fmt::Write as _;
+
+        let len = len.min(self.memory.size().saturating_sub(addr as usize));
+        let bytes = self.memory.read_direct(addr, len).unwrap_or_default();
+        let mut dump = String::new();
+        for (line, chunk) in bytes.chunks(16).enumerate() {
+            let _ = write!(dump, "{:08x}:", addr as usize + line * 16);
+            for column in 0..16 {
+                if column % 2 == 0 {
+                    dump.push(' ');
+                }
+                match chunk.get(column) {
+                    Some(byte) => { let _ = write!(dump, "{:02x}", byte); }
+                    None => dump.push_str("  "),
+                }
+            }
+            dump.push_str("  ");
+            dump.extend(chunk.iter().map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' }));
+            dump.push('\n');
+        }
+        dump
+    }
+
+    /// Ajoute `instrs` après le code courant (voir `BytecodeFile::append_program`) et les
+    /// exécute à partir de la première instruction ajoutée, en conservant registres et
+    /// mémoire: chaque appel prolonge la session précédente, à la manière d'un REPL.
+    pub fn append_and_run(&mut self, instrs: &[Instruction]) -> VMResult<VMState> {
+        let register_limit = NUM_GENERAL_REGISTERS.min(self.config.num_registers);
+        for instruction in instrs {
+            if let Some(&index) = instruction.register_operands().iter().find(|&&r| r as usize >= register_limit) {
+                return Err(VMError::InvalidRegister { index });
+            }
+        }
+
+        let program = self.program
+            .as_mut()
+            .ok_or_else(|| VMError::execution_error("Aucun programme chargé"))?;
+        let start = program.append_program(instrs).map_err(|e| VMError::config_error(&e))?;
+        let code_start = program.code_load_addr();
+        let code_bytes: Vec<u8> = instrs.iter().flat_map(|instr| instr.encode()).collect();
+        self.memory
+            .write_block(code_start + start, &code_bytes)
+            .map_err(|_| VMError::memory_error("Échec d'écriture du code en mémoire"))?;
+
+        self.pipeline.clear_in_flight();
+        self.pc = (code_start + start) as usize;
+        self.run()?;
+        Ok(self.state.clone())
+    }
+
+    /// Exécute des cycles jusqu'à ce que la prochaine instruction à charger soit à `pc`
+    /// (sans l'exécuter). Retourne `Ok(true)` si `pc` est atteint, `Ok(false)` si le
+    /// programme s'arrête avant. Ne fait rien si la VM est déjà arrêtée sur `pc`.
+    pub fn run_to_pc(&mut self, pc: u32) -> VMResult<bool> {
+        self.step_until(|vm| vm.pc as u32 == pc)
+    }
+
+    /// Exécute des cycles jusqu'à ce que l'instruction à `target_pc` atteigne le writeback
+    /// (premier retrait, même dans une boucle), puis s'arrête. Si `target_pc` n'est jamais
+    /// atteint, l'exécution va jusqu'au HALT. Retourne l'état de la VM à l'arrêt.
+    pub fn run_until(&mut self, target_pc: u32) -> VMResult<VMState> {
+        let retired_before = self.execution_profile().execution_count(target_pc);
+        self.step_until(|vm| vm.execution_profile().execution_count(target_pc) > retired_before)?;
+        Ok(self.state.clone())
+    }
+
+    /// Avance cycle par cycle tant que la VM tourne, en vérifiant `stop` avant chaque
+    /// cycle et en respectant `max_cycles`. Retourne `true` si `stop` a arrêté l'exécution.
+    fn step_until(&mut self, mut stop: impl FnMut(&PunkVM) -> bool) -> VMResult<bool> {
+        if self.program.is_none() {
+            return Err(VMError::execution_error("Aucun programme chargé"));
+        }
+        if self.state == VMState::Ready {
+            self.state = VMState::Running;
+        }
+
+        while self.state == VMState::Running {
+            if stop(self) {
+                return Ok(true);
+            }
+            if let Some(limit) = self.config.max_cycles {
+                if self.cycles >= limit {
+                    let err = VMError::CycleLimitExceeded { cycles: self.cycles };
+                    self.state = VMState::Error(err.clone());
+                    return Err(err);
+                }
+            }
+            self.step()?;
+        }
+        Ok(false)
+    }
+
     /// Exécute un seul cycle du pipeline
     pub fn step(&mut self) -> VMResult<()> {
         if self.state != VMState::Running {
@@ -333,18 +1191,17 @@ impl PunkVM {
 
         // Exécution d'un cycle pipeline
         let program_code = &self.program.as_ref().unwrap().code;
-        let pipeline_state = self
-            .pipeline
-            .cycle(
-                self.pc as u32,
-                &mut self.registers,
-                &mut self.memory,
-                &mut self.alu,
-                program_code,
-            )
-            .map_err(|pipe_err| {
-                VMError::execution_error(&format!("Erreur pipeline: {}", pipe_err))
-            })?;
+        let pipeline_result = self.pipeline.cycle(
+            self.pc as u32,
+            &mut self.registers,
+            &mut self.memory,
+            &mut self.alu,
+            program_code,
+        );
+        let pipeline_state = match pipeline_result {
+            Ok(pipeline_state) => pipeline_state,
+            Err(pipe_err) => return Err(self.pipeline_error(pipe_err)),
+        };
 
 
         // Ici on va commencer à tracer l'état du pipeline
@@ -359,16 +1216,20 @@ impl PunkVM {
         // Mise à jour compteurs
         self.cycles += 1;
         self.instructions_executed += pipeline_state.instructions_completed as u64;
+        self.dispatch_syscall()?;
 
 
         // Vérifier s'il y a un halt
         if pipeline_state.halted {
             self.state = VMState::Halted;
+            self.drain_memory_on_halt()?;
 
             //genere un rapport de synthese si le trace est active
             if let Some(tracer) = &self.tracer {
                 println!("\n{}", tracer.generate_summary())
             }
+        } else {
+            self.check_watchpoint();
         }
 
         Ok(())
@@ -377,7 +1238,7 @@ impl PunkVM {
     // /// Réinitialise la machine virtuelle
     pub fn reset(&mut self) {
         println!("PunkVM::reset() - début");
-        self.pc = 0;
+        self.pc = self.program.as_ref().map_or(0, |program| program.code_load_addr() as usize);
         self.registers = vec![0; self.config.num_registers];
         self.cycles = 0;
         self.instructions_executed = 0;
@@ -398,6 +1259,7 @@ impl PunkVM {
         // let ras_stats = self.ras.get_ras_stats();
         let (mem_pushes, mem_pops, mem_overflow, mem_underflow) = self.pipeline.get_memory_stack_stats();
         let (btb_hits, btb_misses, btb_hit_rate, btb_correct_targets, btb_incorrect_targets, btb_accuracy) = self.pipeline.get_btb_stats();
+        let stage_busy = self.pipeline.stats().stage_busy_cycles;
 
         VMStats {
             cycles: self.cycles,
@@ -421,17 +1283,39 @@ impl PunkVM {
             
             l1_data_hits: self.memory.stats().l1_hits,
             l1_data_misses: self.memory.stats().l1_misses,
-            l1_inst_hits: 0, // Pour l'instant, on track seulement data
-            l1_inst_misses: 0,
+            l1_inst_hits: self.memory.stats().l1_inst_hits,
+            l1_inst_misses: self.memory.stats().l1_inst_misses,
+            icache_prefetch_hits: self.memory.stats().icache_prefetch_hits,
+            instruction_fetch_stalls: self.pipeline.stats().instruction_fetch_stalls,
             l2_hits: self.memory.stats().l2_hits,
             l2_misses: self.memory.stats().l2_misses,
-            l2_writebacks: 0, // À implémenter plus tard
-            l2_prefetch_hits: 0, // À implémenter plus tard
-            memory_accesses: self.memory.stats().l1_misses + self.memory.stats().l2_misses,
-            average_memory_latency: 0.0, // À calculer plus tard
+            l2_writebacks: self.memory.l2_writebacks(),
+            l2_prefetch_hits: self.memory.stats().l2_prefetch_hits,
+            memory_accesses: self.memory.stats().l1_misses
+                + self.memory.stats().l2_misses
+                + self.memory.stats().memory_writes
+                + self.memory.l2_writebacks(),
+            average_memory_latency: if self.memory.stats().timed_accesses > 0 {
+                self.memory.stats().total_access_latency as f64 / self.memory.stats().timed_accesses as f64
+            } else {
+                0.0
+            },
             branch_flush: self.pipeline.stats().branch_flush,
+            squashed_instructions: self.pipeline.stats().squashed_instructions,
+            speculative_squashes: self.pipeline.stats().speculative_squashes,
+            flush_preserved_instructions: self.pipeline.stats().flush_preserved_instructions,
+            writeback_port_stalls: self.pipeline.stats().writeback_port_stalls,
+            speculation_stalls: self.pipeline.stats().speculation_stalls,
+            memory_stall_cycles: self.pipeline.stats().memory_stall_cycles,
+            fused_branch_count: self.pipeline.stats().fused_branch_count,
+            fetch_busy_cycles: stage_busy[0],
+            decode_busy_cycles: stage_busy[1],
+            execute_busy_cycles: stage_busy[2],
+            memory_busy_cycles: stage_busy[3],
+            writeback_busy_cycles: stage_busy[4],
             branch_predictor: self.pipeline.stats().branch_predictions,
             branch_prediction_rate: self.pipeline.stats().branch_predictor_rate,
+            branch_mispredictions: self.pipeline.stats().branch_misses,
             
             // Statistiques BTB
             btb_hits,
@@ -544,6 +1428,10 @@ impl PunkVM {
                 let parallel_stats = self.get_parallel_engine_stats();
                 parallel_stats.resource_conflicts
             },
+            parallel_engine_alu_issue_blocked: self.get_parallel_engine_stats().alu_issue_blocked,
+            parallel_engine_agu_issue_blocked: self.get_parallel_engine_stats().agu_issue_blocked,
+            parallel_engine_simd_issue_blocked: self.get_parallel_engine_stats().simd_issue_blocked,
+            parallel_engine_shared_port_conflicts: self.get_parallel_engine_stats().shared_port_conflicts,
             parallel_engine_alu_utilization: {
                 let parallel_stats = self.get_parallel_engine_stats();
                 parallel_stats.alu_utilization
@@ -567,9 +1455,55 @@ impl PunkVM {
         }
     }
 
+    /// Mode de privilège courant (utilisateur au démarrage, superviseur après un Syscall)
+    pub fn privilege_mode(&self) -> PrivilegeMode {
+        self.pipeline.privilege_mode()
+    }
+
+    /// Flags courants de l'ALU (zéro, négatif, débordement, retenue), tels que lus
+    /// par les branchements, traps et déplacements conditionnels
+    pub fn flags(&self) -> ALUFlags {
+        self.alu.flags
+    }
+
+    /// Instantané des registres inter-étages du pipeline (latches IF/ID, ID/EX, EX/MEM, MEM/WB)
+    /// ainsi que de `next_pc`, `stalled` et `halted`, pour les visualiseurs externes
+    pub fn pipeline_state(&self) -> PipelineState {
+        self.pipeline.state.clone()
+    }
+
+    /// Récupère la sortie écrite par le programme sur le port console MMIO
+    pub fn take_output(&mut self) -> Vec<u8> {
+        self.memory.take_output()
+    }
+
+    /// Exporte l'état appris du prédicteur de branchement (voir `BranchPredictor::export_state`)
+    pub fn export_branch_predictor_state(&self) -> Vec<u8> {
+        self.pipeline.branch_predictor().export_state()
+    }
+
+    /// Recharge un état de prédicteur exporté, pour démarrer une exécution sans phase de chauffe
+    pub fn import_branch_predictor_state(&mut self, state: &[u8]) -> VMResult<()> {
+        self.pipeline
+            .branch_predictor_mut()
+            .import_state(state)
+            .map_err(|e| VMError::config_error(&e))
+    }
+
+    /// Nombre d'instructions retirées par opcode
+    /// Retourne None si `VMConfig::enable_opcode_histogram` est désactivé
+    pub fn opcode_histogram(&self) -> Option<HashMap<Opcode, u64>> {
+        self.pipeline.opcode_counts().cloned()
+    }
+
+    /// Statistiques de la VM sérialisées en JSON
+    pub fn stats_as_json(&self) -> String {
+        self.stats().to_json()
+    }
+
 
     pub fn get_ras_stats(&self) -> RASStats {
-        self.get_ras_stats()
+        self.pipeline.get_ras_stats()
     }
 
     /// Retourne une référence au VectorALU pour accéder aux registres vectoriels
@@ -599,6 +1533,86 @@ impl PunkVM {
         &self.state
     }
 
+    /// Exécute `program` dans une VM neuve, forwarding et store buffer activés ou non,
+    /// et retourne registres, mémoire et nombre de cycles
+    pub fn run_program_with_options(
+        program: Vec<Instruction>,
+        enable_forwarding: bool,
+        enable_store_buffer: bool,
+    ) -> VMResult<ExecutionResult> {
+        execute_program_with_options(program, enable_forwarding, enable_store_buffer)
+    }
+
+    /// Registres avec leur nom d'assembleur (R0..R15, puis SP, BP, RA)
+    pub fn registers_named(&self) -> Vec<(String, u64)> {
+        self.registers
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| (register_name(i), value))
+            .collect()
+    }
+
+    /// Décode et met en forme la seule instruction située à `pc`, lue dans le code
+    /// chargé en mémoire: registres nommés, cibles de branchement résolues.
+    /// Un `pc` hors du code ou au milieu d'une instruction est une erreur de décodage.
+    pub fn disasm_at(&self, pc: u32) -> VMResult<String> {
+        let program = self
+            .program
+            .as_ref()
+            .ok_or_else(|| VMError::ExecutionError("Aucun programme chargé".to_string()))?;
+        let code_base = program.code_load_addr();
+        let code_size = program.code_size();
+        if pc < code_base || pc >= code_base + code_size {
+            return Err(VMError::DecodeError(format!("PC 0x{:08X} hors du segment de code", pc)));
+        }
+        let code = self
+            .memory
+            .read_direct(code_base, code_size as usize)
+            .map_err(|e| VMError::memory_error(&e.to_string()))?;
+
+        // Les frontières d'instruction ne se retrouvent qu'en décodant depuis le début du code
+        let target = (pc - code_base) as usize;
+        let mut offset = 0;
+        while offset < target {
+            let (_, size) = Instruction::decode(&code[offset..])
+                .map_err(|e| VMError::DecodeError(e.to_string()))?;
+            offset += size;
+        }
+        if offset != target {
+            return Err(VMError::DecodeError(format!("PC 0x{:08X} au milieu d'une instruction", pc)));
+        }
+
+        let (instruction, size) = Instruction::decode(&code[offset..])
+            .map_err(|e| VMError::DecodeError(e.to_string()))?;
+        let next_pc = pc + size as u32;
+        let operands = [instruction.get_arg1_value(), instruction.get_arg2_value(), instruction.get_arg3_value()]
+            .into_iter()
+            .filter_map(|arg| match arg {
+                Ok(ArgValue::None) => None,
+                Ok(ArgValue::Register(r)) => Some(register_name(r as usize)),
+                Ok(ArgValue::Immediate(value)) => Some(format!("#{}", value)),
+                Ok(ArgValue::RelativeAddr(delta)) => Some(format!("0x{:08X}", (next_pc as i64 + delta as i64) as u32)),
+                Ok(ArgValue::AbsoluteAddr(addr)) => Some(format!("0x{:08X}", addr)),
+                Ok(ArgValue::RegisterOffset(base, disp)) => Some(format!("[{}{:+}]", register_name(base as usize), disp)),
+                Ok(ArgValue::Indexed(base, index, scale, disp)) => Some(format!(
+                    "[{}+{}*{}{:+}]",
+                    register_name(base as usize),
+                    register_name(index as usize),
+                    scale,
+                    disp
+                )),
+                Ok(ArgValue::Label(label)) => Some(label),
+                Err(_) => Some("?".to_string()),
+            })
+            .collect::<Vec<_>>();
+
+        if operands.is_empty() {
+            Ok(instruction.opcode.name().to_string())
+        } else {
+            Ok(format!("{} {}", instruction.opcode.name(), operands.join(", ")))
+        }
+    }
+
     /// Charge le segment de code en mémoire
     fn load_code_segment(&mut self, program: &BytecodeFile) -> VMResult<()> {
         let code_segment = program
@@ -607,11 +1621,8 @@ impl PunkVM {
             .find(|s| s.segment_type == Code)
             .ok_or_else(|| VMError::memory_error("Segment de code manquant"))?;
 
-        // Encoder les instructions
-        let mut code_bytes = Vec::new();
-        for instr in &program.code {
-            code_bytes.extend_from_slice(&instr.encode());
-        }
+        // Encoder les instructions (suivies des octets non décodables éventuels)
+        let code_bytes = program.code_bytes();
 
         // Vérifier la cohérence de taille
         if code_bytes.len() != code_segment.size as usize {
@@ -668,6 +1679,3125 @@ impl PunkVM {
             Err(VMError::execution_error("Le traçage n'est pas activé"))
         }
     }
+
+    /// Exporter les traces dans un fichier JSON
+    pub fn export_traces_to_json(&self, file_path: &str) -> VMResult<()> {
+        if let Some(tracer) = &self.tracer {
+            tracer.export_to_json(file_path)
+        } else {
+            Err(VMError::execution_error("Le traçage n'est pas activé"))
+        }
+    }
+
+    /// Profil d'exécution: retraits par adresse et transitions entre instructions retirées
+    pub fn execution_profile(&self) -> &ExecutionProfile {
+        self.pipeline.execution_profile()
+    }
+
+    /// Exporte au format DOT (Graphviz) le CFG du programme chargé, annoté du nombre
+    /// d'exécutions de chaque bloc de base et du nombre de traversées de chaque arc
+    pub fn export_cfg_dot<P: AsRef<Path>>(&self, path: P) -> VMResult<()> {
+        let program = self.program.as_ref()
+            .ok_or_else(|| VMError::execution_error("Aucun programme chargé"))?;
+        std::fs::write(path, cfg_to_dot(&program.code, program.code_load_addr(), self.execution_profile()))?;
+        Ok(())
+    }
+
+    /// Enregistre la trace courante comme trace de référence
+    pub fn capture_golden_trace(&self, file_path: &str) -> VMResult<()> {
+        if let Some(tracer) = &self.tracer {
+            tracer.capture_golden(file_path)
+        } else {
+            Err(VMError::execution_error("Le traçage n'est pas activé"))
+        }
+    }
+
+    /// Compare la trace courante à une trace de référence et retourne le premier écart
+    pub fn compare_against_golden(&self, file_path: &str) -> VMResult<Option<GoldenMismatch>> {
+        if let Some(tracer) = &self.tracer {
+            tracer.compare_against_golden(file_path)
+        } else {
+            Err(VMError::execution_error("Le traçage n'est pas activé"))
+        }
+    }
+}
+
+/// Nom d'assembleur d'un registre (R0..R15, puis SP, BP, RA)
+fn register_name(index: usize) -> String {
+    match index {
+        16 => "SP".to_string(),
+        17 => "BP".to_string(),
+        18 => "RA".to_string(),
+        _ => format!("R{}", index),
+    }
+}
+
+/// Résumé compact: état d'arrêt, PC, puis registres généraux (4 par ligne) et spéciaux
+impl fmt::Display for PunkVM {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "PunkVM [{}] PC=0x{:08X} cycles={} instructions={}",
+            self.state, self.pc, self.cycles, self.instructions_executed
+        )?;
+        let named = self.registers_named();
+        for row in named.chunks(4) {
+            let line: Vec<String> = row.iter().map(|(name, value)| format!("{}={}", name, value)).collect();
+            writeln!(f, "  {}", line.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bytecode::files::{SegmentMetadata, SegmentType};
+    use crate::bytecode::instructions::Instruction;
+    use crate::bytecode::format::{ArgType, InstructionFormat};
+    use crate::bytecode::lint::LintKind;
+    use crate::pvm::memorys::COW_PAGE_SIZE;
+    use crate::pvm::harness::build_program;
+    use crate::pipeline::PipelineStats;
+
+    /// Exécute une division (Div ou Mod) dont le diviseur R1 vaut zéro
+    fn run_division_by_zero(opcode: Opcode) -> (VMResult<()>, u32, PunkVM) {
+        let mov = Instruction::create_reg_imm8(Opcode::Mov, 0, 42); // R0 = 42, R1 = 0
+        let faulting_pc = mov.total_size() as u32;
+        let program = build_program(vec![
+            mov,
+            Instruction::create_reg_reg_reg(opcode, 2, 0, 1),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program).unwrap();
+        let result = vm.run();
+        (result, faulting_pc, vm)
+    }
+
+    #[test]
+    fn test_div_by_zero_traps() {
+        let (result, faulting_pc, vm) = run_division_by_zero(Opcode::Div);
+        assert_eq!(result, Err(VMError::DivisionByZero { pc: faulting_pc }));
+        assert!(matches!(vm.state, VMState::Error(_)));
+        assert_eq!(vm.registers[2], 0);
+    }
+
+    #[test]
+    fn test_mod_by_zero_traps() {
+        let (result, faulting_pc, _) = run_division_by_zero(Opcode::Mod);
+        assert_eq!(result, Err(VMError::DivisionByZero { pc: faulting_pc }));
+    }
+
+    /// Pile de quatre emplacements (0x8000..0x8020): exécute `code` suivi de HALT
+    fn run_on_small_stack(mut code: Vec<Instruction>) -> (VMResult<()>, PunkVM) {
+        code.push(Instruction::create_no_args(Opcode::Halt));
+        let config = VMConfig { stack_base: 0x8000, stack_size: 0x20, ..VMConfig::default() };
+        let mut vm = PunkVM::with_config(config);
+        vm.load_program_from_bytecode(build_program(code)).unwrap();
+        let result = vm.run();
+        (result, vm)
+    }
+
+    #[test]
+    fn test_push_past_stack_capacity_overflows() {
+        let mut code = vec![Instruction::create_reg_imm8(Opcode::Mov, 0, 1)];
+        code.extend((0..4).map(|_| Instruction::create_single_reg(Opcode::Push, 0)));
+        let (result, vm) = run_on_small_stack(code.clone());
+        assert_eq!(result, Ok(()));
+        assert_eq!(vm.registers[16], 0x8000);
+
+        let faulting_pc = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_single_reg(Opcode::Push, 0));
+        let (result, vm) = run_on_small_stack(code);
+        assert_eq!(result, Err(VMError::StackOverflow { pc: faulting_pc, sp: 0x8000 }));
+        assert!(matches!(vm.state, VMState::Error(_)));
+    }
+
+    #[test]
+    fn test_push_wide_immediates_round_trip_through_stack() {
+        let (result, vm) = run_on_small_stack(vec![
+            Instruction::create_push_immediate64(0, 0xDEAD_BEEF_CAFE_BABE),
+            Instruction::create_push_immediate32(0, 0x8765_4321),
+            Instruction::create_push_immediate16(0, 0xFFFE),
+            Instruction::create_pop_register(1),
+            Instruction::create_pop_register(2),
+            Instruction::create_pop_register(3),
+        ]);
+        assert_eq!(result, Ok(()));
+        assert_eq!(vm.registers[1], 0xFFFE);
+        assert_eq!(vm.registers[2], 0x8765_4321);
+        assert_eq!(vm.registers[3], 0xDEAD_BEEF_CAFE_BABE);
+        // Le registre nommé par le PUSH immédiat n'est ni lu ni modifié
+        assert_eq!(vm.registers[0], 0);
+        assert_eq!(vm.registers[16], 0x8020);
+    }
+
+    #[test]
+    fn test_pop_empty_stack_underflows() {
+        let (result, vm) = run_on_small_stack(vec![Instruction::create_single_reg(Opcode::Pop, 0)]);
+        assert_eq!(result, Err(VMError::StackUnderflow { pc: 0, sp: 0x8020 }));
+        // SP n'a pas dépassé le sommet de la pile
+        assert_eq!(vm.registers[16], 0x8020);
+    }
+
+    /// Contrôle de bornes: trap 7 si l'index R0 >= la longueur R1 (non signé), puis R2 = 1
+    fn run_bounds_check(index: u8, len: u8) -> (VMResult<()>, u32, PunkVM) {
+        let mut code = vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 0, index),
+            Instruction::create_reg_imm8(Opcode::Mov, 1, len),
+            Instruction::create_reg_reg(Opcode::Cmp, 0, 1),
+        ];
+        let trap_pc = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_trap_if(Opcode::TrapIfAboveEqual, 7));
+        code.push(Instruction::create_reg_imm8(Opcode::Mov, 2, 1));
+        code.push(Instruction::create_no_args(Opcode::Halt));
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(build_program(code)).unwrap();
+        let result = vm.run();
+        (result, trap_pc, vm)
+    }
+
+    /// R2 = max(R0, R1) (signé) sans branchement: Cmp puis CmovGreater
+    fn run_branchless_max(a: u8, b: u8, negate_a: bool) -> PunkVM {
+        let mut code = vec![Instruction::create_reg_imm8(Opcode::Mov, 0, a)];
+        if negate_a {
+            code.push(Instruction::create_single_reg(Opcode::Neg, 0));
+        }
+        code.extend([
+            Instruction::create_reg_imm8(Opcode::Mov, 1, b),
+            Instruction::create_reg_reg(Opcode::Mov, 2, 0),
+            Instruction::create_reg_reg(Opcode::Cmp, 1, 0),
+            Instruction::create_reg_reg(Opcode::CmovGreater, 2, 1),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(build_program(code)).unwrap();
+        vm.run().unwrap();
+        vm
+    }
+
+    /// Boucle de décompte (R0 = 12 -> 0) exécutée avec le prédicteur donné
+    fn run_countdown_with_predictor(kind: PredictorType) -> PipelineStats {
+        let mut code = vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 0, 12),
+            Instruction::create_single_reg(Opcode::Dec, 0),
+        ];
+        let loop_addr = Instruction::calculate_current_address(&code[..1]);
+        let branch_addr = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_jump_if_not_zero(branch_addr, loop_addr));
+        code.push(Instruction::create_no_args(Opcode::Halt));
+
+        let config = VMConfig { branch_predictor_kind: kind, ..VMConfig::default() };
+        let mut vm = PunkVM::with_config(config);
+        vm.load_program_from_bytecode(build_program(code)).unwrap();
+        vm.run().unwrap();
+        assert_eq!(vm.registers[0], 0);
+        vm.pipeline.stats()
+    }
+
+    #[test]
+    fn test_pipeline_state_reflects_fetched_instruction() {
+        let program = build_program(vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 5),
+            Instruction::create_reg_imm8(Opcode::Mov, 2, 7),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program).unwrap();
+        vm.state = VMState::Running;
+        vm.step().unwrap();
+
+        let state = vm.pipeline_state();
+        let fetched = state.fetch_decode.expect("une instruction chargée au premier cycle");
+        assert_eq!((fetched.pc, fetched.instruction.opcode), (0, Opcode::Mov));
+        assert_eq!(fetched.instruction.args, vec![1, 5]);
+        assert_eq!(state.next_pc, vm.pc as u32);
+        assert!(!state.stalled && !state.halted);
+
+        // L'instantané est une copie: avancer la VM ne le modifie pas
+        vm.run().unwrap();
+        assert!(vm.pipeline_state().halted);
+        assert_eq!(state.decode_execute.map(|de| de.pc), Some(0));
+    }
+
+    #[test]
+    fn test_rdinstret_counts_instructions_retired_in_between() {
+        let mut code = vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 5, 3),
+            Instruction::create_single_reg(Opcode::RdInstret, 0),
+            Instruction::create_single_reg(Opcode::Dec, 5),
+        ];
+        // Boucle de 3 tours (Dec + JmpIfNotZero), puis deux Mov indépendants
+        let loop_addr = Instruction::calculate_current_address(&code[..2]);
+        let branch_addr = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_jump_if_not_zero(branch_addr, loop_addr));
+        code.extend([
+            Instruction::create_reg_imm8(Opcode::Mov, 2, 1),
+            Instruction::create_reg_imm8(Opcode::Mov, 3, 2),
+            Instruction::create_single_reg(Opcode::RdInstret, 1),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(build_program(code)).unwrap();
+        vm.run().unwrap();
+
+        // Le premier RdInstret voit le Mov qui le précède
+        assert_eq!(vm.registers[0], 1);
+        // RdInstret lui-même, 3 x (Dec, Jnz), 2 x Mov
+        assert_eq!(vm.registers[1] - vm.registers[0], 1 + 6 + 2);
+        assert_eq!(vm.pipeline.stats().instructions, vm.registers[1] + 1);
+    }
+
+    /// Boucle de 10 tours contenant 8 branchements distincts, alternativement toujours pris
+    /// et jamais pris, exécutée avec le prédicteur `kind` limité à `entries` entrées
+    fn run_many_branches(kind: PredictorType, entries: Option<usize>) -> PipelineStats {
+        let mut code = vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 0, 10),
+            Instruction::create_reg_reg(Opcode::Cmp, 1, 1), // Z = 1 pour tout le tour
+        ];
+        let loop_addr = Instruction::calculate_current_address(&code[..1]);
+        for i in 0..8 {
+            let branch_addr = Instruction::calculate_current_address(&code);
+            let probe = Instruction::create_jump_if_equal(branch_addr, 0);
+            let nop = Instruction::create_no_args(Opcode::Nop);
+            // Cible: juste après le NOP qui suit le branchement
+            let target = branch_addr + probe.total_size() as u32 + nop.total_size() as u32;
+            code.push(if i % 2 == 0 {
+                Instruction::create_jump_if_equal(branch_addr, target)
+            } else {
+                Instruction::create_jump_if_not_equal(branch_addr, target)
+            });
+            code.push(nop);
+        }
+        code.push(Instruction::create_single_reg(Opcode::Dec, 0));
+        let branch_addr = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_jump_if_not_zero(branch_addr, loop_addr));
+        code.push(Instruction::create_no_args(Opcode::Halt));
+
+        let config = VMConfig {
+            branch_predictor_kind: kind,
+            branch_predictor_entries: entries,
+            ..VMConfig::default()
+        };
+        let mut vm = PunkVM::with_config(config);
+        vm.load_program_from_bytecode(build_program(code)).unwrap();
+        vm.run().unwrap();
+        assert_eq!(vm.registers[0], 0);
+        vm.pipeline.stats()
+    }
+
+    #[test]
+    fn test_tiny_predictor_table_aliases_distinct_branches() {
+        let unbounded = run_many_branches(PredictorType::Dynamic, None);
+        let large = run_many_branches(PredictorType::Dynamic, Some(1024));
+        let tiny = run_many_branches(PredictorType::Dynamic, Some(1));
+
+        assert_eq!(unbounded.branch_aliasing_conflicts, 0);
+        assert_eq!(large.branch_aliasing_conflicts, 0);
+        assert_eq!(large.branch_misses, unbounded.branch_misses);
+        // Une seule entrée partagée par 9 branchements: chaque mise à jour l'arrache au précédent
+        assert!(tiny.branch_aliasing_conflicts > 0);
+        assert!(
+            tiny.branch_misses > large.branch_misses,
+            "table de 1 entrée: {} erreurs, 1024 entrées: {}",
+            tiny.branch_misses,
+            large.branch_misses
+        );
+    }
+
+    #[test]
+    fn test_predictor_entries_bound_every_predictor_table() {
+        // Le prédicteur Hybrid par défaut: table GShare, sélecteur et historiques locaux
+        let default = run_many_branches(PredictorType::Hybrid, None);
+        let large = run_many_branches(PredictorType::Hybrid, Some(4096));
+        let tiny = run_many_branches(PredictorType::Hybrid, Some(1));
+        assert_eq!(large.branch_misses, default.branch_misses);
+        assert!(
+            tiny.branch_misses > large.branch_misses,
+            "Hybrid à 1 entrée: {} erreurs, 4096 entrées: {}",
+            tiny.branch_misses,
+            large.branch_misses
+        );
+
+        for kind in [PredictorType::GShare, PredictorType::Perceptron] {
+            let large = run_many_branches(kind, Some(4096));
+            let tiny = run_many_branches(kind, Some(1));
+            assert!(
+                tiny.branch_misses > large.branch_misses,
+                "{:?} à 1 entrée: {} erreurs, 4096 entrées: {}",
+                kind,
+                tiny.branch_misses,
+                large.branch_misses
+            );
+        }
+    }
+
+    #[test]
+    fn test_stats_diff_reports_only_prediction_counters() {
+        let baseline = run_countdown_with_predictor(PredictorType::Static);
+        assert!(baseline.diff(&run_countdown_with_predictor(PredictorType::Static)).is_empty());
+
+        // Le prédicteur statique rate chaque saut arrière, le dynamique apprend la boucle
+        let diff = baseline.diff(&run_countdown_with_predictor(PredictorType::Hybrid));
+        let prediction_fields = [
+            "branch_hits",
+            "branch_misses",
+            "branch_flush",
+            "squashed_instructions",
+            "speculative_squashes",
+            "flush_preserved_instructions",
+        ];
+        assert!(diff.iter().all(|(name, _, _)| prediction_fields.contains(name)), "{:?}", diff);
+        let misses = diff.iter().find(|(name, _, _)| *name == "branch_misses").unwrap();
+        assert!(misses.1 > misses.2);
+    }
+
+    #[test]
+    fn test_cmov_computes_max_without_branches() {
+        for (a, b, negate_a, expected) in [(9, 4, false, 9u64), (4, 9, false, 9), (3, 2, true, 2), (7, 7, false, 7)] {
+            let vm = run_branchless_max(a, b, negate_a);
+            assert_eq!(vm.registers[2], expected, "max({}{}, {})", if negate_a { "-" } else { "" }, a, b);
+            let stats = vm.stats();
+            assert_eq!(stats.branch_flush, 0);
+            assert_eq!(stats.branch_predictor, 0);
+        }
+        // Les flags restent ceux du Cmp: 7 - 7 = 0
+        assert!(run_branchless_max(7, 7, false).flags().zero);
+        assert!(!run_branchless_max(4, 9, false).flags().zero);
+    }
+
+    #[test]
+    fn test_trap_if_passes_in_bounds() {
+        let (result, _, vm) = run_bounds_check(3, 4);
+        assert_eq!(result, Ok(()));
+        assert_eq!(vm.registers[2], 1);
+    }
+
+    #[test]
+    fn test_trap_if_faults_out_of_bounds() {
+        let (result, trap_pc, vm) = run_bounds_check(4, 4);
+        assert_eq!(result, Err(VMError::ExplicitTrap { pc: trap_pc, code: 7 }));
+        assert!(matches!(vm.state, VMState::Error(_)));
+        assert_eq!(vm.registers[2], 0, "l'instruction suivant le trap ne doit pas s'exécuter");
+    }
+
+    #[test]
+    fn test_same_register_writes_program_order_wins() {
+        // Des MOV indépendants sont émis et écrits en groupe; les deux écritures de R1
+        // (WAW) ne partagent jamais un groupe et la plus récente doit l'emporter.
+        let program = build_program(vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 5),
+            Instruction::create_reg_imm8(Opcode::Mov, 2, 6),
+            Instruction::create_reg_imm8(Opcode::Mov, 3, 8),
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 7),
+            Instruction::create_reg_imm8(Opcode::Mov, 4, 9),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let mut vm = PunkVM::with_config(VMConfig { issue_width: 3, register_write_ports: 3, ..VMConfig::default() });
+        vm.load_program_from_bytecode(program).unwrap();
+        vm.run().unwrap();
+
+        assert!(vm.stats().parallel_engine_parallel_executions > 0);
+        assert_eq!(&vm.registers[1..5], &[7, 6, 8, 9]);
+    }
+
+    #[test]
+    fn test_load_out_of_bounds_faults() {
+        // R1 = 0xFFFF_0000, bien au-delà de la mémoire configurée
+        let program = build_program(vec![
+            Instruction::create_reg_imm32(Opcode::Mov, 1, 0xFFFF_0000),
+            Instruction::create_load_reg_offset(2, 1, 0),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program).unwrap();
+        let result = vm.run();
+
+        assert_eq!(result, Err(VMError::MemoryOutOfBounds { addr: 0xFFFF_0000, size: 8 }));
+        assert!(matches!(vm.state, VMState::Error(_)));
+    }
+
+    #[test]
+    fn test_simd_and_stack_accesses_out_of_bounds_fault() {
+        // La pile par défaut (0xFF000000) est hors de la mémoire configurée; le POP part
+        // d'un SP placé une entrée sous le sommet pour passer la vérification d'underflow
+        let out_of_range: u32 = 0xFFFF_0000;
+        let stack_top = VMConfig::default().stack_base + VMConfig::default().stack_size as u32;
+        let cases = vec![
+            (
+                vec![
+                    Instruction::create_reg_imm32(Opcode::Mov, 1, out_of_range),
+                    Instruction::create_simd128_load(0, 1, 0),
+                ],
+                VMError::MemoryOutOfBounds { addr: out_of_range as u64, size: 16 },
+            ),
+            (
+                vec![
+                    Instruction::create_reg_imm32(Opcode::Mov, 1, out_of_range),
+                    Instruction::create_simd256_store(0, 1, 0),
+                ],
+                VMError::MemoryOutOfBounds { addr: out_of_range as u64, size: 32 },
+            ),
+            (
+                vec![Instruction::create_push_register(0)],
+                VMError::MemoryOutOfBounds { addr: stack_top as u64 - 8, size: 8 },
+            ),
+            (
+                vec![Instruction::create_pop_register(2)],
+                VMError::MemoryOutOfBounds { addr: stack_top as u64 - 8, size: 8 },
+            ),
+        ];
+
+        for (mut code, expected) in cases {
+            let is_pop = code[0].opcode == Opcode::Pop;
+            code.push(Instruction::create_no_args(Opcode::Halt));
+            let mut vm = PunkVM::new();
+            vm.load_program_from_bytecode(build_program(code)).unwrap();
+            if is_pop {
+                vm.registers[16] = stack_top as u64 - 8;
+            }
+            assert_eq!(vm.run(), Err(expected));
+            assert!(matches!(vm.state, VMState::Error(_)));
+        }
+    }
+
+    #[test]
+    fn test_load_store_i16_offsets() {
+        let base: u64 = 0x1000;
+        let program = build_program(vec![
+            Instruction::create_reg_imm16(Opcode::Mov, 1, base as u16),
+            Instruction::create_reg_imm8(Opcode::Mov, 2, 42),
+            Instruction::create_reg_imm8(Opcode::Mov, 3, 99),
+            Instruction::create_store_reg_offset_i16(Opcode::Store, 2, 1, 1000),
+            Instruction::create_store_reg_offset_i16(Opcode::Store, 3, 1, -500),
+            Instruction::create_load_reg_offset_i16(4, 1, 1000),
+            Instruction::create_load_reg_offset_i16(5, 1, -500),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program).unwrap();
+        vm.run().unwrap();
+
+        let snapshot = vm.memory.snapshot();
+        let qword_at = |addr: u64| {
+            let addr = addr as usize;
+            u64::from_le_bytes(snapshot[addr..addr + 8].try_into().unwrap())
+        };
+        assert_eq!(qword_at(base + 1000), 42);
+        assert_eq!(qword_at(base - 500), 99);
+        assert_eq!(vm.registers[4], 42);
+        assert_eq!(vm.registers[5], 99);
+    }
+
+    #[test]
+    fn test_store_load_multiple_round_trip() {
+        let base: u16 = 0x3000;
+        let regs = [0u8, 1, 2, 3, 4];
+        let mut code = vec![Instruction::create_reg_imm16(Opcode::Mov, 6, base)];
+        for &reg in &regs {
+            code.push(Instruction::create_reg_imm8(Opcode::Mov, reg, 10 + reg));
+        }
+        // Ordre volontairement mélangé: le bloc suit toujours l'ordre des numéros de registres
+        code.push(Instruction::create_store_multiple(6, &[3, 0, 4, 1, 2], 8));
+        for &reg in &regs {
+            code.push(Instruction::create_reg_imm8(Opcode::Mov, reg, 0));
+        }
+        code.push(Instruction::create_load_multiple(6, &regs, 8));
+        code.push(Instruction::create_no_args(Opcode::Halt));
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(build_program(code)).unwrap();
+        vm.run().unwrap();
+
+        for &reg in &regs {
+            assert_eq!(vm.registers[reg as usize], 10 + reg as u64);
+        }
+
+        let snapshot = vm.memory.snapshot();
+        for (i, &reg) in regs.iter().enumerate() {
+            let slot = base as usize + 8 + i * 8;
+            let value = u64::from_le_bytes(snapshot[slot..slot + 8].try_into().unwrap());
+            assert_eq!(value, 10 + reg as u64);
+        }
+    }
+
+    #[test]
+    fn test_saturating_variants_run_through_pipeline() {
+        let code = vec![
+            Instruction::create_reg_imm32(Opcode::Mov, 1, u32::MAX),
+            Instruction::create_reg_imm8(Opcode::Mov, 2, 1),
+            Instruction::create_reg_imm64(Opcode::Mov, 3, i64::MIN as u64),
+            Instruction::create_reg_reg_reg(Opcode::AddSatD, 4, 1, 2), // u32::MAX
+            Instruction::create_reg_reg_reg(Opcode::AddSat, 5, 1, 2),  // 2^32: pas de saturation en 64 bits
+            Instruction::create_reg_reg_reg(Opcode::SubSatS, 6, 3, 2), // i64::MIN
+            Instruction::create_reg_reg_reg(Opcode::SubSatSD, 7, 2, 1), // 1 - (-1) = 2 en i32
+            Instruction::create_no_args(Opcode::Halt),
+        ];
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(build_program(code)).unwrap();
+        vm.run().unwrap();
+
+        assert_eq!(vm.registers[4], u32::MAX as u64);
+        assert_eq!(vm.registers[5], 1 << 32);
+        assert_eq!(vm.registers[6], i64::MIN as u64);
+        assert_eq!(vm.registers[7], 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "R16 hors du masque de registres")]
+    fn test_store_multiple_rejects_register_outside_mask() {
+        // R16 (SP) ne tient pas dans le masque 16 bits: il ne doit pas devenir R0
+        Instruction::create_store_multiple(6, &[1, 16], 0);
+    }
+
+    /// Boucle contenant un branchement alterné pris / non pris à chaque itération
+    fn run_alternating_branches(kind: PredictorType) -> VMStats {
+        let mut code = vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 1),  // R1 = 1
+            Instruction::create_reg_imm8(Opcode::Mov, 3, 64), // R3 = nombre d'itérations
+        ];
+        let loop_start = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_reg_reg_reg(Opcode::Xor, 2, 2, 1)); // R2 alterne 1, 0, 1, ...
+        code.push(Instruction::create_reg_reg(Opcode::Cmp, 2, 4));
+        let jeq_addr = Instruction::calculate_current_address(&code);
+        let skip_add = Instruction::create_reg_reg_reg(Opcode::Add, 5, 5, 1);
+        let jeq_size = Instruction::create_jump_if_equal(0, 0).total_size() as u32;
+        let skip_target = jeq_addr + jeq_size + skip_add.total_size() as u32;
+        code.push(Instruction::create_jump_if_equal(jeq_addr, skip_target));
+        code.push(skip_add);
+        code.push(Instruction::create_reg_reg_reg(Opcode::Add, 0, 0, 1));
+        code.push(Instruction::create_reg_reg(Opcode::Cmp, 0, 3));
+        let jl_addr = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_jump_if_less(jl_addr, loop_start));
+        code.push(Instruction::create_no_args(Opcode::Halt));
+
+        let config = VMConfig { branch_predictor_kind: kind, ..VMConfig::default() };
+        let mut vm = PunkVM::with_config(config);
+        vm.load_program_from_bytecode(build_program(code)).unwrap();
+        vm.run().unwrap();
+
+        assert_eq!(vm.registers[0], 64);
+        assert_eq!(vm.registers[5], 32);
+        vm.stats()
+    }
+
+    #[test]
+    fn test_gshare_on_alternating_branches_against_default_predictor() {
+        // Le prédicteur par défaut de VMConfig est le Hybrid; avec un historique plus
+        // court, le gshare apprend l'alternance en moins de branchements
+        let default_kind = VMConfig::default().branch_predictor_kind;
+        assert_eq!(default_kind, PredictorType::Hybrid);
+        let default_stats = run_alternating_branches(default_kind);
+        let bimodal_stats = run_alternating_branches(PredictorType::Dynamic);
+        let gshare_stats = run_alternating_branches(PredictorType::GShare);
+        assert!(
+            gshare_stats.branch_prediction_rate > bimodal_stats.branch_prediction_rate,
+            "bimodal={:.2}%, gshare={:.2}%",
+            bimodal_stats.branch_prediction_rate,
+            gshare_stats.branch_prediction_rate
+        );
+        assert!(
+            gshare_stats.branch_prediction_rate > default_stats.branch_prediction_rate,
+            "hybrid={:.2}%, gshare={:.2}%",
+            default_stats.branch_prediction_rate,
+            gshare_stats.branch_prediction_rate
+        );
+    }
+
+    #[test]
+    fn test_correctly_predicted_taken_branch_redirects_pc() {
+        // Branchement avant toujours pris: une fois bien prédit, il doit quand même
+        // sauter le Mov, le fetch n'étant pas redirigé par la prédiction
+        let mut code = vec![Instruction::create_reg_imm8(Opcode::Mov, 0, 16)];
+        let loop_start = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_reg_reg(Opcode::Cmp, 1, 1)); // Z = 1
+        let je_addr = Instruction::calculate_current_address(&code);
+        let skipped = Instruction::create_reg_imm8(Opcode::Mov, 2, 99);
+        let je_size = Instruction::create_jump_if_equal(0, 0).total_size() as u32;
+        code.push(Instruction::create_jump_if_equal(je_addr, je_addr + je_size + skipped.total_size() as u32));
+        code.push(skipped);
+        code.push(Instruction::create_single_reg(Opcode::Dec, 0));
+        let jnz_addr = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_jump_if_not_zero(jnz_addr, loop_start));
+        code.push(Instruction::create_no_args(Opcode::Halt));
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(build_program(code)).unwrap();
+        vm.run().unwrap();
+
+        assert!(vm.pipeline.stats().branch_hits > 0);
+        assert_eq!(vm.registers[0], 0);
+        assert_eq!(vm.registers[2], 0);
+    }
+
+    /// Reproduit le programme `forwarding_stress_test` de l'exécutable
+    fn forwarding_stress_program() -> Vec<Instruction> {
+        let mut program = vec![
+            Instruction::create_reg_imm16(Opcode::Mov, 0, 100),
+            Instruction::create_reg_reg_reg(Opcode::Add, 0, 0, 0),
+            Instruction::create_reg_reg_reg(Opcode::Mul, 0, 0, 0),
+            Instruction::create_reg_imm8(Opcode::Sub, 0, 1),
+            Instruction::create_reg_imm16(Opcode::Mov, 1, 50),
+            Instruction::create_reg_imm16(Opcode::Mov, 2, 25),
+            Instruction::create_reg_reg_reg(Opcode::Add, 3, 1, 2),
+            Instruction::create_reg_imm16(Opcode::Mov, 4, 0x1000),
+            Instruction::create_store_reg_offset(Opcode::Store, 3, 4, 0),
+            Instruction::create_load_reg_offset(5, 4, 0),
+            Instruction::create_reg_reg_reg(Opcode::Add, 6, 5, 0),
+            Instruction::create_reg_imm8(Opcode::Mov, 6, 1),
+        ];
+        for i in 7..15 {
+            program.push(Instruction::create_reg_reg_reg(Opcode::Add, i, i - 1, 6));
+        }
+        program.push(Instruction::create_reg_imm16(Opcode::Mov, 15, 42));
+        program.push(Instruction::create_reg_reg_reg(Opcode::Add, 7, 15, 14));
+        program.push(Instruction::create_reg_reg_reg(Opcode::Sub, 8, 7, 15));
+        program.push(Instruction::create_no_args(Opcode::Halt));
+        program
+    }
+
+    #[test]
+    fn test_opcode_histogram_matches_static_program() {
+        let program = forwarding_stress_program();
+        let static_count = |opcode: Opcode| {
+            program.iter().filter(|instr| instr.opcode == opcode).count() as u64
+        };
+        let (adds, muls, subs) = (
+            static_count(Opcode::Add),
+            static_count(Opcode::Mul),
+            static_count(Opcode::Sub),
+        );
+
+        let config = VMConfig {
+            enable_opcode_histogram: true,
+            ..VMConfig::default()
+        };
+        let mut vm = PunkVM::with_config(config);
+        vm.load_program_from_bytecode(build_program(program)).unwrap();
+        vm.run().unwrap();
+
+        let histogram = vm.opcode_histogram().expect("histogramme activé");
+        assert_eq!(histogram.get(&Opcode::Add).copied(), Some(adds));
+        assert_eq!(histogram.get(&Opcode::Mul).copied(), Some(muls));
+        assert_eq!(histogram.get(&Opcode::Sub).copied(), Some(subs));
+
+        // Désactivé par défaut
+        assert!(PunkVM::new().opcode_histogram().is_none());
+    }
+
+    /// Programme à un seul branchement conditionnel, toujours pris
+    fn single_taken_branch_program() -> BytecodeFile {
+        let mut code = vec![Instruction::create_reg_reg(Opcode::Cmp, 0, 0)];
+        let jeq_addr = Instruction::calculate_current_address(&code);
+        let skipped = Instruction::create_reg_imm8(Opcode::Mov, 1, 1);
+        let jeq_size = Instruction::create_jump_if_equal(0, 0).total_size() as u32;
+        code.push(Instruction::create_jump_if_equal(jeq_addr, jeq_addr + jeq_size + skipped.total_size() as u32));
+        code.push(skipped);
+        code.push(Instruction::create_no_args(Opcode::Halt));
+        build_program(code)
+    }
+
+    #[test]
+    fn test_stats_report_has_sections_and_finite_rates() {
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(build_program(vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 5),
+            Instruction::create_reg_reg_reg(Opcode::Add, 2, 1, 1),
+            Instruction::create_no_args(Opcode::Halt),
+        ]))
+        .unwrap();
+        vm.run().unwrap();
+
+        // Aucun accès SIMD ni branchement: ces compteurs sont nuls
+        let report = vm.stats().to_string();
+        for header in ["STATISTIQUES D'EXÉCUTION", "Cache Hierarchy", "Forwarding", "SIMD", "AGU", "Dual-Issue"] {
+            assert!(report.contains(header), "section {} absente", header);
+        }
+        assert!(report.contains("Cache d'opérations: 0 hits, 0 misses (0.00%)"));
+        assert!(!report.contains("NaN") && !report.contains("inf"));
+
+        let empty = VMStats::default().to_string();
+        assert!(!empty.contains("NaN") && !empty.contains("inf"));
+    }
+
+    #[test]
+    fn test_stats_report_keeps_every_counter_of_the_old_summary() {
+        let stats = VMStats {
+            writeback_port_stalls: 11,
+            speculation_stalls: 12,
+            instruction_fetch_stalls: 13,
+            flush_preserved_instructions: 14,
+            agu_parallel_executions: 15,
+            dual_issue_total_instructions: 40,
+            dual_issue_parallel_executions: 10,
+            dual_issue_parallel_rate: 25.0,
+            parallel_engine_dependency_stalls: 16,
+            parallel_engine_resource_conflicts: 17,
+            parallel_engine_alu_utilization: 42.5,
+            parallel_engine_agu_utilization: 7.5,
+            parallel_engine_average_queue_depth: 1.25,
+            ..VMStats::default()
+        };
+        let report = stats.to_string();
+        for line in [
+            "Stalls faute de port d'écriture: 11",
+            "Stalls de limite de spéculation: 12",
+            "Stalls de fetch (miss I-cache): 13",
+            "14 conservée(s)",
+            "Exécutions parallèles AGU/ALU: 15",
+            "Exécutions parallèles: 10 (25.00%)",
+            "Efficacité théorique: 50.00% (10/20 max)",
+            "Stalls de dépendances: 16",
+            "Conflits de ressources: 17",
+            "Utilisation ALU/AGU: 42.50%/7.50%",
+            "Profondeur moyenne des queues: 1.25",
+        ] {
+            assert!(report.contains(line), "ligne absente: {}", line);
+        }
+    }
+
+    #[test]
+    fn test_config_builder_defaults_and_overrides() {
+        assert_eq!(VMConfig::builder().build(), VMConfig::default());
+
+        let config = VMConfig::builder()
+            .memory_size(128 * 1024)
+            .enable_forwarding(false)
+            .max_cycles(Some(500))
+            .branch_predictor_kind(PredictorType::Dynamic)
+            .build();
+        assert_eq!(
+            config,
+            VMConfig {
+                memory_size: 128 * 1024,
+                enable_forwarding: false,
+                max_cycles: Some(500),
+                branch_predictor_kind: PredictorType::Dynamic,
+                ..VMConfig::default()
+            }
+        );
+        assert_eq!(PunkVM::with_config(config).memory.size(), 128 * 1024);
+    }
+
+    fn simd_op_cache_hit_rate(simd_op_cache_size: usize) -> f64 {
+        // Quatre opérations distinctes répétées en alternance, sur des sources inchangées
+        let mut code = vec![
+            Instruction::create_simd128_const_i32x4(0, [100, 200, 300, 400]),
+            Instruction::create_simd128_const_i32x4(1, [10, 20, 30, 40]),
+        ];
+        for _ in 0..5 {
+            code.push(Instruction::create_reg_reg_reg(Opcode::Simd128Add, 3, 0, 1));
+            code.push(Instruction::create_reg_reg_reg(Opcode::Simd128Mul, 4, 0, 1));
+            code.push(Instruction::create_reg_reg_reg(Opcode::Simd128Sub, 5, 0, 1));
+            code.push(Instruction::create_reg_reg_reg(Opcode::Simd128Xor, 6, 0, 1));
+        }
+        code.push(Instruction::create_no_args(Opcode::Halt));
+
+        let config = VMConfig::builder().simd_op_cache_size(simd_op_cache_size).build();
+        let mut vm = PunkVM::with_config(config);
+        vm.load_program_from_bytecode(build_program(code)).unwrap();
+        vm.run().unwrap();
+        let result = vm.get_vector_alu().borrow().read_v128(5).unwrap();
+        assert_eq!(unsafe { result.i32x4 }, [90, 180, 270, 360]);
+        vm.stats().simd_cache_hit_rate
+    }
+
+    #[test]
+    fn test_simd_op_cache_hit_rate_grows_with_size() {
+        // Une seule entrée: chaque opération chasse la précédente
+        assert_eq!(simd_op_cache_hit_rate(1), 0.0);
+        // Quatre entrées: seul le premier tour manque
+        assert_eq!(simd_op_cache_hit_rate(4), 80.0);
+        assert_eq!(simd_op_cache_hit_rate(0), 0.0);
+    }
+
+    #[test]
+    fn test_simd256_op_cache_keys_on_operand_values() {
+        // Les constantes 256-bit répètent leurs 16 premiers octets
+        let program = build_program(vec![
+            Instruction::create_simd256_const_i32x8(0, [1, 2, 3, 4, 1, 2, 3, 4]),
+            Instruction::create_simd256_const_i32x8(1, [10; 8]),
+            Instruction::create_simd256_add(2, 0, 1),
+            // Mêmes valeurs dans d'autres registres: hit
+            Instruction::create_simd256_const_i32x8(3, [1, 2, 3, 4, 1, 2, 3, 4]),
+            Instruction::create_simd256_add(4, 3, 1),
+            // Mêmes registres, valeurs modifiées: le résultat ne doit pas venir du cache
+            Instruction::create_simd256_const_i32x8(0, [100; 8]),
+            Instruction::create_simd256_add(5, 0, 1),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program).unwrap();
+        vm.run().unwrap();
+
+        let stats = vm.stats();
+        assert_eq!((stats.simd_cache_hits, stats.simd_cache_misses), (1, 2));
+        let vector_alu = vm.get_vector_alu().borrow();
+        assert_eq!(unsafe { vector_alu.read_v256(4).unwrap().i32x8 }, [11, 12, 13, 14, 11, 12, 13, 14]);
+        assert_eq!(unsafe { vector_alu.read_v256(5).unwrap().i32x8 }, [110; 8]);
+    }
+
+    #[test]
+    fn test_branch_predictor_warm_start_from_exported_state() {
+        let config = VMConfig { branch_predictor_kind: PredictorType::Dynamic, ..VMConfig::default() };
+
+        // Entraînement: le prédicteur survit au rechargement du programme
+        let mut trainer = PunkVM::with_config(config);
+        for _ in 0..3 {
+            trainer.load_program_from_bytecode(single_taken_branch_program()).unwrap();
+            trainer.run().unwrap();
+        }
+        assert_eq!(trainer.registers[1], 0);
+        let state = trainer.export_branch_predictor_state();
+
+        // A froid, le premier branchement est mal prédit
+        let mut cold = PunkVM::with_config(config);
+        cold.load_program_from_bytecode(single_taken_branch_program()).unwrap();
+        cold.run().unwrap();
+        assert_eq!(cold.pipeline.stats().branch_predictions, 1);
+        assert_eq!(cold.pipeline.stats().branch_hits, 0);
+
+        // A chaud, pas de miss initial
+        let mut warm = PunkVM::with_config(config);
+        warm.import_branch_predictor_state(&state).unwrap();
+        warm.load_program_from_bytecode(single_taken_branch_program()).unwrap();
+        warm.run().unwrap();
+        assert_eq!(warm.pipeline.stats().branch_predictions, 1);
+        assert_eq!(warm.pipeline.stats().branch_hits, 1);
+    }
+
+    #[test]
+    fn test_mispredict_preserves_older_instruction() {
+        // Cmp R0, R0 ; Mov R5, 7 (indépendant, plus ancien) ; Jeq pris mais prédit non pris ; Mov R6, 9 (chemin spéculatif) ; Halt
+        let mut code = vec![
+            Instruction::create_reg_reg(Opcode::Cmp, 0, 0),
+            Instruction::create_reg_imm8(Opcode::Mov, 5, 7),
+        ];
+        let jeq_addr = Instruction::calculate_current_address(&code);
+        let younger = Instruction::create_reg_imm8(Opcode::Mov, 6, 9);
+        let jeq_size = Instruction::create_jump_if_equal(0, 0).total_size() as u32;
+        code.push(Instruction::create_jump_if_equal(jeq_addr, jeq_addr + jeq_size + younger.total_size() as u32));
+        code.push(younger);
+        code.push(Instruction::create_no_args(Opcode::Halt));
+
+        let config = VMConfig { branch_predictor_kind: PredictorType::Dynamic, ..VMConfig::default() };
+        let mut vm = PunkVM::with_config(config);
+        vm.load_program_from_bytecode(build_program(code)).unwrap();
+        vm.run().unwrap();
+
+        let stats = vm.stats();
+        assert_eq!(stats.branch_flush, 1);
+        // Le Mov plus ancien, en EX/MEM au moment du flush, retire normalement
+        assert_eq!(stats.flush_preserved_instructions, 1);
+        assert_eq!(vm.registers[5], 7);
+        // L'instruction du chemin non pris ne retire jamais
+        assert_eq!(vm.registers[6], 0);
+    }
+
+    fn run_traced(program: BytecodeFile) -> PunkVM {
+        let mut vm = PunkVM::new();
+        vm.enable_tracing(TracerConfig { enabled: true, log_to_console: false, ..TracerConfig::default() });
+        vm.load_program_from_bytecode(program).unwrap();
+        vm.run().unwrap();
+        vm
+    }
+
+    #[test]
+    fn test_export_cfg_dot_annotates_block_and_edge_counts() {
+        // R0 = 3; boucle: Dec R0 / JmpIfNotZero boucle; Halt
+        let mut code = vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 0, 3),
+            Instruction::create_single_reg(Opcode::Dec, 0),
+        ];
+        let loop_addr = Instruction::calculate_current_address(&code[..1]);
+        let branch_addr = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_jump_if_not_zero(branch_addr, loop_addr));
+        code.push(Instruction::create_no_args(Opcode::Halt));
+        let halt_addr = Instruction::calculate_current_address(&code[..3]);
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(build_program(code)).unwrap();
+        vm.run().unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cfg.dot");
+        vm.export_cfg_dot(&path).unwrap();
+        let dot = std::fs::read_to_string(&path).unwrap();
+
+        assert!(dot.starts_with("digraph cfg {"));
+        let node = |start: u32, last: u32, count: u64| {
+            format!("\"0x{:08X}\" [label=\"0x{:08X}..0x{:08X}\\nexec: {}\"]", start, start, last, count)
+        };
+        let edge = |from: u32, to: u32, count: u64| format!("\"0x{:08X}\" -> \"0x{:08X}\" [label=\"{}\"]", from, to, count);
+        assert_eq!(dot.matches("[label=\"0x").count(), 3);
+        assert!(dot.contains(&node(0, 0, 1)));
+        assert!(dot.contains(&node(loop_addr, branch_addr, 3)));
+        assert!(dot.contains(&node(halt_addr, halt_addr, 1)));
+        assert!(dot.contains(&edge(0, loop_addr, 1)));
+        assert!(dot.contains(&edge(loop_addr, loop_addr, 2)));
+        assert!(dot.contains(&edge(loop_addr, halt_addr, 1)));
+    }
+
+    #[test]
+    fn test_golden_trace_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let golden = dir.path().join("forwarding_stress.golden");
+        let golden = golden.to_str().unwrap();
+
+        let reference = run_traced(build_program(forwarding_stress_program()));
+        reference.capture_golden_trace(golden).unwrap();
+
+        // Une ré-exécution identique reproduit la trace événement par événement
+        let rerun = run_traced(build_program(forwarding_stress_program()));
+        assert_eq!(rerun.compare_against_golden(golden).unwrap(), None);
+
+        // Un programme différent est signalé au premier événement divergent
+        let other = run_traced(single_taken_branch_program());
+        let mismatch = other.compare_against_golden(golden).unwrap().expect("les traces doivent différer");
+        assert_ne!(mismatch.expected, mismatch.actual);
+    }
+
+    #[test]
+    fn test_simd256_lowering_matches_native_execution() {
+        // Le saut relatif enjambe un XOR qui effacerait le résultat: il doit rester correct après abaissement
+        let xor = Instruction::create_simd256_xor(3, 3, 3);
+        let mut code = vec![
+            Instruction::create_simd256_const_i32x8(1, [1, 2, 3, 4, 1, 2, 3, 4]),
+            Instruction::create_simd256_const_i32x8(2, [10, 20, 30, 40, 10, 20, 30, 40]),
+            Instruction::create_simd256_add(3, 1, 2),
+        ];
+        let jump_pc = Instruction::calculate_current_address(&code);
+        let jump_size = Instruction::create_jump(0, 0).total_size() as u32;
+        code.push(Instruction::create_jump(jump_pc, jump_pc + jump_size + xor.total_size() as u32));
+        code.push(xor);
+        code.push(Instruction::create_no_args(Opcode::Halt));
+        let program = build_program(code);
+
+        let mut native = PunkVM::new();
+        native.load_program_from_bytecode(program.clone()).unwrap();
+        native.run().unwrap();
+        let expected = unsafe { native.get_vector_alu().borrow().v256_registers[3].i32x8 };
+        assert_eq!(expected, [11, 22, 33, 44, 11, 22, 33, 44]);
+
+        let lowered = program.lower_simd256_to_simd128().unwrap();
+        assert!(lowered.code.iter().all(|i| i.opcode.category() != crate::bytecode::opcodes::OpcodeCategory::Simd256));
+        assert_eq!(lowered.code.iter().filter(|i| i.opcode == Opcode::Simd128Add).count(), 2);
+        assert_eq!(lowered.segments[0].size, lowered.code_size());
+
+        // Configuration sans 256-bit: le programme est abaissé au chargement
+        let mut narrow = PunkVM::with_config(VMConfig { enable_simd256: false, ..VMConfig::default() });
+        narrow.load_program_from_bytecode(program).unwrap();
+        narrow.run().unwrap();
+        let (low, high) = {
+            let vector_alu = narrow.get_vector_alu().borrow();
+            unsafe { (vector_alu.v128_registers[6].i32x4, vector_alu.v128_registers[7].i32x4) }
+        };
+        assert_eq!([low, high].concat(), expected.to_vec());
+    }
+
+    #[test]
+    fn test_fork_shares_copy_on_write_pages() {
+        let config = VMConfig { memory_size: 4 * 1024 * 1024, copy_on_write_memory: true, ..VMConfig::default() };
+        let mut parent = PunkVM::with_config(config);
+        parent
+            .load_program_from_bytecode(build_program(vec![
+                Instruction::create_reg_imm8(Opcode::Mov, 1, 7),
+                Instruction::create_no_args(Opcode::Halt),
+            ]))
+            .unwrap();
+        // 1 MB de données réparties sur 256 pages
+        let data: Vec<u8> = (0..1024 * 1024).map(|i| (i % 251) as u8).collect();
+        parent.write_memory(0x10000, &data).unwrap();
+        let parent_copies = parent.memory.stats().cow_page_copies;
+
+        let mut child = parent.fork();
+        assert!(child.memory.is_copy_on_write());
+        assert_eq!(child.memory.stats().cow_page_copies, 0);
+
+        child.write_memory(0x10000 + 5 * COW_PAGE_SIZE as u32, &[0xAA, 0xBB]).unwrap();
+        // Seule la page modifiée est dupliquée, l'original n'a rien copié
+        assert_eq!(child.memory.stats().cow_page_copies, 1);
+        assert_eq!(parent.memory.stats().cow_page_copies, parent_copies);
+
+        let offset = 5 * COW_PAGE_SIZE;
+        assert_eq!(parent.read_memory(0x10000 + offset as u32, 2).unwrap(), data[offset..offset + 2].to_vec());
+        assert_eq!(child.read_memory(0x10000 + offset as u32, 2).unwrap(), vec![0xAA, 0xBB]);
+        assert_eq!(child.read_memory(0x10000, data.len()).unwrap()[..offset], data[..offset]);
+
+        // Les deux VM s'exécutent indépendamment
+        parent.run().unwrap();
+        child.run().unwrap();
+        assert_eq!(parent.registers[1], 7);
+        assert_eq!(child.registers[1], 7);
+        assert_eq!(parent.read_memory(0x10000 + offset as u32, 2).unwrap(), data[offset..offset + 2].to_vec());
+    }
+
+    #[test]
+    fn test_copy_on_write_counts_copies_only_after_fork() {
+        let config = VMConfig { memory_size: 1024 * 1024, copy_on_write_memory: true, ..VMConfig::default() };
+        let mut vm = PunkVM::with_config(config);
+        vm.load_program_from_bytecode(build_program(vec![Instruction::create_no_args(Opcode::Halt)]))
+            .unwrap();
+        // Sans fork, aucune page n'est partagée: écrire ne copie rien
+        vm.write_memory(0x8000, &[1, 2, 3, 4]).unwrap();
+        assert_eq!(vm.memory.stats().cow_page_copies, 0);
+
+        // Restaurer un instantané identique dans un fork ne dé-partage aucune page
+        let snapshot = vm.snapshot();
+        let mut child = vm.fork();
+        child.restore(&snapshot).unwrap();
+        assert_eq!(child.memory.stats().cow_page_copies, 0);
+
+        // Seule la page réellement modifiée est dupliquée
+        child.write_memory(0x8000, &[9]).unwrap();
+        assert_eq!(child.memory.stats().cow_page_copies, 1);
+        assert_eq!(vm.read_memory(0x8000, 1).unwrap(), vec![1]);
+    }
+
+    #[test]
+    fn test_store_before_halt_is_visible_after_run() {
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(build_program(vec![
+            Instruction::create_reg_imm16(Opcode::Mov, 1, 0x1234),
+            Instruction::create_reg_imm16(Opcode::Mov, 2, 0x1000),
+            Instruction::create_store_reg_offset(Opcode::Store, 1, 2, 0),
+            Instruction::create_no_args(Opcode::Halt),
+        ]))
+        .unwrap();
+        vm.write_memory(0x1000, &[0xFF; 8]).unwrap();
+
+        vm.run().unwrap();
+
+        assert_eq!(vm.memory.pending_stores(), 0);
+        let stored = vm.read_memory(0x1000, 8).unwrap();
+        assert_eq!(u64::from_le_bytes(stored.try_into().unwrap()), 0x1234);
+    }
+
+    #[test]
+    fn test_out_of_range_register_is_rejected_at_load() {
+        let mut vm = PunkVM::new();
+        let result = vm.load_program_from_bytecode(build_program(vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 5),
+            Instruction::create_reg_reg_reg(Opcode::Add, 20, 1, 1),
+            Instruction::create_no_args(Opcode::Halt),
+        ]));
+        assert!(matches!(result, Err(VMError::InvalidRegister { index: 20 })));
+
+        // Les bases des accès mémoire sont aussi contrôlées
+        let result = vm.load_program_from_bytecode(build_program(vec![
+            Instruction::create_load_reg_offset(1, 16, 0),
+            Instruction::create_no_args(Opcode::Halt),
+        ]));
+        assert!(matches!(result, Err(VMError::InvalidRegister { index: 16 })));
+    }
+
+    /// main: CALL f1; INC R5; HALT
+    /// f1/f2: INC Rn; CALL suivante; INC Rn; RET -- f3: INC R4; RET
+    fn nested_calls_program() -> BytecodeFile {
+        let inc = |r| Instruction::create_single_reg(Opcode::Inc, r);
+        let call_size = Instruction::create_call_relative(0, 0).total_size() as u32;
+        let (inc_size, ret_size) = (inc(0).total_size() as u32, Instruction::create_return().total_size() as u32);
+        let f1 = call_size + inc_size + Instruction::create_no_args(Opcode::Halt).total_size() as u32;
+        let f2 = f1 + 2 * inc_size + call_size + ret_size;
+        let f3 = f2 + 2 * inc_size + call_size + ret_size;
+
+        let mut code = vec![
+            Instruction::create_call_relative(0, f1),
+            inc(5),
+            Instruction::create_no_args(Opcode::Halt),
+        ];
+        for (start, reg, callee) in [(f1, 2, f2), (f2, 3, f3)] {
+            code.push(inc(reg));
+            code.push(Instruction::create_call_relative(start + inc_size, callee));
+            code.push(inc(reg));
+            code.push(Instruction::create_return());
+        }
+        code.push(inc(4));
+        code.push(Instruction::create_return());
+        build_program(code)
+    }
+
+    fn run_nested_calls(ras_size: usize) -> PunkVM {
+        let config = VMConfig { stack_base: 0x8000, stack_size: 0x1000, ras_size, ..VMConfig::default() };
+        let mut vm = PunkVM::with_config(config);
+        vm.load_program_from_bytecode(nested_calls_program()).unwrap();
+        vm.run().unwrap();
+        assert_eq!(&vm.registers[2..6], &[2, 2, 1, 1]);
+        vm
+    }
+
+    #[test]
+    fn test_ras_predicts_nested_returns() {
+        let vm = run_nested_calls(8);
+        let ras = vm.get_ras_stats();
+        assert_eq!((ras.pushes, ras.pops), (3, 3));
+        assert_eq!(ras.hits, 3);
+        assert_eq!(ras.misses, 0);
+        assert_eq!(vm.pipeline.stats().ras_hits, 3);
+        // La pile mémoire est revenue à son sommet initial
+        assert_eq!(vm.registers[16], 0x9000);
+
+        // RAS plus petit que la profondeur d'appel: l'entrée la plus ancienne est perdue,
+        // le dernier RET retombe sur le BTB (encore froid) mais reste correct
+        let vm = run_nested_calls(2);
+        let ras = vm.get_ras_stats();
+        assert_eq!(ras.hits, 2);
+        assert_eq!(ras.misses, 1);
+    }
+
+    #[test]
+    fn test_call_stack_lists_return_addresses_innermost_first() {
+        let program = nested_calls_program();
+        // Adresse suivant chaque CALL, dans l'ordre du code: main, f1, f2
+        let mut return_addresses = Vec::new();
+        let mut addr = 0u32;
+        for instr in &program.code {
+            addr += instr.total_size() as u32;
+            if instr.opcode == Opcode::Call {
+                return_addresses.push(addr);
+            }
+        }
+        let innermost = Instruction::calculate_current_address(&program.code[..program.code.len() - 2]);
+
+        let config = VMConfig { stack_base: 0x8000, stack_size: 0x1000, ..VMConfig::default() };
+        let mut vm = PunkVM::with_config(config);
+        vm.load_program_from_bytecode(program).unwrap();
+        assert!(vm.call_stack().is_empty());
+
+        assert!(vm.run_to_pc(innermost).unwrap());
+        return_addresses.reverse();
+        assert_eq!(vm.call_stack(), return_addresses);
+
+        // Après les RET, plus aucun appel en cours
+        vm.run().unwrap();
+        assert!(vm.call_stack().is_empty());
+        assert_eq!(vm.registers[4], 1);
+    }
+
+    #[test]
+    fn test_watchpoint_fires_on_each_store_in_loop() {
+        // R0 = 3; boucle: [R1] = R0; Dec R0; JmpIfNotZero boucle; puis store SIMD sur 0x3000
+        let mut code = vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 0, 3),
+            Instruction::create_reg_imm32(Opcode::Mov, 1, 0x2000),
+        ];
+        let loop_start = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_store_reg_offset(Opcode::Store, 0, 1, 0));
+        code.push(Instruction::create_single_reg(Opcode::Dec, 0));
+        let branch_addr = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_jump_if_not_zero(branch_addr, loop_start));
+        code.push(Instruction::create_simd128_const_i64x2(0, [5, 7]));
+        code.push(Instruction::create_reg_imm32(Opcode::Mov, 2, 0x3000));
+        code.push(Instruction::create_simd128_store(0, 2, 0));
+        code.push(Instruction::create_no_args(Opcode::Halt));
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(build_program(code)).unwrap();
+        vm.add_watchpoint(0x2000);
+        // Milieu du vecteur de 16 octets écrit à 0x3000
+        vm.add_watchpoint(0x3008);
+
+        let mut hits = Vec::new();
+        loop {
+            vm.run().unwrap();
+            match vm.state() {
+                VMState::Watchpoint { addr, old, new } => hits.push((*addr, *old, *new)),
+                _ => break,
+            }
+        }
+
+        assert_eq!(hits, vec![(0x2000, 0, 3), (0x2000, 3, 2), (0x2000, 2, 1), (0x3008, 0, 7)]);
+        assert_eq!(vm.state(), &VMState::Halted);
+        assert_eq!(vm.read_memory(0x3000, 8).unwrap(), 5u64.to_le_bytes());
+    }
+
+    #[test]
+    fn test_run_until_stops_after_target_retires() {
+        let code = vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 5),
+            Instruction::create_reg_imm8(Opcode::Mov, 2, 7),
+            Instruction::create_reg_reg(Opcode::Add, 1, 2),
+            Instruction::create_reg_reg(Opcode::Add, 1, 2),
+            Instruction::create_reg_reg(Opcode::Add, 1, 2),
+            Instruction::create_no_args(Opcode::Halt),
+            // Jamais atteinte
+            Instruction::create_reg_imm8(Opcode::Mov, 3, 1),
+        ];
+        let first_add = Instruction::calculate_current_address(&code[..2]);
+        let after_halt = Instruction::calculate_current_address(&code[..6]);
+        let program = build_program(code);
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program.clone()).unwrap();
+        assert_eq!(vm.run_until(first_add).unwrap(), VMState::Running);
+        assert_eq!(vm.registers[1], 12);
+
+        // Le reste du programme s'exécute normalement
+        vm.run().unwrap();
+        assert_eq!(vm.registers[1], 26);
+
+        // PC jamais atteint: arrêt sur HALT
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program).unwrap();
+        assert_eq!(vm.run_until(after_halt).unwrap(), VMState::Halted);
+        assert_eq!(vm.registers[3], 0);
+    }
+
+    #[test]
+    fn test_run_until_stops_on_first_loop_iteration() {
+        let mut code = vec![Instruction::create_reg_imm8(Opcode::Mov, 0, 5)];
+        let loop_start = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_single_reg(Opcode::Inc, 3));
+        code.push(Instruction::create_single_reg(Opcode::Dec, 0));
+        let branch_addr = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_jump_if_not_zero(branch_addr, loop_start));
+        code.push(Instruction::create_no_args(Opcode::Halt));
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(build_program(code)).unwrap();
+        assert_eq!(vm.run_until(loop_start).unwrap(), VMState::Running);
+        assert_eq!(vm.registers[3], 1);
+        assert_eq!(vm.run_until(loop_start).unwrap(), VMState::Running);
+        assert_eq!(vm.registers[3], 2);
+    }
+
+    #[test]
+    fn test_restart_reruns_loaded_program_identically() {
+        // R2 = 3 * [0x2000], puis le résultat écrase la donnée initiale
+        let mut code = vec![
+            Instruction::create_reg_imm32(Opcode::Mov, 0, 0x2000),
+            Instruction::create_load_reg_offset(1, 0, 0),
+            Instruction::create_reg_imm8(Opcode::Mov, 3, 3),
+        ];
+        let loop_start = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_reg_reg(Opcode::Add, 2, 1));
+        code.push(Instruction::create_single_reg(Opcode::Dec, 3));
+        let branch_addr = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_jump_if_not_zero(branch_addr, loop_start));
+        code.push(Instruction::create_store_reg_offset(Opcode::Store, 2, 0, 0));
+        code.push(Instruction::create_no_args(Opcode::Halt));
+
+        let mut program = build_program(code);
+        let code_size = program.code_size();
+        program.add_data(&7u64.to_le_bytes());
+        program.segments = vec![
+            SegmentMetadata::new(SegmentType::Code, 0, code_size, 0x100),
+            SegmentMetadata::new(SegmentType::Data, 0, 8, 0x2000),
+        ];
+
+        let mut vm = PunkVM::new();
+        assert!(vm.restart().is_err(), "aucun programme chargé");
+        vm.load_program_from_bytecode(program).unwrap();
+        vm.run().unwrap();
+        let first_registers = vm.registers.clone();
+        let first_stats = vm.stats().to_json();
+        assert_eq!(vm.registers[2], 21);
+        assert_eq!(vm.read_memory(0x2000, 8).unwrap(), 21u64.to_le_bytes());
+
+        vm.restart().unwrap();
+        assert_eq!(vm.state, VMState::Ready);
+        assert_eq!(vm.pc, 0x100);
+        assert_eq!(vm.registers[2], 0);
+        // Le segment de données est réécrit avec sa valeur initiale
+        assert_eq!(vm.read_memory(0x2000, 8).unwrap(), 7u64.to_le_bytes());
+
+        vm.run().unwrap();
+        assert_eq!(vm.registers, first_registers);
+        assert_eq!(vm.stats().to_json(), first_stats);
+
+        // Les watchpoints survivent au redémarrage
+        vm.add_watchpoint(0x2000);
+        vm.restart().unwrap();
+        vm.run().unwrap();
+        assert!(matches!(vm.state, VMState::Watchpoint { addr: 0x2000, .. }));
+
+        // Un échec de réécriture laisse le programme chargé
+        vm.program.as_mut().unwrap().segments[1].load_addr = 0xFFFF_0000;
+        assert!(vm.restart().is_err());
+        assert!(vm.program.is_some());
+    }
+
+    #[test]
+    fn test_endianness_controls_multi_byte_layout() {
+        let load = |op, rd, off| Instruction::new(op, InstructionFormat::reg_regoff(), vec![rd, 0, off]);
+        let program = || build_program(vec![
+            Instruction::create_reg_imm32(Opcode::Mov, 0, 0x2000),
+            Instruction::create_reg_imm32(Opcode::Mov, 1, 0x0102_0304),
+            Instruction::create_store_reg_offset(Opcode::StoreD, 1, 0, 0),
+            // Servis par le store-load forwarding (même adresse que le store)
+            load(Opcode::LoadW, 2, 0),
+            load(Opcode::LoadB, 3, 0),
+            load(Opcode::LoadD, 4, 0),
+            // Servi par la mémoire
+            load(Opcode::LoadB, 5, 1),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let run = |endianness| {
+            let mut vm = PunkVM::with_config(VMConfig { endianness, ..VMConfig::default() });
+            vm.load_program_from_bytecode(program()).unwrap();
+            vm.run().unwrap();
+            vm
+        };
+        let little = run(Endianness::Little);
+        let big = run(Endianness::Big);
+
+        assert_eq!(little.read_memory(0x2000, 4).unwrap(), vec![0x04, 0x03, 0x02, 0x01]);
+        assert_eq!(big.read_memory(0x2000, 4).unwrap(), vec![0x01, 0x02, 0x03, 0x04]);
+        assert_eq!(&little.registers[2..6], &[0x0304, 0x04, 0x0102_0304, 0x03]);
+        assert_eq!(&big.registers[2..6], &[0x0102, 0x01, 0x0102_0304, 0x02]);
+        assert!(big.stats().store_load_forwards > 0);
+    }
+
+    #[test]
+    fn test_store_into_readonly_segment_is_rejected() {
+        const CONSTANT: u64 = 0xC0FF_EE00_1234_5678;
+        let code = vec![
+            Instruction::create_reg_imm32(Opcode::Mov, 0, 0x3000),
+            Instruction::create_load_reg_offset(1, 0, 0),
+            Instruction::create_reg_imm8(Opcode::Mov, 2, 99),
+            // Écriture dans la zone de données: autorisée
+            Instruction::create_store_reg_offset(Opcode::Store, 2, 0, -8),
+            // Écrasement de la constante: interdit
+            Instruction::create_store_reg_offset(Opcode::StoreB, 2, 0, 4),
+            Instruction::create_no_args(Opcode::Halt),
+        ];
+        let mut program = build_program(code);
+        let code_size = program.code_size();
+        program.add_data(&[0; 8]);
+        program.add_readonly_data(&CONSTANT.to_le_bytes());
+        program.segments = vec![
+            SegmentMetadata::new(SegmentType::Code, 0, code_size, 0),
+            SegmentMetadata::new(SegmentType::Data, 0, 8, 0x2FF8),
+            SegmentMetadata::new(SegmentType::ReadOnlyData, 0, 8, 0x3000),
+        ];
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program).unwrap();
+        let expected = VMError::WriteToReadOnly { addr: 0x3004 };
+        assert_eq!(vm.run(), Err(expected.clone()));
+        assert_eq!(vm.state, VMState::Error(expected));
+
+        assert_eq!(vm.registers[1], CONSTANT);
+        assert_eq!(vm.read_memory(0x2FF8, 8).unwrap(), 99u64.to_le_bytes());
+        assert_eq!(vm.read_memory(0x3000, 8).unwrap(), CONSTANT.to_le_bytes());
+    }
+
+    /// `code` suivi de HALT, avec un segment en lecture seule de 16 octets à `readonly_addr`
+    fn program_with_readonly_segment(mut code: Vec<Instruction>, readonly_addr: u32) -> BytecodeFile {
+        code.push(Instruction::create_no_args(Opcode::Halt));
+        let mut program = build_program(code);
+        let code_size = program.code_size();
+        program.add_readonly_data(&[0xAB; 16]);
+        program.segments = vec![
+            SegmentMetadata::new(SegmentType::Code, 0, code_size, 0),
+            SegmentMetadata::new(SegmentType::ReadOnlyData, 0, 16, readonly_addr),
+        ];
+        program
+    }
+
+    #[test]
+    fn test_readonly_segments_guard_stack_masked_stores_and_forks() {
+        // PUSH: la pile (0x2FF0..0x3010) déborde sur le segment protégé 0x3008..0x3018
+        let push = program_with_readonly_segment(vec![Instruction::create_push_immediate8(0, 7)], 0x3008);
+        let config = VMConfig { stack_base: 0x2FF0, stack_size: 0x20, ..VMConfig::default() };
+        let mut vm = PunkVM::with_config(config);
+        vm.load_program_from_bytecode(push).unwrap();
+        assert_eq!(vm.run(), Err(VMError::WriteToReadOnly { addr: 0x3008 }));
+        assert_eq!(vm.read_memory(0x3008, 8).unwrap(), vec![0xAB; 8]);
+
+        // Store masqué SIMD, exécuté par l'étage Execute: seule la lane 1 est écrite
+        let masked_store = program_with_readonly_segment(
+            vec![
+                Instruction::create_reg_imm32(Opcode::Mov, 2, 0x3000),
+                Instruction::create_reg_imm8(Opcode::Mov, 3, 0b0010),
+                Instruction::create_reg_reg_reg(Opcode::Simd128MaskedStore, 0, 2, 3),
+            ],
+            0x3000,
+        );
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(masked_store).unwrap();
+        // Un fork hérite de la protection du programme chargé
+        let mut child = vm.fork();
+        assert_eq!(vm.run(), Err(VMError::WriteToReadOnly { addr: 0x3004 }));
+        assert_eq!(child.run(), Err(VMError::WriteToReadOnly { addr: 0x3004 }));
+        assert_eq!(child.read_memory(0x3000, 16).unwrap(), vec![0xAB; 16]);
+    }
+
+    #[test]
+    fn test_segments_load_at_their_load_addresses() {
+        let mut code = vec![
+            Instruction::create_reg_imm32(Opcode::Mov, 0, 0x2000),
+            Instruction::create_load_reg_offset(1, 0, 0),
+            Instruction::create_reg_imm8(Opcode::Mov, 3, 3),
+        ];
+        let loop_start = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_reg_reg(Opcode::Add, 2, 1));
+        code.push(Instruction::create_single_reg(Opcode::Dec, 3));
+        let branch_addr = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_jump_if_not_zero(branch_addr, loop_start));
+        code.push(Instruction::create_no_args(Opcode::Halt));
+
+        let mut program = build_program(code);
+        let code_size = program.code_size();
+        let data = 0x0102_0304_0506_0708u64.to_le_bytes();
+        program.add_data(&data);
+        program.segments = vec![
+            SegmentMetadata::new(SegmentType::Code, 0, code_size, 0x100),
+            SegmentMetadata::new(SegmentType::Data, 0, data.len() as u32, 0x2000),
+        ];
+        let first_instruction = program.code[0].encode();
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program).unwrap();
+        assert_eq!(vm.pc, 0x100);
+        vm.run().unwrap();
+
+        assert_eq!(vm.registers[1], 0x0102_0304_0506_0708);
+        assert_eq!(vm.registers[2], 3 * 0x0102_0304_0506_0708);
+        assert_eq!(vm.read_memory(0x100, first_instruction.len()).unwrap(), first_instruction);
+        assert_eq!(vm.read_memory(0x2000, 8).unwrap(), data);
+        assert_eq!(vm.execution_profile().execution_count(0x100 + loop_start), 3);
+    }
+
+    #[test]
+    fn test_code_loaded_above_zero_in_cfg_append_and_reset() {
+        let mut program = build_program(vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 5),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+        program.segments[0].load_addr = 0x100;
+        let halt_addr = 0x100 + program.code[0].total_size() as u32;
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program).unwrap();
+        vm.run().unwrap();
+
+        // Le CFG exporté est numéroté à partir de l'adresse de chargement du code
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cfg.dot");
+        vm.export_cfg_dot(&path).unwrap();
+        let dot = std::fs::read_to_string(&path).unwrap();
+        assert!(dot.contains(&format!("\"0x{:08X}\" [label=\"0x{:08X}..0x{:08X}\\nexec: 1\"]", 0x100, 0x100, halt_addr)));
+
+        // Cible absolue relative au bloc ajouté: le saut évite le MOV R1, 99
+        let absolute_jump = |target: u32| {
+            let format = InstructionFormat::new(ArgType::None, ArgType::AbsoluteAddr, ArgType::None);
+            Instruction::new(Opcode::Jmp, format, target.to_le_bytes().to_vec())
+        };
+        let mut block = vec![absolute_jump(0), Instruction::create_reg_imm8(Opcode::Mov, 1, 99)];
+        block[0] = absolute_jump(Instruction::calculate_current_address(&block));
+        block.push(Instruction::create_reg_imm8(Opcode::Mov, 2, 7));
+        block.push(Instruction::create_no_args(Opcode::Halt));
+        assert_eq!(vm.append_and_run(&block).unwrap(), VMState::Halted);
+        assert_eq!((vm.registers[1], vm.registers[2]), (5, 7));
+
+        vm.reset();
+        assert_eq!(vm.pc, 0x100);
+    }
+
+    #[test]
+    fn test_append_and_run_accumulates_on_previous_state() {
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(build_program(vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 5),
+            Instruction::create_reg_imm8(Opcode::Mov, 2, 7),
+            Instruction::create_no_args(Opcode::Halt),
+        ]))
+        .unwrap();
+        vm.run().unwrap();
+
+        let add = [Instruction::create_reg_reg(Opcode::Add, 1, 2), Instruction::create_no_args(Opcode::Halt)];
+        assert_eq!(vm.append_and_run(&add).unwrap(), VMState::Halted);
+        assert_eq!(vm.registers[1], 12);
+        assert_eq!(vm.append_and_run(&add).unwrap(), VMState::Halted);
+        assert_eq!(vm.registers[1], 19);
+
+        // Boucle dont les adresses sont relatives au bloc ajouté: R1 += 3 * R2
+        let mut block = vec![Instruction::create_reg_imm8(Opcode::Mov, 0, 3)];
+        let loop_start = Instruction::calculate_current_address(&block);
+        block.push(Instruction::create_reg_reg(Opcode::Add, 1, 2));
+        block.push(Instruction::create_single_reg(Opcode::Dec, 0));
+        let branch_addr = Instruction::calculate_current_address(&block);
+        block.push(Instruction::create_jump_if_not_zero(branch_addr, loop_start));
+        block.push(Instruction::create_no_args(Opcode::Halt));
+        assert_eq!(vm.append_and_run(&block).unwrap(), VMState::Halted);
+        assert_eq!(vm.registers[1], 40);
+    }
+
+    /// Boucles imbriquées dont la boucle interne commence par un saut: le branchement
+    /// de fin de boucle interne a un autre branchement juste derrière lui sur le chemin prédit
+    fn nested_branches_program() -> BytecodeFile {
+        let jump_size = Instruction::create_jump(0, 0).total_size() as u32;
+        let mut code = vec![Instruction::create_reg_imm8(Opcode::Mov, 0, 3)];
+        let outer = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_reg_imm8(Opcode::Mov, 1, 4));
+        let inner = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_jump(inner, inner + jump_size));
+        code.push(Instruction::create_single_reg(Opcode::Inc, 13));
+        code.push(Instruction::create_single_reg(Opcode::Dec, 1));
+        let at = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_jump_if_not_zero(at, inner));
+        code.push(Instruction::create_single_reg(Opcode::Dec, 0));
+        let at = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_jump_if_not_zero(at, outer));
+        code.push(Instruction::create_no_args(Opcode::Halt));
+        build_program(code)
+    }
+
+    #[test]
+    fn test_speculation_depth_trades_squashes_for_stalls() {
+        let run_with_depth = |max_speculation_depth: Option<usize>| {
+            let config = VMConfig { max_speculation_depth, ..VMConfig::default() };
+            let mut vm = PunkVM::with_config(config);
+            vm.load_program_from_bytecode(nested_branches_program()).unwrap();
+            vm.run().unwrap();
+            assert_eq!(vm.registers[13], 12);
+            vm.stats()
+        };
+
+        let unlimited = run_with_depth(None);
+        let single = run_with_depth(Some(1));
+
+        assert!(unlimited.branch_mispredictions > 0);
+        assert_eq!(unlimited.speculation_stalls, 0);
+        assert!(unlimited.speculative_squashes > 0);
+        assert!(single.speculative_squashes < unlimited.speculative_squashes);
+        assert!(single.speculation_stalls > 0);
+        assert!(single.stalls > unlimited.stalls);
+        assert_eq!(single.cycles - unlimited.cycles, single.speculation_stalls);
+    }
+
+    #[test]
+    fn test_trace_events_carry_instruction_pc() {
+        // Chaîne dépendante: chaque instruction traverse seule les étages
+        let code = vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 5),
+            Instruction::create_reg_reg_reg(Opcode::Add, 2, 1, 1),
+            Instruction::create_reg_reg_reg(Opcode::Add, 3, 2, 2),
+            Instruction::create_no_args(Opcode::Halt),
+        ];
+        let pcs: Vec<u32> = (1..code.len()).map(|i| Instruction::calculate_current_address(&code[..i])).collect();
+        let vm = run_traced(build_program(code));
+
+        let events = vm.tracer.as_ref().unwrap().events();
+        let writeback_pcs: Vec<u32> = events
+            .iter()
+            .filter_map(|e| match e {
+                crate::debug::TraceEvent::Writeback { pc, .. } => Some(*pc),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(writeback_pcs, vec![0, pcs[0], pcs[1]]);
+        assert!(writeback_pcs[1..].iter().all(|&pc| pc != 0));
+
+        let execute_pcs: Vec<u32> = events
+            .iter()
+            .filter_map(|e| match e {
+                crate::debug::TraceEvent::Execute { pc, .. } => Some(*pc),
+                _ => None,
+            })
+            .collect();
+        assert!(execute_pcs.contains(&pcs[0]));
+        assert!(execute_pcs.contains(&pcs[1]));
+    }
+
+    #[test]
+    fn test_trace_sampling_spans_whole_run() {
+        let mut code = vec![Instruction::create_reg_imm8(Opcode::Mov, 0, 40)];
+        let loop_start = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_single_reg(Opcode::Inc, 1));
+        code.push(Instruction::create_single_reg(Opcode::Dec, 0));
+        let branch_addr = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_jump_if_not_zero(branch_addr, loop_start));
+        code.push(Instruction::create_no_args(Opcode::Halt));
+
+        let config = VMConfig { trace_sampling_rate: 10, ..VMConfig::default() };
+        let mut vm = PunkVM::with_config(config);
+        vm.enable_tracing(TracerConfig { enabled: true, log_to_console: false, ..TracerConfig::default() });
+        vm.load_program_from_bytecode(build_program(code)).unwrap();
+        vm.run().unwrap();
+
+        let mut cycles: Vec<u64> = vm.tracer.as_ref().unwrap().events().iter().map(|e| e.cycle()).collect();
+        cycles.dedup();
+        assert!(cycles.windows(2).all(|pair| pair[1] - pair[0] == 10));
+        // Les échantillons couvrent toute l'exécution, pas seulement sa fin
+        assert_eq!(cycles.first(), Some(&0));
+        assert!(vm.cycles - cycles.last().unwrap() <= 10);
+        assert!(cycles.len() as u64 >= vm.cycles / 10);
+    }
+
+    #[test]
+    fn test_retire_events_time_each_instruction() {
+        let program = build_program(vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 5),
+            Instruction::create_reg_reg(Opcode::Add, 2, 1),
+            Instruction::create_reg_reg(Opcode::Add, 3, 2),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+        // Fetch idéal (sans cache): le remplissage à froid de la ligne de code masquerait
+        // le stall de dépendance du premier Add
+        let config = VMConfig { enable_forwarding: false, enable_cache: false, ..VMConfig::default() };
+        let mut vm = PunkVM::with_config(config);
+        vm.enable_tracing(TracerConfig { enabled: true, log_to_console: false, ..TracerConfig::default() });
+        vm.load_program_from_bytecode(program).unwrap();
+        vm.run().unwrap();
+
+        let retired: Vec<_> = vm.tracer.as_ref().unwrap().events().iter().filter_map(|e| match e {
+            crate::debug::TraceEvent::Retire { id, pc, fetch_cycle, retire_cycle } => {
+                Some((*id, *pc, *fetch_cycle, *retire_cycle))
+            }
+            _ => None,
+        }).collect();
+        let add_pc = Instruction::create_reg_imm8(Opcode::Mov, 1, 5).total_size() as u32;
+        let add_size = Instruction::create_reg_reg(Opcode::Add, 2, 1).total_size() as u32;
+        // HALT est retiré comme les autres; les identifiants suivent l'ordre du programme
+        // et un rechargement après stall garde le sien
+        assert_eq!(
+            retired.iter().map(|r| (r.0, r.1)).collect::<Vec<_>>(),
+            vec![(0, 0), (1, add_pc), (2, add_pc + add_size), (3, add_pc + 2 * add_size)]
+        );
+        // Le pipeline fait traverser tous les étages dans le cycle du fetch: une instruction
+        // sans dépendance est retirée dans ce cycle (la profondeur n'ajoute pas de latence)
+        assert_eq!((retired[0].2, retired[0].3), (0, 0));
+        // Sans forwarding, chaque Add dépendant reste bloqué après son fetch
+        for &(_, _, fetch_cycle, retire_cycle) in &retired[1..3] {
+            assert_eq!(retire_cycle - fetch_cycle, 2);
+        }
+        assert!(vm.tracer.as_ref().unwrap().generate_summary().contains("Latence moyenne des instructions"));
+    }
+
+    #[test]
+    fn test_retire_ids_are_fresh_for_each_loop_iteration() {
+        // Boucle d'une seule instruction (Jmp vers lui-même): chaque passage est une
+        // nouvelle instruction, avec son propre identifiant
+        let program = build_program(vec![
+            Instruction::create_jump(0, 0),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+        let mut vm = PunkVM::with_config(VMConfig { max_cycles: Some(20), ..VMConfig::default() });
+        vm.enable_tracing(TracerConfig { enabled: true, log_to_console: false, ..TracerConfig::default() });
+        vm.load_program_from_bytecode(program).unwrap();
+        assert!(vm.run().is_err());
+
+        let retired: Vec<(u64, u32, u64)> = vm.tracer.as_ref().unwrap().events().iter().filter_map(|e| match e {
+            crate::debug::TraceEvent::Retire { id, pc, fetch_cycle, .. } => Some((*id, *pc, *fetch_cycle)),
+            _ => None,
+        }).collect();
+        assert!(retired.len() > 2);
+        assert!(retired.iter().all(|&(_, pc, _)| pc == 0));
+        assert!(retired.windows(2).all(|w| w[0].0 < w[1].0 && w[0].2 < w[1].2), "{:?}", retired);
+    }
+
+    #[test]
+    fn test_export_traces_to_json_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("trace.json");
+        let path = path.to_str().unwrap();
+
+        let mut vm = run_traced(build_program(vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 5),
+            Instruction::create_reg_reg_reg(Opcode::Add, 2, 1, 1),
+            Instruction::create_no_args(Opcode::Halt),
+        ]));
+        // Les chaînes sont échappées
+        vm.tracer.as_mut().unwrap().trace(crate::debug::TraceEvent::Hazard {
+            cycle: 99,
+            hazard_type: "LoadUse".to_string(),
+            stall_cycles: 1,
+            description: "R1 \"en vol\"\n".to_string(),
+        });
+        vm.export_traces_to_json(path).unwrap();
+
+        let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        let events = json.as_array().unwrap();
+        assert_eq!(events.len(), vm.tracer.as_ref().unwrap().events().len());
+
+        let fetch = events.iter().find(|e| e["event"] == "FETCH" && e["instruction"]["opcode"] == "Add").unwrap();
+        assert_eq!(fetch["instruction"]["args"], serde_json::json!([2, 1, 1]));
+        assert!(fetch["cycle"].as_u64().unwrap() > 0);
+
+        let hazard = events.last().unwrap();
+        assert_eq!(hazard["event"], "HAZARD");
+        assert_eq!(hazard["stall_cycles"], 1);
+        assert_eq!(hazard["description"], "R1 \"en vol\"\n");
+    }
+
+    /// Compare R1 = 1 à R2 = `rhs`; exécute `branch` s'il est fourni, sinon s'arrête juste
+    /// après le Cmp. Retourne les flags finaux et vrai si le branchement a été pris
+    fn compare_one_with(rhs: u64, branch: Option<fn(u32, u32) -> Instruction>) -> (ALUFlags, bool) {
+        let mut code = vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 1),
+            Instruction::create_reg_imm64(Opcode::Mov, 2, rhs),
+            Instruction::create_reg_reg(Opcode::Cmp, 1, 2),
+        ];
+        if let Some(branch) = branch {
+            let branch_addr = Instruction::calculate_current_address(&code);
+            let skipped = Instruction::create_reg_imm8(Opcode::Mov, 5, 1);
+            let branch_size = branch(0, 0).total_size() as u32;
+            code.push(branch(branch_addr, branch_addr + branch_size + skipped.total_size() as u32));
+            code.push(skipped);
+        }
+        code.push(Instruction::create_no_args(Opcode::Halt));
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(build_program(code)).unwrap();
+        vm.run().unwrap();
+        assert_eq!(vm.registers[2], rhs);
+        (vm.flags(), branch.is_some() && vm.registers[5] == 0)
+    }
+
+    #[test]
+    fn test_signed_and_unsigned_branches_disagree() {
+        let taken = |rhs: u64, branch: fn(u32, u32) -> Instruction| compare_one_with(rhs, Some(branch)).1;
+
+        // 0xFFFF_FFFF est -1 en dword signé; étendu en signe sur 64 bits,
+        // 1 > -1 en signé mais 1 < 0xFFFF_FFFF_FFFF_FFFF en non signé
+        let minus_one = 0xFFFF_FFFFu32 as i32 as u64;
+        let (flags, _) = compare_one_with(minus_one, None);
+        assert!(flags.carry, "non signé: 1 < 0xFFFF_FFFF_FFFF_FFFF");
+        assert!(!flags.negative && !flags.overflow, "signé: SF = OF, 1 >= -1");
+        assert!(!taken(minus_one, Instruction::create_jump_if_less));
+        assert!(taken(minus_one, Instruction::create_jump_below));
+        assert!(taken(minus_one, Instruction::create_jump_if_greater));
+        assert!(!taken(minus_one, Instruction::create_jump_if_above));
+
+        // Étendu par zéros, 0xFFFF_FFFF est positif sur 64 bits:
+        // les deux familles donnent alors 1 < 0xFFFF_FFFF
+        let (flags, _) = compare_one_with(0xFFFF_FFFF, None);
+        assert!(flags.carry);
+        assert!(flags.negative && !flags.overflow, "signé: SF != OF, 1 < 0xFFFF_FFFF");
+        assert!(taken(0xFFFF_FFFF, Instruction::create_jump_if_less));
+        assert!(taken(0xFFFF_FFFF, Instruction::create_jump_below));
+        assert!(!taken(0xFFFF_FFFF, Instruction::create_jump_if_greater));
+        assert!(!taken(0xFFFF_FFFF, Instruction::create_jump_if_above));
+    }
+
+    #[test]
+    fn test_flag_clobber_between_cmp_and_branch() {
+        let mut code = vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 5),
+            Instruction::create_reg_imm8(Opcode::Mov, 2, 5),
+            Instruction::create_reg_reg(Opcode::Cmp, 1, 2),
+            // Écrase les flags du Cmp: 5 + 5 = 10 remet ZF à 0
+            Instruction::create_reg_reg_reg(Opcode::Add, 3, 1, 2),
+        ];
+        let branch_addr = Instruction::calculate_current_address(&code);
+        let skipped = Instruction::create_reg_imm8(Opcode::Mov, 5, 1);
+        let branch_size = Instruction::create_jump_if_equal(0, 0).total_size() as u32;
+        code.push(Instruction::create_jump_if_equal(
+            branch_addr,
+            branch_addr + branch_size + skipped.total_size() as u32,
+        ));
+        code.push(skipped);
+        code.push(Instruction::create_no_args(Opcode::Halt));
+        let program = build_program(code);
+
+        let lints = program.lint();
+        assert_eq!(lints.len(), 1);
+        assert_eq!(lints[0].kind, LintKind::FlagClobber { writer: Opcode::Add, writer_index: 3 });
+
+        // Résultat effectif: R1 == R2 mais le saut n'est pas pris, les flags viennent de l'Add
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program).unwrap();
+        vm.run().unwrap();
+        assert_eq!(vm.registers[3], 10);
+        assert_eq!(vm.registers[5], 1);
+    }
+
+    /// Rafale de MOV indépendants: chaque instruction produit un résultat
+    fn independent_results_burst() -> BytecodeFile {
+        let mut code: Vec<Instruction> =
+            (1..=8).map(|r| Instruction::create_reg_imm8(Opcode::Mov, r, r * 3)).collect();
+        code.push(Instruction::create_no_args(Opcode::Halt));
+        build_program(code)
+    }
+
+    fn run_burst_with_write_ports(ports: usize) -> PunkVM {
+        let config = VMConfig { issue_width: 2, register_write_ports: ports, ..VMConfig::default() };
+        let mut vm = PunkVM::with_config(config);
+        vm.load_program_from_bytecode(independent_results_burst()).unwrap();
+        vm.run().unwrap();
+        for r in 1..=8 {
+            assert_eq!(vm.registers[r], r as u64 * 3);
+        }
+        vm
+    }
+
+    #[test]
+    fn test_register_write_ports_limit_retirement() {
+        let wide = run_burst_with_write_ports(2);
+        let narrow = run_burst_with_write_ports(1);
+
+        assert_eq!(wide.stats().writeback_port_stalls, 0);
+        // Un seul port: le writeback de la seconde instruction de chaque paire coûte un cycle
+        assert!(narrow.stats().writeback_port_stalls > 0);
+        assert_eq!(
+            narrow.stats().cycles - wide.stats().cycles,
+            narrow.stats().writeback_port_stalls
+        );
+        assert!(narrow.stats().stalls >= narrow.stats().writeback_port_stalls);
+        assert_eq!(narrow.stats().instructions_executed, wide.stats().instructions_executed);
+    }
+
+    /// Variante de `cache_stress_test` (main.rs) qui écrit une ligne de cache sur 16 avant de relire
+    fn cache_stress_program() -> BytecodeFile {
+        let mut code = vec![Instruction::create_reg_imm8(Opcode::Mov, 1, 100)];
+        for line in 0..16u32 {
+            code.push(Instruction::create_reg_imm32(Opcode::Mov, 0, 0x2000 + line * 64));
+            code.push(Instruction::create_store_reg_offset(Opcode::Store, 1, 0, 0));
+        }
+        code.push(Instruction::create_reg_imm32(Opcode::Mov, 0, 0x2000));
+        code.push(Instruction::create_load_reg_offset(2, 0, 0));
+        code.push(Instruction::create_no_args(Opcode::Halt));
+        build_program(code)
+    }
+
+    #[test]
+    fn test_disabled_cache_bypasses_hierarchy() {
+        let config = VMConfig { enable_cache: false, ..VMConfig::default() };
+        let mut vm = PunkVM::with_config(config);
+        vm.load_program_from_bytecode(cache_stress_program()).unwrap();
+        vm.run().unwrap();
+
+        assert_eq!(vm.registers[2], 100);
+        let stats = vm.memory.stats();
+        assert_eq!((stats.l1_hits, stats.l1_misses, stats.l2_hits, stats.l2_misses), (0, 0, 0, 0));
+        assert_eq!((stats.l1_inst_hits, stats.l1_inst_misses, stats.sb_hits), (0, 0, 0));
+        assert!(stats.reads > 0 && stats.writes > 0);
+    }
+
+    #[test]
+    fn test_flush_all_caches_writes_back_and_restores_cold_misses() {
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(build_program(vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 7),
+            Instruction::create_reg_imm32(Opcode::Mov, 4, 0x3000),
+            Instruction::create_store_reg_offset(Opcode::Store, 1, 4, 0),
+            Instruction::create_reg_imm32(Opcode::Mov, 0, 0x2000),
+            Instruction::create_load_reg_offset(2, 0, 0),
+            Instruction::create_no_args(Opcode::Halt),
+        ]))
+        .unwrap();
+        vm.write_memory(0x2000, &100u64.to_le_bytes()).unwrap();
+        vm.run().unwrap();
+        assert_eq!(vm.registers[2], 100);
+        let reload = [Instruction::create_load_reg_offset(3, 0, 0), Instruction::create_no_args(Opcode::Halt)];
+        let misses = |vm: &PunkVM| (vm.memory.stats().l1_misses, vm.memory.stats().l2_misses);
+
+        // Cache chaud: la relecture ne manque aucun niveau
+        let before = misses(&vm);
+        vm.append_and_run(&reload).unwrap();
+        assert_eq!(misses(&vm), before);
+        assert_eq!(vm.stats().l2_writebacks, 0);
+
+        // La ligne du store, sale dans le L2 (write-back), est réécrite au flush;
+        // la relecture repart ensuite de la mémoire principale
+        vm.flush_all_caches().unwrap();
+        let writebacks = vm.stats().l2_writebacks;
+        assert!(writebacks > 0);
+        let before = misses(&vm);
+        vm.append_and_run(&reload).unwrap();
+        let after = misses(&vm);
+        assert!(after.0 > before.0 && after.1 > before.1);
+        assert_eq!(vm.registers[3], 100);
+
+        // Après un flush du seul L1, la relecture est servie par le L2
+        vm.flush_l1().unwrap();
+        let before = vm.memory.stats();
+        vm.append_and_run(&reload).unwrap();
+        let after = vm.memory.stats();
+        assert!(after.l1_misses > before.l1_misses);
+        assert!(after.l2_hits > before.l2_hits);
+        assert_eq!(after.l2_misses, before.l2_misses);
+        assert_eq!(vm.stats().l2_writebacks, writebacks);
+    }
+
+    #[test]
+    fn test_display_shows_stop_reason_and_registers() {
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(build_program(vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 0, 42),
+            Instruction::create_no_args(Opcode::Halt),
+        ]))
+        .unwrap();
+        vm.run().unwrap();
+
+        let summary = vm.to_string();
+        assert!(summary.contains("HALT"));
+        assert!(summary.contains("R0=42"));
+        assert!(summary.contains("SP="));
+    }
+
+    /// Exécute `code` avec un seul slot d'émission et les chemins de forwarding donnés,
+    /// sans cache pour que le miss à froid du fetch ne masque pas les stalls de dépendance
+    fn run_with_forwarding_paths(code: Vec<Instruction>, forward_ex_to_ex: bool, forward_mem_to_ex: bool) -> VMStats {
+        let config = VMConfig { issue_width: 1, forward_ex_to_ex, forward_mem_to_ex, enable_cache: false, ..VMConfig::default() };
+        let mut vm = PunkVM::with_config(config);
+        vm.load_program_from_bytecode(build_program(code)).unwrap();
+        vm.run().unwrap();
+        assert_eq!(vm.registers[3], 10);
+        vm.stats()
+    }
+
+    #[test]
+    fn test_forwarding_paths_toggle_independently() {
+        // Le consommateur lit R1 produit deux instructions plus tôt: chemin MEM -> EX
+        let mem_to_ex = vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 5),
+            Instruction::create_reg_imm8(Opcode::Mov, 2, 7),
+            Instruction::create_reg_reg_reg(Opcode::Add, 3, 1, 1),
+            Instruction::create_no_args(Opcode::Halt),
+        ];
+        // Le consommateur lit R1 produit juste avant: chemin EX -> EX
+        let ex_to_ex = vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 2, 7),
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 5),
+            Instruction::create_reg_reg_reg(Opcode::Add, 3, 1, 1),
+            Instruction::create_no_args(Opcode::Halt),
+        ];
+
+        let full = run_with_forwarding_paths(mem_to_ex.clone(), true, true);
+        let no_mem = run_with_forwarding_paths(mem_to_ex, true, false);
+        assert_eq!(no_mem.stalls, full.stalls + 1);
+        assert_eq!(no_mem.cycles, full.cycles + 1);
+
+        let full = run_with_forwarding_paths(ex_to_ex.clone(), true, true);
+        let no_mem = run_with_forwarding_paths(ex_to_ex.clone(), true, false);
+        assert_eq!(no_mem.stalls, full.stalls);
+        assert_eq!(no_mem.cycles, full.cycles);
+
+        // Sans EX -> EX, la dépendance immédiate attend que la valeur atteigne MEM/WB
+        let no_ex = run_with_forwarding_paths(ex_to_ex, false, true);
+        assert_eq!(no_ex.stalls, full.stalls + 1);
+    }
+
+    #[test]
+    fn test_icache_prefetch_reduces_fetch_stalls() {
+        let run_with_prefetch = |enable_icache_prefetch: bool| {
+            // Code linéaire couvrant une vingtaine de lignes de cache de 64 octets
+            let mut code: Vec<Instruction> =
+                (0..300).map(|i| Instruction::create_reg_imm8(Opcode::Mov, (i % 8) as u8, i as u8)).collect();
+            code.push(Instruction::create_no_args(Opcode::Halt));
+
+            let config = VMConfig { enable_icache_prefetch, ..VMConfig::default() };
+            let mut vm = PunkVM::with_config(config);
+            vm.load_program_from_bytecode(build_program(code)).unwrap();
+            vm.run().unwrap();
+            assert_eq!(vm.registers[299 % 8], 299 % 256);
+            vm.stats()
+        };
+
+        let cold = run_with_prefetch(false);
+        let prefetched = run_with_prefetch(true);
+
+        assert_eq!(cold.icache_prefetch_hits, 0);
+        assert!(prefetched.icache_prefetch_hits > 0);
+        // Sans prefetch, chaque nouvelle ligne de code bloque le fetch pendant son
+        // remplissage; avec, seule la première
+        assert!(cold.l1_inst_misses > 10);
+        assert_eq!(prefetched.l1_inst_misses, 1);
+        assert!(prefetched.instruction_fetch_stalls > 0);
+        assert!(cold.instruction_fetch_stalls >= 10 * prefetched.instruction_fetch_stalls);
+        assert!(prefetched.cycles < cold.cycles);
+    }
+
+    #[test]
+    fn test_issue_width_three_improves_ipc() {
+        let run_with_width = |issue_width: usize| {
+            let config = VMConfig { issue_width, register_write_ports: issue_width, ..VMConfig::default() };
+            let mut vm = PunkVM::with_config(config);
+            vm.load_program_from_bytecode(independent_results_burst()).unwrap();
+            vm.run().unwrap();
+            vm.stats()
+        };
+
+        let dual = run_with_width(2);
+        let triple = run_with_width(3);
+
+        assert_eq!(triple.instructions_executed, dual.instructions_executed);
+        // 8 MOV indépendants: 4 groupes de 2 contre 3 groupes (3 + 3 + 2)
+        assert!(triple.parallel_engine_parallel_executions > dual.parallel_engine_parallel_executions);
+        assert!(triple.cycles < dual.cycles);
+        assert!(triple.ipc > dual.ipc);
+        assert_eq!(triple.parallel_engine_dependency_stalls, 0);
+    }
+
+    #[test]
+    fn test_issue_blocked_counters_identify_agu_bottleneck() {
+        // Chargements indépendants: l'AGU unique limite l'émission, jamais l'ALU
+        let mut code: Vec<Instruction> =
+            (1..=8).map(|r| Instruction::create_load_reg_offset(r, 0, (r * 8) as i8)).collect();
+        code.push(Instruction::create_no_args(Opcode::Halt));
+
+        let mut vm = PunkVM::with_config(VMConfig { issue_width: 2, ..VMConfig::default() });
+        vm.load_program_from_bytecode(build_program(code)).unwrap();
+        vm.run().unwrap();
+        let stats = vm.stats();
+
+        assert!(stats.parallel_engine_agu_issue_blocked > 0);
+        assert!(stats.parallel_engine_agu_issue_blocked > stats.parallel_engine_alu_issue_blocked);
+        assert_eq!(stats.parallel_engine_simd_issue_blocked, 0);
+        assert_eq!(
+            stats.parallel_engine_alu_issue_blocked
+                + stats.parallel_engine_agu_issue_blocked
+                + stats.parallel_engine_simd_issue_blocked,
+            stats.parallel_engine_resource_conflicts
+        );
+    }
+
+    /// Variante de `simd_instruction_test` (main.rs): constantes SIMD, calcul vectoriel et
+    /// préparation scalaire entrelacés, sans dépendance entre les deux flots
+    fn interleaved_simd_scalar_program() -> BytecodeFile {
+        build_program(vec![
+            Instruction::create_simd128_const_i32x4(8, [1, 2, 3, 4]),
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 10),
+            Instruction::create_simd128_const_i32x4(9, [5, 6, 7, 8]),
+            Instruction::create_reg_imm8(Opcode::Mov, 2, 20),
+            Instruction::create_simd128_add(10, 8, 9),
+            Instruction::create_reg_imm8(Opcode::Mov, 3, 30),
+            Instruction::create_simd128_mul(11, 8, 9),
+            Instruction::create_reg_imm8(Opcode::Mov, 4, 40),
+            Instruction::create_no_args(Opcode::Halt),
+        ])
+    }
+
+    #[test]
+    fn test_shared_simd_port_blocks_simd_scalar_co_issue() {
+        let run_with_shared_port = |shared_simd_port: bool| {
+            let config = VMConfig { shared_simd_port, issue_width: 2, ..VMConfig::default() };
+            let mut vm = PunkVM::with_config(config);
+            vm.load_program_from_bytecode(interleaved_simd_scalar_program()).unwrap();
+            vm.run().unwrap();
+            let sum = vm.get_vector_alu().borrow().read_v128(10).unwrap();
+            assert_eq!(unsafe { sum.i32x4 }, [6, 8, 10, 12]);
+            assert_eq!(vm.registers[4], 40);
+            vm.stats()
+        };
+
+        let dedicated = run_with_shared_port(false);
+        let shared = run_with_shared_port(true);
+
+        assert_eq!(dedicated.parallel_engine_shared_port_conflicts, 0);
+        assert!(shared.parallel_engine_shared_port_conflicts > 0);
+        assert!(shared.cycles > dedicated.cycles);
+        assert!(shared.to_string().contains("Conflits de port ALU/SIMD partagé"));
+    }
+
+    #[test]
+    fn test_supervisor_instruction_requires_syscall() {
+        // Mode utilisateur: Sysret est réservé au superviseur
+        let mut user = PunkVM::new();
+        user.load_program_from_bytecode(build_program(vec![
+            Instruction::create_single_reg(Opcode::RdMode, 1),
+            Instruction::create_no_args(Opcode::Sysret),
+            Instruction::create_no_args(Opcode::Halt),
+        ]))
+        .unwrap();
+        let sysret_pc = Instruction::create_single_reg(Opcode::RdMode, 1).total_size() as u32;
+        assert_eq!(user.run(), Err(VMError::PrivilegeViolation { pc: sysret_pc }));
+        assert_eq!(user.registers[1], 0);
+
+        // Après un Syscall, la même instruction réussit et ramène en mode utilisateur
+        let mut kernel = PunkVM::new();
+        kernel.set_syscall_handler(Box::new(|_, _| Ok(())));
+        kernel
+            .load_program_from_bytecode(build_program(vec![
+                Instruction::create_no_args(Opcode::Syscall),
+                Instruction::create_single_reg(Opcode::RdMode, 1),
+                Instruction::create_no_args(Opcode::Sysret),
+                Instruction::create_single_reg(Opcode::RdMode, 2),
+                Instruction::create_no_args(Opcode::Halt),
+            ]))
+            .unwrap();
+        kernel.run().unwrap();
+        assert_eq!(kernel.registers[1], 1);
+        assert_eq!(kernel.registers[2], 0);
+        assert_eq!(kernel.privilege_mode(), PrivilegeMode::User);
+    }
+
+    #[test]
+    fn test_syscall_invokes_registered_handler() {
+        const SYS_SQUARE: u64 = 7;
+        let program = build_program(vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 0, SYS_SQUARE as u8),
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 12),
+            Instruction::create_no_args(Opcode::Syscall),
+            // Le résultat du service est visible par les instructions suivantes
+            Instruction::create_reg_reg_reg(Opcode::Add, 2, 1, 1),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let mut vm = PunkVM::new();
+        vm.set_syscall_handler(Box::new(|registers, _memory| {
+            match registers[0] {
+                SYS_SQUARE => registers[1] *= registers[1],
+                other => return Err(VMError::execution_error(&format!("syscall inconnu: {}", other))),
+            }
+            Ok(())
+        }));
+        vm.load_program_from_bytecode(program.clone()).unwrap();
+        vm.run().unwrap();
+        assert_eq!(vm.registers[1], 144);
+        assert_eq!(vm.registers[2], 288);
+
+        // Sans gestionnaire, le Syscall interrompt la VM
+        let mut unhandled = PunkVM::new();
+        unhandled.load_program_from_bytecode(program.clone()).unwrap();
+        let syscall_pc = Instruction::calculate_current_address(&program.code[..2]);
+        assert_eq!(unhandled.run(), Err(VMError::UnhandledSyscall { number: SYS_SQUARE, pc: syscall_pc }));
+        assert_eq!(unhandled.registers[2], 0);
+        assert!(matches!(unhandled.state(), VMState::Error(_)));
+    }
+
+    #[test]
+    fn test_rotate_program() {
+        let program = build_program(vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 0x81),
+            Instruction::create_reg_imm8(Opcode::Mov, 2, 0x81),
+            Instruction::create_reg_imm8(Opcode::Mov, 3, 4),
+            Instruction::create_reg_reg(Opcode::Rol, 1, 3),
+            Instruction::create_reg_imm8(Opcode::Ror, 2, 4),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program).unwrap();
+        vm.run().unwrap();
+
+        assert_eq!(vm.registers[1], 0x810);
+        assert_eq!(vm.registers[2], 0x1000_0000_0000_0008);
+    }
+
+    #[test]
+    fn test_bit_count_instructions() {
+        let program = build_program(vec![
+            Instruction::create_reg_imm16(Opcode::Mov, 1, 0xFF00),
+            Instruction::create_reg_reg(Opcode::Popcnt, 2, 1),
+            Instruction::create_reg_reg(Opcode::Clz, 3, 1),
+            Instruction::create_reg_reg(Opcode::Ctz, 4, 1),
+            // Résultat dépendant du précédent (forwarding)
+            Instruction::create_reg_reg(Opcode::Popcnt, 5, 2),
+            Instruction::create_reg_imm8(Opcode::Mov, 6, 0),
+            Instruction::create_reg_reg(Opcode::Clz, 7, 6),
+            Instruction::create_reg_reg(Opcode::Ctz, 8, 6),
+            // Popcnt(0) = 0 positionne le flag zéro
+            Instruction::create_reg_reg(Opcode::Popcnt, 9, 6),
+            Instruction::create_reg_reg(Opcode::CmovEqual, 10, 1),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program).unwrap();
+        vm.run().unwrap();
+
+        assert_eq!(&vm.registers[2..=5], &[8, 48, 8, 1]);
+        assert_eq!(&vm.registers[7..=9], &[64, 64, 0]);
+        assert_eq!(vm.registers[10], 0xFF00);
+    }
+
+    #[test]
+    fn test_bitfield_extract_and_insert_back() {
+        let program = build_program(vec![
+            Instruction::create_reg_imm16(Opcode::Mov, 1, 0xABCD),
+            // Bits 8..16 de 0xABCD
+            Instruction::create_bitfield(Opcode::Bfe, 2, 1, 8, 8),
+            Instruction::create_bitfield(Opcode::Bfe, 3, 1, 8, 0),
+            // Réinsertion dans l'octet bas seul, puis à sa place d'origine
+            Instruction::create_reg_imm8(Opcode::Mov, 4, 0xCD),
+            Instruction::create_bitfield(Opcode::Bfi, 4, 2, 8, 8),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program).unwrap();
+        vm.run().unwrap();
+
+        assert_eq!(vm.registers[2], 0xAB);
+        assert_eq!(vm.registers[3], 0);
+        assert_eq!(vm.registers[4], 0xABCD);
+
+        // lsb + width > 64: rejeté au décodage
+        let overflowing = build_program(vec![
+            Instruction::create_reg_imm16(Opcode::Mov, 1, 0xABCD),
+            Instruction::create_bitfield(Opcode::Bfe, 2, 1, 60, 8),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(overflowing).unwrap();
+        assert!(matches!(vm.run(), Err(VMError::Pipeline(PipelineError::Decode { .. }))));
+    }
+
+    #[test]
+    fn test_unsupported_opcode_surfaces_typed_pipeline_error() {
+        let code = vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 7),
+            Instruction::create_no_args(Opcode::Extended),
+            Instruction::create_no_args(Opcode::Halt),
+        ];
+        let illegal_pc = Instruction::calculate_current_address(&code[..1]);
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(build_program(code)).unwrap();
+
+        let expected = VMError::Pipeline(PipelineError::UnsupportedInstruction { pc: illegal_pc, opcode: Opcode::Extended });
+        assert_eq!(vm.run(), Err(expected.clone()));
+        // L'état conserve l'erreur typée, pas seulement son message
+        assert_eq!(vm.state, VMState::Error(expected));
+    }
+
+    #[test]
+    fn test_invalid_opcode_byte_traps_with_pc() {
+        let code = vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 7),
+            Instruction::create_no_args(Opcode::Halt),
+        ];
+        let illegal_pc = Instruction::calculate_current_address(&code[..1]);
+        let program = build_program(code);
+
+        // Fichier bytecode corrompu: l'opcode du HALT est remplacé par un octet invalide
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("corrupt.punk");
+        program.write_to_file(&path).unwrap();
+        let mut bytes = std::fs::read(&path).unwrap();
+        let encoded_code = program.encode_code();
+        let code_offset = bytes.windows(encoded_code.len()).position(|w| w == encoded_code.as_slice()).unwrap();
+        bytes[code_offset + 4 + illegal_pc as usize] = 0xFF;
+        std::fs::write(&path, &bytes).unwrap();
+
+        let mut vm = PunkVM::new();
+        vm.load_program(&path).unwrap();
+        assert_eq!(vm.program.as_ref().unwrap().code.len(), 1);
+
+        let expected = VMError::IllegalInstruction { pc: illegal_pc, byte: 0xFF };
+        assert_eq!(vm.run(), Err(expected.clone()));
+        assert_eq!(vm.state, VMState::Error(expected));
+        // L'instruction valide qui précède a bien été exécutée
+        assert_eq!(vm.registers[1], 7);
+    }
+
+    #[test]
+    fn test_memory_stage_busier_on_load_heavy_program() {
+        let compute_only = build_program(vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 3),
+            Instruction::create_reg_imm8(Opcode::Mov, 2, 4),
+            Instruction::create_reg_reg_reg(Opcode::Add, 3, 1, 2),
+            Instruction::create_reg_reg_reg(Opcode::Mul, 4, 3, 2),
+            Instruction::create_reg_reg_reg(Opcode::Sub, 5, 4, 1),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+        let load_heavy = build_program(vec![
+            Instruction::create_reg_imm32(Opcode::Mov, 1, 0x2000),
+            Instruction::create_load_reg_offset(2, 1, 0),
+            Instruction::create_load_reg_offset(3, 1, 8),
+            Instruction::create_load_reg_offset(4, 1, 64),
+            Instruction::create_load_reg_offset(5, 1, 72),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let utilization = |program: BytecodeFile| {
+            let mut vm = PunkVM::new();
+            vm.load_program_from_bytecode(program).unwrap();
+            vm.run().unwrap();
+            let stats = vm.stats();
+            assert_eq!(stats.stage_utilization(), vm.pipeline.stats().stage_utilization());
+            stats.stage_utilization()
+        };
+        let compute = utilization(compute_only);
+        let loads = utilization(load_heavy);
+
+        for value in compute.iter().chain(&loads) {
+            assert!((0.0..=1.0).contains(value));
+        }
+        assert!(compute[0] > 0.0 && compute[4] > 0.0);
+        assert_eq!(compute[3], 0.0, "aucun accès mémoire dans le programme de calcul");
+        assert!(loads[3] > compute[3], "memory: {} vs {}", loads[3], compute[3]);
+    }
+
+    #[test]
+    fn test_double_shifts_move_bits_across_register_pair() {
+        const HI: u64 = 0x0123_4567_89AB_CDEF;
+        const LO: u64 = 0xFEDC_BA98_7654_3210;
+        let program = build_program(vec![
+            Instruction::create_reg_imm64(Opcode::Mov, 1, HI),
+            Instruction::create_reg_imm64(Opcode::Mov, 2, LO),
+            Instruction::create_reg_imm8(Opcode::Mov, 5, 4),
+            // Couple R1:R2 décalé de 4 bits à gauche
+            Instruction::create_reg_reg_imm8(Opcode::Shld, 1, 2, 4),
+            Instruction::create_reg_reg(Opcode::Shl, 2, 5),
+            Instruction::create_reg_reg(Opcode::Mov, 3, 1),
+            Instruction::create_reg_reg(Opcode::Mov, 4, 2),
+            // Puis ramené de 4 bits à droite
+            Instruction::create_reg_reg_imm8(Opcode::Shrd, 2, 1, 4),
+            Instruction::create_reg_reg(Opcode::Shr, 1, 5),
+            // Cas limites: compteur nul, puis 64 et au-delà
+            Instruction::create_reg_imm64(Opcode::Mov, 6, HI),
+            Instruction::create_reg_reg_imm8(Opcode::Shld, 6, 2, 0),
+            Instruction::create_reg_imm64(Opcode::Mov, 7, HI),
+            Instruction::create_reg_reg_imm8(Opcode::Shld, 7, 2, 64),
+            Instruction::create_reg_imm64(Opcode::Mov, 8, LO),
+            Instruction::create_reg_reg_imm8(Opcode::Shrd, 8, 1, 64),
+            Instruction::create_reg_imm64(Opcode::Mov, 9, HI),
+            Instruction::create_reg_reg_imm8(Opcode::Shld, 9, 2, 68),
+            Instruction::create_reg_imm64(Opcode::Mov, 10, LO),
+            Instruction::create_reg_reg_imm8(Opcode::Shrd, 10, 1, 200),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program).unwrap();
+        vm.run().unwrap();
+
+        // Le quartet haut de R2 passe dans le bas de R1, et inversement
+        assert_eq!(vm.registers[3], 0x1234_5678_9ABC_DEFF);
+        assert_eq!(vm.registers[4], 0xEDCB_A987_6543_2100);
+        assert_eq!((vm.registers[1], vm.registers[2]), (HI, LO));
+
+        assert_eq!(vm.registers[6], HI);
+        assert_eq!(vm.registers[7], LO);
+        assert_eq!(vm.registers[8], HI);
+        assert_eq!(vm.registers[9], LO << 4);
+        assert_eq!(vm.registers[10], 0);
+    }
+
+    #[test]
+    fn test_crc32_program_matches_reference() {
+        let mut code = vec![
+            Instruction::create_reg_imm32(Opcode::Mov, 1, 0xFFFF_FFFF),
+            Instruction::create_reg_imm32(Opcode::Mov, 3, 0xFFFF_FFFF),
+        ];
+        for &byte in b"123456789" {
+            code.push(Instruction::create_reg_imm8(Opcode::Mov, 2, byte));
+            code.push(Instruction::create_reg_reg(Opcode::Crc32, 1, 2));
+        }
+        code.push(Instruction::create_reg_reg(Opcode::Xor, 1, 3));
+        code.push(Instruction::create_no_args(Opcode::Halt));
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(build_program(code)).unwrap();
+        vm.run().unwrap();
+
+        // Valeur de contrôle CRC-32C de "123456789"
+        assert_eq!(vm.registers[1], 0xE306_9283);
+    }
+
+    #[test]
+    fn test_mmio_console_output() {
+        let program = build_program(vec![
+            Instruction::create_reg_imm32(Opcode::Mov, 1, DEFAULT_MMIO_BASE),
+            Instruction::create_reg_imm8(Opcode::Mov, 2, b'H'),
+            Instruction::create_reg_imm8(Opcode::Mov, 3, b'I'),
+            Instruction::create_store_reg_offset(Opcode::StoreB, 2, 1, 0),
+            Instruction::create_store_reg_offset(Opcode::StoreB, 3, 1, 0),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program).unwrap();
+        vm.run().unwrap();
+
+        assert_eq!(vm.take_output(), b"HI");
+        // La sortie est consommée par take_output
+        assert!(vm.take_output().is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_restores_branch_predictor_and_console() {
+        // Boucle de 4 itérations écrivant R0 sur la console à chaque passage
+        let mut code = vec![
+            Instruction::create_reg_imm32(Opcode::Mov, 1, DEFAULT_MMIO_BASE),
+            Instruction::create_reg_imm8(Opcode::Mov, 0, 4),
+        ];
+        let loop_start = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_store_reg_offset(Opcode::StoreB, 0, 1, 0));
+        code.push(Instruction::create_single_reg(Opcode::Dec, 0));
+        let branch_addr = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_jump_if_not_zero(branch_addr, loop_start));
+        code.push(Instruction::create_no_args(Opcode::Halt));
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(build_program(code)).unwrap();
+        assert!(vm.run_until(branch_addr).is_ok());
+        let checkpoint = vm.snapshot();
+        assert_eq!(checkpoint.console_output, vec![4]);
+
+        vm.run().unwrap();
+        let trained = vm.export_branch_predictor_state();
+        assert_ne!(trained, checkpoint.branch_predictor);
+        assert_eq!(vm.take_output(), vec![4, 3, 2, 1]);
+
+        // La sortie déjà émise et le prédicteur reviennent à l'état du snapshot
+        vm.restore(&checkpoint).unwrap();
+        assert_eq!(vm.export_branch_predictor_state(), checkpoint.branch_predictor);
+        vm.run().unwrap();
+        assert_eq!(vm.take_output(), vec![4, 3, 2, 1]);
+        assert_eq!(vm.export_branch_predictor_state(), trained);
+    }
+
+    #[test]
+    fn test_snapshot_restore_replays_identically() {
+        let program = build_program(vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 5),
+            Instruction::create_reg_imm8(Opcode::Mov, 2, 7),
+            Instruction::create_simd128_const_i32x4(9, [1, 2, 3, 4]),
+            Instruction::create_reg_reg_reg(Opcode::Add, 3, 1, 2),
+            Instruction::create_reg_imm32(Opcode::Mov, 0, 0x2000),
+            Instruction::create_store_reg_offset(Opcode::Store, 3, 0, 0),
+            Instruction::create_simd128_add(10, 9, 9),
+            Instruction::create_reg_reg_reg(Opcode::Mul, 4, 3, 3),
+            Instruction::create_store_reg_offset(Opcode::Store, 4, 0, 8),
+            Instruction::create_reg_reg_reg(Opcode::Sub, 5, 4, 1),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program).unwrap();
+        vm.state = VMState::Running;
+        for _ in 0..4 {
+            vm.step().unwrap();
+        }
+        let checkpoint = vm.snapshot();
+
+        let final_state = |vm: &mut PunkVM| {
+            vm.run().unwrap();
+            let v10 = unsafe { vm.get_vector_alu().borrow().v128_registers[10].i32x4 };
+            (vm.registers.clone(), vm.pc, vm.memory.snapshot(), v10, vm.stats().instructions_executed)
+        };
+        let first = final_state(&mut vm);
+        assert_eq!(first.0[5], 139);
+        assert_eq!(first.3, [2, 4, 6, 8]);
+
+        vm.restore(&checkpoint).unwrap();
+        assert_eq!(vm.registers, checkpoint.registers);
+        assert_eq!(vm.pc, checkpoint.pc);
+
+        let second = final_state(&mut vm);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_preloaded_array_sum_via_memory_helpers() {
+        let values: [u32; 8] = [3, 1, 4, 1, 5, 9, 2, 6];
+        let bytes: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+
+        let mut code = vec![
+            Instruction::create_reg_imm32(Opcode::Mov, 0, 0x4000),
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 0),
+        ];
+        for i in 0..values.len() as u8 {
+            code.push(Instruction::new(
+                Opcode::LoadD,
+                InstructionFormat::reg_regoff(),
+                vec![2, 0, i * 4],
+            ));
+            code.push(Instruction::create_reg_reg_reg(Opcode::Add, 1, 1, 2));
+        }
+        code.push(Instruction::create_store_reg_offset(Opcode::Store, 1, 0, 32));
+        code.push(Instruction::create_no_args(Opcode::Halt));
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(build_program(code)).unwrap();
+        vm.write_memory(0x4000, &bytes).unwrap();
+        assert_eq!(vm.read_memory(0x4000, bytes.len()).unwrap(), bytes);
+        vm.run().unwrap();
+
+        let sum = vm.read_memory(0x4020, 8).unwrap();
+        assert_eq!(u64::from_le_bytes(sum.try_into().unwrap()), 31);
+
+        // Les accès hors mémoire sont refusés
+        let end = vm.memory.size() as u32;
+        assert!(matches!(vm.read_memory(end - 4, 8), Err(VMError::MemoryOutOfBounds { .. })));
+        assert!(vm.write_memory(end, &[1]).is_err());
+    }
+
+    #[test]
+    fn test_dump_memory_formats_hex_and_ascii_columns() {
+        let mut vm = PunkVM::new();
+        let mut pattern = b"PunkVM dump!".to_vec();
+        pattern.extend(0u8..20);
+        vm.write_memory(0x4000, &pattern).unwrap();
+
+        let dump = vm.dump_memory(0x4000, pattern.len());
+        let lines: Vec<&str> = dump.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], "00004000: 5075 6e6b 564d 2064 756d 7021 0001 0203  PunkVM dump!....");
+        assert_eq!(lines[1], "00004010: 0405 0607 0809 0a0b 0c0d 0e0f 1011 1213  ................");
+        // Une ligne incomplète garde l'alignement de la colonne ASCII
+        assert_eq!(vm.dump_memory(0x4000, 4), "00004000: 5075 6e6b                                Punk\n");
+    }
+
+    #[test]
+    fn test_signed_loads_sign_extend_and_unsigned_loads_zero_extend() {
+        let load = |opcode, rd, offset| Instruction::new(opcode, InstructionFormat::reg_regoff(), vec![rd, 0, offset]);
+        let program = build_program(vec![
+            Instruction::create_reg_imm32(Opcode::Mov, 0, 0x2000),
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 0xFF),
+            Instruction::create_store_reg_offset(Opcode::StoreB, 1, 0, 0),
+            // Relus depuis le store buffer
+            load(Opcode::LoadB, 2, 0),
+            load(Opcode::LoadSB, 3, 0),
+            // Relus depuis la mémoire: 0xFFFE à 0x2010, 0x7FFFFFFE à 0x2010 en 32 bits
+            load(Opcode::LoadW, 4, 0x10),
+            load(Opcode::LoadSW, 5, 0x10),
+            load(Opcode::LoadSD, 6, 0x10),
+            load(Opcode::LoadSD, 7, 0x20),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program).unwrap();
+        vm.write_memory(0x2010, &0x7FFF_FFFEu32.to_le_bytes()).unwrap();
+        vm.write_memory(0x2020, &0x8000_0000u32.to_le_bytes()).unwrap();
+        vm.run().unwrap();
+
+        assert_eq!(vm.registers[2], 255);
+        assert_eq!(vm.registers[3] as i64, -1);
+        assert_eq!(vm.registers[4], 0xFFFE);
+        assert_eq!(vm.registers[5] as i64, -2);
+        assert_eq!(vm.registers[6], 0x7FFF_FFFE);
+        assert_eq!(vm.registers[7] as i64, i32::MIN as i64);
+    }
+
+    #[test]
+    fn test_disasm_at_decodes_single_instructions() {
+        let mut code = vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 0, 3),
+            Instruction::create_reg_reg_reg(Opcode::Add, 1, 1, 0),
+            Instruction::create_single_reg(Opcode::Dec, 0),
+        ];
+        let loop_addr = Instruction::calculate_current_address(&code[..1]);
+        let branch_addr = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_jump_if_not_zero(branch_addr, loop_addr));
+        code.push(Instruction::create_no_args(Opcode::Halt));
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(build_program(code)).unwrap();
+
+        assert_eq!(vm.disasm_at(0).unwrap(), "Mov R0, #3");
+        assert_eq!(vm.disasm_at(loop_addr).unwrap(), "Add R1, R1, R0");
+        assert_eq!(vm.disasm_at(branch_addr).unwrap(), format!("JmpIfNotZero 0x{:08X}", loop_addr));
+        assert!(matches!(vm.disasm_at(loop_addr + 1), Err(VMError::DecodeError(_))));
+    }
+
+    #[test]
+    fn test_random_replacement_is_reproducible_with_same_seed() {
+        // 12 lignes espacées de 32 Ko: même ensemble du L2 (8 voies), qui déborde
+        let conflict_pattern = |policy, seed| {
+            let config = VMConfig {
+                cache_replacement_policy: policy,
+                cache_rng_seed: seed,
+                ..VMConfig::default()
+            };
+            let mut vm = PunkVM::with_config(config);
+            for _ in 0..6 {
+                for line in 0..12u32 {
+                    vm.memory.read_byte(line * 32 * 1024).unwrap();
+                }
+            }
+            vm.memory.stats().l2_hits
+        };
+
+        let first = conflict_pattern(ReplacementPolicy::Random, 42);
+        assert_eq!(conflict_pattern(ReplacementPolicy::Random, 42), first);
+        // LRU évince toujours la ligne qui va être redemandée; Random en garde certaines
+        assert_eq!(conflict_pattern(ReplacementPolicy::LRU, 42), 0);
+        assert!(first > 0);
+    }
+
+    #[test]
+    fn test_forwarding() {
+        // Chaîne de dépendances: chaque Add lit le résultat des deux précédents
+        let mut program = vec![Instruction::create_reg_imm8(Opcode::Mov, 1, 1)];
+        program.push(Instruction::create_reg_reg_reg(Opcode::Add, 2, 1, 1));
+        for rd in 3..=7 {
+            program.push(Instruction::create_reg_reg_reg(Opcode::Add, rd, rd - 1, rd - 2));
+        }
+        program.push(Instruction::create_no_args(Opcode::Halt));
+
+        let without = PunkVM::run_program_with_options(program.clone(), false, true).unwrap();
+        let with = PunkVM::run_program_with_options(program, true, true).unwrap();
+
+        assert_eq!(without.registers[7], 21);
+        assert_eq!(with.registers[7], 21);
+        assert!(with.cycles < without.cycles, "{} >= {}", with.cycles, without.cycles);
+    }
+
+    #[test]
+    fn test_store_load_hazards() {
+        let mut program = vec![
+            Instruction::create_reg_imm16(Opcode::Mov, 0, 0x1000),
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 42),
+            Instruction::create_store_reg_offset(Opcode::Store, 1, 0, 0), // mem[0x1000] = r1
+        ];
+        // Quatre lectures dans le même set du L1 (4 voies, conflit tous les 0x2000)
+        // évincent la ligne écrite: sans store buffer, la relecture part en L2
+        for k in 1..=4u16 {
+            program.push(Instruction::create_reg_imm16(Opcode::Mov, 5, 0x1000 + k * 0x2000));
+            program.push(Instruction::create_load_reg_offset(6, 5, 0));
+        }
+        program.extend([
+            Instruction::create_load_reg_offset(2, 0, 0),          // r2 = mem[0x1000]
+            Instruction::create_reg_reg_reg(Opcode::Add, 3, 2, 1), // r3 = r2 + r1
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let no_sb = PunkVM::run_program_with_options(program.clone(), true, false).unwrap();
+        let sb = PunkVM::run_program_with_options(program, true, true).unwrap();
+
+        for result in [&no_sb, &sb] {
+            assert_eq!(result.registers[3], 84);
+            assert_eq!(result.memory_snapshot[0x1000], 42);
+        }
+        // Avec le buffer, la relecture est servie par forwarding au lieu d'un accès L2
+        assert!(sb.cycles < no_sb.cycles, "{} >= {}", sb.cycles, no_sb.cycles);
+    }
+
+    #[test]
+    fn test_full_store_buffer_stalls_until_drained() {
+        // Trois StoreD de 4 octets dans un buffer de 4 entrées vidé à 1 octet par cycle,
+        // puis une lecture à cheval sur deux écritures: non transférable, elle attend
+        let program = || build_program(vec![
+            Instruction::create_reg_imm32(Opcode::Mov, 0, 0x2000),
+            Instruction::create_reg_imm32(Opcode::Mov, 1, 0x1111_1111),
+            Instruction::create_reg_imm32(Opcode::Mov, 2, 0x2222_2222),
+            Instruction::create_reg_imm32(Opcode::Mov, 3, 0x3333_3333),
+            Instruction::create_store_reg_offset(Opcode::StoreD, 1, 0, 0),
+            Instruction::create_store_reg_offset(Opcode::StoreD, 2, 0, 4),
+            Instruction::create_store_reg_offset(Opcode::StoreD, 3, 0, 8),
+            Instruction::new(Opcode::LoadD, InstructionFormat::reg_regoff(), vec![4, 0, 6]),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+        let run = |drain_rate| {
+            let config = VMConfig { store_buffer_size: 4, store_drain_rate: drain_rate, ..VMConfig::default() };
+            let mut vm = PunkVM::with_config(config);
+            vm.load_program_from_bytecode(program()).unwrap();
+            vm.run().unwrap();
+            vm
+        };
+
+        let evicting = run(0);
+        let draining = run(1);
+
+        assert_eq!(evicting.memory.stats().store_buffer_stalls, 0);
+        assert!(draining.memory.stats().store_buffer_stalls > 0);
+        assert!(draining.stats().stalls > evicting.stats().stalls);
+        for vm in [&evicting, &draining] {
+            assert_eq!(vm.registers[4], 0x3333_2222);
+            assert_eq!(
+                vm.read_memory(0x2000, 12).unwrap(),
+                [0x11u8; 4].iter().chain(&[0x22; 4]).chain(&[0x33; 4]).copied().collect::<Vec<_>>()
+            );
+        }
+    }
+
+    /// Somme d'un tableau de u32 parcouru par un index: LoadD R4, [R1 + R2*4]
+    fn run_indexed_array_sum(values: &[u32]) -> (PunkVM, VMStats) {
+        let bytes: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+        let mut code = vec![
+            Instruction::create_reg_imm32(Opcode::Mov, 1, 0x4000),
+            Instruction::create_reg_imm8(Opcode::Mov, 2, 0),
+            Instruction::create_reg_imm8(Opcode::Mov, 3, values.len() as u8),
+            Instruction::create_reg_imm8(Opcode::Mov, 5, 0),
+        ];
+        let loop_addr: u32 = code.iter().map(|i| i.total_size() as u32).sum();
+        code.push(Instruction::new(Opcode::LoadD, InstructionFormat::reg_indexed(), vec![4, 1, 2, 4, 0]));
+        code.push(Instruction::create_reg_reg_reg(Opcode::Add, 5, 5, 4));
+        code.push(Instruction::create_single_reg(Opcode::Inc, 2));
+        code.push(Instruction::create_reg_reg(Opcode::Cmp, 2, 3));
+        let branch_addr: u32 = code.iter().map(|i| i.total_size() as u32).sum();
+        code.push(Instruction::create_jump_if_not_equal(branch_addr, loop_addr));
+        // Dernier élément via déplacement négatif (le mot suivant le tableau est nul)
+        code.push(Instruction::create_load_indexed(6, 1, 2, 4, -4));
+        code.push(Instruction::create_no_args(Opcode::Halt));
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(build_program(code)).unwrap();
+        vm.write_memory(0x4000, &bytes).unwrap();
+        vm.run().unwrap();
+        let stats = vm.stats();
+        (vm, stats)
+    }
+
+    #[test]
+    fn test_indexed_load_walks_array_with_stride_prediction() {
+        let values: Vec<u32> = (1..=16).map(|i| i * 3).collect();
+        let (vm, long) = run_indexed_array_sum(&values);
+        assert_eq!(vm.registers[5], values.iter().map(|&v| v as u64).sum::<u64>());
+        assert_eq!(vm.registers[6], *values.last().unwrap() as u64);
+
+        // Le stride de 4 octets est appris au fil des itérations
+        let (_, short) = run_indexed_array_sum(&values[..3]);
+        assert!(long.agu_stride_accuracy > short.agu_stride_accuracy);
+        assert!(long.agu_stride_accuracy > 0.8);
+    }
+
+    fn run_cold_then_warm_load(memory_latency: u32) -> VMStats {
+        let program = build_program(vec![
+            Instruction::create_reg_imm32(Opcode::Mov, 1, 0x4000),
+            Instruction::create_load_reg_offset(2, 1, 0), // miss L1 et L2
+            Instruction::create_load_reg_offset(3, 1, 0), // hit L1
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+        let config = VMConfig { l1_latency: 1, l2_latency: 12, memory_latency, ..VMConfig::default() };
+        let mut vm = PunkVM::with_config(config);
+        vm.load_program_from_bytecode(program).unwrap();
+        vm.run().unwrap();
+        vm.stats()
+    }
+
+    #[test]
+    fn test_cold_load_stalls_for_memory_latency() {
+        let slow = run_cold_then_warm_load(100);
+        let fast = run_cold_then_warm_load(20);
+
+        // Seul le miss complet attend: le hit L1 est absorbé par l'étage Memory
+        assert_eq!(slow.memory_stall_cycles, 99);
+        assert_eq!(fast.memory_stall_cycles, 19);
+        assert_eq!(slow.cycles - fast.cycles, 80);
+        assert_eq!(slow.instructions_executed, fast.instructions_executed);
+        assert_eq!(slow.average_memory_latency, (100.0 + 1.0) / 2.0);
+    }
+
+    #[test]
+    fn test_indexed_store_writes_base_plus_index() {
+        let program = build_program(vec![
+            Instruction::create_reg_imm32(Opcode::Mov, 1, 0x4000),
+            Instruction::create_reg_imm8(Opcode::Mov, 2, 0x24),
+            Instruction::create_reg_imm32(Opcode::Mov, 3, 0xCAFE),
+            Instruction::create_store_reg_indexed(3, 1, 2),
+            Instruction::create_load_indexed(4, 1, 2, 1, 0),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program).unwrap();
+        vm.run().unwrap();
+
+        assert_eq!(vm.read_memory(0x4024, 2).unwrap(), vec![0xFE, 0xCA]);
+        assert_eq!(vm.read_memory(0x4000, 2).unwrap(), vec![0, 0]);
+        assert_eq!(vm.registers[4], 0xCAFE);
+    }
+
+    fn run_stride8_loop(iterations: u8) -> VMStats {
+        // R5 += [0x4000 + R2 * 8] pour R2 = 0..iterations
+        let mut code = vec![
+            Instruction::create_reg_imm32(Opcode::Mov, 1, 0x4000),
+            Instruction::create_reg_imm8(Opcode::Mov, 2, 0),
+            Instruction::create_reg_imm8(Opcode::Mov, 3, iterations),
+        ];
+        let loop_addr = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_load_indexed(4, 1, 2, 8, 0));
+        code.push(Instruction::create_reg_reg_reg(Opcode::Add, 5, 5, 4));
+        code.push(Instruction::create_single_reg(Opcode::Inc, 2));
+        code.push(Instruction::create_reg_reg(Opcode::Cmp, 2, 3));
+        let branch_addr = Instruction::calculate_current_address(&code);
+        code.push(Instruction::create_jump_if_not_equal(branch_addr, loop_addr));
+        code.push(Instruction::create_no_args(Opcode::Halt));
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(build_program(code)).unwrap();
+        vm.run().unwrap();
+        vm.stats()
+    }
+
+    #[test]
+    fn test_stride_prefetch_hits_grow_with_iterations() {
+        let short = run_stride8_loop(16);
+        let long = run_stride8_loop(64);
+
+        // Une ligne de 64 octets couvre 8 itérations: chaque nouvelle ligne a été préchargée
+        assert!(short.l2_prefetch_hits > 0);
+        assert!(long.l2_prefetch_hits > short.l2_prefetch_hits);
+        assert!(long.l2_prefetch_hits >= 6);
+        // Les lignes préchargées évitent les miss complets
+        assert!(long.l2_misses < 8);
+    }
+
+    #[test]
+    fn test_lea_computes_address_without_memory_access() {
+        let program = build_program(vec![
+            Instruction::create_reg_imm32(Opcode::Mov, 0, 0x1000),
+            Instruction::create_lea(5, 0, 16),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program).unwrap();
+        let calculations_before = vm.stats().agu_total_calculations;
+        let accesses_before = vm.memory.stats();
+        vm.run().unwrap();
+
+        assert_eq!(vm.registers[5], 0x1010);
+        assert_eq!(vm.stats().agu_total_calculations, calculations_before + 1);
+        assert_eq!(vm.memory.stats().reads, accesses_before.reads);
+        assert_eq!(vm.memory.stats().writes, accesses_before.writes);
+    }
+
+    #[test]
+    fn test_run_with_input_sums_arguments() {
+        // R0 = R0 + R1 + R2 + mot de données d'entrée
+        let program = build_program(vec![
+            Instruction::create_reg_reg_reg(Opcode::Add, 0, 0, 1),
+            Instruction::create_reg_reg_reg(Opcode::Add, 0, 0, 2),
+            Instruction::create_reg_imm32(Opcode::Mov, 5, INPUT_DATA_ADDR),
+            Instruction::create_load_reg_offset(4, 5, 0),
+            Instruction::create_reg_reg_reg(Opcode::Add, 0, 0, 4),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program).unwrap();
+        vm.run_with_input(&[3, 4, 5], &1000u64.to_le_bytes()).unwrap();
+        assert_eq!(vm.registers[0], 1012);
+
+        // Trop d'arguments pour la convention d'appel
+        let too_many = [0u64; INPUT_ARG_REGISTERS + 1];
+        assert!(matches!(vm.run_with_input(&too_many, &[]), Err(VMError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_max_cycles_stops_infinite_loop() {
+        // Jmp vers lui-même: le programme ne se termine jamais
+        let program = build_program(vec![
+            Instruction::create_jump(0, 0),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let config = VMConfig { max_cycles: Some(50), ..VMConfig::default() };
+        let mut vm = PunkVM::with_config(config);
+        vm.load_program_from_bytecode(program).unwrap();
+
+        assert_eq!(vm.run(), Err(VMError::CycleLimitExceeded { cycles: 50 }));
+        assert_eq!(vm.stats().cycles, 50);
+    }
+
+    #[test]
+    fn test_simd128_masked_access_at_memory_end() {
+        // Les deux dernières lanes d'un vecteur posé à 8 octets de la fin tombent hors mémoire
+        let tail = (VMConfig::default().memory_size - 8) as u32;
+        let program = build_program(vec![
+            Instruction::create_reg_imm32(Opcode::Mov, 0, tail),
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 0b0011),
+            Instruction::create_reg_reg_reg(Opcode::Simd128MaskedLoad, 8, 0, 1),
+            Instruction::create_reg_imm32(Opcode::Mov, 2, 0x2000),
+            Instruction::create_reg_imm8(Opcode::Mov, 3, 0b1111),
+            Instruction::create_reg_reg_reg(Opcode::Simd128MaskedStore, 8, 2, 3),
+            Instruction::create_reg_imm32(Opcode::Mov, 5, 0x3000),
+            Instruction::create_reg_reg_reg(Opcode::Simd128MaskedStore, 8, 5, 1),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program).unwrap();
+        for addr in (0x2000..0x2010).chain(0x3000..0x3010).step_by(4) {
+            vm.memory.write_dword(addr, 0xDEAD).unwrap();
+        }
+        vm.memory.write_dword(tail, 11).unwrap();
+        vm.memory.write_dword(tail + 4, 22).unwrap();
+        let reads_before = vm.memory.stats().reads;
+
+        vm.run().unwrap();
+
+        let ram = vm.memory.snapshot();
+        let lanes = |base: usize| -> Vec<u32> {
+            (0..4)
+                .map(|lane| {
+                    let at = base + lane * 4;
+                    u32::from_le_bytes(ram[at..at + 4].try_into().unwrap())
+                })
+                .collect()
+        };
+        // Chargement masqué: lanes hors masque mises à zéro
+        assert_eq!(lanes(0x2000), vec![11, 22, 0, 0]);
+        // Stockage masqué: les lanes 2 et 3 de la destination sont intactes
+        assert_eq!(lanes(0x3000), vec![11, 22, 0xDEAD, 0xDEAD]);
+        // Seules les deux lanes actives ont été lues (4 octets chacune)
+        assert_eq!(vm.memory.stats().reads - reads_before, 8);
+    }
+
+    #[test]
+    fn test_simd128_fma_single_rounding() {
+        // (1 + 2^-12)^2 = 1 + 2^-11 + 2^-24 : le terme 2^-24 est perdu si le produit est arrondi
+        let x = 1.0 + f32::EPSILON * 2048.0;
+        let c = -(1.0 + f32::EPSILON * 4096.0);
+        let program = build_program(vec![
+            Instruction::create_simd128_const_f32x4(1, [x, 2.0, -1.5, 0.5]),
+            Instruction::create_simd128_const_f32x4(2, [x, 3.0, 4.0, 0.25]),
+            Instruction::create_simd128_const_f32x4(3, [c, 1.0, 10.0, -0.125]),
+            Instruction::create_simd128_fma(4, 1, 2, 3),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program).unwrap();
+        vm.run().unwrap();
+
+        let vector_alu = vm.get_vector_alu();
+        let lanes = unsafe { vector_alu.borrow().read_v128(4).unwrap().f32x4 };
+        assert_eq!(lanes, [f32::EPSILON / 2.0, 7.0, 4.0, 0.0]);
+        assert_ne!(x * x + c, lanes[0], "mul puis add arrondit deux fois");
+        // Les constantes ne passent pas par l'ALU vectorielle, la FMA compte pour une seule opération
+        assert_eq!(vector_alu.borrow().get_simd_stats().simd128_ops, 1);
+    }
+
+    #[test]
+    fn test_simd128_blend_selects_larger_lanes() {
+        let a = [5, -3, 7, i32::MIN];
+        let b = [2, 4, 7, -1];
+        // Masque: lanes où max(a, b) == a, puis sélection de a ou b selon ce masque
+        let program = build_program(vec![
+            Instruction::create_simd128_const_i32x4(0, a),
+            Instruction::create_simd128_const_i32x4(1, b),
+            Instruction::create_simd_vector_128(Opcode::Simd128Max, 2, 0, 1),
+            Instruction::create_simd_vector_128(Opcode::Simd128Cmp, 3, 2, 0),
+            Instruction::create_simd128_blend(4, 0, 1, 3),
+            Instruction::create_reg_imm8(Opcode::Mov, 4, 99),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program).unwrap();
+        vm.run().unwrap();
+
+        let expected: [i32; 4] = std::array::from_fn(|i| if a[i] >= b[i] { a[i] } else { b[i] });
+        let vector_alu = vm.get_vector_alu();
+        assert_eq!(unsafe { vector_alu.borrow().read_v128(4).unwrap().i32x4 }, expected);
+        // V4 et R4 sont des registres distincts: le blend n'écrase pas le registre général
+        assert_eq!(vm.registers[4], 99);
+    }
+
+    #[test]
+    fn test_simd128_horizontal_reductions_write_gpr() {
+        let program = build_program(vec![
+            Instruction::create_simd128_const_i32x4(0, [1, 2, 3, 4]),
+            Instruction::create_simd128_hadd(1, 0),
+            Instruction::create_simd128_hmin(2, 0),
+            Instruction::create_simd128_hmax(3, 0),
+            Instruction::create_simd128_const_f32x4(5, [0.5, 1.5, -2.0, 8.0]),
+            Instruction::create_simd128_horizontal(Opcode::Simd128HAdd, 4, 5, true),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program).unwrap();
+        vm.run().unwrap();
+
+        assert_eq!(vm.registers[1], 10);
+        assert_eq!(vm.registers[2], 1);
+        assert_eq!(vm.registers[3], 4);
+        assert_eq!(f32::from_bits(vm.registers[4] as u32), 8.0);
+    }
+
+    #[test]
+    fn test_simd128_64bit_lane_arithmetic() {
+        let program = build_program(vec![
+            Instruction::create_simd128_const_f64x2(0, [1e10, 2e10]),
+            Instruction::create_simd128_const_f64x2(1, [0.5, -3e10]),
+            Instruction::create_simd128_add_f64x2(2, 0, 1),
+            Instruction::create_simd128_mul_f64x2(3, 0, 0),
+            Instruction::create_simd128_const_i64x2(4, [1 << 40, -1]),
+            Instruction::create_simd128_const_i64x2(5, [3, i64::MIN + 1]),
+            Instruction::create_simd128_add_i64x2(6, 4, 5),
+            Instruction::create_simd128_mul_i64x2(7, 4, 5),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program).unwrap();
+        vm.run().unwrap();
+
+        let vector_alu = vm.get_vector_alu();
+        let read = |reg| vector_alu.borrow().read_v128(reg).unwrap();
+        assert_eq!(unsafe { read(2).f64x2 }, [1e10 + 0.5, -1e10]);
+        assert_eq!(unsafe { read(3).f64x2 }, [1e20, 4e20]);
+        // Les retenues traversent les 32 bits de poids faible, contrairement à i32x4
+        assert_eq!(unsafe { read(6).i64x2 }, [(1 << 40) + 3, i64::MIN]);
+        assert_eq!(unsafe { read(7).i64x2 }, [3 << 40, i64::MAX]);
+    }
+
+    #[test]
+    fn test_simd_i64_lanes_do_not_carry_into_neighbours() {
+        let program = build_program(vec![
+            Instruction::create_simd128_const_i64x2(0, [1_000_000_000_000, 2]),
+            Instruction::create_simd128_const_i64x2(1, [3, 4_000_000_000_000]),
+            Instruction::create_simd128_add_i64x2(2, 0, 1),
+            // Les constantes 256-bit dupliquent leurs 128 bits dans les deux moitiés
+            Instruction::create_simd256_const_i64x4(0, [1_000_000_000_000, 2, 0, 0]),
+            Instruction::create_simd256_const_i64x4(1, [3, 4_000_000_000_000, 0, 0]),
+            Instruction::create_simd256_add_i64x4(2, 0, 1),
+            Instruction::create_simd256_mul_i64x4(3, 0, 1),
+            Instruction::create_no_args(Opcode::Halt),
+        ]);
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(program).unwrap();
+        vm.run().unwrap();
+
+        let vector_alu = vm.get_vector_alu();
+        let alu = vector_alu.borrow();
+        // Aucune retenue ne passe d'une lane 64 bits à sa voisine
+        assert_eq!(unsafe { alu.read_v128(2).unwrap().i64x2 }, [1_000_000_000_003, 4_000_000_000_002]);
+        assert_eq!(
+            unsafe { alu.read_v256(2).unwrap().i64x4 },
+            [1_000_000_000_003, 4_000_000_000_002, 1_000_000_000_003, 4_000_000_000_002]
+        );
+        assert_eq!(
+            unsafe { alu.read_v256(3).unwrap().i64x4 },
+            [3_000_000_000_000, 8_000_000_000_000, 3_000_000_000_000, 8_000_000_000_000]
+        );
+    }
+
+    #[test]
+    fn test_simd128_lanes_round_trip_through_gprs() {
+        let mut code = vec![
+            Instruction::create_reg_imm8(Opcode::Mov, 0, 7),
+            Instruction::create_reg_imm8(Opcode::Mov, 1, 20),
+            Instruction::create_reg_imm8(Opcode::Mov, 2, 30),
+            Instruction::create_reg_imm8(Opcode::Mov, 3, 5),
+            Instruction::create_single_reg(Opcode::Neg, 3), // R3 = -5
+        ];
+        // V2 construit lane par lane depuis R0..R3
+        code.extend((0..4).map(|lane| Instruction::create_simd128_insert_lane(2, lane, lane)));
+        code.push(Instruction::create_simd128_extract_lane(4, 2, 3));
+        code.push(Instruction::create_simd128_extract_lane(5, 2, 0));
+        code.push(Instruction::create_simd128_extract_lane(6, 2, 2));
+        code.push(Instruction::create_no_args(Opcode::Halt));
+
+        let mut vm = PunkVM::new();
+        vm.load_program_from_bytecode(build_program(code)).unwrap();
+        vm.run().unwrap();
+
+        let lanes = unsafe { vm.get_vector_alu().borrow().read_v128(2).unwrap().i32x4 };
+        assert_eq!(lanes, [7, 20, 30, -5]);
+        assert_eq!(vm.registers[4] as i64, -5, "la lane est étendue en signe");
+        assert_eq!(vm.registers[5], 7);
+        assert_eq!(vm.registers[6], 30);
+        // L'insertion n'écrit aucun registre général
+        assert_eq!(&vm.registers[0..3], &[7, 20, 30]);
+    }
+
+    #[test]
+    fn test_simd128_lane_out_of_range_is_rejected() {
+        for instr in [
+            Instruction::create_simd128_extract_lane(1, 0, 4),
+            Instruction::create_simd128_insert_lane(0, 1, 4),
+        ] {
+            let mut vm = PunkVM::new();
+            vm.load_program_from_bytecode(build_program(vec![instr, Instruction::create_no_args(Opcode::Halt)]))
+                .unwrap();
+            assert!(vm.run().is_err());
+        }
+    }
 }
 
 
@@ -2,6 +2,8 @@
 use std::fmt;
 
 use std::io::Error;
+
+use crate::pvm::pipeline_errors::PipelineError;
 #[derive(Debug, Clone, PartialEq)]
 pub enum VMError {
     MemoryError(String),
@@ -12,6 +14,29 @@ pub enum VMError {
     ExecutionError(String),
     ALUError(String),
     DecodeError(String),
+    /// Division ou modulo par zéro à l'adresse `pc`
+    DivisionByZero { pc: u32 },
+    MemoryOutOfBounds { addr: u64, size: usize },
+    /// Instruction réservée au mode superviseur exécutée en mode utilisateur à l'adresse `pc`
+    PrivilegeViolation { pc: u32 },
+    /// Syscall exécuté à l'adresse `pc` sans gestionnaire enregistré (`number` = R0)
+    UnhandledSyscall { number: u64, pc: u32 },
+    /// Opérande registre hors des registres généraux (R0-R15)
+    InvalidRegister { index: u8 },
+    /// Budget `VMConfig::max_cycles` épuisé avant la fin du programme
+    CycleLimitExceeded { cycles: u64 },
+    /// Trap conditionnel (`TrapIf*`) déclenché à l'adresse `pc` avec le code `code`
+    ExplicitTrap { pc: u32, code: u8 },
+    /// PUSH/CALL à l'adresse `pc` qui ferait descendre SP sous la base de la pile
+    StackOverflow { pc: u32, sp: u64 },
+    /// POP/RET à l'adresse `pc` alors que la pile est vide (SP au sommet de la zone de pile)
+    StackUnderflow { pc: u32, sp: u64 },
+    /// Octet `byte` à l'adresse `pc` qui ne correspond à aucun opcode (bytecode corrompu)
+    IllegalInstruction { pc: u32, byte: u8 },
+    /// Store vers l'adresse `addr`, située dans un segment en lecture seule
+    WriteToReadOnly { addr: u32 },
+    /// Erreur typée du pipeline (décodage, instruction illégale, interblocage)
+    Pipeline(PipelineError),
     // StackError(String),
 }
 
@@ -63,6 +88,34 @@ impl fmt::Display for VMError {
             VMError::ExecutionError(msg) => write!(f, "ExecutionError: {}", msg),
             VMError::ALUError(msg) => write!(f, "ALUError: {}", msg),
             VMError::DecodeError(msg) => write!(f, "DecodeError: {}", msg),
+            VMError::DivisionByZero { pc } => write!(f, "DivisionByZero: PC=0x{:08X}", pc),
+            VMError::MemoryOutOfBounds { addr, size } => {
+                write!(f, "MemoryOutOfBounds: addr=0x{:08X}, size={}", addr, size)
+            }
+            VMError::PrivilegeViolation { pc } => write!(f, "PrivilegeViolation: PC=0x{:08X}", pc),
+            VMError::UnhandledSyscall { number, pc } => {
+                write!(f, "UnhandledSyscall: numéro {} à PC=0x{:08X}", number, pc)
+            }
+            VMError::InvalidRegister { index } => write!(f, "InvalidRegister: R{}", index),
+            VMError::CycleLimitExceeded { cycles } => {
+                write!(f, "CycleLimitExceeded: {} cycles", cycles)
+            }
+            VMError::ExplicitTrap { pc, code } => {
+                write!(f, "ExplicitTrap: code {} à PC=0x{:08X}", code, pc)
+            }
+            VMError::StackOverflow { pc, sp } => {
+                write!(f, "StackOverflow: SP=0x{:08X} à PC=0x{:08X}", sp, pc)
+            }
+            VMError::StackUnderflow { pc, sp } => {
+                write!(f, "StackUnderflow: SP=0x{:08X} à PC=0x{:08X}", sp, pc)
+            }
+            VMError::IllegalInstruction { pc, byte } => {
+                write!(f, "IllegalInstruction: opcode 0x{:02X} à PC=0x{:08X}", byte, pc)
+            }
+            VMError::WriteToReadOnly { addr } => {
+                write!(f, "WriteToReadOnly: addr=0x{:08X}", addr)
+            }
+            VMError::Pipeline(err) => write!(f, "PipelineError: {}", err),
         }
     }
 }
@@ -73,5 +126,14 @@ impl From<Error> for VMError {
     }
 }
 
+impl From<PipelineError> for VMError {
+    fn from(err: PipelineError) -> Self {
+        match err {
+            PipelineError::IllegalInstruction { pc, byte } => VMError::IllegalInstruction { pc, byte },
+            err => VMError::Pipeline(err),
+        }
+    }
+}
+
 /// Resultat type pour les operation de la VM
 pub type VMResult<T> = Result<T, VMError>;